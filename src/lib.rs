@@ -14,15 +14,50 @@
 //! more use cases come up. If you need functionality that this crate doesn't
 //! provide yet, please [open an issue] or, better yet, [submit a pull request].
 //!
+//! # Optional features
+//!
+//! - `security` (enabled by default): Securing and unsecuring of frames,
+//!   using `mac::FrameSerDesContext` and the types in
+//!   [`mac::frame::security`]. Without it, the crate does not depend on
+//!   `ccm` and `cipher`, and frames are written without security, using
+//!   [`mac::FooterMode`] as the context. Use [`mac::Frame::try_read_unsecured`]
+//!   to reject received secured frames as required by the standard.
+//! - `defmt`: Implements [`defmt::Format`] for the public types, so they can
+//!   be logged using [`defmt`].
+//! - `serde`: Implements `Serialize` and `Deserialize` from [`serde`] for the
+//!   public types. Use [`mac::OwnedFrame`] to deserialize frames from formats
+//!   that can't borrow their payload.
+//! - `arbitrary`: Implements `Arbitrary` from [`arbitrary`] for the public
+//!   types, generating structurally valid frames for fuzzing and property
+//!   testing. Enabling this feature links `std`.
+//! - `soft-crypto`: Provides `Aes128SecurityContext` in
+//!   [`mac::frame::security`], which secures frames with AES-128 CCM* in
+//!   software, using the [`aes`] crate. Implies `security`.
+//! - `std`: Implements `std::error::Error` for the error types.
+//! - `test-vectors`: Provides the example secured frames of the standard in
+//!   `mac::frame::security::test_vectors`, to check that a CCM* backend
+//!   conforms to it.
+//!
 //! [IEEE 802.15.4]: https://en.wikipedia.org/wiki/IEEE_802.15.4
 //! [`dw1000` crate]: https://crates.io/crates/dw1000
 //! [DW1000]: https://www.decawave.com/product/dw1000-radio-ic/
 //! [open an issue]: https://github.com/braun-robotics/rust-ieee802.15.4/issues
 //! [submit a pull request]: https://github.com/braun-robotics/rust-ieee802.15.4/pulls
+//! [`defmt`]: https://crates.io/crates/defmt
+//! [`defmt::Format`]: https://docs.rs/defmt/latest/defmt/trait.Format.html
+//! [`serde`]: https://crates.io/crates/serde
+//! [`arbitrary`]: https://crates.io/crates/arbitrary
+//! [`aes`]: https://crates.io/crates/aes
 
 #![deny(missing_docs)]
 #![no_std]
 
+// The `Arbitrary` derive refers to `std`, which is fine as fuzzing and
+// property testing happen on the host. The `std` feature needs it for
+// `std::error::Error`.
+#[cfg(any(test, feature = "arbitrary", feature = "std"))]
+extern crate std;
+
 #[macro_use]
 mod utils;
 pub mod mac;
@@ -3,13 +3,84 @@
 //! Work in progress
 
 use byte::{check_len, BytesExt, TryRead, TryWrite};
-use core::convert::From;
-use core::mem;
+use core::convert::{From, TryFrom};
+use core::ops::Range;
+use core::time::Duration;
+use core::{fmt, mem};
 
-use crate::mac::{ExtendedAddress, ShortAddress};
+use crate::mac::ie::{IeWriter, NestedIe};
+use crate::mac::{
+    Address, DecodeError, ExtendedAddress, Frame, FrameContent, FrameType,
+    FrameVersion, Header, PanId, ShortAddress,
+};
+
+/// The number of symbols in a superframe slot with superframe order 0
+/// (aBaseSlotDuration)
+pub const BASE_SLOT_DURATION: u32 = 60;
+
+/// The number of slots in a superframe (aNumSuperframeSlots)
+pub const NUM_SUPERFRAME_SLOTS: u32 = 16;
+
+/// The number of symbols in a superframe with superframe order 0
+/// (aBaseSuperframeDuration)
+pub const BASE_SUPERFRAME_DURATION: u32 =
+    BASE_SLOT_DURATION * NUM_SUPERFRAME_SLOTS;
+
+/// The minimum length of the contention access period (CAP), in symbols
+/// (aMinCAPLength)
+pub const MIN_CAP_LENGTH: u32 = 440;
+
+/// The number of symbols in a CSMA-CA backoff period (aUnitBackoffPeriod)
+pub const UNIT_BACKOFF_PERIOD: u32 = 20;
+
+/// The largest initial backoff exponent of slotted CSMA-CA when battery life
+/// extension is enabled
+pub const BATT_LIFE_EXT_MAX_BE: u8 = 2;
+
+/// The smallest value of macBattLifeExtPeriods
+pub const MIN_BATT_LIFE_EXT_PERIODS: u8 = 6;
+
+/// The largest value of macBattLifeExtPeriods
+pub const MAX_BATT_LIFE_EXT_PERIODS: u8 = 41;
+
+/// Convert a number of symbols to a duration, for a PHY with `symbol_rate`
+/// symbols per second
+///
+/// Returns `None` if `symbol_rate` is 0.
+pub fn symbols_to_duration(symbols: u32, symbol_rate: u32) -> Option<Duration> {
+    if symbol_rate == 0 {
+        return None;
+    }
+    Some(Duration::from_nanos(
+        symbols as u64 * 1_000_000_000 / symbol_rate as u64,
+    ))
+}
 
 /// Beacon order is used to calculate the beacon interval
+///
+/// # Example
+///
+/// ``` rust
+/// use core::convert::TryFrom;
+/// use core::time::Duration;
+/// use ieee802154::mac::beacon::BeaconOrder;
+/// use ieee802154::mac::DecodeError;
+///
+/// let order = BeaconOrder::try_from(6)?;
+/// assert_eq!(order.beacon_interval(), Some(61_440));
+/// // The 2.4 GHz O-QPSK PHY sends 62.5 ksymbol/s
+/// assert_eq!(
+///     order.beacon_interval_time(62_500),
+///     Some(Duration::from_micros(983_040))
+/// );
+///
+/// assert_eq!(BeaconOrder::try_from(15)?, BeaconOrder::OnDemand);
+/// assert_eq!(BeaconOrder::try_from(16), Err(DecodeError::InvalidOrder(16)));
+/// # Ok::<(), DecodeError>(())
+/// ```
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BeaconOrder {
     /// Used to calculate at which interval beacons are sent
     ///
@@ -19,12 +90,40 @@ pub enum BeaconOrder {
     OnDemand,
 }
 
-impl From<u8> for BeaconOrder {
+impl BeaconOrder {
+    /// The beacon interval, in symbols
+    ///
+    /// Returns `None` if beacons are only sent on demand.
+    pub fn beacon_interval(&self) -> Option<u32> {
+        match *self {
+            BeaconOrder::BeaconOrder(order) if order <= 14 => {
+                Some(BASE_SUPERFRAME_DURATION << order)
+            }
+            _ => None,
+        }
+    }
+
+    /// The beacon interval, for a PHY with `symbol_rate` symbols per second
+    ///
+    /// Returns `None` if beacons are only sent on demand, or if
+    /// `symbol_rate` is 0.
+    pub fn beacon_interval_time(&self, symbol_rate: u32) -> Option<Duration> {
+        symbols_to_duration(self.beacon_interval()?, symbol_rate)
+    }
+}
+
+impl TryFrom<u8> for BeaconOrder {
+    type Error = DecodeError;
+
     /// Convert u8 to beacon order
-    fn from(value: u8) -> Self {
+    ///
+    /// 15 means that beacons are only sent on demand, and larger values are
+    /// invalid.
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
         match value {
-            0..=14 => BeaconOrder::BeaconOrder(value),
-            _ => BeaconOrder::OnDemand,
+            0..=14 => Ok(BeaconOrder::BeaconOrder(value)),
+            15 => Ok(BeaconOrder::OnDemand),
+            _ => Err(DecodeError::InvalidOrder(value)),
         }
     }
 }
@@ -39,8 +138,44 @@ impl From<BeaconOrder> for u8 {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for BeaconOrder {
+    fn arbitrary(
+        u: &mut arbitrary::Unstructured<'a>,
+    ) -> arbitrary::Result<Self> {
+        BeaconOrder::try_from(u.int_in_range(0..=15)?)
+            .map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
 /// Superframe order, amount of time during wich this superframe is active
+///
+/// # Example
+///
+/// ``` rust
+/// use core::convert::TryFrom;
+/// use core::time::Duration;
+/// use ieee802154::mac::beacon::SuperframeOrder;
+/// use ieee802154::mac::DecodeError;
+///
+/// let order = SuperframeOrder::try_from(4)?;
+/// assert_eq!(order.superframe_duration(), Some(15_360));
+/// assert_eq!(
+///     order.superframe_duration_time(62_500),
+///     Some(Duration::from_micros(245_760))
+/// );
+/// assert_eq!(
+///     order.slot_duration_time(62_500),
+///     Some(Duration::from_micros(15_360))
+/// );
+///
+/// assert_eq!(SuperframeOrder::try_from(15)?, SuperframeOrder::Inactive);
+/// assert!(SuperframeOrder::try_from(16).is_err());
+/// # Ok::<(), DecodeError>(())
+/// ```
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SuperframeOrder {
     /// Ammount of time that the superframe is active
     ///
@@ -50,12 +185,61 @@ pub enum SuperframeOrder {
     Inactive,
 }
 
-impl From<u8> for SuperframeOrder {
+impl SuperframeOrder {
+    /// The duration of the active part of the superframe, in symbols
+    ///
+    /// Returns `None` if the superframe is inactive.
+    pub fn superframe_duration(&self) -> Option<u32> {
+        self.slot_duration()
+            .map(|slot_duration| slot_duration * NUM_SUPERFRAME_SLOTS)
+    }
+
+    /// The duration of a superframe slot, in symbols
+    ///
+    /// Returns `None` if the superframe is inactive.
+    pub fn slot_duration(&self) -> Option<u32> {
+        match *self {
+            SuperframeOrder::SuperframeOrder(order) if order <= 14 => {
+                Some(BASE_SLOT_DURATION << order)
+            }
+            _ => None,
+        }
+    }
+
+    /// The duration of the active part of the superframe, for a PHY with
+    /// `symbol_rate` symbols per second
+    ///
+    /// Returns `None` if the superframe is inactive, or if `symbol_rate` is
+    /// 0.
+    pub fn superframe_duration_time(
+        &self,
+        symbol_rate: u32,
+    ) -> Option<Duration> {
+        symbols_to_duration(self.superframe_duration()?, symbol_rate)
+    }
+
+    /// The duration of a superframe slot, for a PHY with `symbol_rate`
+    /// symbols per second
+    ///
+    /// Returns `None` if the superframe is inactive, or if `symbol_rate` is
+    /// 0.
+    pub fn slot_duration_time(&self, symbol_rate: u32) -> Option<Duration> {
+        symbols_to_duration(self.slot_duration()?, symbol_rate)
+    }
+}
+
+impl TryFrom<u8> for SuperframeOrder {
+    type Error = DecodeError;
+
     /// Convert u8 to superframe order
-    fn from(value: u8) -> Self {
+    ///
+    /// 15 means that the superframe is inactive, and larger values are
+    /// invalid.
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
         match value {
-            0..=14 => SuperframeOrder::SuperframeOrder(value),
-            _ => SuperframeOrder::Inactive,
+            0..=14 => Ok(SuperframeOrder::SuperframeOrder(value)),
+            15 => Ok(SuperframeOrder::Inactive),
+            _ => Err(DecodeError::InvalidOrder(value)),
         }
     }
 }
@@ -70,11 +254,23 @@ impl From<SuperframeOrder> for u8 {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for SuperframeOrder {
+    fn arbitrary(
+        u: &mut arbitrary::Unstructured<'a>,
+    ) -> arbitrary::Result<Self> {
+        SuperframeOrder::try_from(u.int_in_range(0..=15)?)
+            .map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
 /// Superframe specification
 ///
 /// The superframe specification describes the organisation of frames in the
 /// air when using superframes and/or periodical beacons.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SuperframeSpecification {
     /// Beacon order, 0-15, where 15 is on demand.
     ///
@@ -92,6 +288,307 @@ pub struct SuperframeSpecification {
     pub association_permit: bool,
 }
 
+impl SuperframeSpecification {
+    /// The size of the superframe specification field, in octets
+    pub const OCTET_SIZE: usize = 2;
+
+    /// Create a superframe specification, without battery life extension,
+    /// that isn't sent by the PAN coordinator and doesn't permit associations
+    ///
+    /// Returns an error if the combination of orders and final CAP slot is
+    /// invalid, see [`SuperframeSpecification::validate`].
+    ///
+    /// # Example
+    ///
+    /// ``` rust
+    /// use ieee802154::mac::beacon::{
+    ///     BeaconOrder, SuperframeOrder, SuperframeSpecification,
+    ///     SuperframeSpecificationError,
+    /// };
+    ///
+    /// let spec = SuperframeSpecification::new(
+    ///     BeaconOrder::BeaconOrder(6),
+    ///     SuperframeOrder::SuperframeOrder(4),
+    ///     15,
+    /// );
+    /// assert!(spec.is_ok());
+    ///
+    /// let spec = SuperframeSpecification::new(
+    ///     BeaconOrder::BeaconOrder(4),
+    ///     SuperframeOrder::SuperframeOrder(6),
+    ///     15,
+    /// );
+    /// assert_eq!(
+    ///     spec,
+    ///     Err(SuperframeSpecificationError::SuperframeOrderTooLarge)
+    /// );
+    /// ```
+    pub fn new(
+        beacon_order: BeaconOrder,
+        superframe_order: SuperframeOrder,
+        final_cap_slot: u8,
+    ) -> Result<Self, SuperframeSpecificationError> {
+        let spec = Self {
+            beacon_order,
+            superframe_order,
+            final_cap_slot,
+            battery_life_extension: false,
+            pan_coordinator: false,
+            association_permit: false,
+        };
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    /// Check that the superframe specification describes a valid superframe
+    ///
+    /// The final CAP slot must be a superframe slot. If the PAN is
+    /// beacon-enabled and the superframe is active, the superframe order may
+    /// not exceed the beacon order, and the CAP must be at least
+    /// [`MIN_CAP_LENGTH`] symbols long. In a PAN that isn't beacon-enabled,
+    /// the superframe order and the final CAP slot are ignored.
+    pub fn validate(&self) -> Result<(), SuperframeSpecificationError> {
+        if self.final_cap_slot as u32 >= NUM_SUPERFRAME_SLOTS {
+            return Err(SuperframeSpecificationError::InvalidFinalCapSlot(
+                self.final_cap_slot,
+            ));
+        }
+        let (beacon_order, superframe_order) =
+            match (self.beacon_order, self.superframe_order) {
+                (
+                    BeaconOrder::BeaconOrder(bo),
+                    SuperframeOrder::SuperframeOrder(so),
+                ) => (bo, so),
+                _ => return Ok(()),
+            };
+        if superframe_order > beacon_order {
+            return Err(SuperframeSpecificationError::SuperframeOrderTooLarge);
+        }
+        if self.final_cap_slot < min_final_cap_slot(superframe_order) {
+            return Err(SuperframeSpecificationError::CapTooShort);
+        }
+        Ok(())
+    }
+
+    /// The initial backoff exponent of slotted CSMA-CA, for a macMinBE of
+    /// `min_be`
+    ///
+    /// With battery life extension, this is at most
+    /// [`BATT_LIFE_EXT_MAX_BE`].
+    pub fn initial_backoff_exponent(&self, min_be: u8) -> u8 {
+        if self.battery_life_extension {
+            min_be.min(BATT_LIFE_EXT_MAX_BE)
+        } else {
+            min_be
+        }
+    }
+
+    /// The symbols, relative to the start of the beacon, in which a
+    /// transmission may start when battery life extension is enabled
+    ///
+    /// `beacon_duration` is the duration of the beacon frame and `ifs` the
+    /// interframe spacing after it, in symbols. Transmissions start in the
+    /// first `batt_life_ext_periods` (macBattLifeExtPeriods) full backoff
+    /// periods after the IFS, and the window ends with the CAP.
+    ///
+    /// Returns `None` if battery life extension isn't enabled, or if the
+    /// superframe isn't active.
+    ///
+    /// # Example
+    ///
+    /// ``` rust
+    /// use ieee802154::mac::beacon::{
+    ///     BeaconOrder, SuperframeOrder, SuperframeSpecification,
+    /// };
+    ///
+    /// let mut spec = SuperframeSpecification::new(
+    ///     BeaconOrder::BeaconOrder(6),
+    ///     SuperframeOrder::SuperframeOrder(4),
+    ///     15,
+    /// )
+    /// .unwrap();
+    /// spec.battery_life_extension = true;
+    ///
+    /// // A beacon of 35 symbols, followed by a SIFS of 12 symbols
+    /// assert_eq!(spec.battery_life_extension_window(35, 12, 6), Some(60..180));
+    /// assert_eq!(spec.initial_backoff_exponent(3), 2);
+    /// ```
+    pub fn battery_life_extension_window(
+        &self,
+        beacon_duration: u32,
+        ifs: u32,
+        batt_life_ext_periods: u8,
+    ) -> Option<Range<u32>> {
+        if !self.battery_life_extension {
+            return None;
+        }
+        if let BeaconOrder::OnDemand = self.beacon_order {
+            return None;
+        }
+        let slot_duration = self.superframe_order.slot_duration()?;
+        let cap_end = (self.final_cap_slot as u32 + 1)
+            .min(NUM_SUPERFRAME_SLOTS)
+            * slot_duration;
+        // Backoff periods are aligned with the start of the beacon
+        let start = (beacon_duration + ifs).div_ceil(UNIT_BACKOFF_PERIOD)
+            * UNIT_BACKOFF_PERIOD;
+        let end = start + batt_life_ext_periods as u32 * UNIT_BACKOFF_PERIOD;
+        Some(start.min(cap_end)..end.min(cap_end))
+    }
+
+    /// Calculate the timing of the superframe, for a PHY with `symbol_rate`
+    /// symbols per second
+    ///
+    /// See [`SuperframeTiming::new`].
+    pub fn timing(&self, symbol_rate: u32) -> Option<SuperframeTiming> {
+        SuperframeTiming::new(self, symbol_rate)
+    }
+}
+
+/// The lowest final CAP slot that leaves a CAP of at least [`MIN_CAP_LENGTH`]
+/// symbols
+fn min_final_cap_slot(superframe_order: u8) -> u8 {
+    let slot_duration = BASE_SLOT_DURATION << superframe_order;
+    (MIN_CAP_LENGTH.div_ceil(slot_duration) - 1) as u8
+}
+
+/// Signals an invalid superframe specification
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SuperframeSpecificationError {
+    /// The final CAP slot isn't a superframe slot
+    InvalidFinalCapSlot(u8),
+    /// The superframe order exceeds the beacon order
+    SuperframeOrderTooLarge,
+    /// The CAP is shorter than [`MIN_CAP_LENGTH`]
+    CapTooShort,
+}
+
+impl fmt::Display for SuperframeSpecificationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SuperframeSpecificationError::InvalidFinalCapSlot(slot) => {
+                write!(f, "invalid final CAP slot: {}", slot)
+            }
+            SuperframeSpecificationError::SuperframeOrderTooLarge => {
+                f.write_str("superframe order exceeds beacon order")
+            }
+            SuperframeSpecificationError::CapTooShort => {
+                f.write_str("CAP shorter than aMinCAPLength")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SuperframeSpecificationError {}
+
+/// The timing of a beacon-enabled superframe
+///
+/// The durations are in symbols, and can be converted to microseconds with
+/// the symbol rate of the PHY.
+///
+/// # Example
+///
+/// ``` rust
+/// use ieee802154::mac::beacon::{
+///     BeaconOrder, SuperframeOrder, SuperframeSpecification,
+/// };
+///
+/// let spec = SuperframeSpecification {
+///     beacon_order: BeaconOrder::BeaconOrder(6),
+///     superframe_order: SuperframeOrder::SuperframeOrder(4),
+///     final_cap_slot: 11,
+///     battery_life_extension: false,
+///     pan_coordinator: true,
+///     association_permit: true,
+/// };
+///
+/// // The 2.4 GHz O-QPSK PHY sends 62.5 ksymbol/s
+/// let timing = spec.timing(62_500).unwrap();
+/// assert_eq!(timing.beacon_interval, 61_440);
+/// assert_eq!(timing.beacon_interval_us(), 983_040);
+/// assert_eq!(timing.superframe_duration_us(), 245_760);
+/// assert_eq!(timing.slot_duration, 960);
+/// assert_eq!(timing.slot_duration_us(), 15_360);
+/// assert_eq!(timing.cap_length, 11_520);
+/// assert_eq!(timing.cap_length_us(), 184_320);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SuperframeTiming {
+    /// The symbol rate of the PHY, in symbols per second
+    pub symbol_rate: u32,
+    /// The time between the starts of two beacons, in symbols
+    pub beacon_interval: u32,
+    /// The duration of the active part of the superframe, in symbols
+    pub superframe_duration: u32,
+    /// The duration of a superframe slot, in symbols
+    pub slot_duration: u32,
+    /// The duration of the contention access period (CAP), in symbols
+    ///
+    /// This includes the beacon, which is sent at the start of the first
+    /// slot.
+    pub cap_length: u32,
+}
+
+impl SuperframeTiming {
+    /// Calculate the timing of the superframe described by `spec`, for a PHY
+    /// with `symbol_rate` symbols per second
+    ///
+    /// Returns `None` if the PAN isn't beacon-enabled or the superframe is
+    /// inactive, if the superframe order is larger than the beacon order, or
+    /// if `symbol_rate` is 0.
+    pub fn new(
+        spec: &SuperframeSpecification,
+        symbol_rate: u32,
+    ) -> Option<Self> {
+        let beacon_interval = spec.beacon_order.beacon_interval()?;
+        let superframe_duration =
+            spec.superframe_order.superframe_duration()?;
+        let slot_duration = spec.superframe_order.slot_duration()?;
+        if superframe_duration > beacon_interval || symbol_rate == 0 {
+            return None;
+        }
+        let cap_slots =
+            (spec.final_cap_slot as u32 + 1).min(NUM_SUPERFRAME_SLOTS);
+        Some(Self {
+            symbol_rate,
+            beacon_interval,
+            superframe_duration,
+            slot_duration,
+            cap_length: cap_slots * slot_duration,
+        })
+    }
+
+    /// Convert a duration in symbols to microseconds
+    pub fn symbols_to_us(&self, symbols: u32) -> u64 {
+        symbols as u64 * 1_000_000 / self.symbol_rate as u64
+    }
+
+    /// The time between the starts of two beacons, in microseconds
+    pub fn beacon_interval_us(&self) -> u64 {
+        self.symbols_to_us(self.beacon_interval)
+    }
+
+    /// The duration of the active part of the superframe, in microseconds
+    pub fn superframe_duration_us(&self) -> u64 {
+        self.symbols_to_us(self.superframe_duration)
+    }
+
+    /// The duration of a superframe slot, in microseconds
+    pub fn slot_duration_us(&self) -> u64 {
+        self.symbols_to_us(self.slot_duration)
+    }
+
+    /// The duration of the contention access period (CAP), in microseconds
+    pub fn cap_length_us(&self) -> u64 {
+        self.symbols_to_us(self.cap_length)
+    }
+}
+
 const BATTERY_LIFE_EXTENSION: u8 = 0b0001_0000;
 const PAN_COORDINATOR: u8 = 0b0100_0000;
 const ASSOCIATION_PERMIT: u8 = 0b1000_0000;
@@ -101,8 +598,8 @@ impl TryRead<'_> for SuperframeSpecification {
         let offset = &mut 0;
         check_len(&bytes, 2)?;
         let byte: u8 = bytes.read(offset)?;
-        let beacon_order = BeaconOrder::from(byte & 0x0f);
-        let superframe_order = SuperframeOrder::from((byte >> 4) & 0x0f);
+        let beacon_order = BeaconOrder::try_from(byte & 0x0f)?;
+        let superframe_order = SuperframeOrder::try_from((byte >> 4) & 0x0f)?;
         let byte: u8 = bytes.read(offset)?;
         let final_cap_slot = byte & 0x0f;
         let battery_life_extension =
@@ -110,17 +607,18 @@ impl TryRead<'_> for SuperframeSpecification {
         let pan_coordinator = (byte & PAN_COORDINATOR) == PAN_COORDINATOR;
         let association_permit =
             (byte & ASSOCIATION_PERMIT) == ASSOCIATION_PERMIT;
-        Ok((
-            Self {
-                beacon_order,
-                superframe_order,
-                final_cap_slot,
-                battery_life_extension,
-                pan_coordinator,
-                association_permit,
-            },
-            *offset,
-        ))
+        let spec = Self {
+            beacon_order,
+            superframe_order,
+            final_cap_slot,
+            battery_life_extension,
+            pan_coordinator,
+            association_permit,
+        };
+        if let Err(e) = spec.validate() {
+            return Err(DecodeError::InvalidSuperframeSpecification(e).into());
+        }
+        Ok((spec, *offset))
     }
 }
 
@@ -150,9 +648,42 @@ impl TryWrite for SuperframeSpecification {
     }
 }
 
-/// Direction of data
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for SuperframeSpecification {
+    fn arbitrary(
+        u: &mut arbitrary::Unstructured<'a>,
+    ) -> arbitrary::Result<Self> {
+        let beacon_order = u.arbitrary()?;
+        let (superframe_order, min_final_cap_slot) = match beacon_order {
+            BeaconOrder::BeaconOrder(bo) => match u.int_in_range(0..=bo + 1)? {
+                so if so <= bo => (
+                    SuperframeOrder::SuperframeOrder(so),
+                    min_final_cap_slot(so),
+                ),
+                _ => (SuperframeOrder::Inactive, 0),
+            },
+            BeaconOrder::OnDemand => (u.arbitrary()?, 0),
+        };
+        Ok(SuperframeSpecification {
+            beacon_order,
+            superframe_order,
+            final_cap_slot: u.int_in_range(min_final_cap_slot..=15)?,
+            battery_life_extension: u.arbitrary()?,
+            pan_coordinator: u.arbitrary()?,
+            association_permit: u.arbitrary()?,
+        })
+    }
+}
+
+/// Direction of data in a GTS, from the device's perspective
+///
+/// A receive-only GTS is encoded as a set bit in the GTS directions mask, and
+/// a transmit-only GTS as a cleared bit.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-enum Direction {
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Direction {
     /// Receive data
     Receive,
     /// Transmit data
@@ -161,6 +692,8 @@ enum Direction {
 
 /// Descriptor of the guaranteed time slots (GTSs)
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GuaranteedTimeSlotDescriptor {
     /// Device short address used by this slot
     short_address: ShortAddress,
@@ -173,6 +706,9 @@ pub struct GuaranteedTimeSlotDescriptor {
 }
 
 impl GuaranteedTimeSlotDescriptor {
+    /// The size of a GTS descriptor, in octets
+    pub const OCTET_SIZE: usize = 3;
+
     /// Create a new empty slot
     pub fn new() -> Self {
         GuaranteedTimeSlotDescriptor {
@@ -214,111 +750,291 @@ impl TryWrite for GuaranteedTimeSlotDescriptor {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for GuaranteedTimeSlotDescriptor {
+    fn arbitrary(
+        u: &mut arbitrary::Unstructured<'a>,
+    ) -> arbitrary::Result<Self> {
+        Ok(GuaranteedTimeSlotDescriptor {
+            short_address: u.arbitrary()?,
+            starting_slot: u.int_in_range(0..=15)?,
+            length: u.int_in_range(0..=15)?,
+            direction: u.arbitrary()?,
+        })
+    }
+}
+
 impl GuaranteedTimeSlotDescriptor {
+    /// Device short address used by this slot
+    pub fn short_address(&self) -> ShortAddress {
+        self.short_address
+    }
+
+    /// The first superframe slot of the GTS
+    pub fn starting_slot(&self) -> u8 {
+        self.starting_slot
+    }
+
+    /// The number of superframe slots of the GTS
+    pub fn length(&self) -> u8 {
+        self.length
+    }
+
+    /// Direction of the slot, either transmit or receive
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
     /// Set the direction for this slot
     fn set_direction(&mut self, direction: Direction) {
         self.direction = direction;
     }
-    /// Returns `true` if this is a transmit slot
-    fn direction_transmit(&self) -> bool {
-        self.direction == Direction::Transmit
-    }
 }
 
 const COUNT_MASK: u8 = 0b0000_0111;
 const PERMIT: u8 = 0b1000_0000;
 
+/// The maximum number of GTS descriptors in a beacon
+pub const MAX_GTS_DESCRIPTORS: usize = 7;
+
+/// The maximum number of short or extended addresses in the pending address
+/// fields of a beacon
+pub const MAX_PENDING_ADDRESSES: usize = 7;
+
 /// Information of the guaranteed time slots (GTSs)
+///
+/// This holds up to `N` GTS descriptors. A beacon carries up to
+/// [`MAX_GTS_DESCRIPTORS`], but devices that don't use GTSs can reduce `N`
+/// to save memory. Decoding GTS fields with more than `N` descriptors fails.
+///
+/// # Example
+///
+/// ``` rust
+/// use byte::BytesExt;
+/// use ieee802154::mac::beacon::GuaranteedTimeSlotInformation;
+///
+/// // Without GTSs
+/// let gts: GuaranteedTimeSlotInformation<0> = [0x80].read(&mut 0)?;
+/// assert!(gts.permit);
+///
+/// // A GTS descriptor
+/// let bytes = [0x81, 0x00, 0x01, 0x00, 0x1e];
+/// assert!(bytes.read::<GuaranteedTimeSlotInformation<0>>(&mut 0).is_err());
+/// # Ok::<(), byte::Error>(())
+/// ```
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-pub struct GuaranteedTimeSlotInformation {
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GuaranteedTimeSlotInformation<const N: usize = MAX_GTS_DESCRIPTORS> {
     /// Permit GTS
     pub permit: bool,
     slot_count: usize,
-    slots: [GuaranteedTimeSlotDescriptor; 7],
+    #[cfg_attr(feature = "serde", serde(with = "serde_array"))]
+    slots: [GuaranteedTimeSlotDescriptor; N],
+    /// The bits of the GTS directions mask that no descriptor uses
+    unused_direction_bits: u8,
 }
 
 impl GuaranteedTimeSlotInformation {
+    /// The maximum size of the GTS fields, in octets
+    pub const MAX_OCTET_SIZE: usize = 23;
+
+    /// Get the size of the GTS fields with `slot_count` GTS descriptors, in
+    /// octets
+    ///
+    /// This is the size of the GTS specification, and, if there are any
+    /// slots, the GTS directions and the GTS descriptors.
+    pub const fn octet_size_for(slot_count: usize) -> usize {
+        if slot_count == 0 {
+            1
+        } else {
+            1 + 1 + slot_count * GuaranteedTimeSlotDescriptor::OCTET_SIZE
+        }
+    }
+
     /// Create a new empty GTS information
+    ///
+    /// Use [`GuaranteedTimeSlotInformation::empty`] for GTS information with
+    /// room for fewer descriptors.
     pub fn new() -> Self {
+        Self::empty()
+    }
+}
+
+impl<const N: usize> GuaranteedTimeSlotInformation<N> {
+    /// Create a new empty GTS information, with room for `N` descriptors
+    pub fn empty() -> Self {
         GuaranteedTimeSlotInformation {
             permit: false,
             slot_count: 0,
-            slots: [GuaranteedTimeSlotDescriptor::new(); 7],
+            slots: [GuaranteedTimeSlotDescriptor::new(); N],
+            unused_direction_bits: 0,
         }
     }
 
+    /// Get the GTS directions mask
+    ///
+    /// Bit `n` is set if the GTS of descriptor `n` is a receive-only GTS. The
+    /// bits that no descriptor uses are kept as they were decoded.
+    pub fn direction_mask(&self) -> u8 {
+        let used = (1u8 << self.slot_count) - 1;
+        self.slots()
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.direction == Direction::Receive)
+            .fold(self.unused_direction_bits & !used, |mask, (n, _)| {
+                mask | 1 << n
+            })
+    }
+
     /// Get the slots as a slice
     pub fn slots(&self) -> &[GuaranteedTimeSlotDescriptor] {
         &self.slots[..self.slot_count]
     }
+
+    /// Get the size of the GTS fields in octets
+    pub fn get_octet_size(&self) -> usize {
+        GuaranteedTimeSlotInformation::octet_size_for(self.slot_count)
+    }
+
+    /// Add a GTS descriptor
+    ///
+    /// Returns `false`, and leaves the slots unchanged, if there already are
+    /// `N` or 7 GTS descriptors, or if `starting_slot` or `length` are larger
+    /// than 15.
+    ///
+    /// # Example
+    ///
+    /// ``` rust
+    /// use ieee802154::mac::beacon::{Direction, GuaranteedTimeSlotInformation};
+    /// use ieee802154::mac::ShortAddress;
+    ///
+    /// let mut gts = GuaranteedTimeSlotInformation::new();
+    /// assert!(gts.add_slot(ShortAddress(0x0001), 12, 2, Direction::Transmit));
+    /// assert!(gts.add_slot(ShortAddress(0x0002), 14, 1, Direction::Receive));
+    /// assert!(!gts.add_slot(ShortAddress(0x0003), 16, 1, Direction::Receive));
+    ///
+    /// let removed = gts.remove_slot(0).unwrap();
+    /// assert_eq!(removed.short_address(), ShortAddress(0x0001));
+    /// assert_eq!(gts.slots().len(), 1);
+    /// assert_eq!(gts.slots()[0].starting_slot(), 14);
+    /// assert_eq!(gts.slots()[0].direction(), Direction::Receive);
+    /// ```
+    pub fn add_slot(
+        &mut self,
+        short_address: ShortAddress,
+        starting_slot: u8,
+        length: u8,
+        direction: Direction,
+    ) -> bool {
+        if self.slot_count >= N.min(MAX_GTS_DESCRIPTORS)
+            || starting_slot > 0x0f
+            || length > 0x0f
+        {
+            return false;
+        }
+        self.slots[self.slot_count] = GuaranteedTimeSlotDescriptor {
+            short_address,
+            starting_slot,
+            length,
+            direction,
+        };
+        self.slot_count += 1;
+        true
+    }
+
+    /// Remove the GTS descriptor at `index`
+    ///
+    /// The order of the remaining GTS descriptors is kept. Returns `None` if
+    /// there is no GTS descriptor at `index`.
+    pub fn remove_slot(
+        &mut self,
+        index: usize,
+    ) -> Option<GuaranteedTimeSlotDescriptor> {
+        if index >= self.slot_count {
+            return None;
+        }
+        let slot = self.slots[index];
+        self.slots.copy_within(index + 1..self.slot_count, index);
+        self.slot_count -= 1;
+        self.slots[self.slot_count] = GuaranteedTimeSlotDescriptor::new();
+        Some(slot)
+    }
 }
 
-impl TryWrite for GuaranteedTimeSlotInformation {
+#[cfg(feature = "arbitrary")]
+impl<'a, const N: usize> arbitrary::Arbitrary<'a>
+    for GuaranteedTimeSlotInformation<N>
+{
+    fn arbitrary(
+        u: &mut arbitrary::Unstructured<'a>,
+    ) -> arbitrary::Result<Self> {
+        let mut gts = Self::empty();
+        gts.permit = u.arbitrary()?;
+        gts.slot_count = u.int_in_range(0..=N.min(MAX_GTS_DESCRIPTORS))?;
+        for slot in gts.slots[..gts.slot_count].iter_mut() {
+            *slot = u.arbitrary()?;
+        }
+        Ok(gts)
+    }
+}
+
+impl<const N: usize> TryWrite for GuaranteedTimeSlotInformation<N> {
     fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
         let offset = &mut 0;
-        assert!(self.slot_count <= 7);
+        assert!(self.slot_count <= MAX_GTS_DESCRIPTORS);
         let permit = if self.permit { PERMIT } else { 0 };
 
         let header = ((self.slot_count as u8) & COUNT_MASK) | permit;
         bytes.write(offset, header)?;
 
         if self.slot_count > 0 {
-            let direction_mask = {
-                let mut dir = 0x01;
-                let mut direction_mask = 0u8;
-                for n in 0..self.slot_count {
-                    let slot = self.slots[n];
-                    if slot.direction_transmit() {
-                        direction_mask = direction_mask | dir;
-                    }
-                    dir = dir << 1;
-                }
-                direction_mask
-            };
-
-            bytes.write(offset, direction_mask)?;
+            bytes.write(offset, self.direction_mask())?;
 
-            for n in 0..self.slot_count {
-                bytes.write(offset, self.slots[n])?;
+            for &slot in self.slots() {
+                bytes.write(offset, slot)?;
             }
         }
         Ok(*offset)
     }
 }
 
-impl TryRead<'_> for GuaranteedTimeSlotInformation {
+impl<const N: usize> TryRead<'_> for GuaranteedTimeSlotInformation<N> {
     fn try_read(bytes: &[u8], _ctx: ()) -> byte::Result<(Self, usize)> {
         let offset = &mut 0;
         let byte: u8 = bytes.read(offset)?;
         let slot_count = (byte & COUNT_MASK) as usize;
         let permit = (byte & PERMIT) == PERMIT;
+        if slot_count > N {
+            return Err(DecodeError::InsufficientStorage.into());
+        }
         let mut slots = [GuaranteedTimeSlotDescriptor {
             short_address: ShortAddress::broadcast(),
             starting_slot: 0,
             length: 0,
             direction: Direction::Receive,
-        }; 7];
+        }; N];
+        let mut unused_direction_bits = 0;
         if slot_count > 0 {
-            check_len(&bytes[*offset..], 2 + (3 * slot_count))?;
-            let mut direction_mask: u8 = bytes.read(offset)?;
-            for n in 0..slot_count {
-                let mut slot: GuaranteedTimeSlotDescriptor =
-                    bytes.read(offset)?;
-                let direction = if direction_mask & 0b1 == 0b1 {
-                    Direction::Transmit
-                } else {
+            check_len(&bytes[*offset..], 1 + (3 * slot_count))?;
+            let direction_mask: u8 = bytes.read(offset)?;
+            for (n, slot) in slots[..slot_count].iter_mut().enumerate() {
+                *slot = bytes.read(offset)?;
+                let direction = if direction_mask & 1 << n != 0 {
                     Direction::Receive
+                } else {
+                    Direction::Transmit
                 };
                 slot.set_direction(direction);
-                direction_mask = direction_mask >> 1;
-                slots[n] = slot;
             }
+            unused_direction_bits = direction_mask & !((1 << slot_count) - 1);
         }
         Ok((
             Self {
                 permit,
                 slot_count,
                 slots,
+                unused_direction_bits,
             },
             *offset,
         ))
@@ -347,22 +1063,95 @@ const EXTENDED_MASK: u8 = 0b0111_0000;
 /// +-------------+----------+----------------+----------+
 ///      0 - 2         3         4 - 6             7        bit
 /// ```
+///
+/// This holds up to `N` short and `N` extended addresses. A beacon carries
+/// up to [`MAX_PENDING_ADDRESSES`] of each, but devices can reduce `N` to
+/// save memory. Decoding more than `N` addresses of a kind fails.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-pub struct PendingAddress {
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PendingAddress<const N: usize = MAX_PENDING_ADDRESSES> {
     short_address_count: usize,
-    short_addresses: [ShortAddress; 7],
+    #[cfg_attr(feature = "serde", serde(with = "serde_array"))]
+    short_addresses: [ShortAddress; N],
     extended_address_count: usize,
-    extended_addresses: [ExtendedAddress; 7],
+    #[cfg_attr(feature = "serde", serde(with = "serde_array"))]
+    extended_addresses: [ExtendedAddress; N],
 }
 
 impl PendingAddress {
+    /// The maximum size of the pending address fields, in octets
+    pub const MAX_OCTET_SIZE: usize = 71;
+
+    /// Get the size of the pending address fields with the provided amount of
+    /// short and extended addresses, in octets
+    pub const fn octet_size_for(
+        short_count: usize,
+        extended_count: usize,
+    ) -> usize {
+        1 + short_count * 2 + extended_count * 8
+    }
+
     /// Create a new empty PendingAddress struct
+    ///
+    /// Use [`PendingAddress::empty`] for a list with room for fewer
+    /// addresses.
     pub fn new() -> Self {
+        Self::empty()
+    }
+
+    /// Create a pending address list from `addresses`
+    ///
+    /// The list carries no PAN IDs, so the PAN IDs of `addresses` are
+    /// ignored. Returns `None` if there are more than 7 short or more than 7
+    /// extended addresses.
+    ///
+    /// # Example
+    ///
+    /// ``` rust
+    /// use ieee802154::mac::beacon::PendingAddress;
+    /// use ieee802154::mac::{Address, ExtendedAddress, PanId, ShortAddress};
+    ///
+    /// let pan_id = PanId(0xabcd);
+    /// let addresses = [
+    ///     Address::Extended(pan_id, ExtendedAddress(0x0102)),
+    ///     Address::Short(pan_id, ShortAddress(0x0001)),
+    /// ];
+    /// let pending = PendingAddress::from_addresses(addresses).unwrap();
+    /// assert!(pending.contains(Address::Short(pan_id, ShortAddress(0x0001))));
+    ///
+    /// // Short addresses are listed before extended addresses
+    /// let mut iter = pending.addresses(pan_id);
+    /// assert_eq!(iter.next(), Some(addresses[1]));
+    /// assert_eq!(iter.next(), Some(addresses[0]));
+    /// assert_eq!(iter.next(), None);
+    ///
+    /// let too_many = (0..8).map(|n| Address::Short(pan_id, ShortAddress(n)));
+    /// assert_eq!(PendingAddress::from_addresses(too_many), None);
+    /// ```
+    pub fn from_addresses<I>(addresses: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = Address>,
+    {
+        let mut pending = Self::new();
+        for address in addresses {
+            if !pending.push(address) {
+                return None;
+            }
+        }
+        Some(pending)
+    }
+}
+
+impl<const N: usize> PendingAddress<N> {
+    /// Create a new empty PendingAddress struct, with room for `N` short and
+    /// `N` extended addresses
+    pub fn empty() -> Self {
         PendingAddress {
             short_address_count: 0,
-            short_addresses: [ShortAddress::broadcast(); 7],
+            short_addresses: [ShortAddress::broadcast(); N],
             extended_address_count: 0,
-            extended_addresses: [ExtendedAddress::broadcast(); 7],
+            extended_addresses: [ExtendedAddress::broadcast(); N],
         }
     }
 
@@ -374,9 +1163,98 @@ impl PendingAddress {
     pub fn extended_addresses(&self) -> &[ExtendedAddress] {
         &self.extended_addresses[..self.extended_address_count]
     }
+
+    /// Add `address` to the list
+    ///
+    /// The PAN ID of `address` is ignored. Returns `false`, and leaves the
+    /// list unchanged, if there already are `N` or 7 addresses of its kind.
+    pub fn push(&mut self, address: Address) -> bool {
+        let capacity = N.min(MAX_PENDING_ADDRESSES);
+        match address {
+            Address::Short(_, address) => {
+                if self.short_address_count >= capacity {
+                    return false;
+                }
+                self.short_addresses[self.short_address_count] = address;
+                self.short_address_count += 1;
+            }
+            Address::Extended(_, address) => {
+                if self.extended_address_count >= capacity {
+                    return false;
+                }
+                self.extended_addresses[self.extended_address_count] = address;
+                self.extended_address_count += 1;
+            }
+        }
+        true
+    }
+
+    /// Iterate over the addresses, with the short addresses first
+    ///
+    /// The list carries no PAN IDs, so the addresses are given the PAN ID
+    /// `pan_id`, e.g. the PAN ID of the beacon's source.
+    pub fn addresses(
+        &self,
+        pan_id: PanId,
+    ) -> impl Iterator<Item = Address> + '_ {
+        let short = self
+            .short_addresses()
+            .iter()
+            .map(move |&address| Address::Short(pan_id, address));
+        let extended = self
+            .extended_addresses()
+            .iter()
+            .map(move |&address| Address::Extended(pan_id, address));
+        short.chain(extended)
+    }
+
+    /// Whether `address` is in the list
+    ///
+    /// The PAN ID of `address` is ignored.
+    pub fn contains(&self, address: Address) -> bool {
+        match address {
+            Address::Short(_, address) => {
+                self.short_addresses().contains(&address)
+            }
+            Address::Extended(_, address) => {
+                self.extended_addresses().contains(&address)
+            }
+        }
+    }
+
+    /// Get the size of the pending address fields in octets
+    pub fn get_octet_size(&self) -> usize {
+        PendingAddress::octet_size_for(
+            self.short_address_count,
+            self.extended_address_count,
+        )
+    }
 }
 
-impl TryRead<'_> for PendingAddress {
+#[cfg(feature = "arbitrary")]
+impl<'a, const N: usize> arbitrary::Arbitrary<'a> for PendingAddress<N> {
+    fn arbitrary(
+        u: &mut arbitrary::Unstructured<'a>,
+    ) -> arbitrary::Result<Self> {
+        let capacity = N.min(MAX_PENDING_ADDRESSES);
+        let mut pending = Self::empty();
+        pending.short_address_count = u.int_in_range(0..=capacity)?;
+        pending.extended_address_count = u.int_in_range(0..=capacity)?;
+        for addr in
+            pending.short_addresses[..pending.short_address_count].iter_mut()
+        {
+            *addr = u.arbitrary()?;
+        }
+        for addr in pending.extended_addresses[..pending.extended_address_count]
+            .iter_mut()
+        {
+            *addr = u.arbitrary()?;
+        }
+        Ok(pending)
+    }
+}
+
+impl<const N: usize> TryRead<'_> for PendingAddress<N> {
     fn try_read(bytes: &[u8], _ctx: ()) -> byte::Result<(Self, usize)> {
         let offset = &mut 0;
         let ss = mem::size_of::<ShortAddress>();
@@ -384,14 +1262,17 @@ impl TryRead<'_> for PendingAddress {
         let byte: u8 = bytes.read(offset)?;
         let sl = (byte & SHORT_MASK) as usize;
         let el = ((byte & EXTENDED_MASK) >> 4) as usize;
+        if sl > N || el > N {
+            return Err(DecodeError::InsufficientStorage.into());
+        }
         check_len(&bytes[*offset..], (sl * ss) + (el * es))?;
-        let mut short_addresses = [ShortAddress::broadcast(); 7];
-        for n in 0..sl {
-            short_addresses[n] = bytes.read(offset)?;
+        let mut short_addresses = [ShortAddress::broadcast(); N];
+        for address in short_addresses[..sl].iter_mut() {
+            *address = bytes.read(offset)?;
         }
-        let mut extended_addresses = [ExtendedAddress::broadcast(); 7];
-        for n in 0..el {
-            extended_addresses[n] = bytes.read(offset)?;
+        let mut extended_addresses = [ExtendedAddress::broadcast(); N];
+        for address in extended_addresses[..el].iter_mut() {
+            *address = bytes.read(offset)?;
         }
         Ok((
             Self {
@@ -405,11 +1286,11 @@ impl TryRead<'_> for PendingAddress {
     }
 }
 
-impl TryWrite for PendingAddress {
+impl<const N: usize> TryWrite for PendingAddress<N> {
     fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
         let offset = &mut 0;
-        assert!(self.short_address_count <= 7);
-        assert!(self.extended_address_count <= 7);
+        assert!(self.short_address_count <= MAX_PENDING_ADDRESSES);
+        assert!(self.extended_address_count <= MAX_PENDING_ADDRESSES);
 
         let sl = self.short_address_count;
         let el = self.extended_address_count;
@@ -418,31 +1299,119 @@ impl TryWrite for PendingAddress {
             (((el as u8) << 4) & EXTENDED_MASK) | ((sl as u8) & SHORT_MASK); //FIXME give variable meaningful name
         bytes.write(offset, it_s_magic)?;
 
-        for n in 0..self.short_address_count {
-            let addr = self.short_addresses[n];
+        for &addr in self.short_addresses() {
             bytes.write(offset, addr)?;
         }
 
-        for n in 0..self.extended_address_count {
-            let addr = self.extended_addresses[n];
+        for &addr in self.extended_addresses() {
             bytes.write(offset, addr)?;
         }
         Ok(*offset)
     }
 }
 
+/// (De)serialize arrays of any length, which serde only supports up to 32
+/// elements
+#[cfg(feature = "serde")]
+mod serde_array {
+    use core::fmt;
+    use core::marker::PhantomData;
+
+    use serde::de::{Error, SeqAccess, Visitor};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S, T, const N: usize>(
+        array: &[T; N],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Serialize,
+    {
+        serializer.collect_seq(array)
+    }
+
+    pub fn deserialize<'de, D, T, const N: usize>(
+        deserializer: D,
+    ) -> Result<[T; N], D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de> + Copy,
+    {
+        struct ArrayVisitor<T, const N: usize>(PhantomData<T>);
+
+        impl<'de, T, const N: usize> Visitor<'de> for ArrayVisitor<T, N>
+        where
+            T: Deserialize<'de> + Copy,
+        {
+            type Value = [T; N];
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "an array of {} elements", N)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut array = [None; N];
+                for (n, element) in array.iter_mut().enumerate() {
+                    *element = Some(
+                        seq.next_element()?
+                            .ok_or_else(|| Error::invalid_length(n, &self))?,
+                    );
+                }
+                Ok(array.map(Option::unwrap))
+            }
+        }
+
+        deserializer.deserialize_seq(ArrayVisitor(PhantomData))
+    }
+}
+
+const _: () = assert!(
+    GuaranteedTimeSlotInformation::octet_size_for(7)
+        == GuaranteedTimeSlotInformation::MAX_OCTET_SIZE
+);
+const _: () = assert!(
+    PendingAddress::octet_size_for(7, 7) == PendingAddress::MAX_OCTET_SIZE
+);
+
 /// Beacon frame
+///
+/// The beacon holds up to `GTS` GTS descriptors and `PENDING` short and
+/// extended pending addresses, see [`GuaranteedTimeSlotInformation`] and
+/// [`PendingAddress`].
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-pub struct Beacon {
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Beacon<
+    const GTS: usize = MAX_GTS_DESCRIPTORS,
+    const PENDING: usize = MAX_PENDING_ADDRESSES,
+> {
     /// Superframe specification
     pub superframe_spec: SuperframeSpecification,
     /// Guaranteed time slot information
-    pub guaranteed_time_slot_info: GuaranteedTimeSlotInformation,
+    pub guaranteed_time_slot_info: GuaranteedTimeSlotInformation<GTS>,
     /// Pending addresses
-    pub pending_address: PendingAddress,
+    pub pending_address: PendingAddress<PENDING>,
 }
 
-impl TryRead<'_> for Beacon {
+impl<const GTS: usize, const PENDING: usize> Beacon<GTS, PENDING> {
+    /// Get the size of the beacon fields in octets
+    ///
+    /// The beacon payload isn't included.
+    pub fn get_octet_size(&self) -> usize {
+        SuperframeSpecification::OCTET_SIZE
+            + self.guaranteed_time_slot_info.get_octet_size()
+            + self.pending_address.get_octet_size()
+    }
+}
+
+impl<const GTS: usize, const PENDING: usize> TryRead<'_>
+    for Beacon<GTS, PENDING>
+{
     fn try_read(bytes: &[u8], _ctx: ()) -> byte::Result<(Self, usize)> {
         let offset = &mut 0;
         Ok((
@@ -456,7 +1425,7 @@ impl TryRead<'_> for Beacon {
     }
 }
 
-impl TryWrite for Beacon {
+impl<const GTS: usize, const PENDING: usize> TryWrite for Beacon<GTS, PENDING> {
     fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
         let offset = &mut 0;
         bytes.write(offset, self.superframe_spec)?;
@@ -466,6 +1435,403 @@ impl TryWrite for Beacon {
     }
 }
 
+/// Beacon frame, with the beacon payload
+///
+/// The beacon payload (macBeaconPayload) follows the beacon fields, and makes
+/// up the payload of the frame. Use [`Frame::beacon_payload`] to get it from
+/// a received beacon. The beacon is sent from `source`, without destination.
+///
+/// # Example
+///
+/// ``` rust
+/// use ieee802154::mac::beacon::{
+///     Beacon, BeaconFrame, BeaconOrder, GuaranteedTimeSlotInformation,
+///     PendingAddress, SuperframeOrder, SuperframeSpecification,
+/// };
+/// use ieee802154::mac::{Address, FooterMode, Frame, PanId, ShortAddress};
+/// use byte::BytesExt;
+///
+/// let beacon = BeaconFrame {
+///     seq: 3,
+///     source: Address::Short(PanId(0xabcd), ShortAddress(0x0000)),
+///     beacon: Beacon {
+///         superframe_spec: SuperframeSpecification {
+///             beacon_order: BeaconOrder::OnDemand,
+///             superframe_order: SuperframeOrder::Inactive,
+///             final_cap_slot: 15,
+///             battery_life_extension: false,
+///             pan_coordinator: true,
+///             association_permit: true,
+///         },
+///         guaranteed_time_slot_info: GuaranteedTimeSlotInformation::new(),
+///         pending_address: PendingAddress::new(),
+///     },
+///     payload: &[0x12, 0x34],
+/// };
+///
+/// let mut bytes = [0u8; 32];
+/// let len = &mut 0;
+/// bytes.write_with(len, beacon.to_frame(), FooterMode::None)?;
+/// assert_eq!(
+///     bytes[..*len],
+///     [
+///         0x00, 0x80, // frame control
+///         0x03, // sequence number
+///         0xcd, 0xab, 0x00, 0x00, // source
+///         0xff, 0xcf, // superframe specification
+///         0x00, // GTS specification
+///         0x00, // pending address specification
+///         0x12, 0x34, // beacon payload
+///     ]
+/// );
+///
+/// let frame: Frame = bytes[..*len].read_with(&mut 0, FooterMode::None)?;
+/// assert_eq!(frame.beacon_payload(), Some(&[0x12, 0x34][..]));
+/// # Ok::<(), byte::Error>(())
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BeaconFrame<'a> {
+    /// Sequence number
+    pub seq: u8,
+    /// Source address
+    pub source: Address,
+    /// The beacon fields
+    pub beacon: Beacon,
+    /// Beacon payload
+    pub payload: &'a [u8],
+}
+
+impl<'a> BeaconFrame<'a> {
+    /// Assemble the beacon frame
+    ///
+    /// The footer of the returned frame is zeroed.
+    pub fn to_frame(&self) -> Frame<'a> {
+        let header = Header {
+            frame_type: FrameType::Beacon,
+            frame_pending: false,
+            ack_request: false,
+            pan_id_compress: false,
+            ie_present: false,
+            version: FrameVersion::Ieee802154_2003,
+            seq: self.seq,
+            destination: None,
+            source: Some(self.source),
+            auxiliary_security_header: None,
+        };
+        Frame {
+            header,
+            content: FrameContent::Beacon(self.beacon),
+            payload: self.payload,
+            footer: [0; 2],
+        }
+    }
+}
+
+/// Description of a PAN, assembled from a received beacon
+///
+/// # Example
+///
+/// ``` rust
+/// use ieee802154::mac::beacon::PanDescriptor;
+/// use ieee802154::mac::{
+///     Address, FooterMode, Frame, PanId, ShortAddress,
+/// };
+/// use byte::BytesExt;
+///
+/// let bytes = [
+///     0x00, 0x80, // frame control
+///     0x03, // sequence number
+///     0xcd, 0xab, 0x00, 0x00, // source
+///     0xff, 0xcf, // superframe specification
+///     0x80, // GTS specification
+///     0x00, // pending address specification
+/// ];
+/// let frame: Frame = bytes.read_with(&mut 0, FooterMode::None)?;
+///
+/// let pan = PanDescriptor::from_beacon(&frame, 11, 0xe0, 123_456).unwrap();
+/// assert_eq!(pan.pan_id(), PanId(0xabcd));
+/// assert_eq!(pan.coordinator, Address::Short(PanId(0xabcd), ShortAddress(0)));
+/// assert_eq!(pan.channel, 11);
+/// assert!(pan.gts_permit);
+/// assert!(pan.superframe_spec.association_permit);
+/// # Ok::<(), byte::Error>(())
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PanDescriptor {
+    /// Address and PAN ID of the coordinator that sent the beacon
+    pub coordinator: Address,
+    /// The channel on which the beacon was received
+    pub channel: u8,
+    /// Superframe specification of the beacon
+    pub superframe_spec: SuperframeSpecification,
+    /// The coordinator accepts GTS requests
+    pub gts_permit: bool,
+    /// Link quality indication (LQI) of the beacon
+    pub link_quality: u8,
+    /// The time at which the beacon was received
+    pub timestamp: u64,
+}
+
+impl PanDescriptor {
+    /// Assemble the PAN descriptor of a beacon, received on `channel` at
+    /// `timestamp` with a link quality of `link_quality`
+    ///
+    /// Returns `None` if `frame` isn't a beacon with a source address, e.g.
+    /// an Enhanced Beacon, which has no superframe specification.
+    pub fn from_beacon<const GTS: usize, const PENDING: usize>(
+        frame: &Frame<'_, GTS, PENDING>,
+        channel: u8,
+        link_quality: u8,
+        timestamp: u64,
+    ) -> Option<Self> {
+        let beacon = match &frame.content {
+            FrameContent::Beacon(beacon) => beacon,
+            _ => return None,
+        };
+        Some(Self {
+            coordinator: frame.header.source?,
+            channel,
+            superframe_spec: beacon.superframe_spec,
+            gts_permit: beacon.guaranteed_time_slot_info.permit,
+            link_quality,
+            timestamp,
+        })
+    }
+
+    /// PAN ID of the coordinator
+    pub fn pan_id(&self) -> PanId {
+        self.coordinator.pan_id()
+    }
+
+    /// Compare this PAN descriptor to one assembled from a later beacon of
+    /// the same coordinator, and report which parameters changed
+    ///
+    /// The link quality and timestamp differ between any two beacons, and
+    /// are not compared.
+    ///
+    /// # Example
+    ///
+    /// ``` rust
+    /// use ieee802154::mac::beacon::{
+    ///     BeaconOrder, PanDescriptor, SuperframeOrder,
+    ///     SuperframeSpecification,
+    /// };
+    /// use ieee802154::mac::{Address, PanId, ShortAddress};
+    ///
+    /// let old = PanDescriptor {
+    ///     coordinator: Address::Short(PanId(0xabcd), ShortAddress(0)),
+    ///     channel: 11,
+    ///     superframe_spec: SuperframeSpecification::new(
+    ///         BeaconOrder::BeaconOrder(6),
+    ///         SuperframeOrder::SuperframeOrder(4),
+    ///         15,
+    ///     )
+    ///     .unwrap(),
+    ///     gts_permit: false,
+    ///     link_quality: 0xe0,
+    ///     timestamp: 0,
+    /// };
+    ///
+    /// let mut new = old;
+    /// new.channel = 15;
+    /// new.superframe_spec.association_permit = true;
+    /// new.timestamp = 983_040;
+    ///
+    /// let changes = old.changes(&new);
+    /// assert!(changes.channel);
+    /// assert!(changes.association_permit);
+    /// assert!(!changes.pan_id);
+    /// assert!(changes.requires_realignment());
+    /// ```
+    pub fn changes(&self, newer: &PanDescriptor) -> BeaconChanges {
+        let coordinator = match (self.coordinator, newer.coordinator) {
+            (Address::Short(_, old), Address::Short(_, new)) => old != new,
+            (Address::Extended(_, old), Address::Extended(_, new)) => {
+                old != new
+            }
+            _ => true,
+        };
+        let (old, new) = (&self.superframe_spec, &newer.superframe_spec);
+        BeaconChanges {
+            pan_id: self.pan_id() != newer.pan_id(),
+            coordinator,
+            channel: self.channel != newer.channel,
+            beacon_order: old.beacon_order != new.beacon_order,
+            superframe_order: old.superframe_order != new.superframe_order,
+            final_cap_slot: old.final_cap_slot != new.final_cap_slot,
+            battery_life_extension: old.battery_life_extension
+                != new.battery_life_extension,
+            pan_coordinator: old.pan_coordinator != new.pan_coordinator,
+            association_permit: old.association_permit
+                != new.association_permit,
+            gts_permit: self.gts_permit != newer.gts_permit,
+        }
+    }
+}
+
+/// Parameters that changed between two beacons of the same coordinator
+///
+/// See [`PanDescriptor::changes`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BeaconChanges {
+    /// The PAN ID changed
+    pub pan_id: bool,
+    /// The coordinator address changed, or switched between short and
+    /// extended addressing
+    pub coordinator: bool,
+    /// The beacon was received on another channel
+    pub channel: bool,
+    /// The beacon order changed
+    pub beacon_order: bool,
+    /// The superframe order changed
+    pub superframe_order: bool,
+    /// The final CAP slot changed
+    pub final_cap_slot: bool,
+    /// Battery life extension was enabled or disabled
+    pub battery_life_extension: bool,
+    /// The PAN coordinator flag changed
+    pub pan_coordinator: bool,
+    /// The coordinator started or stopped accepting associations
+    pub association_permit: bool,
+    /// The coordinator started or stopped accepting GTS requests
+    pub gts_permit: bool,
+}
+
+impl BeaconChanges {
+    /// Nothing changed
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// A device that tracks the coordinator has to realign to it
+    ///
+    /// This is the case when the PAN ID, coordinator address, channel or
+    /// superframe timing changed, which are the parameters that a
+    /// coordinator realignment command announces.
+    pub fn requires_realignment(&self) -> bool {
+        self.pan_id
+            || self.coordinator
+            || self.channel
+            || self.beacon_order
+            || self.superframe_order
+            || self.final_cap_slot
+            || self.battery_life_extension
+    }
+}
+
+/// Enhanced Beacon frame
+///
+/// Enhanced Beacons are Beacon frames of version
+/// [`FrameVersion::Ieee802154`], which carry IEs instead of the beacon
+/// fields. They are used to advertise a network, e.g. by 6TiSCH and Wi-SUN.
+///
+/// The nested IEs are carried in an MLME payload IE. The beacon is broadcast
+/// to the PAN of `source`.
+///
+/// # Example
+///
+/// ``` rust
+/// use ieee802154::mac::beacon::EnhancedBeacon;
+/// use ieee802154::mac::ie::{NestedIe, TschSynchronizationIe};
+/// use ieee802154::mac::{
+///     Address, ExtendedAddress, FooterMode, FrameContent, PanId,
+/// };
+/// use byte::BytesExt;
+///
+/// let ies = [NestedIe::TschSynchronization(TschSynchronizationIe {
+///     asn: 0x0102,
+///     join_metric: 1,
+/// })];
+/// let beacon = EnhancedBeacon {
+///     seq: 7,
+///     source: Address::Extended(PanId(0xabcd), ExtendedAddress(0x1122)),
+///     ies: &ies,
+///     payload: &[],
+/// };
+///
+/// let mut buffer = [0u8; 32];
+/// let frame = beacon.to_frame(&mut buffer)?;
+/// assert_eq!(frame.content, FrameContent::EnhancedBeacon);
+/// assert!(frame.header.ie_present);
+///
+/// let mut bytes = [0u8; 127];
+/// let len = &mut 0;
+/// bytes.write_with(len, frame, FooterMode::None)?;
+/// assert_eq!(
+///     bytes[..*len],
+///     [
+///         0x40, 0xea, // frame control
+///         0x07, // sequence number
+///         0xcd, 0xab, 0xff, 0xff, // destination
+///         0x22, 0x11, 0, 0, 0, 0, 0, 0, // source
+///         0x00, 0x3f, // Header termination 1 IE
+///         0x08, 0x88, // MLME IE
+///         0x06, 0x1a, 0x02, 0x01, 0x00, 0x00, 0x00, 0x01, // TSCH sync IE
+///     ]
+/// );
+/// # Ok::<(), byte::Error>(())
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct EnhancedBeacon<'a> {
+    /// Sequence number
+    pub seq: u8,
+    /// Source address
+    pub source: Address,
+    /// Nested IEs
+    pub ies: &'a [NestedIe<'a>],
+    /// Beacon payload
+    pub payload: &'a [u8],
+}
+
+impl EnhancedBeacon<'_> {
+    /// Assemble the beacon frame
+    ///
+    /// The IEs and the beacon payload are written to `buffer`, and make up
+    /// the payload of the returned frame. Its footer is zeroed.
+    pub fn to_frame<'p>(
+        &self,
+        buffer: &'p mut [u8],
+    ) -> byte::Result<Frame<'p>> {
+        let len = &mut 0;
+        if !self.ies.is_empty() {
+            let mut writer = IeWriter::new(&mut buffer[..]);
+            for &ie in self.ies {
+                writer.nested_ie(ie)?;
+            }
+            *len = writer.finish(!self.payload.is_empty())?;
+        }
+        buffer.write(len, self.payload)?;
+
+        let header = Header {
+            frame_type: FrameType::Beacon,
+            frame_pending: false,
+            ack_request: false,
+            pan_id_compress: true,
+            ie_present: !self.ies.is_empty(),
+            version: FrameVersion::Ieee802154,
+            seq: self.seq,
+            destination: Some(Address::Short(
+                self.source.pan_id(),
+                ShortAddress::broadcast(),
+            )),
+            source: Some(self.source),
+            auxiliary_security_header: None,
+        };
+        Ok(Frame {
+            header,
+            content: FrameContent::EnhancedBeacon,
+            payload: &buffer[..*len],
+            footer: [0; 2],
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -485,6 +1851,240 @@ mod tests {
         assert_eq!(ss.association_permit, false);
     }
 
+    #[test]
+    fn validate_superframe_specification() {
+        // Superframe order 0 needs 8 CAP slots of 60 symbols
+        let data = [0x00, 0x06];
+        let result = data.read::<SuperframeSpecification>(&mut 0);
+        assert_eq!(
+            result,
+            Err(DecodeError::InvalidSuperframeSpecification(
+                SuperframeSpecificationError::CapTooShort
+            )
+            .into())
+        );
+        let data = [0x00, 0x07];
+        assert!(data.read::<SuperframeSpecification>(&mut 0).is_ok());
+
+        let data = [0x54, 0x0f];
+        let result = data.read::<SuperframeSpecification>(&mut 0);
+        assert_eq!(
+            result,
+            Err(DecodeError::InvalidSuperframeSpecification(
+                SuperframeSpecificationError::SuperframeOrderTooLarge
+            )
+            .into())
+        );
+
+        // The superframe order and final CAP slot are ignored without beacons
+        let data = [0x3f, 0x00];
+        assert!(data.read::<SuperframeSpecification>(&mut 0).is_ok());
+        // An inactive superframe has no CAP
+        let data = [0xf4, 0x00];
+        assert!(data.read::<SuperframeSpecification>(&mut 0).is_ok());
+
+        assert_eq!(
+            SuperframeSpecification::new(
+                BeaconOrder::OnDemand,
+                SuperframeOrder::Inactive,
+                16,
+            ),
+            Err(SuperframeSpecificationError::InvalidFinalCapSlot(16))
+        );
+        let spec = SuperframeSpecification::new(
+            BeaconOrder::BeaconOrder(14),
+            SuperframeOrder::SuperframeOrder(14),
+            0,
+        )
+        .unwrap();
+        assert_eq!(spec.validate(), Ok(()));
+    }
+
+    #[test]
+    fn order_durations() {
+        let order = BeaconOrder::try_from(14).unwrap();
+        assert_eq!(order.beacon_interval(), Some(960 << 14));
+        // 251.65824 s at 62.5 ksymbol/s
+        assert_eq!(
+            order.beacon_interval_time(62_500),
+            Some(Duration::from_micros(251_658_240))
+        );
+        assert_eq!(order.beacon_interval_time(0), None);
+        assert_eq!(BeaconOrder::OnDemand.beacon_interval_time(62_500), None);
+
+        // 40 kbit/s BPSK sends one symbol per bit
+        let order = SuperframeOrder::try_from(0).unwrap();
+        assert_eq!(
+            order.superframe_duration_time(40_000),
+            Some(Duration::from_millis(24))
+        );
+        assert_eq!(
+            order.slot_duration_time(40_000),
+            Some(Duration::from_micros(1_500))
+        );
+        assert_eq!(SuperframeOrder::Inactive.slot_duration_time(40_000), None);
+
+        for value in 16..=u8::MAX {
+            assert_eq!(
+                BeaconOrder::try_from(value),
+                Err(DecodeError::InvalidOrder(value))
+            );
+            assert_eq!(
+                SuperframeOrder::try_from(value),
+                Err(DecodeError::InvalidOrder(value))
+            );
+        }
+        for value in 0..=15u8 {
+            assert_eq!(u8::from(BeaconOrder::try_from(value).unwrap()), value);
+            assert_eq!(
+                u8::from(SuperframeOrder::try_from(value).unwrap()),
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn beacon_changes() {
+        let old = PanDescriptor {
+            coordinator: Address::Short(PanId(0xabcd), ShortAddress(0)),
+            channel: 11,
+            superframe_spec: SuperframeSpecification::new(
+                BeaconOrder::BeaconOrder(6),
+                SuperframeOrder::SuperframeOrder(4),
+                15,
+            )
+            .unwrap(),
+            gts_permit: true,
+            link_quality: 0xe0,
+            timestamp: 0,
+        };
+
+        let mut new = old;
+        new.link_quality = 0x40;
+        new.timestamp = 983_040;
+        assert!(old.changes(&new).is_empty());
+
+        new.gts_permit = false;
+        new.superframe_spec.association_permit = true;
+        let changes = old.changes(&new);
+        assert!(!changes.is_empty());
+        assert!(changes.gts_permit);
+        assert!(changes.association_permit);
+        assert!(!changes.requires_realignment());
+
+        let mut new = old;
+        new.coordinator = Address::Short(PanId(0x1234), ShortAddress(0));
+        let changes = old.changes(&new);
+        assert!(changes.pan_id);
+        assert!(!changes.coordinator);
+        assert!(changes.requires_realignment());
+
+        new.coordinator = Address::Extended(PanId(0xabcd), ExtendedAddress(0));
+        let changes = old.changes(&new);
+        assert!(!changes.pan_id);
+        assert!(changes.coordinator);
+
+        let mut new = old;
+        new.superframe_spec.superframe_order =
+            SuperframeOrder::SuperframeOrder(5);
+        let changes = old.changes(&new);
+        assert_eq!(
+            changes,
+            BeaconChanges {
+                superframe_order: true,
+                ..BeaconChanges::default()
+            }
+        );
+        assert!(changes.requires_realignment());
+    }
+
+    #[test]
+    fn battery_life_extension() {
+        let mut spec = SuperframeSpecification::new(
+            BeaconOrder::BeaconOrder(0),
+            SuperframeOrder::SuperframeOrder(0),
+            7,
+        )
+        .unwrap();
+        assert_eq!(spec.initial_backoff_exponent(3), 3);
+        assert_eq!(spec.battery_life_extension_window(40, 40, 6), None);
+
+        spec.battery_life_extension = true;
+        assert_eq!(spec.initial_backoff_exponent(3), 2);
+        assert_eq!(spec.initial_backoff_exponent(1), 1);
+        // Starts on a backoff period boundary
+        assert_eq!(
+            spec.battery_life_extension_window(40, 40, 6),
+            Some(80..200)
+        );
+        assert_eq!(
+            spec.battery_life_extension_window(41, 40, 6),
+            Some(100..220)
+        );
+        // Ends with the CAP of 8 slots of 60 symbols
+        assert_eq!(
+            spec.battery_life_extension_window(
+                40,
+                40,
+                MAX_BATT_LIFE_EXT_PERIODS
+            ),
+            Some(80..480)
+        );
+
+        spec.superframe_order = SuperframeOrder::Inactive;
+        assert_eq!(spec.battery_life_extension_window(40, 40, 6), None);
+    }
+
+    #[test]
+    fn pan_descriptor() {
+        use crate::mac::FooterMode;
+
+        let beacon = BeaconFrame {
+            seq: 1,
+            source: Address::Extended(
+                PanId(0x1234),
+                ExtendedAddress(0x0123_4567_89ab_cdef),
+            ),
+            beacon: Beacon {
+                superframe_spec: SuperframeSpecification::new(
+                    BeaconOrder::BeaconOrder(6),
+                    SuperframeOrder::SuperframeOrder(4),
+                    15,
+                )
+                .unwrap(),
+                guaranteed_time_slot_info: GuaranteedTimeSlotInformation::new(),
+                pending_address: PendingAddress::new(),
+            },
+            payload: &[],
+        };
+        let mut bytes = [0u8; 32];
+        let len = &mut 0;
+        bytes
+            .write_with(len, beacon.to_frame(), FooterMode::None)
+            .unwrap();
+        let frame: Frame =
+            bytes[..*len].read_with(&mut 0, FooterMode::None).unwrap();
+
+        let pan = PanDescriptor::from_beacon(&frame, 26, 0x40, 1_000).unwrap();
+        assert_eq!(pan.coordinator, beacon.source);
+        assert_eq!(pan.pan_id(), PanId(0x1234));
+        assert_eq!(pan.channel, 26);
+        assert_eq!(pan.superframe_spec, beacon.beacon.superframe_spec);
+        assert!(!pan.gts_permit);
+        assert_eq!(pan.link_quality, 0x40);
+        assert_eq!(pan.timestamp, 1_000);
+
+        let mut data = frame;
+        data.content = FrameContent::Data;
+        assert_eq!(PanDescriptor::from_beacon(&data, 26, 0x40, 1_000), None);
+        let mut anonymous = frame;
+        anonymous.header.source = None;
+        assert_eq!(
+            PanDescriptor::from_beacon(&anonymous, 26, 0x40, 1_000),
+            None
+        );
+    }
+
     #[test]
     fn decode_gts_information() {
         let data = [0x00];
@@ -554,11 +2154,11 @@ mod tests {
         assert_eq!(slots[0].short_address, ShortAddress(0x1234));
         assert_eq!(slots[0].starting_slot, 1);
         assert_eq!(slots[0].length, 1);
-        assert_eq!(slots[0].direction, Direction::Transmit);
+        assert_eq!(slots[0].direction, Direction::Receive);
         assert_eq!(slots[1].short_address, ShortAddress(0x5678));
         assert_eq!(slots[1].starting_slot, 4);
         assert_eq!(slots[1].length, 1);
-        assert_eq!(slots[1].direction, Direction::Receive);
+        assert_eq!(slots[1].direction, Direction::Transmit);
 
         assert_eq!(beacon.pending_address.short_addresses().len(), 0);
         assert_eq!(beacon.pending_address.extended_addresses().len(), 0);
@@ -591,11 +2191,11 @@ mod tests {
         assert_eq!(slots[0].short_address, ShortAddress(0x1234));
         assert_eq!(slots[0].starting_slot, 1);
         assert_eq!(slots[0].length, 1);
-        assert_eq!(slots[0].direction, Direction::Receive);
+        assert_eq!(slots[0].direction, Direction::Transmit);
         assert_eq!(slots[1].short_address, ShortAddress(0x5678));
         assert_eq!(slots[1].starting_slot, 4);
         assert_eq!(slots[1].length, 1);
-        assert_eq!(slots[1].direction, Direction::Transmit);
+        assert_eq!(slots[1].direction, Direction::Receive);
 
         assert_eq!(beacon.pending_address.short_addresses().len(), 2);
         assert_eq!(
@@ -629,13 +2229,14 @@ mod tests {
             short_address: ShortAddress(0x1234),
             starting_slot: 1,
             length: 1,
-            direction: Direction::Transmit,
+            direction: Direction::Receive,
         };
 
         let guaranteed_time_slot_info = GuaranteedTimeSlotInformation {
             permit: true,
             slot_count: 1,
             slots,
+            unused_direction_bits: 0,
         };
 
         let mut short_addresses = [ShortAddress::broadcast(); 7];
@@ -660,6 +2261,13 @@ mod tests {
         let mut len = 0usize;
         buffer.write(&mut len, beacon).unwrap();
         assert_eq!(len, 18);
+        assert_eq!(
+            len,
+            SuperframeSpecification::OCTET_SIZE
+                + GuaranteedTimeSlotInformation::octet_size_for(1)
+                + PendingAddress::octet_size_for(1, 1)
+        );
+        assert_eq!(len, beacon.get_octet_size());
         assert_eq!(
             buffer[..len],
             [
@@ -668,4 +2276,306 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn maintain_gts_slots() {
+        let mut gts = GuaranteedTimeSlotInformation::new();
+        for n in 0..7 {
+            assert!(gts.add_slot(
+                ShortAddress(n),
+                n as u8,
+                1,
+                Direction::Receive
+            ));
+        }
+        assert!(!gts.add_slot(ShortAddress(7), 7, 1, Direction::Receive));
+        assert_eq!(
+            gts.get_octet_size(),
+            GuaranteedTimeSlotInformation::MAX_OCTET_SIZE
+        );
+
+        assert_eq!(gts.remove_slot(7), None);
+        assert_eq!(
+            gts.remove_slot(3).unwrap().short_address(),
+            ShortAddress(3)
+        );
+        assert_eq!(gts.remove_slot(6), None);
+        let addresses: [u16; 6] = [0, 1, 2, 4, 5, 6];
+        for (slot, &address) in gts.slots().iter().zip(addresses.iter()) {
+            assert_eq!(slot.short_address(), ShortAddress(address));
+            assert_eq!(slot.starting_slot(), address as u8);
+            assert_eq!(slot.length(), 1);
+        }
+
+        // The slots survive a round trip
+        assert!(gts.add_slot(ShortAddress(9), 9, 3, Direction::Transmit));
+        let mut buffer = [0u8; 32];
+        let mut len = 0usize;
+        buffer.write(&mut len, gts).unwrap();
+        let decoded: GuaranteedTimeSlotInformation =
+            buffer[..len].read(&mut 0).unwrap();
+        assert_eq!(decoded.slots(), gts.slots());
+        assert_eq!(decoded.slots()[6].direction(), Direction::Transmit);
+    }
+
+    #[test]
+    fn gts_direction_mask_round_trip() {
+        // Two receive-only GTSs and a transmit-only GTS, with the reserved
+        // bit and an unused bit of the directions mask set
+        let data = [
+            0x83, 0xc5, 0x34, 0x12, 0x11, 0x78, 0x56, 0x24, 0xbc, 0x9a, 0x37,
+        ];
+        let gts: GuaranteedTimeSlotInformation = data.read(&mut 0).unwrap();
+        let slots = gts.slots();
+        assert_eq!(slots.len(), 3);
+        assert_eq!(slots[0].direction(), Direction::Receive);
+        assert_eq!(slots[1].direction(), Direction::Transmit);
+        assert_eq!(slots[2].direction(), Direction::Receive);
+        assert_eq!(gts.direction_mask(), 0xc5);
+
+        let mut buffer = [0u8; 16];
+        let mut len = 0usize;
+        buffer.write(&mut len, gts).unwrap();
+        assert_eq!(buffer[..len], data);
+
+        // The mask follows the descriptors as they change
+        let mut gts = gts;
+        gts.remove_slot(0).unwrap();
+        assert_eq!(gts.direction_mask(), 0xc2);
+        assert!(gts.add_slot(ShortAddress(1), 1, 1, Direction::Transmit));
+        assert_eq!(gts.direction_mask(), 0xc2);
+    }
+
+    #[test]
+    fn bounded_storage() {
+        let mut gts = GuaranteedTimeSlotInformation::<2>::empty();
+        assert!(gts.add_slot(ShortAddress(1), 13, 1, Direction::Transmit));
+        assert!(gts.add_slot(ShortAddress(2), 14, 1, Direction::Receive));
+        assert!(!gts.add_slot(ShortAddress(3), 15, 1, Direction::Receive));
+
+        let mut bytes = [0u8; 8];
+        let len = &mut 0;
+        bytes.write(len, gts).unwrap();
+        assert_eq!(*len, gts.get_octet_size());
+        assert_eq!(
+            bytes[..*len].read::<GuaranteedTimeSlotInformation<2>>(&mut 0),
+            Ok(gts)
+        );
+        assert_eq!(
+            bytes[..*len].read::<GuaranteedTimeSlotInformation<1>>(&mut 0),
+            Err(DecodeError::InsufficientStorage.into())
+        );
+
+        let pan_id = PanId(0x1234);
+        let mut pending = PendingAddress::<1>::empty();
+        assert!(pending.push(Address::Short(pan_id, ShortAddress(1))));
+        assert!(!pending.push(Address::Short(pan_id, ShortAddress(2))));
+        assert!(pending.push(Address::Extended(pan_id, ExtendedAddress(1))));
+        assert_eq!(pending.addresses(pan_id).count(), 2);
+
+        let beacon = Beacon {
+            superframe_spec: SuperframeSpecification::new(
+                BeaconOrder::OnDemand,
+                SuperframeOrder::Inactive,
+                15,
+            )
+            .unwrap(),
+            guaranteed_time_slot_info:
+                GuaranteedTimeSlotInformation::<0>::empty(),
+            pending_address: pending,
+        };
+        let mut bytes = [0u8; 16];
+        let len = &mut 0;
+        bytes.write(len, beacon).unwrap();
+        assert_eq!(bytes[..*len].read::<Beacon<0, 1>>(&mut 0), Ok(beacon));
+        // The default storage decodes any beacon
+        let decoded: Beacon = bytes[..*len].read(&mut 0).unwrap();
+        assert_eq!(
+            decoded.pending_address.short_addresses(),
+            [ShortAddress(1)]
+        );
+        assert_eq!(
+            bytes[..*len].read::<Beacon<0, 0>>(&mut 0),
+            Err(DecodeError::InsufficientStorage.into())
+        );
+    }
+
+    #[test]
+    fn pending_address_list() {
+        let pan_id = PanId(0x1234);
+        let short = (0..7).map(|n| Address::Short(pan_id, ShortAddress(n)));
+        let extended = (0..7)
+            .map(|n| Address::Extended(pan_id, ExtendedAddress(n as u64)));
+        let mut pending =
+            PendingAddress::from_addresses(extended.chain(short)).unwrap();
+        assert_eq!(pending.get_octet_size(), PendingAddress::MAX_OCTET_SIZE);
+        assert!(!pending.push(Address::Short(pan_id, ShortAddress(7))));
+        assert!(!pending.push(Address::Extended(pan_id, ExtendedAddress(7))));
+        assert!(!pending.contains(Address::Short(pan_id, ShortAddress(7))));
+        assert!(pending
+            .contains(Address::Extended(PanId(0x4321), ExtendedAddress(6))));
+
+        let mut buffer = [0u8; PendingAddress::MAX_OCTET_SIZE];
+        let mut len = 0usize;
+        buffer.write(&mut len, pending).unwrap();
+        let decoded: PendingAddress = buffer[..len].read(&mut 0).unwrap();
+        assert!(decoded.addresses(pan_id).eq(pending.addresses(pan_id)));
+        assert_eq!(decoded.addresses(pan_id).count(), 14);
+        assert_eq!(
+            decoded.addresses(pan_id).nth(7),
+            Some(Address::Extended(pan_id, ExtendedAddress(0)))
+        );
+    }
+
+    #[test]
+    fn superframe_timing() {
+        assert_eq!(BeaconOrder::BeaconOrder(0).beacon_interval(), Some(960));
+        assert_eq!(
+            BeaconOrder::BeaconOrder(14).beacon_interval(),
+            Some(15_728_640)
+        );
+        assert_eq!(BeaconOrder::OnDemand.beacon_interval(), None);
+        assert_eq!(
+            SuperframeOrder::SuperframeOrder(0).slot_duration(),
+            Some(60)
+        );
+        assert_eq!(SuperframeOrder::Inactive.superframe_duration(), None);
+
+        let mut spec = SuperframeSpecification {
+            beacon_order: BeaconOrder::BeaconOrder(3),
+            superframe_order: SuperframeOrder::SuperframeOrder(3),
+            final_cap_slot: 15,
+            battery_life_extension: false,
+            pan_coordinator: true,
+            association_permit: false,
+        };
+        // The 868 MHz BPSK PHY sends 20 ksymbol/s
+        let timing = spec.timing(20_000).unwrap();
+        assert_eq!(timing.beacon_interval, 7680);
+        assert_eq!(timing.superframe_duration, 7680);
+        assert_eq!(timing.cap_length, timing.superframe_duration);
+        assert_eq!(timing.beacon_interval_us(), 384_000);
+        assert_eq!(timing.slot_duration_us(), 24_000);
+        assert_eq!(spec.timing(0), None);
+
+        // The superframe can't be longer than the beacon interval
+        spec.superframe_order = SuperframeOrder::SuperframeOrder(4);
+        assert_eq!(spec.timing(20_000), None);
+
+        // Not beacon-enabled
+        spec.beacon_order = BeaconOrder::OnDemand;
+        assert_eq!(spec.timing(20_000), None);
+    }
+
+    #[test]
+    fn enhanced_beacon_payload() {
+        use crate::mac::ie::TschSynchronizationIe;
+        use crate::mac::{FooterMode, PanId};
+
+        let ies = [NestedIe::TschSynchronization(TschSynchronizationIe {
+            asn: 0x0102,
+            join_metric: 1,
+        })];
+        let beacon = EnhancedBeacon {
+            seq: 7,
+            source: Address::Extended(PanId(0xabcd), ExtendedAddress(0x1122)),
+            ies: &ies,
+            payload: &[0xaa, 0xbb],
+        };
+        let mut buffer = [0u8; 32];
+        let frame = beacon.to_frame(&mut buffer).unwrap();
+        assert_ne!(frame.payload, [0xaa, 0xbb]);
+        assert_eq!(frame.beacon_payload(), Some(&[0xaa, 0xbb][..]));
+
+        let mut bytes = [0u8; 64];
+        let mut len = 0usize;
+        bytes.write_with(&mut len, frame, FooterMode::None).unwrap();
+        let decoded: Frame =
+            bytes[..len].read_with(&mut 0, FooterMode::None).unwrap();
+        assert_eq!(decoded.beacon_payload(), Some(&[0xaa, 0xbb][..]));
+
+        // Not a beacon
+        let mut data = decoded;
+        data.content = FrameContent::Data;
+        assert_eq!(data.beacon_payload(), None);
+    }
+
+    #[test]
+    fn dsme_beacon() {
+        use crate::mac::ie::{
+            BeaconBitmap, DsmePanDescriptorIe, DsmeSuperframeSpecification,
+            HeaderIe, TimeSynchronizationSpecification,
+        };
+        use crate::mac::{FooterMode, PanId};
+
+        let ie = DsmePanDescriptorIe {
+            superframe_spec: SuperframeSpecification::new(
+                BeaconOrder::BeaconOrder(8),
+                SuperframeOrder::SuperframeOrder(4),
+                15,
+            )
+            .unwrap(),
+            pending_address: PendingAddress::new(),
+            dsme_superframe_spec: DsmeSuperframeSpecification {
+                multi_superframe_order: 6,
+                channel_hopping: false,
+                cap_reduction: true,
+                deferred_beacon: false,
+            },
+            time_synchronization: TimeSynchronizationSpecification::default(),
+            beacon_bitmap: BeaconBitmap {
+                sd_index: 0,
+                sd_bitmap: &[0x01],
+            },
+            channel_hopping: None,
+            group_ack: None,
+        };
+        let mut ies = [0u8; 32];
+        let mut writer = IeWriter::new(&mut ies);
+        writer.header_ie(HeaderIe::DsmePanDescriptor(ie)).unwrap();
+        let len = writer.finish(false).unwrap();
+        let mut frame = EnhancedBeacon {
+            seq: 1,
+            source: Address::Short(PanId(0x1234), ShortAddress(0x0000)),
+            ies: &[],
+            payload: &[],
+        }
+        .to_frame(&mut [])
+        .unwrap();
+        frame.header.ie_present = true;
+        frame.payload = &ies[..len];
+
+        let mut bytes = [0u8; 64];
+        let mut len = 0usize;
+        bytes.write_with(&mut len, frame, FooterMode::None).unwrap();
+        let decoded: Frame =
+            bytes[..len].read_with(&mut 0, FooterMode::None).unwrap();
+        let descriptor = decoded.dsme_pan_descriptor().unwrap();
+        assert_eq!(descriptor, ie);
+
+        let dsme_spec = descriptor.dsme_superframe_spec;
+        assert!(dsme_spec.cap_reduction);
+        assert!(!dsme_spec.channel_hopping);
+        assert_eq!(dsme_spec.multi_superframe_duration(), Some(61_440));
+        assert_eq!(
+            dsme_spec.superframes_per_multi_superframe(
+                descriptor.superframe_spec.superframe_order
+            ),
+            Some(4)
+        );
+        assert_eq!(
+            dsme_spec.superframes_per_multi_superframe(
+                SuperframeOrder::SuperframeOrder(7)
+            ),
+            None
+        );
+
+        // Not an Enhanced Beacon with IEs
+        let mut data = decoded;
+        data.content = FrameContent::Data;
+        assert_eq!(data.dsme_pan_descriptor(), None);
+        let mut without_ies = decoded;
+        without_ies.header.ie_present = false;
+        assert_eq!(without_ies.dsme_pan_descriptor(), None);
+    }
 }
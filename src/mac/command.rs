@@ -1,13 +1,25 @@
 //! MAC commands
 //!
 //! Work in progress
-
-use crate::mac::frame::{
-    header::{PanId, ShortAddress},
-    DecodeError,
+//!
+//! Only the fixed fields of commands are decoded into [`Command`]. The
+//! variable-length fields of the commands added in IEEE 802.15.4-2015, e.g.
+//! the DSME and SRM commands, remain in the payload of the frame, so they are
+//! encoded again unchanged. The same goes for commands with an identifier
+//! this crate doesn't know, e.g. proprietary commands, which are decoded into
+//! [`Command::Unknown`].
+//!
+//! The fields of the DSME GTS commands can be read from the payload with
+//! [`DsmeGtsRequest`] and [`DsmeGtsAllocation`].
+
+use crate::mac::beacon::Direction;
+use crate::mac::frame::header::{FrameVersion, PanId, ShortAddress};
+use crate::mac::frame::security::SecurityParameters;
+use crate::mac::{
+    Address, ExtendedAddress, Frame, FrameContent, FrameType, Header,
 };
 use crate::utils::OptionalFrom;
-use byte::{check_len, BytesExt, TryRead, TryWrite};
+use byte::{check_len, BytesExt, TryRead, TryWrite, LE};
 
 extended_enum!(
     /// MAC command identifiers
@@ -30,19 +42,81 @@ extended_enum!(
     CoordinatorRealignment => 8,
     /// Guaranteed time slot request, request a guaranteed time slot
     GuaranteedTimeSlotRequest => 9,
+    /// TRLE management request, used in LECIM networks
+    TrleManagementRequest => 0x0a,
+    /// TRLE management response, used in LECIM networks
+    TrleManagementResponse => 0x0b,
+    /// DSME association request, request association to a DSME PAN
+    DsmeAssociationRequest => 0x13,
+    /// DSME association response
+    DsmeAssociationResponse => 0x14,
+    /// DSME GTS request, request allocation or deallocation of DSME GTSs
+    DsmeGtsRequest => 0x15,
+    /// DSME GTS reply
+    DsmeGtsReply => 0x16,
+    /// DSME GTS notify, announce a change of DSME GTSs to the neighbors
+    DsmeGtsNotify => 0x17,
+    /// DSME information request
+    DsmeInformationRequest => 0x18,
+    /// DSME information reply
+    DsmeInformationReply => 0x19,
+    /// DSME beacon allocation notification
+    DsmeBeaconAllocationNotification => 0x1a,
+    /// DSME beacon collision notification
+    DsmeBeaconCollisionNotification => 0x1b,
+    /// DSME link report
+    DsmeLinkReport => 0x1c,
+    /// RIT data request, sent by a device that wakes up in RIT mode
+    RitDataRequest => 0x20,
+    /// DSME beacon scheduling (DBS) request
+    DbsRequest => 0x21,
+    /// DSME beacon scheduling (DBS) response
+    DbsResponse => 0x22,
+    /// RIT data response
+    RitDataResponse => 0x23,
+    /// Vendor specific command
+    VendorSpecific => 0x24,
+    /// Spectrum resource measurement (SRM) request
+    SrmRequest => 0x25,
+    /// Spectrum resource measurement (SRM) response
+    SrmResponse => 0x26,
+    /// Spectrum resource measurement (SRM) report
+    SrmReport => 0x27,
+    /// Spectrum resource measurement (SRM) information
+    SrmInformation => 0x28,
 );
 
+const CAP_ALTERNATE_PAN_COORDINATOR: u8 = 0x01;
 const CAP_FFD: u8 = 0x02;
 const CAP_MAINS_POWER: u8 = 0x04;
 const CAP_IDLE_RECEIVE: u8 = 0x08;
+const CAP_FAST_ASSOCIATION: u8 = 0x10;
+const CAP_RESERVED: u8 = 0x20;
 const CAP_FRAME_PROTECTION: u8 = 0x40;
 const CAP_ALLOCATE_ADDRESS: u8 = 0x80;
 
 /// Association request capability information
 ///
 /// Sent with association request to report the capabilities of the device.
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+/// The reserved bits are kept, so they are encoded again unchanged.
+///
+/// # Example
+///
+/// ``` rust
+/// use ieee802154::mac::command::CapabilityInformation;
+///
+/// let capability = CapabilityInformation::from_bits(0xae);
+/// assert!(capability.full_function_device);
+/// assert!(!capability.fast_association);
+/// assert_eq!(capability.reserved, 0x20);
+/// assert_eq!(capability.bits(), 0xae);
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CapabilityInformation {
+    /// Device is capable of becoming the PAN coordinator
+    pub alternate_pan_coordinator: bool,
     /// Full-function device (FFD) or a reduced-function device (RFD)
     /// RFD and FFD have different function sets.
     pub full_function_device: bool,
@@ -50,81 +124,220 @@ pub struct CapabilityInformation {
     pub mains_power: bool,
     /// Receive is enabled while idle
     pub idle_receive: bool,
+    /// Device requests fast association
+    pub fast_association: bool,
     /// Frames are cryptographically protected
     pub frame_protection: bool,
     /// Device wish to have an short address allocated by the coordinator
     pub allocate_address: bool,
+    /// The reserved bits, at their position in the capability information
+    /// byte
+    pub reserved: u8,
 }
 
-impl From<u8> for CapabilityInformation {
-    fn from(byte: u8) -> Self {
-        let full_function_device = byte & CAP_FFD == CAP_FFD;
-        let mains_power = byte & CAP_MAINS_POWER == CAP_MAINS_POWER;
-        let idle_receive = byte & CAP_IDLE_RECEIVE == CAP_IDLE_RECEIVE;
-        let frame_protection =
-            byte & CAP_FRAME_PROTECTION == CAP_FRAME_PROTECTION;
-        let allocate_address =
-            byte & CAP_ALLOCATE_ADDRESS == CAP_ALLOCATE_ADDRESS;
+impl CapabilityInformation {
+    /// Creates an instance of [`CapabilityInformation`] from the provided
+    /// bits
+    pub const fn from_bits(bits: u8) -> Self {
         Self {
-            full_function_device,
-            mains_power,
-            idle_receive,
-            frame_protection,
-            allocate_address,
+            alternate_pan_coordinator: bits & CAP_ALTERNATE_PAN_COORDINATOR
+                != 0,
+            full_function_device: bits & CAP_FFD != 0,
+            mains_power: bits & CAP_MAINS_POWER != 0,
+            idle_receive: bits & CAP_IDLE_RECEIVE != 0,
+            fast_association: bits & CAP_FAST_ASSOCIATION != 0,
+            frame_protection: bits & CAP_FRAME_PROTECTION != 0,
+            allocate_address: bits & CAP_ALLOCATE_ADDRESS != 0,
+            reserved: bits & CAP_RESERVED,
         }
     }
-}
 
-impl From<CapabilityInformation> for u8 {
-    fn from(ar: CapabilityInformation) -> Self {
-        let mut byte = 0u8;
-        if ar.full_function_device {
-            byte = byte | CAP_FFD;
+    /// Get the bits of the capability information byte
+    pub const fn bits(&self) -> u8 {
+        let mut bits = self.reserved & CAP_RESERVED;
+        if self.alternate_pan_coordinator {
+            bits |= CAP_ALTERNATE_PAN_COORDINATOR;
         }
-        if ar.mains_power {
-            byte = byte | CAP_MAINS_POWER;
+        if self.full_function_device {
+            bits |= CAP_FFD;
         }
-        if ar.idle_receive {
-            byte = byte | CAP_IDLE_RECEIVE;
+        if self.mains_power {
+            bits |= CAP_MAINS_POWER;
         }
-        if ar.frame_protection {
-            byte = byte | CAP_FRAME_PROTECTION;
+        if self.idle_receive {
+            bits |= CAP_IDLE_RECEIVE;
         }
-        if ar.allocate_address {
-            byte = byte | CAP_ALLOCATE_ADDRESS;
+        if self.fast_association {
+            bits |= CAP_FAST_ASSOCIATION;
         }
-        byte
+        if self.frame_protection {
+            bits |= CAP_FRAME_PROTECTION;
+        }
+        if self.allocate_address {
+            bits |= CAP_ALLOCATE_ADDRESS;
+        }
+        bits
     }
 }
 
-extended_enum!(
-    /// Association Status
-    AssociationStatus, u8,
+impl From<u8> for CapabilityInformation {
+    fn from(byte: u8) -> Self {
+        Self::from_bits(byte)
+    }
+}
+
+impl From<CapabilityInformation> for u8 {
+    fn from(ar: CapabilityInformation) -> Self {
+        ar.bits()
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for CapabilityInformation {
+    fn arbitrary(
+        u: &mut arbitrary::Unstructured<'a>,
+    ) -> arbitrary::Result<Self> {
+        Ok(Self::from_bits(u.arbitrary()?))
+    }
+}
+
+/// Association Status
+///
+/// Status codes that are reserved in IEEE 802.15.4-2015 are kept as
+/// [`AssociationStatus::Unknown`], so they are encoded again unchanged.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AssociationStatus {
     /// Successful
-    Successful => 0x00,
+    Successful,
     /// Network (PAN) at capacity
-    NetworkAtCapacity => 0x01,
+    NetworkAtCapacity,
     /// Access to PAN denied
-    AccessDenied => 0x02,
+    AccessDenied,
     /// Duplicate hopping sequence offset
-    HoppingSequenceOffsetDuplication => 0x03,
+    HoppingSequenceOffsetDuplication,
     /// Fast association was successful
-    FastAssociationSuccesful => 0x80,
-);
+    FastAssociationSuccesful,
+    /// A reserved status code
+    ///
+    /// Only status codes that have no other variant are decoded into this
+    /// variant. Use [`AssociationStatus::from`] to construct it, as e.g.
+    /// `Unknown(0x00)` is encoded like [`AssociationStatus::Successful`], but
+    /// doesn't compare equal to it.
+    Unknown(u8),
+}
 
-extended_enum!(
-    /// Disassociation Reason
-    DisassociationReason, u8,
+impl From<u8> for AssociationStatus {
+    fn from(byte: u8) -> Self {
+        match byte {
+            0x00 => AssociationStatus::Successful,
+            0x01 => AssociationStatus::NetworkAtCapacity,
+            0x02 => AssociationStatus::AccessDenied,
+            0x03 => AssociationStatus::HoppingSequenceOffsetDuplication,
+            0x80 => AssociationStatus::FastAssociationSuccesful,
+            status => AssociationStatus::Unknown(status),
+        }
+    }
+}
+
+impl From<AssociationStatus> for u8 {
+    fn from(status: AssociationStatus) -> Self {
+        match status {
+            AssociationStatus::Successful => 0x00,
+            AssociationStatus::NetworkAtCapacity => 0x01,
+            AssociationStatus::AccessDenied => 0x02,
+            AssociationStatus::HoppingSequenceOffsetDuplication => 0x03,
+            AssociationStatus::FastAssociationSuccesful => 0x80,
+            AssociationStatus::Unknown(status) => status,
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for AssociationStatus {
+    fn arbitrary(
+        u: &mut arbitrary::Unstructured<'a>,
+    ) -> arbitrary::Result<Self> {
+        // Only generates reserved status codes as `Unknown`
+        Ok(Self::from(u.arbitrary::<u8>()?))
+    }
+}
+
+impl AssociationStatus {
+    /// Whether the association was successful
+    pub fn is_successful(&self) -> bool {
+        matches!(
+            self,
+            AssociationStatus::Successful
+                | AssociationStatus::FastAssociationSuccesful
+        )
+    }
+}
+
+/// Disassociation Reason
+///
+/// Reason codes that are reserved in IEEE 802.15.4-2015 are kept as
+/// [`DisassociationReason::Unknown`], so they are encoded again unchanged.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DisassociationReason {
     /// Coordinator requested device to leave
-    CoordinatorLeave => 1,
+    CoordinatorLeave,
     /// Device requested to leave
-    DeviceLeave => 2,
-);
+    DeviceLeave,
+    /// A reserved reason code
+    ///
+    /// Only reason codes that have no other variant are decoded into this
+    /// variant. Use [`DisassociationReason::from`] to construct it, as e.g.
+    /// `Unknown(1)` is encoded like [`DisassociationReason::CoordinatorLeave`],
+    /// but doesn't compare equal to it.
+    Unknown(u8),
+}
+
+impl From<u8> for DisassociationReason {
+    fn from(byte: u8) -> Self {
+        match byte {
+            1 => DisassociationReason::CoordinatorLeave,
+            2 => DisassociationReason::DeviceLeave,
+            reason => DisassociationReason::Unknown(reason),
+        }
+    }
+}
+
+impl From<DisassociationReason> for u8 {
+    fn from(reason: DisassociationReason) -> Self {
+        match reason {
+            DisassociationReason::CoordinatorLeave => 1,
+            DisassociationReason::DeviceLeave => 2,
+            DisassociationReason::Unknown(reason) => reason,
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for DisassociationReason {
+    fn arbitrary(
+        u: &mut arbitrary::Unstructured<'a>,
+    ) -> arbitrary::Result<Self> {
+        // Only generates reserved reason codes as `Unknown`
+        Ok(Self::from(u.arbitrary::<u8>()?))
+    }
+}
 
 /// Coordinator re-alignment data
 ///
 /// Changes to the PAN sent by the coordinator.
+///
+/// The channel page is never present in frames of version
+/// [`FrameVersion::Ieee802154_2003`]. In later frame versions, it is present
+/// if the command is followed by more bytes. Use
+/// [`Command::try_read_for_version`] to decode it accordingly.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct CoordinatorRealignmentData {
     /// PAN id that the coordinator will use
     pub pan_id: PanId,
@@ -138,29 +351,25 @@ pub struct CoordinatorRealignmentData {
     pub channel_page: Option<u8>,
 }
 
-impl TryWrite for CoordinatorRealignmentData {
-    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
-        let offset = &mut 0;
-        bytes.write(offset, self.pan_id)?;
-        bytes.write(offset, self.coordinator_address)?;
-        bytes.write(offset, self.channel)?;
-        bytes.write(offset, self.device_address)?;
-        if let Some(channel_page) = self.channel_page {
-            bytes.write(offset, channel_page)?;
-        }
-        Ok(*offset)
+impl CoordinatorRealignmentData {
+    /// Get the size of this data in octets
+    pub fn get_octet_size(&self) -> usize {
+        7 + self.channel_page.is_some() as usize
     }
-}
 
-impl TryRead<'_> for CoordinatorRealignmentData {
-    fn try_read(bytes: &[u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+    fn try_read_for_version(
+        bytes: &[u8],
+        version: FrameVersion,
+    ) -> byte::Result<(Self, usize)> {
         let offset = &mut 0;
         check_len(&bytes, 7)?;
         let pan_id = bytes.read(offset)?;
         let coordinator_address = bytes.read(offset)?;
         let channel = bytes.read(offset)?;
         let device_address = bytes.read(offset)?;
-        let channel_page = if bytes.len() > *offset {
+        let channel_page = if version != FrameVersion::Ieee802154_2003
+            && bytes.len() > *offset
+        {
             Some(bytes.read(offset)?)
         } else {
             None
@@ -178,49 +387,514 @@ impl TryRead<'_> for CoordinatorRealignmentData {
     }
 }
 
+impl TryWrite for CoordinatorRealignmentData {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write(offset, self.pan_id)?;
+        bytes.write(offset, self.coordinator_address)?;
+        bytes.write(offset, self.channel)?;
+        bytes.write(offset, self.device_address)?;
+        if let Some(channel_page) = self.channel_page {
+            bytes.write(offset, channel_page)?;
+        }
+        Ok(*offset)
+    }
+}
+
+impl TryRead<'_> for CoordinatorRealignmentData {
+    fn try_read(bytes: &[u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        Self::try_read_for_version(bytes, FrameVersion::Ieee802154)
+    }
+}
+
+const GTSC_LENGTH_MASK: u8 = 0x0f;
 const GTSC_RECEIVE_ONLY: u8 = 0x10;
 const GTSC_ALLOCATION: u8 = 0x20;
 
+/// The longest GTS that can be requested, in superframe slots
+pub const MAX_GTS_LENGTH: u8 = 15;
+
+/// Whether a GTS request allocates or deallocates a GTS
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum GtsRequestType {
+    /// Allocate a new GTS
+    Allocate,
+    /// Deallocate an existing GTS
+    Deallocate,
+}
+
 /// Guaranteed time slot characteristics
 ///
 /// GTS configuration requested with the guaranteed time slot request command.
+///
+/// # Example
+///
+/// ``` rust
+/// use ieee802154::mac::beacon::Direction;
+/// use ieee802154::mac::command::{GtsCharacteristics, GtsRequestType};
+///
+/// let characteristics = GtsCharacteristics::builder(3)
+///     .direction(Direction::Receive)
+///     .build()
+///     .unwrap();
+/// assert_eq!(characteristics.request_type, GtsRequestType::Allocate);
+/// assert_eq!(u8::from(characteristics), 0x33);
+/// assert_eq!(GtsCharacteristics::from(0x33), characteristics);
+///
+/// // A GTS spans at least one slot
+/// assert!(GtsCharacteristics::builder(0).build().is_none());
+/// ```
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-pub struct GuaranteedTimeSlotCharacteristics {
-    /// Number of slots requested
-    pub count: u8,
-    /// Receive only slots, otherwise transmit
-    pub receive_only: bool,
-    /// Request type, Allocate or de-allocate
-    pub allocation: bool,
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GtsCharacteristics {
+    /// Number of superframe slots requested, up to [`MAX_GTS_LENGTH`]
+    pub length: u8,
+    /// Direction of the requested slots
+    pub direction: Direction,
+    /// Whether the slots are allocated or deallocated
+    pub request_type: GtsRequestType,
+}
+
+impl GtsCharacteristics {
+    /// Start building GTS characteristics for a GTS of `length` slots, see
+    /// [`GtsCharacteristicsBuilder`]
+    pub const fn builder(length: u8) -> GtsCharacteristicsBuilder {
+        GtsCharacteristicsBuilder::new(length)
+    }
 }
 
-impl From<u8> for GuaranteedTimeSlotCharacteristics {
+impl From<u8> for GtsCharacteristics {
     fn from(byte: u8) -> Self {
-        let receive_only = byte & GTSC_RECEIVE_ONLY == GTSC_RECEIVE_ONLY;
-        let allocation = byte & GTSC_ALLOCATION == GTSC_ALLOCATION;
+        let direction = if byte & GTSC_RECEIVE_ONLY == GTSC_RECEIVE_ONLY {
+            Direction::Receive
+        } else {
+            Direction::Transmit
+        };
+        let request_type = if byte & GTSC_ALLOCATION == GTSC_ALLOCATION {
+            GtsRequestType::Allocate
+        } else {
+            GtsRequestType::Deallocate
+        };
         Self {
-            count: (byte & 0x0f),
-            receive_only,
-            allocation,
+            length: byte & GTSC_LENGTH_MASK,
+            direction,
+            request_type,
         }
     }
 }
 
-impl From<GuaranteedTimeSlotCharacteristics> for u8 {
-    fn from(gtsc: GuaranteedTimeSlotCharacteristics) -> Self {
-        let mut byte = gtsc.count & 0x0f;
-        if gtsc.receive_only {
-            byte = byte | GTSC_RECEIVE_ONLY;
+impl From<GtsCharacteristics> for u8 {
+    fn from(gtsc: GtsCharacteristics) -> Self {
+        let mut byte = gtsc.length & GTSC_LENGTH_MASK;
+        if gtsc.direction == Direction::Receive {
+            byte |= GTSC_RECEIVE_ONLY;
         }
-        if gtsc.allocation {
-            byte = byte | GTSC_ALLOCATION;
+        if gtsc.request_type == GtsRequestType::Allocate {
+            byte |= GTSC_ALLOCATION;
         }
         byte
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for GtsCharacteristics {
+    fn arbitrary(
+        u: &mut arbitrary::Unstructured<'a>,
+    ) -> arbitrary::Result<Self> {
+        Ok(GtsCharacteristics {
+            length: u.int_in_range(0..=MAX_GTS_LENGTH)?,
+            direction: u.arbitrary()?,
+            request_type: u.arbitrary()?,
+        })
+    }
+}
+
+/// A builder of [`GtsCharacteristics`] that refuses invalid lengths
+///
+/// The GTS is a transmit GTS that is allocated, unless set otherwise.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct GtsCharacteristicsBuilder {
+    length: u8,
+    direction: Direction,
+    request_type: GtsRequestType,
+}
+
+impl GtsCharacteristicsBuilder {
+    /// Start building GTS characteristics for a GTS of `length` slots
+    pub const fn new(length: u8) -> Self {
+        Self {
+            length,
+            direction: Direction::Transmit,
+            request_type: GtsRequestType::Allocate,
+        }
+    }
+
+    /// Set the direction of the GTS
+    pub const fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Set whether the GTS is allocated or deallocated
+    pub const fn request_type(mut self, request_type: GtsRequestType) -> Self {
+        self.request_type = request_type;
+        self
+    }
+
+    /// Build the GTS characteristics
+    ///
+    /// Returns `None` if the length is 0 or exceeds [`MAX_GTS_LENGTH`].
+    pub const fn build(self) -> Option<GtsCharacteristics> {
+        if self.length == 0 || self.length > MAX_GTS_LENGTH {
+            return None;
+        }
+        Some(GtsCharacteristics {
+            length: self.length,
+            direction: self.direction,
+            request_type: self.request_type,
+        })
+    }
+}
+
+const DSME_GTS_MANAGEMENT_TYPE_MASK: u8 = 0x07;
+const DSME_GTS_DIRECTION: u8 = 0x08;
+const DSME_GTS_PRIORITIZED_CHANNEL_ACCESS: u8 = 0x10;
+const DSME_GTS_STATUS_MASK: u8 = 0xe0;
+const DSME_GTS_STATUS_OFFSET: u8 = 5;
+
+/// Management type of a DSME GTS command
+///
+/// Management types that are reserved in IEEE 802.15.4-2015 are kept as
+/// [`DsmeGtsManagementType::Unknown`], so they are encoded again unchanged.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum DsmeGtsManagementType {
+    /// Deallocate DSME GTSs
+    Deallocation,
+    /// Allocate DSME GTSs
+    Allocation,
+    /// Notify of a duplicated allocation of DSME GTSs
+    DuplicatedAllocationNotification,
+    /// Reduce the number of allocated DSME GTSs
+    Reduce,
+    /// Restart the allocation of DSME GTSs
+    Restart,
+    /// DSME GTSs expired
+    Expiration,
+    /// A reserved management type
+    Unknown(u8),
+}
+
+impl From<u8> for DsmeGtsManagementType {
+    fn from(bits: u8) -> Self {
+        match bits & DSME_GTS_MANAGEMENT_TYPE_MASK {
+            0 => DsmeGtsManagementType::Deallocation,
+            1 => DsmeGtsManagementType::Allocation,
+            2 => DsmeGtsManagementType::DuplicatedAllocationNotification,
+            3 => DsmeGtsManagementType::Reduce,
+            4 => DsmeGtsManagementType::Restart,
+            5 => DsmeGtsManagementType::Expiration,
+            management_type => DsmeGtsManagementType::Unknown(management_type),
+        }
+    }
+}
+
+impl From<DsmeGtsManagementType> for u8 {
+    fn from(management_type: DsmeGtsManagementType) -> Self {
+        match management_type {
+            DsmeGtsManagementType::Deallocation => 0,
+            DsmeGtsManagementType::Allocation => 1,
+            DsmeGtsManagementType::DuplicatedAllocationNotification => 2,
+            DsmeGtsManagementType::Reduce => 3,
+            DsmeGtsManagementType::Restart => 4,
+            DsmeGtsManagementType::Expiration => 5,
+            DsmeGtsManagementType::Unknown(management_type) => {
+                management_type & DSME_GTS_MANAGEMENT_TYPE_MASK
+            }
+        }
+    }
+}
+
+/// DSME GTS management field of the DSME GTS commands
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct DsmeGtsManagement {
+    /// The management type
+    pub management_type: DsmeGtsManagementType,
+    /// The direction of the DSME GTSs, from the sender's perspective
+    pub direction: Direction,
+    /// The DSME GTSs are used for prioritized channel access
+    pub prioritized_channel_access: bool,
+    /// The status of the request, in a reply or notify command, 0-7
+    pub status: u8,
+}
+
+impl DsmeGtsManagement {
+    /// Creates an instance of [`DsmeGtsManagement`] from the provided bits
+    pub fn from_bits(bits: u8) -> Self {
+        Self {
+            management_type: DsmeGtsManagementType::from(bits),
+            direction: if bits & DSME_GTS_DIRECTION != 0 {
+                Direction::Receive
+            } else {
+                Direction::Transmit
+            },
+            prioritized_channel_access: bits
+                & DSME_GTS_PRIORITIZED_CHANNEL_ACCESS
+                != 0,
+            status: (bits & DSME_GTS_STATUS_MASK) >> DSME_GTS_STATUS_OFFSET,
+        }
+    }
+
+    /// Get the bits of the DSME GTS management field
+    pub fn bits(&self) -> u8 {
+        let mut bits = u8::from(self.management_type);
+        if self.direction == Direction::Receive {
+            bits |= DSME_GTS_DIRECTION;
+        }
+        if self.prioritized_channel_access {
+            bits |= DSME_GTS_PRIORITIZED_CHANNEL_ACCESS;
+        }
+        bits | (self.status << DSME_GTS_STATUS_OFFSET) & DSME_GTS_STATUS_MASK
+    }
+}
+
+/// DSME slot allocation bitmap (SAB) specification
+///
+/// The SAB sub-block is a bitmap of the DSME GTSs of consecutive
+/// superframes, starting at the superframe with the index
+/// `sub_block_index`. Bit `n` is the least significant bit of octet `n / 8`.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DsmeSabSpecification<'a> {
+    /// The length of the SAB sub-block, in the units given by the channel
+    /// diversity mode of the PAN
+    pub sub_block_length: u8,
+    /// The index of the first superframe in the SAB sub-block
+    pub sub_block_index: u16,
+    /// The SAB sub-block
+    pub sub_block: &'a [u8],
+}
+
+impl DsmeSabSpecification<'_> {
+    /// Whether the DSME GTS with the given bit index is set in the SAB
+    /// sub-block
+    ///
+    /// Bits beyond the end of the SAB sub-block are not set.
+    pub fn is_allocated(&self, index: usize) -> bool {
+        self.sub_block
+            .get(index / 8)
+            .is_some_and(|octet| octet & (1 << (index % 8)) != 0)
+    }
+
+    /// Iterate over the bit indices of the DSME GTSs that are set in the SAB
+    /// sub-block
+    pub fn allocated(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.sub_block.len() * 8).filter(move |&i| self.is_allocated(i))
+    }
+
+    /// Get the size of this SAB specification in octets
+    pub fn get_octet_size(&self) -> usize {
+        1 + 2 + self.sub_block.len()
+    }
+}
+
+impl<'a> TryRead<'a> for DsmeSabSpecification<'a> {
+    /// Read the SAB specification, whose SAB sub-block is the rest of
+    /// `bytes`
+    fn try_read(bytes: &'a [u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        check_len(bytes, 3)?;
+        let sub_block_length = bytes.read(offset)?;
+        let sub_block_index = bytes.read_with(offset, LE)?;
+        let sub_block = &bytes[*offset..];
+        Ok((
+            Self {
+                sub_block_length,
+                sub_block_index,
+                sub_block,
+            },
+            bytes.len(),
+        ))
+    }
+}
+
+impl TryWrite for DsmeSabSpecification<'_> {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write(offset, self.sub_block_length)?;
+        bytes.write_with(offset, self.sub_block_index, LE)?;
+        bytes.write(offset, self.sub_block)?;
+        Ok(*offset)
+    }
+}
+
+/// The fields of a [`Command::DsmeGtsRequest`]
+///
+/// The fields are in the payload of the frame.
+///
+/// # Example
+///
+/// ``` rust
+/// use byte::BytesExt;
+/// use ieee802154::mac::beacon::Direction;
+/// use ieee802154::mac::command::{
+///     DsmeGtsManagement, DsmeGtsManagementType, DsmeGtsRequest,
+///     DsmeSabSpecification,
+/// };
+///
+/// let payload = [0x01, 0x02, 0x03, 0x00, 0x04, 0x01, 0x03, 0x00, 0x00, 0x12];
+/// let request: DsmeGtsRequest = payload.read(&mut 0)?;
+/// assert_eq!(
+///     request,
+///     DsmeGtsRequest {
+///         management: DsmeGtsManagement {
+///             management_type: DsmeGtsManagementType::Allocation,
+///             direction: Direction::Transmit,
+///             prioritized_channel_access: false,
+///             status: 0,
+///         },
+///         number_of_slots: 2,
+///         preferred_superframe_id: 3,
+///         preferred_slot_id: 4,
+///         sab: DsmeSabSpecification {
+///             sub_block_length: 1,
+///             sub_block_index: 3,
+///             sub_block: &[0x00, 0x12],
+///         },
+///     }
+/// );
+/// assert_eq!(request.sab.allocated().collect::<Vec<_>>(), [9, 12]);
+/// # Ok::<(), byte::Error>(())
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DsmeGtsRequest<'a> {
+    /// The DSME GTS management field
+    pub management: DsmeGtsManagement,
+    /// The number of DSME GTSs to allocate or deallocate
+    pub number_of_slots: u8,
+    /// The preferred superframe of the DSME GTSs
+    pub preferred_superframe_id: u16,
+    /// The preferred slot of the DSME GTSs
+    pub preferred_slot_id: u8,
+    /// The SAB specification
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub sab: DsmeSabSpecification<'a>,
+}
+
+impl DsmeGtsRequest<'_> {
+    /// Get the size of the fields in octets
+    pub fn get_octet_size(&self) -> usize {
+        1 + 1 + 2 + 1 + self.sab.get_octet_size()
+    }
+}
+
+impl<'a> TryRead<'a> for DsmeGtsRequest<'a> {
+    fn try_read(bytes: &'a [u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        check_len(bytes, 5)?;
+        let management = DsmeGtsManagement::from_bits(bytes.read(offset)?);
+        let number_of_slots = bytes.read(offset)?;
+        let preferred_superframe_id = bytes.read_with(offset, LE)?;
+        let preferred_slot_id = bytes.read(offset)?;
+        let sab = bytes.read(offset)?;
+        Ok((
+            Self {
+                management,
+                number_of_slots,
+                preferred_superframe_id,
+                preferred_slot_id,
+                sab,
+            },
+            *offset,
+        ))
+    }
+}
+
+impl TryWrite for DsmeGtsRequest<'_> {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write(offset, self.management.bits())?;
+        bytes.write(offset, self.number_of_slots)?;
+        bytes.write_with(offset, self.preferred_superframe_id, LE)?;
+        bytes.write(offset, self.preferred_slot_id)?;
+        bytes.write(offset, self.sab)?;
+        Ok(*offset)
+    }
+}
+
+/// The fields of a [`Command::DsmeGtsReply`] or a [`Command::DsmeGtsNotify`]
+///
+/// The fields are in the payload of the frame.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DsmeGtsAllocation<'a> {
+    /// The DSME GTS management field
+    pub management: DsmeGtsManagement,
+    /// The short address of the device that the DSME GTSs are allocated with
+    pub destination: ShortAddress,
+    /// The channel offset of the DSME GTSs
+    pub channel_offset: u16,
+    /// The SAB specification
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub sab: DsmeSabSpecification<'a>,
+}
+
+impl DsmeGtsAllocation<'_> {
+    /// Get the size of the fields in octets
+    pub fn get_octet_size(&self) -> usize {
+        1 + 2 + 2 + self.sab.get_octet_size()
+    }
+}
+
+impl<'a> TryRead<'a> for DsmeGtsAllocation<'a> {
+    fn try_read(bytes: &'a [u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        check_len(bytes, 5)?;
+        let management = DsmeGtsManagement::from_bits(bytes.read(offset)?);
+        let destination = bytes.read(offset)?;
+        let channel_offset = bytes.read_with(offset, LE)?;
+        let sab = bytes.read(offset)?;
+        Ok((
+            Self {
+                management,
+                destination,
+                channel_offset,
+                sab,
+            },
+            *offset,
+        ))
+    }
+}
+
+impl TryWrite for DsmeGtsAllocation<'_> {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write(offset, self.management.bits())?;
+        bytes.write(offset, self.destination)?;
+        bytes.write_with(offset, self.channel_offset, LE)?;
+        bytes.write(offset, self.sab)?;
+        Ok(*offset)
+    }
+}
+
 /// MAC commands
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Command {
     /// Association request, request association to a PAN
     AssociationRequest(CapabilityInformation),
@@ -239,59 +913,232 @@ pub enum Command {
     /// Coordinator re-alignment, the coordinator will change PAN parameters
     CoordinatorRealignment(CoordinatorRealignmentData),
     /// Request a guaranteed time slot (GTS)
-    GuaranteedTimeSlotRequest(GuaranteedTimeSlotCharacteristics),
+    GuaranteedTimeSlotRequest(GtsCharacteristics),
+    /// TRLE management request, the fields are in the payload
+    TrleManagementRequest,
+    /// TRLE management response, the fields are in the payload
+    TrleManagementResponse,
+    /// DSME association request, the fields are in the payload
+    DsmeAssociationRequest,
+    /// DSME association response, the fields are in the payload
+    DsmeAssociationResponse,
+    /// DSME GTS request, the fields are in the payload
+    ///
+    /// Read the fields from the payload with [`DsmeGtsRequest`].
+    DsmeGtsRequest,
+    /// DSME GTS reply, the fields are in the payload
+    ///
+    /// Read the fields from the payload with [`DsmeGtsAllocation`].
+    DsmeGtsReply,
+    /// DSME GTS notify, the fields are in the payload
+    ///
+    /// Read the fields from the payload with [`DsmeGtsAllocation`].
+    DsmeGtsNotify,
+    /// DSME information request, the fields are in the payload
+    DsmeInformationRequest,
+    /// DSME information reply, the fields are in the payload
+    DsmeInformationReply,
+    /// DSME beacon allocation notification, with the beacon slot that the
+    /// sender allocated
+    DsmeBeaconAllocationNotification(u16),
+    /// DSME beacon collision notification, with the beacon slot in which
+    /// beacons collided
+    DsmeBeaconCollisionNotification(u16),
+    /// DSME link report, the fields are in the payload
+    DsmeLinkReport,
+    /// RIT data request, the IEs are in the payload
+    RitDataRequest,
+    /// DBS request, the fields are in the payload
+    DbsRequest,
+    /// DBS response, the fields are in the payload
+    DbsResponse,
+    /// RIT data response, the IEs are in the payload
+    RitDataResponse,
+    /// Vendor specific command, with the OUI of the vendor
+    ///
+    /// The vendor specific information is in the payload.
+    VendorSpecific([u8; 3]),
+    /// SRM request, the fields are in the payload
+    SrmRequest,
+    /// SRM response, the fields are in the payload
+    SrmResponse,
+    /// SRM report, the fields are in the payload
+    SrmReport,
+    /// SRM information, the fields are in the payload
+    SrmInformation,
+    /// A command with a reserved or proprietary command identifier, the
+    /// fields are in the payload
+    ///
+    /// Only identifiers that aren't a [`CommandId`] are decoded into this
+    /// variant.
+    Unknown(u8),
+}
+
+impl Command {
+    /// Get the command identifier of this command
+    ///
+    /// Returns `None` for [`Command::Unknown`], use [`Command::id`] to get its
+    /// raw command identifier.
+    pub fn command_id(&self) -> Option<CommandId> {
+        Some(match self {
+            Command::AssociationRequest(_) => CommandId::AssociationRequest,
+            Command::AssociationResponse(..) => CommandId::AssociationResponse,
+            Command::DisassociationNotification(_) => {
+                CommandId::DisassociationNotification
+            }
+            Command::DataRequest => CommandId::DataRequest,
+            Command::PanIdConflictNotification => {
+                CommandId::PanIdConflictNotification
+            }
+            Command::OrphanNotification => CommandId::OrphanNotification,
+            Command::BeaconRequest => CommandId::BeaconRequest,
+            Command::CoordinatorRealignment(_) => {
+                CommandId::CoordinatorRealignment
+            }
+            Command::GuaranteedTimeSlotRequest(_) => {
+                CommandId::GuaranteedTimeSlotRequest
+            }
+            Command::TrleManagementRequest => CommandId::TrleManagementRequest,
+            Command::TrleManagementResponse => {
+                CommandId::TrleManagementResponse
+            }
+            Command::DsmeAssociationRequest => {
+                CommandId::DsmeAssociationRequest
+            }
+            Command::DsmeAssociationResponse => {
+                CommandId::DsmeAssociationResponse
+            }
+            Command::DsmeGtsRequest => CommandId::DsmeGtsRequest,
+            Command::DsmeGtsReply => CommandId::DsmeGtsReply,
+            Command::DsmeGtsNotify => CommandId::DsmeGtsNotify,
+            Command::DsmeInformationRequest => {
+                CommandId::DsmeInformationRequest
+            }
+            Command::DsmeInformationReply => CommandId::DsmeInformationReply,
+            Command::DsmeBeaconAllocationNotification(_) => {
+                CommandId::DsmeBeaconAllocationNotification
+            }
+            Command::DsmeBeaconCollisionNotification(_) => {
+                CommandId::DsmeBeaconCollisionNotification
+            }
+            Command::DsmeLinkReport => CommandId::DsmeLinkReport,
+            Command::RitDataRequest => CommandId::RitDataRequest,
+            Command::DbsRequest => CommandId::DbsRequest,
+            Command::DbsResponse => CommandId::DbsResponse,
+            Command::RitDataResponse => CommandId::RitDataResponse,
+            Command::VendorSpecific(_) => CommandId::VendorSpecific,
+            Command::SrmRequest => CommandId::SrmRequest,
+            Command::SrmResponse => CommandId::SrmResponse,
+            Command::SrmReport => CommandId::SrmReport,
+            Command::SrmInformation => CommandId::SrmInformation,
+            Command::Unknown(_) => return None,
+        })
+    }
+
+    /// Whether fields of this command are in the payload of the frame
+    ///
+    /// This is the case for the commands of IEEE 802.15.4-2015 whose
+    /// variable-length fields aren't decoded, for vendor specific commands
+    /// and for [`Command::Unknown`].
+    pub fn has_payload_fields(&self) -> bool {
+        !matches!(
+            self,
+            Command::AssociationRequest(_)
+                | Command::AssociationResponse(..)
+                | Command::DisassociationNotification(_)
+                | Command::DataRequest
+                | Command::PanIdConflictNotification
+                | Command::OrphanNotification
+                | Command::BeaconRequest
+                | Command::CoordinatorRealignment(_)
+                | Command::GuaranteedTimeSlotRequest(_)
+                | Command::DsmeBeaconAllocationNotification(_)
+                | Command::DsmeBeaconCollisionNotification(_)
+        )
+    }
+
+    /// Get the size of this command in octets
+    ///
+    /// This is the size of the command identifier and of the fields that are
+    /// decoded into the command. Fields in the payload of the frame aren't
+    /// included.
+    pub fn get_octet_size(&self) -> usize {
+        1 + match self {
+            Command::AssociationRequest(_)
+            | Command::DisassociationNotification(_) => 1,
+            Command::AssociationResponse(..) => 3,
+            Command::CoordinatorRealignment(data) => data.get_octet_size(),
+            Command::GuaranteedTimeSlotRequest(_) => 1,
+            Command::DsmeBeaconAllocationNotification(_)
+            | Command::DsmeBeaconCollisionNotification(_) => 2,
+            Command::VendorSpecific(_) => 3,
+            _ => 0,
+        }
+    }
+
+    /// Get the raw command identifier of this command
+    pub fn id(&self) -> u8 {
+        match self {
+            Command::Unknown(id) => *id,
+            command => command
+                .command_id()
+                .map(u8::from)
+                .expect("only unknown commands lack a command identifier"),
+        }
+    }
 }
 
 impl TryWrite for Command {
     fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
         let offset = &mut 0;
+        bytes.write(offset, self.id())?;
         match self {
             Command::AssociationRequest(capability) => {
-                bytes.write(offset, u8::from(CommandId::AssociationRequest))?;
                 bytes.write(offset, u8::from(capability))?;
             }
             Command::AssociationResponse(address, status) => {
-                bytes
-                    .write(offset, u8::from(CommandId::AssociationResponse))?;
                 bytes.write(offset, address)?;
                 bytes.write(offset, u8::from(status))?;
             }
             Command::DisassociationNotification(reason) => {
-                bytes.write(
-                    offset,
-                    u8::from(CommandId::DisassociationNotification),
-                )?;
                 bytes.write(offset, u8::from(reason))?;
             }
-            Command::DataRequest => {
-                bytes.write(offset, u8::from(CommandId::DataRequest))?;
-            }
-            Command::PanIdConflictNotification => {
-                bytes.write(
-                    offset,
-                    u8::from(CommandId::PanIdConflictNotification),
-                )?;
-            }
-            Command::OrphanNotification => {
-                bytes.write(offset, u8::from(CommandId::OrphanNotification))?;
-            }
-            Command::BeaconRequest => {
-                bytes.write(offset, u8::from(CommandId::BeaconRequest))?;
-            }
+            Command::DataRequest
+            | Command::PanIdConflictNotification
+            | Command::OrphanNotification
+            | Command::BeaconRequest => {}
             Command::CoordinatorRealignment(data) => {
-                bytes.write(
-                    offset,
-                    u8::from(CommandId::CoordinatorRealignment),
-                )?;
                 bytes.write(offset, data)?;
             }
             Command::GuaranteedTimeSlotRequest(characteristics) => {
-                bytes.write(
-                    offset,
-                    u8::from(CommandId::GuaranteedTimeSlotRequest),
-                )?;
                 bytes.write(offset, u8::from(characteristics))?;
             }
+            Command::DsmeBeaconAllocationNotification(sequence_number)
+            | Command::DsmeBeaconCollisionNotification(sequence_number) => {
+                bytes.write_with(offset, sequence_number, LE)?;
+            }
+            Command::VendorSpecific(oui) => {
+                bytes.write(offset, &oui[..])?;
+            }
+            Command::TrleManagementRequest
+            | Command::TrleManagementResponse
+            | Command::DsmeAssociationRequest
+            | Command::DsmeAssociationResponse
+            | Command::DsmeGtsRequest
+            | Command::DsmeGtsReply
+            | Command::DsmeGtsNotify
+            | Command::DsmeInformationRequest
+            | Command::DsmeInformationReply
+            | Command::DsmeLinkReport
+            | Command::RitDataRequest
+            | Command::DbsRequest
+            | Command::DbsResponse
+            | Command::RitDataResponse
+            | Command::SrmRequest
+            | Command::SrmResponse
+            | Command::SrmReport
+            | Command::SrmInformation
+            | Command::Unknown(_) => {}
         }
         Ok(*offset)
     }
@@ -299,9 +1146,26 @@ impl TryWrite for Command {
 
 impl TryRead<'_> for Command {
     fn try_read(bytes: &[u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        Self::try_read_for_version(bytes, FrameVersion::Ieee802154)
+    }
+}
+
+impl Command {
+    /// Read a command of a frame of the given version
+    ///
+    /// The version determines the fields that are present, e.g. the channel
+    /// page of [`CoordinatorRealignmentData`]. Reading a command with
+    /// [`BytesExt::read`] assumes the current version.
+    pub fn try_read_for_version(
+        bytes: &[u8],
+        version: FrameVersion,
+    ) -> byte::Result<(Self, usize)> {
         let offset = &mut 0;
-        let cmd = CommandId::optional_from(bytes.read::<u8>(offset)?)
-            .ok_or(DecodeError::InvalidValue)?;
+        let id: u8 = bytes.read(offset)?;
+        let cmd = match CommandId::optional_from(id) {
+            Some(cmd) => cmd,
+            None => return Ok((Command::Unknown(id), *offset)),
+        };
         Ok((
             match cmd {
                 CommandId::AssociationRequest => {
@@ -312,15 +1176,12 @@ impl TryRead<'_> for Command {
                 CommandId::AssociationResponse => {
                     let address: ShortAddress = bytes.read(offset)?;
                     let status =
-                        AssociationStatus::optional_from(bytes.read(offset)?)
-                            .ok_or(DecodeError::InvalidValue)?;
+                        AssociationStatus::from(bytes.read::<u8>(offset)?);
                     Command::AssociationResponse(address, status)
                 }
                 CommandId::DisassociationNotification => {
-                    let reason = DisassociationReason::optional_from(
-                        bytes.read(offset)?,
-                    )
-                    .ok_or(DecodeError::InvalidValue)?;
+                    let reason =
+                        DisassociationReason::from(bytes.read::<u8>(offset)?);
                     Command::DisassociationNotification(reason)
                 }
                 CommandId::DataRequest => Command::DataRequest,
@@ -330,21 +1191,669 @@ impl TryRead<'_> for Command {
                 CommandId::OrphanNotification => Command::OrphanNotification,
                 CommandId::BeaconRequest => Command::BeaconRequest,
                 CommandId::CoordinatorRealignment => {
-                    Command::CoordinatorRealignment(bytes.read(offset)?)
+                    let (data, len) =
+                        CoordinatorRealignmentData::try_read_for_version(
+                            &bytes[*offset..],
+                            version,
+                        )?;
+                    *offset += len;
+                    Command::CoordinatorRealignment(data)
                 }
                 CommandId::GuaranteedTimeSlotRequest => {
                     let characteristics =
-                        GuaranteedTimeSlotCharacteristics::from(
-                            bytes.read::<u8>(offset)?,
-                        );
+                        GtsCharacteristics::from(bytes.read::<u8>(offset)?);
                     Command::GuaranteedTimeSlotRequest(characteristics)
                 }
+                CommandId::TrleManagementRequest => {
+                    Command::TrleManagementRequest
+                }
+                CommandId::TrleManagementResponse => {
+                    Command::TrleManagementResponse
+                }
+                CommandId::DsmeAssociationRequest => {
+                    Command::DsmeAssociationRequest
+                }
+                CommandId::DsmeAssociationResponse => {
+                    Command::DsmeAssociationResponse
+                }
+                CommandId::DsmeGtsRequest => Command::DsmeGtsRequest,
+                CommandId::DsmeGtsReply => Command::DsmeGtsReply,
+                CommandId::DsmeGtsNotify => Command::DsmeGtsNotify,
+                CommandId::DsmeInformationRequest => {
+                    Command::DsmeInformationRequest
+                }
+                CommandId::DsmeInformationReply => {
+                    Command::DsmeInformationReply
+                }
+                CommandId::DsmeBeaconAllocationNotification => {
+                    Command::DsmeBeaconAllocationNotification(
+                        bytes.read_with(offset, LE)?,
+                    )
+                }
+                CommandId::DsmeBeaconCollisionNotification => {
+                    Command::DsmeBeaconCollisionNotification(
+                        bytes.read_with(offset, LE)?,
+                    )
+                }
+                CommandId::DsmeLinkReport => Command::DsmeLinkReport,
+                CommandId::RitDataRequest => Command::RitDataRequest,
+                CommandId::DbsRequest => Command::DbsRequest,
+                CommandId::DbsResponse => Command::DbsResponse,
+                CommandId::RitDataResponse => Command::RitDataResponse,
+                CommandId::VendorSpecific => {
+                    check_len(&bytes[*offset..], 3)?;
+                    let mut oui = [0u8; 3];
+                    oui.copy_from_slice(&bytes[*offset..*offset + 3]);
+                    *offset += 3;
+                    Command::VendorSpecific(oui)
+                }
+                CommandId::SrmRequest => Command::SrmRequest,
+                CommandId::SrmResponse => Command::SrmResponse,
+                CommandId::SrmReport => Command::SrmReport,
+                CommandId::SrmInformation => Command::SrmInformation,
             },
             *offset,
         ))
     }
 }
 
+/// The orphan notification frame, sent by a device that lost its coordinator
+///
+/// The frame is broadcast, with the extended address of the orphaned device
+/// as the source. The coordinator of the device replies with an
+/// [`OrphanRealignmentFrame`].
+///
+/// # Example
+///
+/// ``` rust
+/// use byte::BytesExt;
+/// use ieee802154::mac::command::OrphanNotificationFrame;
+/// use ieee802154::mac::{ExtendedAddress, FooterMode, Frame};
+///
+/// let notification = OrphanNotificationFrame {
+///     seq: 3,
+///     orphan: ExtendedAddress(0x0102_0304_0506_0708),
+/// };
+///
+/// let mut bytes = [0u8; 32];
+/// let len = &mut 0;
+/// bytes.write_with(len, notification.to_frame(), FooterMode::None)?;
+/// assert_eq!(
+///     bytes[..*len],
+///     [
+///         0x43, 0xc8, // frame control
+///         0x03, // sequence number
+///         0xff, 0xff, 0xff, 0xff, // destination
+///         0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01, // source
+///         0x06, // orphan notification
+///     ]
+/// );
+///
+/// let frame: Frame = bytes[..*len].read_with(&mut 0, FooterMode::None)?;
+/// assert_eq!(OrphanNotificationFrame::from_frame(&frame), Some(notification));
+/// # Ok::<(), byte::Error>(())
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct OrphanNotificationFrame {
+    /// Sequence number
+    pub seq: u8,
+    /// Extended address of the orphaned device
+    pub orphan: ExtendedAddress,
+}
+
+impl OrphanNotificationFrame {
+    /// Assemble the orphan notification frame
+    ///
+    /// The footer of the returned frame is zeroed.
+    pub fn to_frame(&self) -> Frame<'static> {
+        let header = Header {
+            frame_type: FrameType::MacCommand,
+            frame_pending: false,
+            ack_request: false,
+            pan_id_compress: true,
+            ie_present: false,
+            version: FrameVersion::Ieee802154_2003,
+            seq: self.seq,
+            destination: Some(Address::Short(
+                PanId::broadcast(),
+                ShortAddress::broadcast(),
+            )),
+            source: Some(Address::Extended(PanId::broadcast(), self.orphan)),
+            auxiliary_security_header: None,
+        };
+        Frame {
+            header,
+            content: FrameContent::Command(Command::OrphanNotification),
+            payload: &[],
+            footer: [0; 2],
+        }
+    }
+
+    /// Get the orphan notification that `frame` carries
+    ///
+    /// Returns `None` if `frame` is not an orphan notification command with
+    /// an extended source address.
+    pub fn from_frame(frame: &Frame) -> Option<Self> {
+        match (frame.content, frame.header.source) {
+            (
+                FrameContent::Command(Command::OrphanNotification),
+                Some(Address::Extended(_, orphan)),
+            ) => Some(Self {
+                seq: frame.header.seq,
+                orphan,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// The coordinator realignment frame, sent by a coordinator in reply to an
+/// [`OrphanNotificationFrame`]
+///
+/// The frame is sent to the extended address of the orphaned device, from
+/// the extended address of the coordinator, and requests an acknowledgement.
+/// The [`CoordinatorRealignmentData::device_address`] is the short address
+/// of the orphaned device, or `0xfffe` if it has none.
+///
+/// # Example
+///
+/// ``` rust
+/// use byte::BytesExt;
+/// use ieee802154::mac::command::{
+///     CoordinatorRealignmentData, OrphanRealignmentFrame,
+/// };
+/// use ieee802154::mac::{ExtendedAddress, FooterMode, PanId, ShortAddress};
+///
+/// let realignment = OrphanRealignmentFrame {
+///     seq: 9,
+///     orphan: ExtendedAddress(0x0102_0304_0506_0708),
+///     coordinator: ExtendedAddress(0x1112_1314_1516_1718),
+///     data: CoordinatorRealignmentData {
+///         pan_id: PanId(0xabcd),
+///         coordinator_address: ShortAddress(0x0000),
+///         channel: 15,
+///         device_address: ShortAddress(0x1234),
+///         channel_page: None,
+///     },
+/// };
+///
+/// let mut bytes = [0u8; 40];
+/// let len = &mut 0;
+/// bytes.write_with(len, realignment.to_frame(), FooterMode::None)?;
+/// assert_eq!(
+///     bytes[..*len],
+///     [
+///         0x23, 0xcc, // frame control
+///         0x09, // sequence number
+///         0xff, 0xff, // destination PAN ID
+///         0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01, // destination
+///         0xcd, 0xab, // source PAN ID
+///         0x18, 0x17, 0x16, 0x15, 0x14, 0x13, 0x12, 0x11, // source
+///         0x08, 0xcd, 0xab, 0x00, 0x00, 0x0f, 0x34, 0x12, // realignment
+///     ]
+/// );
+/// # Ok::<(), byte::Error>(())
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct OrphanRealignmentFrame {
+    /// Sequence number
+    pub seq: u8,
+    /// Extended address of the orphaned device
+    pub orphan: ExtendedAddress,
+    /// Extended address of the coordinator
+    pub coordinator: ExtendedAddress,
+    /// The PAN parameters of the coordinator
+    pub data: CoordinatorRealignmentData,
+}
+
+impl OrphanRealignmentFrame {
+    /// Assemble the coordinator realignment frame
+    ///
+    /// The frame is of version [`FrameVersion::Ieee802154_2006`] if it
+    /// carries a channel page, and of version
+    /// [`FrameVersion::Ieee802154_2003`] otherwise. The footer of the
+    /// returned frame is zeroed.
+    pub fn to_frame(&self) -> Frame<'static> {
+        let version = if self.data.channel_page.is_some() {
+            FrameVersion::Ieee802154_2006
+        } else {
+            FrameVersion::Ieee802154_2003
+        };
+        let header = Header {
+            frame_type: FrameType::MacCommand,
+            frame_pending: false,
+            ack_request: true,
+            pan_id_compress: false,
+            ie_present: false,
+            version,
+            seq: self.seq,
+            destination: Some(Address::Extended(
+                PanId::broadcast(),
+                self.orphan,
+            )),
+            source: Some(Address::Extended(self.data.pan_id, self.coordinator)),
+            auxiliary_security_header: None,
+        };
+        Frame {
+            header,
+            content: FrameContent::Command(Command::CoordinatorRealignment(
+                self.data,
+            )),
+            payload: &[],
+            footer: [0; 2],
+        }
+    }
+}
+
+/// The data request frame, sent by a device to poll its coordinator for
+/// pending data
+///
+/// The frame is sent from `source` to `coordinator` and requests an
+/// acknowledgement. The source PAN ID is compressed if both addresses are in
+/// the same PAN. Use [`DataRequestFrame::keep_receiver_on`] on the frames
+/// received afterwards, to tell whether the coordinator has more data.
+///
+/// # Example
+///
+/// ``` rust
+/// use byte::BytesExt;
+/// use ieee802154::mac::command::DataRequestFrame;
+/// use ieee802154::mac::{
+///     Address, ExtendedAddress, FooterMode, Frame, PanId, ShortAddress,
+/// };
+///
+/// let request = DataRequestFrame {
+///     seq: 4,
+///     coordinator: Address::Short(PanId(0xabcd), ShortAddress(0x0000)),
+///     source: Address::Extended(PanId(0xabcd), ExtendedAddress(0x0102)),
+/// };
+///
+/// let mut bytes = [0u8; 32];
+/// let len = &mut 0;
+/// bytes.write_with(len, request.to_frame(), FooterMode::None)?;
+/// assert_eq!(
+///     bytes[..*len],
+///     [
+///         0x63, 0xc8, // frame control
+///         0x04, // sequence number
+///         0xcd, 0xab, 0x00, 0x00, // destination
+///         0x02, 0x01, 0, 0, 0, 0, 0, 0, // source
+///         0x04, // data request
+///     ]
+/// );
+///
+/// // The coordinator acknowledges the request, and has data pending
+/// let ack: Frame = [0x12, 0x00, 0x04].read_with(&mut 0, FooterMode::None)?;
+/// assert!(request.keep_receiver_on(&ack));
+/// # Ok::<(), byte::Error>(())
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DataRequestFrame {
+    /// Sequence number
+    pub seq: u8,
+    /// Address of the coordinator that is polled
+    pub coordinator: Address,
+    /// Address of the polling device
+    pub source: Address,
+}
+
+impl DataRequestFrame {
+    /// Assemble the data request frame
+    ///
+    /// The footer of the returned frame is zeroed.
+    pub fn to_frame(&self) -> Frame<'static> {
+        let header = Header {
+            frame_type: FrameType::MacCommand,
+            frame_pending: false,
+            ack_request: true,
+            pan_id_compress: self.coordinator.pan_id() == self.source.pan_id(),
+            ie_present: false,
+            version: FrameVersion::Ieee802154_2003,
+            seq: self.seq,
+            destination: Some(self.coordinator),
+            source: Some(self.source),
+            auxiliary_security_header: None,
+        };
+        Frame {
+            header,
+            content: FrameContent::Command(Command::DataRequest),
+            payload: &[],
+            footer: [0; 2],
+        }
+    }
+
+    /// Whether the receiver has to stay on after receiving `frame`, because
+    /// the coordinator has more data pending
+    ///
+    /// This is the frame pending bit of the acknowledgement of this request,
+    /// or of a frame that the coordinator sent afterwards. Acknowledgements
+    /// of other frames are ignored.
+    pub fn keep_receiver_on(&self, frame: &Frame) -> bool {
+        if frame.header.frame_type == FrameType::Acknowledgement
+            && frame.header.seq != self.seq
+        {
+            return false;
+        }
+        frame.header.frame_pending
+    }
+}
+
+/// The PAN ID conflict notification frame, sent by a device that detected a
+/// PAN ID conflict to its coordinator
+///
+/// The frame is sent from the extended address of the device to the extended
+/// address of its coordinator, within the PAN of the device, and requests an
+/// acknowledgement. It is secured with `security`, and is of version
+/// [`FrameVersion::Ieee802154_2006`] if it is secured, and of version
+/// [`FrameVersion::Ieee802154_2003`] otherwise.
+///
+/// # Example
+///
+/// ``` rust
+/// use byte::BytesExt;
+/// use ieee802154::mac::command::PanIdConflictNotificationFrame;
+/// use ieee802154::mac::frame::security::SecurityParameters;
+/// use ieee802154::mac::{ExtendedAddress, FooterMode, PanId};
+///
+/// let notification = PanIdConflictNotificationFrame {
+///     seq: 5,
+///     pan_id: PanId(0xabcd),
+///     coordinator: ExtendedAddress(0x1112_1314_1516_1718),
+///     device: ExtendedAddress(0x0102_0304_0506_0708),
+///     security: SecurityParameters::NONE,
+/// };
+///
+/// let mut bytes = [0u8; 32];
+/// let len = &mut 0;
+/// bytes.write_with(len, notification.to_frame(), FooterMode::None)?;
+/// assert_eq!(
+///     bytes[..*len],
+///     [
+///         0x63, 0xcc, // frame control
+///         0x05, // sequence number
+///         0xcd, 0xab, // destination PAN ID
+///         0x18, 0x17, 0x16, 0x15, 0x14, 0x13, 0x12, 0x11, // destination
+///         0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01, // source
+///         0x05, // PAN ID conflict notification
+///     ]
+/// );
+/// # Ok::<(), byte::Error>(())
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PanIdConflictNotificationFrame {
+    /// Sequence number
+    pub seq: u8,
+    /// The PAN ID of the device, which is in conflict
+    pub pan_id: PanId,
+    /// Extended address of the coordinator
+    pub coordinator: ExtendedAddress,
+    /// Extended address of the device
+    pub device: ExtendedAddress,
+    /// The security parameters of the frame
+    pub security: SecurityParameters,
+}
+
+impl PanIdConflictNotificationFrame {
+    /// Assemble the PAN ID conflict notification frame
+    ///
+    /// A secured frame still has to be written with a security context. The
+    /// footer of the returned frame is zeroed.
+    pub fn to_frame(&self) -> Frame<'static> {
+        let auxiliary_security_header =
+            self.security.auxiliary_security_header();
+        let version = if auxiliary_security_header.is_some() {
+            FrameVersion::Ieee802154_2006
+        } else {
+            FrameVersion::Ieee802154_2003
+        };
+        let header = Header {
+            frame_type: FrameType::MacCommand,
+            frame_pending: false,
+            ack_request: true,
+            pan_id_compress: true,
+            ie_present: false,
+            version,
+            seq: self.seq,
+            destination: Some(Address::Extended(self.pan_id, self.coordinator)),
+            source: Some(Address::Extended(self.pan_id, self.device)),
+            auxiliary_security_header,
+        };
+        Frame {
+            header,
+            content: FrameContent::Command(Command::PanIdConflictNotification),
+            payload: &[],
+            footer: [0; 2],
+        }
+    }
+}
+
+/// The association request frame, sent by a device that wants to join a PAN
+///
+/// The frame is sent from the extended address of the device, with the
+/// broadcast PAN ID, to the coordinator of the PAN, and requests an
+/// acknowledgement. The coordinator replies with an
+/// [`AssociationResponseFrame`].
+///
+/// # Example
+///
+/// ``` rust
+/// use byte::BytesExt;
+/// use ieee802154::mac::command::{
+///     AssociationRequestFrame, CapabilityInformation,
+/// };
+/// use ieee802154::mac::{
+///     Address, ExtendedAddress, FooterMode, Frame, PanId, ShortAddress,
+/// };
+///
+/// let request = AssociationRequestFrame {
+///     seq: 1,
+///     coordinator: Address::Short(PanId(0xabcd), ShortAddress(0x0000)),
+///     device: ExtendedAddress(0x0102_0304_0506_0708),
+///     capability: CapabilityInformation::from_bits(0x8e),
+/// };
+///
+/// let mut bytes = [0u8; 32];
+/// let len = &mut 0;
+/// bytes.write_with(len, request.to_frame(), FooterMode::None)?;
+/// assert_eq!(
+///     bytes[..*len],
+///     [
+///         0x23, 0xc8, // frame control
+///         0x01, // sequence number
+///         0xcd, 0xab, 0x00, 0x00, // destination
+///         0xff, 0xff, // source PAN ID
+///         0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01, // source
+///         0x01, 0x8e, // association request
+///     ]
+/// );
+///
+/// let frame: Frame = bytes[..*len].read_with(&mut 0, FooterMode::None)?;
+/// assert_eq!(AssociationRequestFrame::from_frame(&frame), Some(request));
+/// # Ok::<(), byte::Error>(())
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AssociationRequestFrame {
+    /// Sequence number
+    pub seq: u8,
+    /// Address of the coordinator, within the PAN to join
+    pub coordinator: Address,
+    /// Extended address of the device
+    pub device: ExtendedAddress,
+    /// Capabilities of the device
+    pub capability: CapabilityInformation,
+}
+
+impl AssociationRequestFrame {
+    /// Assemble the association request frame
+    ///
+    /// The footer of the returned frame is zeroed.
+    pub fn to_frame(&self) -> Frame<'static> {
+        let header = Header {
+            frame_type: FrameType::MacCommand,
+            frame_pending: false,
+            ack_request: true,
+            pan_id_compress: false,
+            ie_present: false,
+            version: FrameVersion::Ieee802154_2003,
+            seq: self.seq,
+            destination: Some(self.coordinator),
+            source: Some(Address::Extended(PanId::broadcast(), self.device)),
+            auxiliary_security_header: None,
+        };
+        Frame {
+            header,
+            content: FrameContent::Command(Command::AssociationRequest(
+                self.capability,
+            )),
+            payload: &[],
+            footer: [0; 2],
+        }
+    }
+
+    /// Get the association request that `frame` carries
+    ///
+    /// Returns `None` if `frame` is not an association request command with
+    /// a destination address and an extended source address.
+    pub fn from_frame(frame: &Frame) -> Option<Self> {
+        match (frame.content, frame.header.destination, frame.header.source) {
+            (
+                FrameContent::Command(Command::AssociationRequest(capability)),
+                Some(coordinator),
+                Some(Address::Extended(_, device)),
+            ) => Some(Self {
+                seq: frame.header.seq,
+                coordinator,
+                device,
+                capability,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// The association response frame, sent by a coordinator in reply to an
+/// [`AssociationRequestFrame`]
+///
+/// The frame is sent from the extended address of the coordinator to the
+/// extended address of the device, within the PAN of the coordinator, and
+/// requests an acknowledgement.
+///
+/// # Example
+///
+/// ``` rust
+/// use byte::BytesExt;
+/// use ieee802154::mac::command::{
+///     AssociationResponseFrame, AssociationStatus,
+/// };
+/// use ieee802154::mac::{ExtendedAddress, FooterMode, Frame, PanId, ShortAddress};
+///
+/// let response = AssociationResponseFrame {
+///     seq: 2,
+///     pan_id: PanId(0xabcd),
+///     coordinator: ExtendedAddress(0x1112_1314_1516_1718),
+///     device: ExtendedAddress(0x0102_0304_0506_0708),
+///     short_address: ShortAddress(0x1234),
+///     status: AssociationStatus::Successful,
+/// };
+///
+/// let mut bytes = [0u8; 32];
+/// let len = &mut 0;
+/// bytes.write_with(len, response.to_frame(), FooterMode::None)?;
+/// assert_eq!(
+///     bytes[..*len],
+///     [
+///         0x63, 0xcc, // frame control
+///         0x02, // sequence number
+///         0xcd, 0xab, // destination PAN ID
+///         0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01, // destination
+///         0x18, 0x17, 0x16, 0x15, 0x14, 0x13, 0x12, 0x11, // source
+///         0x02, 0x34, 0x12, 0x00, // association response
+///     ]
+/// );
+///
+/// let frame: Frame = bytes[..*len].read_with(&mut 0, FooterMode::None)?;
+/// assert_eq!(AssociationResponseFrame::from_frame(&frame), Some(response));
+/// # Ok::<(), byte::Error>(())
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AssociationResponseFrame {
+    /// Sequence number
+    pub seq: u8,
+    /// PAN ID of the coordinator
+    pub pan_id: PanId,
+    /// Extended address of the coordinator
+    pub coordinator: ExtendedAddress,
+    /// Extended address of the device that requested association
+    pub device: ExtendedAddress,
+    /// The short address allocated to the device
+    ///
+    /// `0xfffe` if the device has to use its extended address, and `0xffff`
+    /// if the association was unsuccessful.
+    pub short_address: ShortAddress,
+    /// The status of the association
+    pub status: AssociationStatus,
+}
+
+impl AssociationResponseFrame {
+    /// Assemble the association response frame
+    ///
+    /// The footer of the returned frame is zeroed.
+    pub fn to_frame(&self) -> Frame<'static> {
+        let header = Header {
+            frame_type: FrameType::MacCommand,
+            frame_pending: false,
+            ack_request: true,
+            pan_id_compress: true,
+            ie_present: false,
+            version: FrameVersion::Ieee802154_2003,
+            seq: self.seq,
+            destination: Some(Address::Extended(self.pan_id, self.device)),
+            source: Some(Address::Extended(self.pan_id, self.coordinator)),
+            auxiliary_security_header: None,
+        };
+        Frame {
+            header,
+            content: FrameContent::Command(Command::AssociationResponse(
+                self.short_address,
+                self.status,
+            )),
+            payload: &[],
+            footer: [0; 2],
+        }
+    }
+
+    /// Get the association response that `frame` carries
+    ///
+    /// Returns `None` if `frame` is not an association response command with
+    /// extended destination and source addresses.
+    pub fn from_frame(frame: &Frame) -> Option<Self> {
+        match (frame.content, frame.header.destination, frame.header.source) {
+            (
+                FrameContent::Command(Command::AssociationResponse(
+                    short_address,
+                    status,
+                )),
+                Some(Address::Extended(pan_id, device)),
+                Some(Address::Extended(_, coordinator)),
+            ) => Some(Self {
+                seq: frame.header.seq,
+                pan_id,
+                coordinator,
+                device,
+                short_address,
+                status,
+            }),
+            _ => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -358,11 +1867,14 @@ mod tests {
         assert_eq!(
             command,
             Command::AssociationRequest(CapabilityInformation {
+                alternate_pan_coordinator: false,
                 full_function_device: true,
                 mains_power: true,
                 idle_receive: true,
+                fast_association: false,
                 frame_protection: false,
                 allocate_address: true,
+                reserved: 0,
             })
         );
     }
@@ -370,11 +1882,14 @@ mod tests {
     #[test]
     fn encode_association_request() {
         let command = Command::AssociationRequest(CapabilityInformation {
+            alternate_pan_coordinator: false,
             full_function_device: false,
             mains_power: false,
             idle_receive: false,
+            fast_association: false,
             frame_protection: false,
             allocate_address: false,
+            reserved: 0,
         });
         let mut data = [0u8; 32];
         let mut len = 0usize;
@@ -384,11 +1899,14 @@ mod tests {
         assert_eq!(data[..len], [0x01, 0x00]);
 
         let command = Command::AssociationRequest(CapabilityInformation {
+            alternate_pan_coordinator: false,
             full_function_device: true,
             mains_power: false,
             idle_receive: false,
+            fast_association: false,
             frame_protection: false,
             allocate_address: false,
+            reserved: 0,
         });
         let mut len = 0usize;
         data.write(&mut len, command).unwrap();
@@ -397,11 +1915,14 @@ mod tests {
         assert_eq!(data[..len], [0x01, 0x02]);
 
         let command = Command::AssociationRequest(CapabilityInformation {
+            alternate_pan_coordinator: false,
             full_function_device: false,
             mains_power: true,
             idle_receive: false,
+            fast_association: false,
             frame_protection: false,
             allocate_address: false,
+            reserved: 0,
         });
         let mut len = 0usize;
         data.write(&mut len, command).unwrap();
@@ -410,11 +1931,14 @@ mod tests {
         assert_eq!(data[..len], [0x01, 0x04]);
 
         let command = Command::AssociationRequest(CapabilityInformation {
+            alternate_pan_coordinator: false,
             full_function_device: false,
             mains_power: false,
             idle_receive: true,
+            fast_association: false,
             frame_protection: false,
             allocate_address: false,
+            reserved: 0,
         });
         let mut len = 0usize;
         data.write(&mut len, command).unwrap();
@@ -423,11 +1947,14 @@ mod tests {
         assert_eq!(data[..len], [0x01, 0x08]);
 
         let command = Command::AssociationRequest(CapabilityInformation {
+            alternate_pan_coordinator: false,
             full_function_device: false,
             mains_power: false,
             idle_receive: false,
+            fast_association: false,
             frame_protection: true,
             allocate_address: false,
+            reserved: 0,
         });
         let mut len = 0usize;
         data.write(&mut len, command).unwrap();
@@ -436,11 +1963,14 @@ mod tests {
         assert_eq!(data[..len], [0x01, 0x40]);
 
         let command = Command::AssociationRequest(CapabilityInformation {
+            alternate_pan_coordinator: false,
             full_function_device: false,
             mains_power: false,
             idle_receive: false,
+            fast_association: false,
             frame_protection: false,
             allocate_address: true,
+            reserved: 0,
         });
         let mut len = 0usize;
         data.write(&mut len, command).unwrap();
@@ -449,6 +1979,27 @@ mod tests {
         assert_eq!(data[..len], [0x01, 0x80]);
     }
 
+    #[test]
+    fn capability_information_bits() {
+        for bits in 0..=u8::MAX {
+            let capability = CapabilityInformation::from_bits(bits);
+            assert_eq!(capability.bits(), bits);
+
+            let command = Command::AssociationRequest(capability);
+            let mut data = [0u8; 2];
+            data.write(&mut 0, command).unwrap();
+            assert_eq!(data, [0x01, bits]);
+            assert_eq!(data.read::<Command>(&mut 0), Ok(command));
+        }
+
+        let capability = CapabilityInformation {
+            alternate_pan_coordinator: true,
+            fast_association: true,
+            ..Default::default()
+        };
+        assert_eq!(capability.bits(), 0x11);
+    }
+
     #[test]
     fn decode_association_response() {
         let data = [0x02, 0x40, 0x77, 0x00];
@@ -511,17 +2062,19 @@ mod tests {
             )
         );
 
-        let data = [0x02, 0x00, 0x00, 0x04];
-        let result = data.read::<Command>(&mut len);
-        assert!(result.is_err());
-
-        let data = [0x02, 0x00, 0x00, 0x7f];
-        let result = data.read::<Command>(&mut len);
-        assert!(result.is_err());
-
-        let data = [0x02, 0x00, 0x00, 0x81];
-        let result = data.read::<Command>(&mut len);
-        assert!(result.is_err());
+        for &status in &[0x04, 0x7f, 0x81] {
+            let data = [0x02, 0x00, 0x00, status];
+            let mut len = 0usize;
+            let command: Command = data.read(&mut len).unwrap();
+            assert_eq!(len, data.len());
+            assert_eq!(
+                command,
+                Command::AssociationResponse(
+                    ShortAddress(0x0000),
+                    AssociationStatus::Unknown(status)
+                )
+            );
+        }
     }
 
     #[test]
@@ -576,6 +2129,44 @@ mod tests {
 
         assert_eq!(len, data.len());
         assert_eq!(data[..len], [0x02, 0xf0, 0x0f, 0x80]);
+
+        let command = Command::AssociationResponse(
+            ShortAddress(0x0ff0),
+            AssociationStatus::Unknown(0x81),
+        );
+        let mut len = 0usize;
+        data.write(&mut len, command).unwrap();
+        assert_eq!(len, data.len());
+        assert_eq!(data[..len], [0x02, 0xf0, 0x0f, 0x81]);
+
+        assert!(AssociationStatus::FastAssociationSuccesful.is_successful());
+        assert!(!AssociationStatus::Unknown(0x81).is_successful());
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn arbitrary_association_status_round_trips() {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        for byte in 0..=u8::MAX {
+            let data = [byte];
+            let mut u = Unstructured::new(&data);
+            let status = AssociationStatus::arbitrary(&mut u).unwrap();
+            assert_eq!(AssociationStatus::from(u8::from(status)), status);
+        }
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn arbitrary_disassociation_reason_round_trips() {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        for byte in 0..=u8::MAX {
+            let data = [byte];
+            let mut u = Unstructured::new(&data);
+            let reason = DisassociationReason::arbitrary(&mut u).unwrap();
+            assert_eq!(DisassociationReason::from(u8::from(reason)), reason);
+        }
     }
 
     #[test]
@@ -602,13 +2193,22 @@ mod tests {
             )
         );
 
-        let data = [0x03, 0x00];
-        let result = data.read::<Command>(&mut len);
-        assert!(result.is_err());
-
-        let data = [0x03, 0x03];
-        let result = data.read::<Command>(&mut len);
-        assert!(result.is_err());
+        for &reason in &[0x00, 0x03, 0x80] {
+            let data = [0x03, reason];
+            let mut len = 0usize;
+            let command: Command = data.read(&mut len).unwrap();
+            assert_eq!(len, data.len());
+            assert_eq!(
+                command,
+                Command::DisassociationNotification(
+                    DisassociationReason::Unknown(reason)
+                )
+            );
+
+            let mut bytes = [0u8; 2];
+            bytes.write(&mut 0, command).unwrap();
+            assert_eq!(bytes, data);
+        }
     }
 
     #[test]
@@ -665,6 +2265,25 @@ mod tests {
                 channel_page: Some(1),
             })
         );
+
+        // Frames of version 2003 don't carry the channel page
+        let (command, len) =
+            Command::try_read_for_version(&data, FrameVersion::Ieee802154_2003)
+                .unwrap();
+        assert_eq!(len, data.len() - 1);
+        assert_eq!(
+            command,
+            Command::CoordinatorRealignment(CoordinatorRealignmentData {
+                pan_id: PanId(0x1234),
+                coordinator_address: ShortAddress(0x4321),
+                channel: 11,
+                device_address: ShortAddress(0xabcd),
+                channel_page: None,
+            })
+        );
+
+        // Truncated data
+        assert!(data[..7].read::<Command>(&mut 0).is_err());
     }
 
     #[test]
@@ -714,13 +2333,11 @@ mod tests {
         assert_eq!(len, data.len());
         assert_eq!(
             command,
-            Command::GuaranteedTimeSlotRequest(
-                GuaranteedTimeSlotCharacteristics {
-                    count: 1,
-                    receive_only: false,
-                    allocation: false,
-                }
-            )
+            Command::GuaranteedTimeSlotRequest(GtsCharacteristics {
+                length: 1,
+                direction: Direction::Transmit,
+                request_type: GtsRequestType::Deallocate,
+            })
         );
 
         let data = [0x09, 0x12];
@@ -729,13 +2346,11 @@ mod tests {
         assert_eq!(len, data.len());
         assert_eq!(
             command,
-            Command::GuaranteedTimeSlotRequest(
-                GuaranteedTimeSlotCharacteristics {
-                    count: 2,
-                    receive_only: true,
-                    allocation: false,
-                }
-            )
+            Command::GuaranteedTimeSlotRequest(GtsCharacteristics {
+                length: 2,
+                direction: Direction::Receive,
+                request_type: GtsRequestType::Deallocate,
+            })
         );
 
         let data = [0x09, 0x23];
@@ -744,13 +2359,11 @@ mod tests {
         assert_eq!(len, data.len());
         assert_eq!(
             command,
-            Command::GuaranteedTimeSlotRequest(
-                GuaranteedTimeSlotCharacteristics {
-                    count: 3,
-                    receive_only: false,
-                    allocation: true,
-                }
-            )
+            Command::GuaranteedTimeSlotRequest(GtsCharacteristics {
+                length: 3,
+                direction: Direction::Transmit,
+                request_type: GtsRequestType::Allocate,
+            })
         );
     }
 
@@ -758,13 +2371,11 @@ mod tests {
     fn encode_guaranteed_time_slot_request() {
         let mut data = [0u8; 32];
 
-        let command = Command::GuaranteedTimeSlotRequest(
-            GuaranteedTimeSlotCharacteristics {
-                count: 1,
-                receive_only: false,
-                allocation: false,
-            },
-        );
+        let command = Command::GuaranteedTimeSlotRequest(GtsCharacteristics {
+            length: 1,
+            direction: Direction::Transmit,
+            request_type: GtsRequestType::Deallocate,
+        });
         let mut len = 0usize;
         data.write(&mut len, command).unwrap();
 
@@ -772,26 +2383,22 @@ mod tests {
 
         assert_eq!(data[..len], [0x09, 0x01]);
 
-        let command = Command::GuaranteedTimeSlotRequest(
-            GuaranteedTimeSlotCharacteristics {
-                count: 15,
-                receive_only: true,
-                allocation: false,
-            },
-        );
+        let command = Command::GuaranteedTimeSlotRequest(GtsCharacteristics {
+            length: 15,
+            direction: Direction::Receive,
+            request_type: GtsRequestType::Deallocate,
+        });
         let mut len = 0usize;
         data.write(&mut len, command).unwrap();
 
         assert_eq!(len, 2);
         assert_eq!(data[..len], [0x09, 0x1f]);
 
-        let command = Command::GuaranteedTimeSlotRequest(
-            GuaranteedTimeSlotCharacteristics {
-                count: 15,
-                receive_only: false,
-                allocation: true,
-            },
-        );
+        let command = Command::GuaranteedTimeSlotRequest(GtsCharacteristics {
+            length: 15,
+            direction: Direction::Transmit,
+            request_type: GtsRequestType::Allocate,
+        });
         let mut len = 0usize;
         data.write(&mut len, command).unwrap();
 
@@ -799,6 +2406,52 @@ mod tests {
         assert_eq!(data[..len], [0x09, 0x2f]);
     }
 
+    #[test]
+    fn gts_characteristics_builder() {
+        for &length in &[1, MAX_GTS_LENGTH] {
+            for &direction in &[Direction::Transmit, Direction::Receive] {
+                for &request_type in
+                    &[GtsRequestType::Allocate, GtsRequestType::Deallocate]
+                {
+                    let characteristics = GtsCharacteristics::builder(length)
+                        .direction(direction)
+                        .request_type(request_type)
+                        .build()
+                        .unwrap();
+                    assert_eq!(
+                        characteristics,
+                        GtsCharacteristics {
+                            length,
+                            direction,
+                            request_type,
+                        }
+                    );
+
+                    let command =
+                        Command::GuaranteedTimeSlotRequest(characteristics);
+                    let mut data = [0u8; 2];
+                    let mut len = 0usize;
+                    data.write(&mut len, command).unwrap();
+                    assert_eq!(len, 2);
+                    assert_eq!(data.read::<Command>(&mut 0), Ok(command));
+                }
+            }
+        }
+
+        assert!(GtsCharacteristics::builder(0).build().is_none());
+        assert!(GtsCharacteristics::builder(MAX_GTS_LENGTH + 1)
+            .build()
+            .is_none());
+        // Reserved bits are ignored
+        assert_eq!(
+            GtsCharacteristics::from(0xc1),
+            GtsCharacteristics::builder(1)
+                .request_type(GtsRequestType::Deallocate)
+                .build()
+                .unwrap()
+        );
+    }
+
     #[test]
     fn decode_other_commands() {
         let data = [0x04];
@@ -858,4 +2511,293 @@ mod tests {
         assert_eq!(len, 1);
         assert_eq!(data[..len], [0x07]);
     }
+
+    #[test]
+    fn decode_encode_2015_commands() {
+        for (data, command) in [
+            (&[0x0a][..], Command::TrleManagementRequest),
+            (&[0x13][..], Command::DsmeAssociationRequest),
+            (&[0x17][..], Command::DsmeGtsNotify),
+            (
+                &[0x1a, 0x34, 0x12][..],
+                Command::DsmeBeaconAllocationNotification(0x1234),
+            ),
+            (
+                &[0x1b, 0x01, 0x00][..],
+                Command::DsmeBeaconCollisionNotification(0x0001),
+            ),
+            (&[0x20][..], Command::RitDataRequest),
+            (&[0x22][..], Command::DbsResponse),
+            (
+                &[0x24, 0x00, 0x12, 0x4b][..],
+                Command::VendorSpecific([0x00, 0x12, 0x4b]),
+            ),
+            (&[0x28][..], Command::SrmInformation),
+        ] {
+            let mut len = 0usize;
+            let decoded: Command = data.read(&mut len).unwrap();
+            assert_eq!(len, data.len());
+            assert_eq!(decoded, command);
+
+            let mut buf = [0u8; 8];
+            let mut len = 0usize;
+            buf.write(&mut len, command).unwrap();
+            assert_eq!(&buf[..len], data);
+            assert_eq!(len, command.get_octet_size());
+        }
+
+        // Truncated fields
+        assert!([0x1a, 0x34].read::<Command>(&mut 0).is_err());
+        assert!([0x24, 0x00, 0x12].read::<Command>(&mut 0).is_err());
+    }
+
+    #[test]
+    fn command_octet_size() {
+        let commands = [
+            Command::AssociationRequest(CapabilityInformation::from_bits(0x8e)),
+            Command::AssociationResponse(
+                ShortAddress(0x1234),
+                AssociationStatus::Successful,
+            ),
+            Command::DisassociationNotification(
+                DisassociationReason::DeviceLeave,
+            ),
+            Command::DataRequest,
+            Command::CoordinatorRealignment(CoordinatorRealignmentData {
+                pan_id: PanId(0xabcd),
+                coordinator_address: ShortAddress(0x0000),
+                channel: 11,
+                device_address: ShortAddress(0x0001),
+                channel_page: Some(0),
+            }),
+            Command::GuaranteedTimeSlotRequest(
+                GtsCharacteristics::builder(2).build().unwrap(),
+            ),
+            Command::Unknown(0xc0),
+        ];
+        for command in commands {
+            let mut buf = [0u8; 16];
+            let mut len = 0usize;
+            buf.write(&mut len, command).unwrap();
+            assert_eq!(len, command.get_octet_size());
+        }
+    }
+
+    #[test]
+    fn dsme_gts_commands() {
+        let management = DsmeGtsManagement {
+            management_type: DsmeGtsManagementType::Deallocation,
+            direction: Direction::Receive,
+            prioritized_channel_access: true,
+            status: 5,
+        };
+        assert_eq!(management.bits(), 0xb8);
+        assert_eq!(DsmeGtsManagement::from_bits(0xb8), management);
+        assert_eq!(
+            DsmeGtsManagement::from_bits(0x07).management_type,
+            DsmeGtsManagementType::Unknown(7)
+        );
+        for bits in 0..=u8::MAX {
+            assert_eq!(DsmeGtsManagement::from_bits(bits).bits(), bits);
+        }
+
+        let reply = DsmeGtsAllocation {
+            management,
+            destination: ShortAddress(0x1234),
+            channel_offset: 0x0002,
+            sab: DsmeSabSpecification {
+                sub_block_length: 1,
+                sub_block_index: 0x0100,
+                sub_block: &[0x81],
+            },
+        };
+        assert!(reply.sab.is_allocated(0));
+        assert!(!reply.sab.is_allocated(1));
+        assert!(reply.sab.is_allocated(7));
+        assert!(!reply.sab.is_allocated(8));
+
+        let mut bytes = [0u8; 16];
+        let mut len = 0usize;
+        bytes.write(&mut len, reply).unwrap();
+        assert_eq!(len, reply.get_octet_size());
+        assert_eq!(
+            bytes[..len],
+            [0xb8, 0x34, 0x12, 0x02, 0x00, 0x01, 0x00, 0x01, 0x81]
+        );
+        assert_eq!(bytes[..len].read::<DsmeGtsAllocation>(&mut 0), Ok(reply));
+
+        // The SAB specification is missing
+        assert!(bytes[..5].read::<DsmeGtsAllocation>(&mut 0).is_err());
+        assert!([0x01, 0x02, 0x03, 0x00, 0x04]
+            .read::<DsmeGtsRequest>(&mut 0)
+            .is_err());
+    }
+
+    #[test]
+    fn unknown_command() {
+        use crate::mac::{FooterMode, TrailingBytes};
+
+        let data = [
+            0x43, 0xc8, // frame control
+            0x01, // sequence number
+            0xff, 0xff, 0xff, 0xff, // destination
+            0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01, // source
+            0x0c, // reserved command identifier
+            0xde, 0xad, 0xbe, 0xef, // payload
+        ];
+        let frame: Frame = data.read_with(&mut 0, FooterMode::None).unwrap();
+        assert_eq!(
+            frame.content,
+            FrameContent::Command(Command::Unknown(0x0c))
+        );
+        assert_eq!(frame.payload, [0xde, 0xad, 0xbe, 0xef]);
+        // The payload is the fields of the command, not trailing bytes
+        let strict: Frame = data
+            .read_with(&mut 0, (FooterMode::None, TrailingBytes::Reject))
+            .unwrap();
+        assert_eq!(strict, frame);
+
+        let command = Command::Unknown(0xc0);
+        assert_eq!(command.command_id(), None);
+        assert_eq!(command.id(), 0xc0);
+        assert_eq!(Command::DataRequest.id(), 0x04);
+
+        let mut buf = [0u8; 32];
+        let mut len = 0usize;
+        buf.write_with(&mut len, frame, FooterMode::None).unwrap();
+        assert_eq!(buf[..len], data);
+    }
+
+    #[test]
+    fn orphan_realignment_frame() {
+        use crate::mac::FooterMode;
+
+        let realignment = OrphanRealignmentFrame {
+            seq: 1,
+            orphan: ExtendedAddress(0x0102_0304_0506_0708),
+            coordinator: ExtendedAddress(0x1112_1314_1516_1718),
+            data: CoordinatorRealignmentData {
+                pan_id: PanId(0xabcd),
+                coordinator_address: ShortAddress(0x0001),
+                channel: 26,
+                device_address: ShortAddress(0xfffe),
+                channel_page: Some(0),
+            },
+        };
+        let frame = realignment.to_frame();
+        assert_eq!(frame.header.version, FrameVersion::Ieee802154_2006);
+
+        let mut bytes = [0u8; 40];
+        let mut len = 0usize;
+        bytes.write_with(&mut len, frame, FooterMode::None).unwrap();
+        let decoded: Frame =
+            bytes[..len].read_with(&mut 0, FooterMode::None).unwrap();
+        assert_eq!(decoded.header, frame.header);
+        assert_eq!(decoded.content, frame.content);
+        assert!(decoded.payload.is_empty());
+
+        // Not an orphan notification
+        assert_eq!(OrphanNotificationFrame::from_frame(&decoded), None);
+    }
+
+    #[test]
+    fn association_handshake_frames() {
+        use crate::mac::FooterMode;
+
+        let device = ExtendedAddress(0x0102_0304_0506_0708);
+        let coordinator = ExtendedAddress(0x1112_1314_1516_1718);
+        let request = AssociationRequestFrame {
+            seq: 1,
+            coordinator: Address::Extended(PanId(0xabcd), coordinator),
+            device,
+            capability: CapabilityInformation {
+                allocate_address: true,
+                ..Default::default()
+            },
+        };
+        let frame = request.to_frame();
+        assert_eq!(frame.header.frame_type, FrameType::MacCommand);
+        assert!(frame.header.ack_request);
+        assert!(!frame.header.pan_id_compress);
+
+        let mut bytes = [0u8; 40];
+        let mut len = 0usize;
+        bytes.write_with(&mut len, frame, FooterMode::None).unwrap();
+        let decoded: Frame =
+            bytes[..len].read_with(&mut 0, FooterMode::None).unwrap();
+        assert_eq!(
+            AssociationRequestFrame::from_frame(&decoded),
+            Some(request)
+        );
+        assert_eq!(AssociationResponseFrame::from_frame(&decoded), None);
+
+        let response = AssociationResponseFrame {
+            seq: 2,
+            pan_id: PanId(0xabcd),
+            coordinator,
+            device,
+            short_address: ShortAddress::broadcast(),
+            status: AssociationStatus::NetworkAtCapacity,
+        };
+        let mut len = 0usize;
+        bytes
+            .write_with(&mut len, response.to_frame(), FooterMode::None)
+            .unwrap();
+        let decoded: Frame =
+            bytes[..len].read_with(&mut 0, FooterMode::None).unwrap();
+        assert_eq!(
+            AssociationResponseFrame::from_frame(&decoded),
+            Some(response)
+        );
+        assert_eq!(AssociationRequestFrame::from_frame(&decoded), None);
+    }
+
+    #[test]
+    fn data_request_frame() {
+        let request = DataRequestFrame {
+            seq: 7,
+            coordinator: Address::Short(PanId(0x1234), ShortAddress(0x0000)),
+            source: Address::Short(PanId(0x4321), ShortAddress(0x0001)),
+        };
+        let mut frame = request.to_frame();
+        assert!(frame.header.ack_request);
+        assert!(!frame.header.pan_id_compress);
+
+        // The acknowledgement of another frame
+        frame.header.frame_type = FrameType::Acknowledgement;
+        frame.header.frame_pending = true;
+        frame.header.seq = 8;
+        assert!(!request.keep_receiver_on(&frame));
+        // A data frame with more data pending
+        frame.header.frame_type = FrameType::Data;
+        assert!(request.keep_receiver_on(&frame));
+        frame.header.frame_pending = false;
+        assert!(!request.keep_receiver_on(&frame));
+    }
+
+    #[test]
+    fn secured_pan_id_conflict_notification_frame() {
+        use crate::mac::frame::security::{KeyIdentifier, SecurityLevel};
+
+        let notification = PanIdConflictNotificationFrame {
+            seq: 5,
+            pan_id: PanId(0xabcd),
+            coordinator: ExtendedAddress(0x1112_1314_1516_1718),
+            device: ExtendedAddress(0x0102_0304_0506_0708),
+            security: SecurityParameters::new(
+                SecurityLevel::ENCMIC32,
+                Some(KeyIdentifier {
+                    key_source: None,
+                    key_index: 1,
+                }),
+            ),
+        };
+        let frame = notification.to_frame();
+        assert!(frame.header.has_security());
+        assert_eq!(frame.header.version, FrameVersion::Ieee802154_2006);
+        assert_eq!(
+            frame.header.destination,
+            Some(Address::Extended(PanId(0xabcd), notification.coordinator))
+        );
+    }
 }
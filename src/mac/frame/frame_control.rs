@@ -8,6 +8,9 @@ use super::DecodeError;
 ///
 /// [`Header`]: super::header::Header
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum FrameType {
     /// Beacon
     Beacon = 0b000,
@@ -35,7 +38,7 @@ impl FrameType {
     /// let frame_type = FrameType::from_bits(0b001);
     /// assert_eq!(frame_type, Some(FrameType::Data));
     /// ```
-    pub fn from_bits(bits: u8) -> Option<Self> {
+    pub const fn from_bits(bits: u8) -> Option<Self> {
         match bits {
             0b000 => Some(FrameType::Beacon),
             0b001 => Some(FrameType::Data),
@@ -48,6 +51,9 @@ impl FrameType {
 
 /// Defines version information for a frame
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum FrameVersion {
     /// A frame conforming to the 802.15.4-2003 standard
     Ieee802154_2003 = 0b00,
@@ -71,7 +77,7 @@ impl FrameVersion {
     /// let version = FrameVersion::from_bits(0b0);
     /// assert_eq!(version, Some(FrameVersion::Ieee802154_2003));
     /// ```
-    pub fn from_bits(bits: u8) -> Option<Self> {
+    pub const fn from_bits(bits: u8) -> Option<Self> {
         match bits {
             0b00 => Some(FrameVersion::Ieee802154_2003),
             0b01 => Some(FrameVersion::Ieee802154_2006),
@@ -92,6 +98,9 @@ impl FrameVersion {
 /// assert_eq!(address_mode, AddressMode::Short);
 /// ```
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum AddressMode {
     /// PAN identifier and address field are not present
     None = 0b00,
@@ -134,7 +143,7 @@ impl AddressMode {
     /// let address_mode = AddressMode::from_bits(0b10).unwrap();
     /// assert_eq!(address_mode, AddressMode::Short);
     /// ```
-    pub fn from_bits(bits: u8) -> Result<Self, DecodeError> {
+    pub const fn from_bits(bits: u8) -> Result<Self, DecodeError> {
         match bits {
             0b00 => Ok(AddressMode::None),
             0b10 => Ok(AddressMode::Short),
@@ -142,6 +151,155 @@ impl AddressMode {
             _ => Err(DecodeError::InvalidAddressMode(bits)),
         }
     }
+
+    /// Get the size of an address in this mode, in octets
+    ///
+    /// This does not include the size of the PAN identifier that precedes
+    /// the address.
+    pub const fn address_octet_size(&self) -> usize {
+        match self {
+            AddressMode::None => 0,
+            AddressMode::Short => 2,
+            AddressMode::Extended => 8,
+        }
+    }
+}
+
+/// The decoded Frame Control field of a MAC frame
+///
+/// All conversions are `const fn`, so this type can be used to build RX
+/// filter tables or to check frame control encodings at compile time.
+///
+/// # Example
+///
+/// ``` rust
+/// use ieee802154::mac::{AddressMode, FrameControl, FrameType, FrameVersion};
+///
+/// const DATA_FRAME: FrameControl = match FrameControl::from_bits(0x9801) {
+///     Ok(fc) => fc,
+///     Err(_) => panic!(),
+/// };
+///
+/// assert_eq!(DATA_FRAME.frame_type, FrameType::Data);
+/// assert_eq!(DATA_FRAME.version, FrameVersion::Ieee802154_2006);
+/// assert_eq!(DATA_FRAME.dest_addr_mode, AddressMode::Short);
+/// assert_eq!(DATA_FRAME.src_addr_mode, AddressMode::Short);
+/// assert_eq!(DATA_FRAME.to_bits(), 0x9801);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct FrameControl {
+    /// Frame Type
+    pub frame_type: FrameType,
+    /// Security Enabled
+    pub security: bool,
+    /// Frame Pending
+    pub frame_pending: bool,
+    /// Acknowledgement Request
+    pub ack_request: bool,
+    /// PAN ID Compress
+    pub pan_id_compress: bool,
+    /// Information Elements Present
+    pub ie_present: bool,
+    /// Destination address mode
+    pub dest_addr_mode: AddressMode,
+    /// Frame version
+    pub version: FrameVersion,
+    /// Source address mode
+    pub src_addr_mode: AddressMode,
+}
+
+impl FrameControl {
+    /// Decode the Frame Control field from its (little endian) 16-bit value
+    ///
+    /// Returns an error, if the frame type, frame version or one of the
+    /// address modes is invalid.
+    pub const fn from_bits(bits: u16) -> Result<Self, DecodeError> {
+        let frame_type =
+            ((bits & mask::FRAME_TYPE) >> offset::FRAME_TYPE) as u8;
+        let version = ((bits & mask::VERSION) >> offset::VERSION) as u8;
+        let dest_addr_mode =
+            ((bits & mask::DEST_ADDR_MODE) >> offset::DEST_ADDR_MODE) as u8;
+        let src_addr_mode =
+            ((bits & mask::SRC_ADDR_MODE) >> offset::SRC_ADDR_MODE) as u8;
+
+        let version = match FrameVersion::from_bits(version) {
+            Some(version) => version,
+            None => return Err(DecodeError::InvalidFrameVersion(version)),
+        };
+        let frame_type = match FrameType::from_bits(frame_type) {
+            Some(frame_type) => frame_type,
+            None => return Err(DecodeError::InvalidFrameType(frame_type)),
+        };
+        let dest_addr_mode = match AddressMode::from_bits(dest_addr_mode) {
+            Ok(mode) => mode,
+            Err(e) => return Err(e),
+        };
+        let src_addr_mode = match AddressMode::from_bits(src_addr_mode) {
+            Ok(mode) => mode,
+            Err(e) => return Err(e),
+        };
+
+        Ok(FrameControl {
+            frame_type,
+            security: bits & mask::SECURITY != 0,
+            frame_pending: bits & mask::PENDING != 0,
+            ack_request: bits & mask::ACK != 0,
+            pan_id_compress: bits & mask::PAN_ID_COMPRESS != 0,
+            ie_present: bits & mask::IE_PRESENT != 0,
+            dest_addr_mode,
+            version,
+            src_addr_mode,
+        })
+    }
+
+    /// Get the size of the header fields described by this frame control
+    /// field, in octets
+    ///
+    /// This is the size of the frame control field, the sequence number and
+    /// the addressing fields. It does not include the auxiliary security
+    /// header, the size of which is determined by its own security control
+    /// field.
+    ///
+    /// # Example
+    ///
+    /// ``` rust
+    /// use ieee802154::mac::FrameControl;
+    ///
+    /// const HEADER_LEN: usize = match FrameControl::from_bits(0x9841) {
+    ///     Ok(fc) => fc.header_octet_size(),
+    ///     Err(_) => panic!(),
+    /// };
+    /// assert_eq!(HEADER_LEN, 9);
+    /// ```
+    pub const fn header_octet_size(&self) -> usize {
+        let mut len = 2 + 1;
+        if !matches!(self.dest_addr_mode, AddressMode::None) {
+            len += 2 + self.dest_addr_mode.address_octet_size();
+        }
+        if !matches!(self.src_addr_mode, AddressMode::None) {
+            if !self.pan_id_compress {
+                len += 2;
+            }
+            len += self.src_addr_mode.address_octet_size();
+        }
+        len
+    }
+
+    /// Encode the Frame Control field into its (little endian) 16-bit value
+    pub const fn to_bits(&self) -> u16 {
+        (self.frame_type as u16) << offset::FRAME_TYPE
+            | (self.security as u16) << offset::SECURITY
+            | (self.frame_pending as u16) << offset::PENDING
+            | (self.ack_request as u16) << offset::ACK
+            | (self.pan_id_compress as u16) << offset::PAN_ID_COMPRESS
+            | (self.ie_present as u16) << offset::IE_PRESENT
+            | (self.dest_addr_mode as u16) << offset::DEST_ADDR_MODE
+            | (self.version as u16) << offset::VERSION
+            | (self.src_addr_mode as u16) << offset::SRC_ADDR_MODE
+    }
 }
 
 pub mod offset {
@@ -150,6 +308,7 @@ pub mod offset {
     pub const PENDING: u16 = 4;
     pub const ACK: u16 = 5;
     pub const PAN_ID_COMPRESS: u16 = 6;
+    pub const IE_PRESENT: u16 = 9;
     pub const DEST_ADDR_MODE: u16 = 10;
     pub const VERSION: u16 = 12;
     pub const SRC_ADDR_MODE: u16 = 14u16;
@@ -161,6 +320,7 @@ pub mod mask {
     pub const PENDING: u16 = 0x0010;
     pub const ACK: u16 = 0x0020;
     pub const PAN_ID_COMPRESS: u16 = 0x0040;
+    pub const IE_PRESENT: u16 = 0x0200;
     pub const DEST_ADDR_MODE: u16 = 0x0C00;
     pub const VERSION: u16 = 0x3000;
     pub const SRC_ADDR_MODE: u16 = 0xC000;
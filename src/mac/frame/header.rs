@@ -5,16 +5,21 @@
 //! [`Header`]: struct.Header.html
 
 use byte::{check_len, BytesExt, TryRead, TryWrite, LE};
+#[cfg(feature = "security")]
 use cipher::{consts::U16, BlockCipher, NewBlockCipher};
 use hash32_derive::Hash32;
 
-pub use super::frame_control::{AddressMode, FrameType, FrameVersion};
-use super::DecodeError;
-use super::{
-    frame_control::{mask, offset},
-    security::{KeyDescriptorLookup, SecurityContext},
+pub use super::frame_control::{
+    AddressMode, FrameControl, FrameType, FrameVersion,
+};
+#[cfg(feature = "security")]
+use super::security::{
+    AsnProvider, CcmBackend, FrameCounterStorage, KeyDescriptorLookup,
+    NonceGenerator, SecurityContext, SecurityStatistics,
 };
 use super::{security::AuxiliarySecurityHeader, EncodeError};
+#[cfg(feature = "security")]
+use core::borrow::BorrowMut;
 
 /// MAC frame header
 ///
@@ -22,6 +27,8 @@ use super::{security::AuxiliarySecurityHeader, EncodeError};
 ///
 /// [MAC frame format start at 5.2]: http://ecee.colorado.edu/~liue/teaching/comm_standards/2015S_zigbee/802.15.4-2011.pdf
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Header {
     // * Frame Control Field * /
     /// Frame Type
@@ -51,6 +58,15 @@ pub struct Header {
     /// present.
     pub pan_id_compress: bool,
 
+    /// Information Elements Present
+    ///
+    /// Whether the frame contains Information Elements, which can only be the
+    /// case for frames of version [`FrameVersion::Ieee802154`]. The IEs are
+    /// part of the payload of the [`Frame`].
+    ///
+    /// [`Frame`]: super::Frame
+    pub ie_present: bool,
+
     /// Frame version
     pub version: FrameVersion,
 
@@ -77,25 +93,28 @@ pub struct Header {
 
 impl Header {
     /// Get the size of this header in octets
+    ///
+    /// This includes the auxiliary security header, if present.
     pub fn get_octet_size(&self) -> usize {
-        // Frame control + sequence number
-        let mut len = 3;
-
-        for i in [self.destination, self.source].iter() {
-            match i {
-                Some(addr) => {
-                    // pan ID
-                    len += 2;
-                    // Address length
-                    match addr {
-                        Address::Short(..) => len += 2,
-                        Address::Extended(..) => len += 8,
-                    }
-                }
-                _ => {}
-            }
+        let aux_len = match self.auxiliary_security_header {
+            Some(aux_sec_header) => aux_sec_header.get_octet_size(),
+            None => 0,
+        };
+        self.frame_control().header_octet_size() + aux_len
+    }
+
+    fn frame_control(&self) -> FrameControl {
+        FrameControl {
+            frame_type: self.frame_type,
+            security: self.auxiliary_security_header.is_some(),
+            frame_pending: self.frame_pending,
+            ack_request: self.ack_request,
+            pan_id_compress: self.pan_id_compress,
+            ie_present: self.ie_present,
+            dest_addr_mode: AddressMode::from(self.destination),
+            version: self.version,
+            src_addr_mode: AddressMode::from(self.source),
         }
-        len
     }
 
     /// Whether this header has security enabled
@@ -104,6 +123,44 @@ impl Header {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Header {
+    fn arbitrary(
+        u: &mut arbitrary::Unstructured<'a>,
+    ) -> arbitrary::Result<Self> {
+        let destination: Option<Address> = u.arbitrary()?;
+        let mut source: Option<Address> = u.arbitrary()?;
+
+        // PAN ID compression requires both addresses, and implies that they
+        // share the destination's PAN ID
+        let pan_id_compress =
+            destination.is_some() && source.is_some() && u.arbitrary()?;
+        if let (true, Some(dst), Some(src)) =
+            (pan_id_compress, destination, source)
+        {
+            source = Some(match src {
+                Address::Short(_, addr) => Address::Short(dst.pan_id(), addr),
+                Address::Extended(_, addr) => {
+                    Address::Extended(dst.pan_id(), addr)
+                }
+            });
+        }
+
+        Ok(Header {
+            frame_type: u.arbitrary()?,
+            frame_pending: u.arbitrary()?,
+            ack_request: u.arbitrary()?,
+            pan_id_compress,
+            ie_present: u.arbitrary()?,
+            version: u.arbitrary()?,
+            seq: u.arbitrary()?,
+            destination,
+            source,
+            auxiliary_security_header: u.arbitrary()?,
+        })
+    }
+}
+
 impl TryRead<'_> for Header {
     fn try_read(bytes: &[u8], _ctx: ()) -> byte::Result<(Self, usize)> {
         let offset = &mut 0;
@@ -112,34 +169,17 @@ impl TryRead<'_> for Header {
 
         /* Decode Frame Control Field */
         let bits: u16 = bytes.read_with(offset, LE)?;
-
-        let frame_type =
-            ((bits & mask::FRAME_TYPE) >> offset::FRAME_TYPE) as u8;
-        let security = ((bits & mask::SECURITY) >> offset::SECURITY) as u8;
-
-        let frame_pending = ((bits & mask::PENDING) >> offset::PENDING) as u8;
-        let ack_request = ((bits & mask::ACK) >> offset::ACK) as u8;
-        let pan_id_compress =
-            ((bits & mask::PAN_ID_COMPRESS) >> offset::PAN_ID_COMPRESS) as u8;
-
-        let dest_addr_mode =
-            ((bits & mask::DEST_ADDR_MODE) >> offset::DEST_ADDR_MODE) as u8;
-        let version = ((bits & mask::VERSION) >> offset::VERSION) as u8;
-        let src_addr_mode =
-            ((bits & mask::SRC_ADDR_MODE) >> offset::SRC_ADDR_MODE) as u8;
-
-        let version = FrameVersion::from_bits(version)
-            .ok_or(DecodeError::InvalidFrameVersion(version))?;
-        let frame_type = FrameType::from_bits(frame_type)
-            .ok_or(DecodeError::InvalidFrameType(frame_type))?;
-        let dest_addr_mode = AddressMode::from_bits(dest_addr_mode)?;
-        let src_addr_mode = AddressMode::from_bits(src_addr_mode)?;
-
-        // make bool values
-        let security = security > 0;
-        let frame_pending = frame_pending > 0;
-        let ack_request = ack_request > 0;
-        let pan_id_compress = pan_id_compress > 0;
+        let FrameControl {
+            frame_type,
+            security,
+            frame_pending,
+            ack_request,
+            pan_id_compress,
+            ie_present,
+            dest_addr_mode,
+            version,
+            src_addr_mode,
+        } = FrameControl::from_bits(bits)?;
 
         /* Decode header depending on Frame Control Fields */
 
@@ -202,6 +242,7 @@ impl TryRead<'_> for Header {
             frame_pending,
             ack_request,
             pan_id_compress,
+            ie_present,
             version,
             seq,
             destination,
@@ -213,37 +254,27 @@ impl TryRead<'_> for Header {
     }
 }
 
-impl<AEADBLKCIPH, KEYDESCLO>
-    TryWrite<&Option<&mut SecurityContext<AEADBLKCIPH, KEYDESCLO>>> for Header
-where
-    AEADBLKCIPH: NewBlockCipher + BlockCipher<BlockSize = U16>,
-    KEYDESCLO: KeyDescriptorLookup<AEADBLKCIPH::KeySize>,
-{
-    fn try_write(
+impl Header {
+    /// Write the header, using `frame_counter` as the frame counter of the
+    /// auxiliary security header
+    fn write_with_frame_counter(
         self,
         bytes: &mut [u8],
-        sec_ctx: &Option<&mut SecurityContext<AEADBLKCIPH, KEYDESCLO>>,
+        frame_counter: Option<u32>,
     ) -> byte::Result<usize> {
         let offset = &mut 0;
-        let dest_addr_mode = AddressMode::from(self.destination);
-        let src_addr_mode = AddressMode::from(self.source);
-
-        let security = self.auxiliary_security_header.is_some();
+        let frame_control = self.frame_control();
+        let security = frame_control.security;
 
-        let frame_control_raw = (self.frame_type as u16) << offset::FRAME_TYPE
-            | (security as u16) << offset::SECURITY
-            | (self.frame_pending as u16) << offset::PENDING
-            | (self.ack_request as u16) << offset::ACK
-            | (self.pan_id_compress as u16) << offset::PAN_ID_COMPRESS
-            | (dest_addr_mode as u16) << offset::DEST_ADDR_MODE
-            | (self.version as u16) << offset::VERSION
-            | (src_addr_mode as u16) << offset::SRC_ADDR_MODE;
-
-        bytes.write_with(offset, frame_control_raw, LE)?;
+        bytes.write_with(offset, frame_control.to_bits(), LE)?;
 
         // Write Sequence Number
         bytes.write(offset, self.seq)?;
 
+        if self.pan_id_compress && self.destination.is_none() {
+            return Err(EncodeError::InvalidPanIdCompress)?;
+        }
+
         // Write addresses
         if let Some(destination) = self.destination {
             bytes.write_with(offset, destination, AddressEncoding::Normal)?;
@@ -260,29 +291,90 @@ where
             (Some(source), false) => {
                 bytes.write_with(offset, source, AddressEncoding::Normal)?;
             }
-            (None, true) => {
-                panic!("frame control request compress source address without contain this address")
-            }
+            (None, true) => return Err(EncodeError::InvalidPanIdCompress)?,
             (None, false) => (),
         }
 
-        if security && sec_ctx.is_none() {
+        if security && frame_counter.is_none() {
             return Err(EncodeError::MissingSecurityCtx)?;
         } else if security {
-            match self.auxiliary_security_header {
-                Some(aux_sec_head) => match sec_ctx {
-                    Some(sec_ctx) => {
-                        bytes.write_with(offset, aux_sec_head, sec_ctx)?;
-                    }
-                    None => return Err(EncodeError::UnknownError)?,
-                },
-                None => return Err(EncodeError::UnknownError)?,
+            match (self.auxiliary_security_header, frame_counter) {
+                (Some(aux_sec_head), Some(frame_counter)) => {
+                    *offset += aux_sec_head.write_with_frame_counter(
+                        &mut bytes[*offset..],
+                        frame_counter,
+                    )?;
+                }
+                _ => return Err(EncodeError::UnknownError)?,
             }
         }
         Ok(*offset)
     }
 }
 
+/// Writes a header without security
+///
+/// Writing a header that has an auxiliary security header fails with
+/// [`EncodeError::MissingSecurityCtx`], as there is no frame counter to write.
+impl TryWrite for Header {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        self.write_with_frame_counter(bytes, None)
+    }
+}
+
+#[cfg(feature = "security")]
+impl<
+        AEADBLKCIPH,
+        KEYDESCLO,
+        FRAMECTR,
+        CCMBACKEND,
+        ASNPROV,
+        NONCEGEN,
+        STATS,
+    >
+    TryWrite<
+        &Option<
+            &mut SecurityContext<
+                AEADBLKCIPH,
+                KEYDESCLO,
+                FRAMECTR,
+                CCMBACKEND,
+                ASNPROV,
+                NONCEGEN,
+                STATS,
+            >,
+        >,
+    > for Header
+where
+    AEADBLKCIPH: NewBlockCipher + BlockCipher<BlockSize = U16>,
+    KEYDESCLO: KeyDescriptorLookup<AEADBLKCIPH::KeySize>,
+    FRAMECTR: FrameCounterStorage,
+    CCMBACKEND: CcmBackend<AEADBLKCIPH::KeySize>,
+    ASNPROV: AsnProvider,
+    NONCEGEN: NonceGenerator,
+    STATS: BorrowMut<SecurityStatistics>,
+{
+    fn try_write(
+        self,
+        bytes: &mut [u8],
+        sec_ctx: &Option<
+            &mut SecurityContext<
+                AEADBLKCIPH,
+                KEYDESCLO,
+                FRAMECTR,
+                CCMBACKEND,
+                ASNPROV,
+                NONCEGEN,
+                STATS,
+            >,
+        >,
+    ) -> byte::Result<usize> {
+        let frame_counter =
+            sec_ctx.as_ref().map(|sec_ctx| sec_ctx.frame_counter.load());
+        self.write_with_frame_counter(bytes, frame_counter)
+    }
+}
+
 /// Personal Area Network Identifier
 ///
 /// A 16-bit value that identifies a PAN
@@ -295,6 +387,9 @@ where
 /// let pan_id = PanId(0x0123);
 /// ```
 #[derive(Clone, Copy, Debug, Eq, Hash, Hash32, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct PanId(pub u16);
 
 impl PanId {
@@ -332,6 +427,9 @@ impl TryRead<'_> for PanId {
 /// let short_address = ShortAddress(0x0123);
 /// ```
 #[derive(Clone, Copy, Debug, Eq, Hash, Hash32, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ShortAddress(pub u16);
 
 impl ShortAddress {
@@ -371,6 +469,9 @@ impl TryRead<'_> for ShortAddress {
 /// let ext_address = ExtendedAddress(0x0123456789abcdef);
 /// ```
 #[derive(Clone, Copy, Debug, Eq, Hash, Hash32, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ExtendedAddress(pub u64);
 
 impl ExtendedAddress {
@@ -400,6 +501,9 @@ impl TryRead<'_> for ExtendedAddress {
 
 /// An address that might contain an PAN ID and address
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Address {
     /// Short (16-bit) address and PAN ID (16-bit)
     Short(PanId, ShortAddress),
@@ -11,21 +11,37 @@
 // - change &[u8] => bytes::Buf
 // - remove one variant enums
 
-use crate::mac::beacon::Beacon;
+use crate::mac::beacon::{
+    Beacon, SuperframeSpecificationError, MAX_GTS_DESCRIPTORS,
+    MAX_PENDING_ADDRESSES,
+};
 use crate::mac::command::Command;
+use crate::mac::ie::{
+    DsmePanDescriptorIe, EncodedInformationElements, HeaderIe,
+};
 
 mod frame_control;
 pub mod header;
 pub mod security;
-use byte::{ctx::Bytes, BytesExt, TryRead, TryWrite, LE};
+use byte::{check_len, ctx::Bytes, BytesExt, TryRead, TryWrite, LE};
+#[cfg(feature = "security")]
 use ccm::aead::generic_array::typenum::consts::U16;
+#[cfg(feature = "security")]
 use cipher::{BlockCipher, BlockEncrypt, NewBlockCipher};
-use header::FrameType;
+#[cfg(feature = "security")]
+use core::borrow::BorrowMut;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
 pub use header::Header;
+use header::{FrameType, FrameVersion};
 
+use self::security::SecurityError;
+#[cfg(feature = "security")]
 use self::security::{
-    default::Unimplemented, DeviceDescriptorLookup, KeyDescriptorLookup,
-    SecurityContext, SecurityError,
+    default::Unimplemented, AsnProvider, CcmBackend, DeviceDescriptorLookup,
+    FrameCounterStorage, KeyDescriptorLookup, NonceGenerator, SecurityContext,
+    SecurityStatistics, SoftwareCcm, StandardNonce, UnsecuredMetadata,
 };
 
 /// An IEEE 802.15.4 MAC frame
@@ -50,7 +66,6 @@ use self::security::{
 ///     FrameType,
 ///     FooterMode,
 ///     PanId,
-///     FrameSerDesContext,
 /// };
 /// use byte::BytesExt;
 ///
@@ -108,7 +123,6 @@ use self::security::{
 ///   FrameVersion,
 ///   Header,
 ///   PanId,
-///   FrameSerDesContext,
 /// };
 /// use byte::BytesExt;
 ///
@@ -118,6 +132,7 @@ use self::security::{
 ///         frame_pending:   false,
 ///         ack_request:     false,
 ///         pan_id_compress: false,
+///         ie_present:      false,
 ///         version:         FrameVersion::Ieee802154_2006,
 ///
 ///         seq:             0x00,
@@ -134,7 +149,7 @@ use self::security::{
 /// let mut bytes = [0u8; 32];
 /// let mut len = 0usize;
 ///
-/// bytes.write_with(&mut len, frame, &mut FrameSerDesContext::no_security(FooterMode::Explicit)).unwrap();
+/// bytes.write_with(&mut len, frame, FooterMode::Explicit).unwrap();
 ///
 /// let expected_bytes = [
 ///     0x01, 0x98,             // frame control
@@ -147,15 +162,51 @@ use self::security::{
 /// assert_eq!(bytes[..len], expected_bytes);
 /// ```
 ///
+/// ## Beacon storage
+///
+/// The content of a beacon frame has room for `GTS` GTS descriptors, and
+/// `PENDING` short and extended pending addresses, which default to the
+/// maximum a beacon can carry. Devices that don't use GTSs or pending
+/// addresses can decode frames with less storage, which fails for beacons
+/// with more entries. Frames can only be encoded with the default storage.
+///
+/// ```rust
+/// use byte::BytesExt;
+/// use core::mem::size_of;
+/// use ieee802154::mac::{FooterMode, Frame};
+///
+/// assert!(size_of::<Frame<'_, 0, 0>>() < size_of::<Frame>());
+///
+/// // A beacon without GTSs and pending addresses
+/// let bytes = [0x00, 0x80, 0x03, 0xcd, 0xab, 0x00, 0x00, 0xff, 0xcf, 0x00, 0x00];
+/// let frame: Frame<'_, 0, 0> = bytes.read_with(&mut 0, FooterMode::None)?;
+/// assert_eq!(frame.beacon_payload(), Some(&[][..]));
+///
+/// // A beacon with a pending short address
+/// let bytes = [
+///     0x00, 0x80, 0x03, 0xcd, 0xab, 0x00, 0x00, 0xff, 0xcf, 0x00, 0x01, 0x01,
+///     0x00,
+/// ];
+/// let result = bytes.read_with::<Frame<'_, 0, 0>>(&mut 0, FooterMode::None);
+/// assert!(result.is_err());
+/// # Ok::<(), byte::Error>(())
+/// ```
+///
 /// [decode]: #method.try_read
 /// [encode]: #method.try_write
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
-pub struct Frame<'p> {
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Frame<
+    'p,
+    const GTS: usize = MAX_GTS_DESCRIPTORS,
+    const PENDING: usize = MAX_PENDING_ADDRESSES,
+> {
     /// Header
     pub header: Header,
 
     /// Content
-    pub content: FrameContent,
+    pub content: FrameContent<GTS, PENDING>,
 
     /// Payload
     pub payload: &'p [u8],
@@ -171,29 +222,128 @@ pub struct Frame<'p> {
     pub footer: [u8; 2],
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a, const GTS: usize, const PENDING: usize> arbitrary::Arbitrary<'a>
+    for Frame<'a, GTS, PENDING>
+{
+    fn arbitrary(
+        u: &mut arbitrary::Unstructured<'a>,
+    ) -> arbitrary::Result<Self> {
+        let mut header: Header = u.arbitrary()?;
+        // IEs are only part of frames of the 802.15.4-2015 version. The
+        // payload of a command frame with IEs would have to hold the command
+        header.ie_present &= header.version == FrameVersion::Ieee802154
+            && header.frame_type != FrameType::MacCommand;
+        // The content has to match the frame type in the header
+        let content = match header.frame_type {
+            FrameType::Beacon if header.version == FrameVersion::Ieee802154 => {
+                FrameContent::EnhancedBeacon
+            }
+            FrameType::Beacon => FrameContent::Beacon(u.arbitrary()?),
+            FrameType::Data => FrameContent::Data,
+            FrameType::Acknowledgement => FrameContent::Acknowledgement,
+            FrameType::MacCommand => match u.arbitrary()? {
+                // Frames of version 2003 can't carry the channel page
+                Command::CoordinatorRealignment(mut data)
+                    if header.version == FrameVersion::Ieee802154_2003 =>
+                {
+                    data.channel_page = None;
+                    FrameContent::Command(Command::CoordinatorRealignment(data))
+                }
+                command => FrameContent::Command(command),
+            },
+        };
+        Ok(Frame {
+            header,
+            content,
+            payload: u.arbitrary()?,
+            footer: u.arbitrary()?,
+        })
+    }
+}
+
 /// A context that is used for serializing and deserializing frames, which also
 /// stores the frame counter
-pub struct FrameSerDesContext<'a, AEADBLKCIPH, KEYDESCLO>
-where
+#[cfg(feature = "security")]
+pub struct FrameSerDesContext<
+    'a,
+    AEADBLKCIPH,
+    KEYDESCLO,
+    FRAMECTR = u32,
+    CCMBACKEND = SoftwareCcm<AEADBLKCIPH>,
+    ASNPROV = (),
+    NONCEGEN = StandardNonce,
+    STATS = SecurityStatistics,
+> where
     AEADBLKCIPH: NewBlockCipher + BlockCipher<BlockSize = U16>,
     KEYDESCLO: KeyDescriptorLookup<AEADBLKCIPH::KeySize>,
+    FRAMECTR: FrameCounterStorage,
+    CCMBACKEND: CcmBackend<AEADBLKCIPH::KeySize>,
+    ASNPROV: AsnProvider,
+    NONCEGEN: NonceGenerator,
+    STATS: BorrowMut<SecurityStatistics>,
 {
     /// The footer mode to use when handling frames
     footer_mode: FooterMode,
     /// The security context for handling frames (if any)
-    security_ctx: Option<&'a mut SecurityContext<AEADBLKCIPH, KEYDESCLO>>,
+    security_ctx: Option<
+        &'a mut SecurityContext<
+            AEADBLKCIPH,
+            KEYDESCLO,
+            FRAMECTR,
+            CCMBACKEND,
+            ASNPROV,
+            NONCEGEN,
+            STATS,
+        >,
+    >,
 }
 
-impl<'a, AEADBLKCIPH, KEYDESCLO> FrameSerDesContext<'a, AEADBLKCIPH, KEYDESCLO>
+#[cfg(feature = "security")]
+impl<
+        'a,
+        AEADBLKCIPH,
+        KEYDESCLO,
+        FRAMECTR,
+        CCMBACKEND,
+        ASNPROV,
+        NONCEGEN,
+        STATS,
+    >
+    FrameSerDesContext<
+        'a,
+        AEADBLKCIPH,
+        KEYDESCLO,
+        FRAMECTR,
+        CCMBACKEND,
+        ASNPROV,
+        NONCEGEN,
+        STATS,
+    >
 where
     AEADBLKCIPH: NewBlockCipher + BlockCipher<BlockSize = U16>,
     KEYDESCLO: KeyDescriptorLookup<AEADBLKCIPH::KeySize>,
+    FRAMECTR: FrameCounterStorage,
+    CCMBACKEND: CcmBackend<AEADBLKCIPH::KeySize>,
+    ASNPROV: AsnProvider,
+    NONCEGEN: NonceGenerator,
+    STATS: BorrowMut<SecurityStatistics>,
 {
     /// Create a new frame serialization/deserialization context with the specified footer mode
     /// and security context
     pub fn new(
         mode: FooterMode,
-        security_ctx: Option<&'a mut SecurityContext<AEADBLKCIPH, KEYDESCLO>>,
+        security_ctx: Option<
+            &'a mut SecurityContext<
+                AEADBLKCIPH,
+                KEYDESCLO,
+                FRAMECTR,
+                CCMBACKEND,
+                ASNPROV,
+                NONCEGEN,
+                STATS,
+            >,
+        >,
     ) -> Self {
         FrameSerDesContext {
             footer_mode: mode,
@@ -202,6 +352,22 @@ where
     }
 }
 
+/// A [`FrameSerDesContext`] with an [`AnySecurityContext`]
+///
+/// [`AnySecurityContext`]: security::AnySecurityContext
+#[cfg(feature = "security")]
+pub type AnyFrameSerDesContext<'a, 'b> = FrameSerDesContext<
+    'a,
+    Unimplemented,
+    &'b dyn KeyDescriptorLookup<U16>,
+    &'b mut dyn FrameCounterStorage,
+    &'b mut dyn CcmBackend<U16>,
+    &'b dyn AsnProvider,
+    &'b dyn NonceGenerator,
+    &'b mut SecurityStatistics,
+>;
+
+#[cfg(feature = "security")]
 impl FrameSerDesContext<'_, Unimplemented, Unimplemented> {
     /// Create a new frame serialization/deserialization context with the specified footer mode,
     /// that does not facilitate any security functionality
@@ -213,35 +379,335 @@ impl FrameSerDesContext<'_, Unimplemented, Unimplemented> {
     }
 }
 
-impl<AEADBLKCIPH, KEYDESCLO>
-    TryWrite<&mut FrameSerDesContext<'_, AEADBLKCIPH, KEYDESCLO>> for Frame<'_>
+/// The lengths of a frame that was written with
+/// [`Frame::try_write_and_secure`]
+#[cfg(feature = "security")]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SecuredLength {
+    /// The number of bytes that were written
+    pub len: usize,
+    /// The length of the MIC that was appended to the payload, or 0 if the
+    /// frame is not secured
+    pub mic_len: usize,
+    /// The length of the MPDU, including the FCS
+    ///
+    /// With [`FooterMode::None`], the FCS is not written, but it is still
+    /// included here, as it is usually appended by the radio.
+    pub mpdu_len: usize,
+}
+
+/// A received secured frame whose MIC has been verified, but that has not
+/// been unsecured
+///
+/// See [`Frame::try_read_and_verify`].
+#[cfg(feature = "security")]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct VerifiedFrame<'p> {
+    /// Header
+    pub header: Header,
+
+    /// The content and payload of the frame, as received
+    ///
+    /// Depending on the security level, this is (partially) encrypted.
+    pub secured_payload: &'p [u8],
+
+    /// The MIC of the frame
+    pub mic: &'p [u8],
+
+    /// Footer
+    ///
+    /// Like the other fields, this is as received.
+    pub footer: [u8; 2],
+
+    /// Whether the MIC matches the frame
+    pub mic_valid: bool,
+}
+
+/// Write the content of a frame, followed by its payload
+///
+/// The Command ID of a MAC command frame with IEs follows the IEs, so the
+/// payload of such a frame holds the IEs and the command, as they appear in
+/// the frame. The content isn't written separately then, but has to match the
+/// command in the payload.
+fn write_content_and_payload<const GTS: usize, const PENDING: usize>(
+    bytes: &mut [u8],
+    offset: &mut usize,
+    header: &Header,
+    content: FrameContent<GTS, PENDING>,
+    payload: &[u8],
+) -> byte::Result<()> {
+    if header.ie_present && matches!(content, FrameContent::Command(_)) {
+        let in_payload =
+            payload.read_with::<FrameContent<GTS, PENDING>>(&mut 0, header)?;
+        if in_payload != content {
+            return Err(EncodeError::CommandMismatch.into());
+        }
+    } else {
+        bytes.write(offset, content)?;
+    }
+    bytes.write(offset, payload)?;
+    Ok(())
+}
+
+/// Writes a frame without security
+///
+/// Writing a frame that has an auxiliary security header fails with
+/// [`EncodeError::MissingSecurityCtx`]. Use a [`FrameSerDesContext`] with a
+/// security context to write secured frames.
+impl TryWrite<FooterMode> for Frame<'_> {
+    fn try_write(
+        self,
+        bytes: &mut [u8],
+        mode: FooterMode,
+    ) -> byte::Result<usize> {
+        let offset = &mut 0;
+
+        bytes.write_with(offset, self.header, ())?;
+        write_content_and_payload(
+            bytes,
+            offset,
+            &self.header,
+            self.content,
+            self.payload,
+        )?;
+
+        match mode {
+            FooterMode::None => {}
+            FooterMode::Explicit => bytes.write(offset, &self.footer[..])?,
+        }
+
+        Ok(*offset)
+    }
+}
+
+#[cfg(feature = "security")]
+impl<
+        AEADBLKCIPH,
+        KEYDESCLO,
+        FRAMECTR,
+        CCMBACKEND,
+        ASNPROV,
+        NONCEGEN,
+        STATS,
+    >
+    TryWrite<
+        &mut FrameSerDesContext<
+            '_,
+            AEADBLKCIPH,
+            KEYDESCLO,
+            FRAMECTR,
+            CCMBACKEND,
+            ASNPROV,
+            NONCEGEN,
+            STATS,
+        >,
+    > for Frame<'_>
 where
     AEADBLKCIPH: NewBlockCipher + BlockCipher<BlockSize = U16> + BlockEncrypt,
     KEYDESCLO: KeyDescriptorLookup<AEADBLKCIPH::KeySize>,
+    FRAMECTR: FrameCounterStorage,
+    CCMBACKEND: CcmBackend<AEADBLKCIPH::KeySize>,
+    ASNPROV: AsnProvider,
+    NONCEGEN: NonceGenerator,
+    STATS: BorrowMut<SecurityStatistics>,
 {
     fn try_write(
         self,
         bytes: &mut [u8],
-        context: &mut FrameSerDesContext<AEADBLKCIPH, KEYDESCLO>,
+        context: &mut FrameSerDesContext<
+            AEADBLKCIPH,
+            KEYDESCLO,
+            FRAMECTR,
+            CCMBACKEND,
+            ASNPROV,
+            NONCEGEN,
+            STATS,
+        >,
     ) -> byte::Result<usize> {
+        self.try_write_and_secure(bytes, context)
+            .map(|length| length.len)
+    }
+}
+
+impl<'a, const GTS: usize, const PENDING: usize> Frame<'a, GTS, PENDING> {
+    /// Get the beacon payload (macBeaconPayload) of a beacon frame
+    ///
+    /// This is the payload of a [`FrameContent::Beacon`] frame. The payload
+    /// of a [`FrameContent::EnhancedBeacon`] frame starts with its IEs, which
+    /// aren't part of the beacon payload.
+    ///
+    /// Returns `None` if this is not a beacon frame, or if its IEs are
+    /// malformed.
+    pub fn beacon_payload(&self) -> Option<&'a [u8]> {
+        match self.content {
+            FrameContent::Beacon(_) => Some(self.payload),
+            FrameContent::EnhancedBeacon if self.header.ie_present => {
+                let offset = &mut 0;
+                self.payload
+                    .read::<EncodedInformationElements>(offset)
+                    .ok()?;
+                Some(&self.payload[*offset..])
+            }
+            FrameContent::EnhancedBeacon => Some(self.payload),
+            _ => None,
+        }
+    }
+
+    /// The DSME PAN Descriptor IE of a DSME beacon
+    ///
+    /// Returns `None` if this is not an Enhanced Beacon that carries the IE,
+    /// or if its IEs are malformed.
+    pub fn dsme_pan_descriptor(&self) -> Option<DsmePanDescriptorIe<'a>> {
+        if !matches!(self.content, FrameContent::EnhancedBeacon)
+            || !self.header.ie_present
+        {
+            return None;
+        }
+        let ies: EncodedInformationElements = self.payload.read(&mut 0).ok()?;
+        ies.header_ies().find_map(|ie| match ie {
+            Ok(HeaderIe::DsmePanDescriptor(ie)) => Some(ie),
+            _ => None,
+        })
+    }
+}
+
+impl<'a> Frame<'a> {
+    /// Write a frame like its [`TryWrite`] implementation with a
+    /// [`FrameSerDesContext`] does, and return the lengths of the written
+    /// frame
+    ///
+    /// This tells how many bytes the MIC appended to the payload, and the
+    /// length of the MPDU, e.g. to set the PHY length field or a DMA
+    /// descriptor, without deriving them from the security level.
+    ///
+    /// # Example
+    ///
+    /// ``` rust
+    /// use aes::Aes128;
+    /// use ieee802154::mac::{
+    ///     frame::security::{
+    ///         AuxiliarySecurityHeader, KeyIdentifier, KeyTable,
+    ///         SecurityContext, SecurityControl, SecurityLevel, U16,
+    ///     },
+    ///     Address, ExtendedAddress, FooterMode, Frame, FrameContent,
+    ///     FrameSerDesContext, FrameType, FrameVersion, Header, PanId,
+    /// };
+    ///
+    /// let frame = Frame {
+    ///     header: Header {
+    ///         frame_type: FrameType::Data,
+    ///         frame_pending: false,
+    ///         ack_request: false,
+    ///         pan_id_compress: false,
+    ///         ie_present: false,
+    ///         seq: 1,
+    ///         version: FrameVersion::Ieee802154,
+    ///         destination: None,
+    ///         source: Some(Address::Extended(PanId(0x1234), ExtendedAddress(1))),
+    ///         auxiliary_security_header: Some(AuxiliarySecurityHeader::new(
+    ///             SecurityControl::new(SecurityLevel::MIC64),
+    ///             None,
+    ///         )),
+    ///     },
+    ///     content: FrameContent::Data,
+    ///     payload: &[1, 2, 3],
+    ///     footer: [0, 0],
+    /// };
+    ///
+    /// // Without a key, securing fails
+    /// let mut sec_ctx: SecurityContext<Aes128, KeyTable<U16>> =
+    ///     SecurityContext::new(1, 0, KeyTable::new(&[]));
+    /// let mut buf = [0u8; 127];
+    /// assert!(frame
+    ///     .try_write_and_secure(
+    ///         &mut buf,
+    ///         &mut FrameSerDesContext::new(FooterMode::None, Some(&mut sec_ctx)),
+    ///     )
+    ///     .is_err());
+    ///
+    /// // Unsecured frames have no MIC
+    /// let mut frame = frame;
+    /// frame.header.auxiliary_security_header = None;
+    /// let length = frame
+    ///     .try_write_and_secure(
+    ///         &mut buf,
+    ///         &mut FrameSerDesContext::new(FooterMode::None, Some(&mut sec_ctx)),
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!(length.mic_len, 0);
+    /// assert_eq!(length.mpdu_len, length.len + 2);
+    /// ```
+    #[cfg(feature = "security")]
+    pub fn try_write_and_secure<
+        AEADBLKCIPH,
+        KEYDESCLO,
+        FRAMECTR,
+        CCMBACKEND,
+        ASNPROV,
+        NONCEGEN,
+        STATS,
+    >(
+        self,
+        bytes: &mut [u8],
+        context: &mut FrameSerDesContext<
+            AEADBLKCIPH,
+            KEYDESCLO,
+            FRAMECTR,
+            CCMBACKEND,
+            ASNPROV,
+            NONCEGEN,
+            STATS,
+        >,
+    ) -> byte::Result<SecuredLength>
+    where
+        AEADBLKCIPH:
+            NewBlockCipher + BlockCipher<BlockSize = U16> + BlockEncrypt,
+        KEYDESCLO: KeyDescriptorLookup<AEADBLKCIPH::KeySize>,
+        FRAMECTR: FrameCounterStorage,
+        CCMBACKEND: CcmBackend<AEADBLKCIPH::KeySize>,
+        ASNPROV: AsnProvider,
+        NONCEGEN: NonceGenerator,
+        STATS: BorrowMut<SecurityStatistics>,
+    {
+        let mut frame = self;
+        if let Some(ctx) = context.security_ctx.as_ref() {
+            let header = &mut frame.header;
+            if header.auxiliary_security_header.is_none()
+                && header.version != FrameVersion::Ieee802154_2003
+                && security::is_securable(header)
+            {
+                header.auxiliary_security_header = ctx
+                    .security_policy
+                    .auxiliary_security_header(header.frame_type);
+            }
+        }
+
         let mode = &context.footer_mode;
         let offset = &mut 0;
 
-        bytes.write_with(offset, self.header, &context.security_ctx)?;
-        bytes.write(offset, self.content)?;
+        bytes.write_with(offset, frame.header, &context.security_ctx)?;
 
         let mut security_enabled = false;
+        let mut mic_len = 0;
 
         if let Some(ctx) = context.security_ctx.as_mut() {
-            let write_secured = security::secure_frame(
-                self,
-                ctx,
-                context.footer_mode,
-                &mut bytes[*offset..],
-            );
+            let write_secured =
+                security::secure_frame(frame, ctx, bytes, *offset);
+            ctx.statistics.borrow_mut().record_secure(&write_secured);
             match write_secured {
                 Ok(len) => {
                     security_enabled = true;
+                    mic_len = frame.header.auxiliary_security_header.map_or(
+                        0,
+                        |aux_sec_header| {
+                            aux_sec_header
+                                .control
+                                .security_level
+                                .get_mic_octet_size()
+                        },
+                    );
                     *offset += len
                 }
                 Err(e) => match e {
@@ -252,116 +718,803 @@ where
         }
 
         if !security_enabled {
-            bytes.write(offset, self.payload.as_ref())?;
+            write_content_and_payload(
+                bytes,
+                offset,
+                &frame.header,
+                frame.content,
+                frame.payload,
+            )?;
         }
 
-        match mode {
-            FooterMode::None => {}
-            // TODO: recalculate the footer after encryption?
-            FooterMode::Explicit => bytes.write(offset, &self.footer[..])?,
-        }
+        let fcs_len = frame.footer.len();
+        let mpdu_len = match mode {
+            FooterMode::None => *offset + fcs_len,
+            FooterMode::Explicit => {
+                bytes.write(offset, &frame.footer[..])?;
+                *offset
+            }
+        };
 
-        Ok(*offset)
+        Ok(SecuredLength {
+            len: *offset,
+            mic_len,
+            mpdu_len,
+        })
     }
-}
 
-impl<'a> Frame<'a> {
     /// Try to read a frame. If the frame is secured, it will be unsecured
     ///
-    /// Currently, this function does not support the explicit footer mode,
-    /// as the FCS has to be calculated over the payload before it is unsecured,
-    /// which isn't implemented yet
+    /// Frames without security are checked against the security level table
+    /// of the security context, if any.
+    ///
+    /// If the footer mode is [`FooterMode::Explicit`], the footer is expected
+    /// to follow the authentication tag. It is returned as received, i.e.
+    /// calculated over the secured frame.
     ///
     /// Use [`FrameSerDesContext::no_security`] and/or [`Unimplemented`] if you
-    /// do not want to use any security, or simply [`Frame::try_read`]
-    pub fn try_read_and_unsecure<AEADBLKCIPH, KEYDESCLO, DEVDESCLO>(
+    /// do not want to use any security, or simply [`Frame::try_read`]. Secured
+    /// frames are then rejected with [`SecurityError::SecurityDisabled`].
+    ///
+    /// Use [`Frame::try_read_and_unsecure_with_metadata`] to find out which
+    /// key and frame counter a secured frame used.
+    #[cfg(feature = "security")]
+    pub fn try_read_and_unsecure<
+        AEADBLKCIPH,
+        KEYDESCLO,
+        FRAMECTR,
+        CCMBACKEND,
+        ASNPROV,
+        NONCEGEN,
+        STATS,
+        DEVDESCLO,
+    >(
         buf: &'a mut [u8],
-        ctx: &mut FrameSerDesContext<'_, AEADBLKCIPH, KEYDESCLO>,
+        ctx: &mut FrameSerDesContext<
+            '_,
+            AEADBLKCIPH,
+            KEYDESCLO,
+            FRAMECTR,
+            CCMBACKEND,
+            ASNPROV,
+            NONCEGEN,
+            STATS,
+        >,
         dev_desc_lo: &mut DEVDESCLO,
     ) -> Result<(Frame<'a>, usize), SecurityError>
     where
         AEADBLKCIPH:
             NewBlockCipher + BlockCipher<BlockSize = U16> + BlockEncrypt,
         KEYDESCLO: KeyDescriptorLookup<AEADBLKCIPH::KeySize>,
-        DEVDESCLO: DeviceDescriptorLookup,
+        FRAMECTR: FrameCounterStorage,
+        CCMBACKEND: CcmBackend<AEADBLKCIPH::KeySize>,
+        ASNPROV: AsnProvider,
+        NONCEGEN: NonceGenerator,
+        STATS: BorrowMut<SecurityStatistics>,
+        DEVDESCLO: DeviceDescriptorLookup + ?Sized,
+    {
+        let (frame, _, len) =
+            Self::try_read_and_unsecure_with_metadata(buf, ctx, dev_desc_lo)?;
+        Ok((frame, len))
+    }
+
+    /// Try to read a frame like [`Frame::try_read_and_unsecure`], and return
+    /// the security metadata of the frame alongside it
+    ///
+    /// The metadata is `None` if the frame is not secured.
+    #[cfg(feature = "security")]
+    pub fn try_read_and_unsecure_with_metadata<
+        AEADBLKCIPH,
+        KEYDESCLO,
+        FRAMECTR,
+        CCMBACKEND,
+        ASNPROV,
+        NONCEGEN,
+        STATS,
+        DEVDESCLO,
+    >(
+        buf: &'a mut [u8],
+        ctx: &mut FrameSerDesContext<
+            '_,
+            AEADBLKCIPH,
+            KEYDESCLO,
+            FRAMECTR,
+            CCMBACKEND,
+            ASNPROV,
+            NONCEGEN,
+            STATS,
+        >,
+        dev_desc_lo: &mut DEVDESCLO,
+    ) -> Result<(Frame<'a>, Option<UnsecuredMetadata>, usize), SecurityError>
+    where
+        AEADBLKCIPH:
+            NewBlockCipher + BlockCipher<BlockSize = U16> + BlockEncrypt,
+        KEYDESCLO: KeyDescriptorLookup<AEADBLKCIPH::KeySize>,
+        FRAMECTR: FrameCounterStorage,
+        CCMBACKEND: CcmBackend<AEADBLKCIPH::KeySize>,
+        ASNPROV: AsnProvider,
+        NONCEGEN: NonceGenerator,
+        STATS: BorrowMut<SecurityStatistics>,
+        DEVDESCLO: DeviceDescriptorLookup + ?Sized,
     {
         let offset = &mut 0;
-        let header: Header = buf.read(offset)?;
-        let content = buf.read_with(offset, &header)?;
+        let header: Header =
+            buf.read(offset).map_err(SecurityError::ReadError)?;
+
+        let footer_len = match ctx.footer_mode {
+            FooterMode::None => 0,
+            FooterMode::Explicit => 2,
+        };
+        check_len(&buf[*offset..], footer_len)
+            .map_err(SecurityError::ReadError)?;
+        let footer_start = buf.len() - footer_len;
 
         let mut tag_size = 0;
+        let mut metadata = None;
 
         if header.has_security() {
             if let Some(sec_ctx) = ctx.security_ctx.as_mut() {
-                tag_size = match security::unsecure_frame(
+                let unsecured = security::unsecure_frame(
                     &header,
-                    &mut buf[*offset..],
+                    &mut buf[..footer_start],
+                    *offset,
                     sec_ctx,
-                    ctx.footer_mode,
                     dev_desc_lo,
-                ) {
-                    Ok(size) => size,
+                    true,
+                );
+                sec_ctx.statistics.borrow_mut().record_unsecure(&unsecured);
+                match unsecured {
+                    Ok(unsecured) => {
+                        tag_size =
+                            unsecured.security_level.get_mic_octet_size();
+                        metadata = Some(unsecured);
+                    }
                     Err(e) => match e {
-                        SecurityError::SecurityNotEnabled => 0,
+                        SecurityError::SecurityNotEnabled => {}
                         _ => return Err(e),
                     },
-                };
+                }
             } else {
-                return Err(SecurityError::InvalidSecContext);
+                // 7.2.3a, without a security context, security is disabled
+                return Err(SecurityError::SecurityDisabled);
             }
         }
-        let payload =
-            buf.read_with(offset, Bytes::Len(buf.len() - *offset - tag_size))?;
 
-        let frame = Frame {
-            header,
-            content,
-            payload,
-            footer: [0, 0],
-        };
+        // The content is only readable once the frame is unsecured
+        let payload_end = footer_start - tag_size;
+        let content = buf[..payload_end]
+            .read_with(offset, &header)
+            .map_err(SecurityError::ReadError)?;
+        if !header.has_security() {
+            if let Some(sec_ctx) = ctx.security_ctx.as_ref() {
+                security::check_unsecured_frame(
+                    &header,
+                    &content,
+                    sec_ctx,
+                    dev_desc_lo,
+                )?;
+            }
+        }
+        let payload = buf
+            .read_with(offset, Bytes::Len(payload_end - *offset))
+            .map_err(SecurityError::ReadError)?;
+        *offset += tag_size;
+
+        let mut footer = [0, 0];
+        if footer_len != 0 {
+            footer.copy_from_slice(&buf[*offset..*offset + footer_len]);
+            *offset += footer_len;
+        }
+
+        let frame = Frame {
+            header,
+            content,
+            payload,
+            footer,
+        };
+
+        Ok((frame, metadata, *offset))
+    }
+
+    /// Try to read a secured frame and verify its MIC, without unsecuring it
+    ///
+    /// This is meant for devices that need to check the integrity of frames
+    /// that they pass on, such as border routers and relays. `buf` is not
+    /// modified. CCM* can only verify the MIC of an encrypted frame by
+    /// decrypting it, which is done in `scratch`. It must be at least as long
+    /// as `buf`.
+    ///
+    /// The security checks of [`Frame::try_read_and_unsecure`] are performed,
+    /// and any that fail result in an error. A MIC that does not match is
+    /// reported in [`VerifiedFrame::mic_valid`] instead. The frame counter of
+    /// the source device is not updated, as the frame is not accepted by this
+    /// device.
+    #[cfg(feature = "security")]
+    pub fn try_read_and_verify<
+        AEADBLKCIPH,
+        KEYDESCLO,
+        FRAMECTR,
+        CCMBACKEND,
+        ASNPROV,
+        NONCEGEN,
+        STATS,
+        DEVDESCLO,
+    >(
+        buf: &'a [u8],
+        scratch: &mut [u8],
+        ctx: &mut FrameSerDesContext<
+            '_,
+            AEADBLKCIPH,
+            KEYDESCLO,
+            FRAMECTR,
+            CCMBACKEND,
+            ASNPROV,
+            NONCEGEN,
+            STATS,
+        >,
+        dev_desc_lo: &mut DEVDESCLO,
+    ) -> Result<(VerifiedFrame<'a>, usize), SecurityError>
+    where
+        AEADBLKCIPH:
+            NewBlockCipher + BlockCipher<BlockSize = U16> + BlockEncrypt,
+        KEYDESCLO: KeyDescriptorLookup<AEADBLKCIPH::KeySize>,
+        FRAMECTR: FrameCounterStorage,
+        CCMBACKEND: CcmBackend<AEADBLKCIPH::KeySize>,
+        ASNPROV: AsnProvider,
+        NONCEGEN: NonceGenerator,
+        STATS: BorrowMut<SecurityStatistics>,
+        DEVDESCLO: DeviceDescriptorLookup + ?Sized,
+    {
+        let offset = &mut 0;
+        let header: Header =
+            buf.read(offset).map_err(SecurityError::ReadError)?;
+        let aux_sec_header = header
+            .auxiliary_security_header
+            .ok_or(SecurityError::SecurityNotEnabled)?;
+        let sec_ctx = ctx
+            .security_ctx
+            .as_mut()
+            .ok_or(SecurityError::SecurityDisabled)?;
+
+        let footer_len = match ctx.footer_mode {
+            FooterMode::None => 0,
+            FooterMode::Explicit => 2,
+        };
+        check_len(&buf[*offset..], footer_len)
+            .map_err(SecurityError::ReadError)?;
+        let footer_start = buf.len() - footer_len;
+
+        if scratch.len() < footer_start {
+            return Err(SecurityError::WriteError(byte::Error::Incomplete));
+        }
+        let scratch = &mut scratch[..footer_start];
+        scratch.copy_from_slice(&buf[..footer_start]);
+
+        let verified = security::unsecure_frame(
+            &header,
+            scratch,
+            *offset,
+            sec_ctx,
+            dev_desc_lo,
+            false,
+        );
+        if let Err(e) = &verified {
+            sec_ctx.statistics.borrow_mut().record_error(e);
+        }
+        let mic_valid = match verified {
+            Ok(_) => true,
+            Err(SecurityError::MicMismatch) => false,
+            Err(e) => return Err(e),
+        };
+
+        let mic_len =
+            aux_sec_header.control.security_level.get_mic_octet_size();
+        let secured_payload = buf
+            .read_with(offset, Bytes::Len(footer_start - *offset - mic_len))
+            .map_err(SecurityError::ReadError)?;
+        let mic = buf
+            .read_with(offset, Bytes::Len(mic_len))
+            .map_err(SecurityError::ReadError)?;
+
+        let mut footer = [0, 0];
+        if footer_len != 0 {
+            footer.copy_from_slice(&buf[*offset..*offset + footer_len]);
+            *offset += footer_len;
+        }
+
+        let frame = VerifiedFrame {
+            header,
+            secured_payload,
+            mic,
+            footer,
+            mic_valid,
+        };
+
+        Ok((frame, *offset))
+    }
+
+    /// Try to read a frame on a device that does not use security
+    ///
+    /// This is the behaviour that is required when macSecurityEnabled is
+    /// FALSE: frames that have security enabled are rejected with
+    /// [`SecurityError::SecurityDisabled`], instead of failing to decode.
+    /// Other errors are reported as [`SecurityError::ReadError`].
+    ///
+    /// This is available without the `security` feature, which leaves out
+    /// all means of securing frames, so that devices without cryptography
+    /// can be built.
+    ///
+    /// # Example
+    ///
+    /// ``` rust
+    /// use ieee802154::mac::{
+    ///     frame::security::SecurityError, FooterMode, Frame,
+    /// };
+    ///
+    /// let bytes = [
+    ///     0x09u8, 0x98,           // frame control, security enabled
+    ///     0x00,                   // sequence number
+    ///     0x12, 0x34, 0x56, 0x78, // PAN identifier and address of destination
+    ///     0x12, 0x34, 0x9a, 0xbc, // PAN identifier and address of source
+    ///     0x05,                   // security control, ENC-MIC-32
+    ///     0x01, 0x00, 0x00, 0x00, // frame counter
+    ///     0xde, 0xf0,             // secured payload
+    ///     0x12, 0x34, 0x56, 0x78, // MIC
+    /// ];
+    ///
+    /// assert!(matches!(
+    ///     Frame::try_read_unsecured(&bytes, FooterMode::None),
+    ///     Err(SecurityError::SecurityDisabled)
+    /// ));
+    /// ```
+    pub fn try_read_unsecured(
+        bytes: &'a [u8],
+        mode: FooterMode,
+    ) -> Result<(Self, usize), SecurityError> {
+        let header: Header =
+            bytes.read(&mut 0).map_err(SecurityError::ReadError)?;
+        if header.has_security() {
+            return Err(SecurityError::SecurityDisabled);
+        }
+        Frame::try_read(bytes, mode).map_err(SecurityError::ReadError)
+    }
+
+    /// Try to read a frame that is followed by `metadata_len` bytes of
+    /// out-of-band radio metadata
+    ///
+    /// Some radios append extra bytes to the received PSDU, such as the RSSI
+    /// and CRC-OK/LQI bytes of CC24xx-style radios, or the LQI byte of nRF
+    /// radios. These bytes are not part of the frame, and would otherwise be
+    /// read as (part of) the payload or the footer.
+    ///
+    /// The trailing `metadata_len` bytes are split off before the frame is
+    /// decoded according to `mode`, and are returned alongside the frame.
+    ///
+    /// # Example
+    ///
+    /// ``` rust
+    /// use ieee802154::mac::{FooterMode, Frame};
+    ///
+    /// let bytes = [
+    ///     0x01u8, 0x98,           // frame control
+    ///     0x00,                   // sequence number
+    ///     0x12, 0x34, 0x56, 0x78, // PAN identifier and address of destination
+    ///     0x12, 0x34, 0x9a, 0xbc, // PAN identifier and address of source
+    ///     0xde, 0xf0,             // payload
+    ///     0xd4, 0xa8,             // RSSI, CRC-OK/LQI
+    /// ];
+    ///
+    /// let (frame, metadata, len) =
+    ///     Frame::try_read_with_metadata(&bytes, FooterMode::None, 2).unwrap();
+    ///
+    /// assert_eq!(frame.payload, &[0xde, 0xf0]);
+    /// assert_eq!(metadata, &[0xd4, 0xa8]);
+    /// assert_eq!(len, bytes.len());
+    /// ```
+    pub fn try_read_with_metadata(
+        bytes: &'a [u8],
+        mode: FooterMode,
+        metadata_len: usize,
+    ) -> byte::Result<(Self, &'a [u8], usize)> {
+        check_len(bytes, metadata_len)?;
+        let (frame_bytes, metadata) =
+            bytes.split_at(bytes.len() - metadata_len);
+
+        let frame = frame_bytes.read_with(&mut 0, mode)?;
+        Ok((frame, metadata, bytes.len()))
+    }
+}
+
+impl<'a, const GTS: usize, const PENDING: usize> TryRead<'a, FooterMode>
+    for Frame<'a, GTS, PENDING>
+{
+    /// Try to read a frame
+    ///
+    /// Frames that have security enabled can not be processed by this function, and an
+    /// error will be returned if the frame contained in `bytes` does have it enabled.
+    ///
+    /// If you expect to receive secured frames, use [`Frame::try_read_and_unsecure`] instead,
+    ///
+    /// Trailing bytes after a command are exposed as the payload, see
+    /// [`TrailingBytes`].
+    fn try_read(
+        bytes: &'a [u8],
+        mode: FooterMode,
+    ) -> byte::Result<(Self, usize)> {
+        Self::try_read(bytes, (mode, TrailingBytes::Expose))
+    }
+}
+
+impl<'a, const GTS: usize, const PENDING: usize>
+    TryRead<'a, (FooterMode, TrailingBytes)> for Frame<'a, GTS, PENDING>
+{
+    /// Try to read a frame, handling trailing bytes after a command as
+    /// configured
+    ///
+    /// # Example
+    ///
+    /// ``` rust
+    /// use byte::BytesExt;
+    /// use ieee802154::mac::frame::TrailingBytes;
+    /// use ieee802154::mac::{DecodeError, FooterMode, Frame};
+    ///
+    /// // A data request, padded with a zero byte
+    /// let bytes = [0x63, 0x88, 0x01, 0xcd, 0xab, 0x00, 0x00, 0x01, 0x00, 0x04, 0x00];
+    ///
+    /// let frame: Frame = bytes
+    ///     .read_with(&mut 0, (FooterMode::None, TrailingBytes::Expose))?;
+    /// assert_eq!(frame.payload, [0x00]);
+    ///
+    /// let result = bytes
+    ///     .read_with::<Frame>(&mut 0, (FooterMode::None, TrailingBytes::Reject));
+    /// assert_eq!(result, Err(DecodeError::TrailingBytes.into()));
+    /// # Ok::<(), byte::Error>(())
+    /// ```
+    fn try_read(
+        bytes: &'a [u8],
+        (mode, trailing_bytes): (FooterMode, TrailingBytes),
+    ) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let header: Header = bytes.read(offset)?;
+
+        let footer_len = match mode {
+            FooterMode::None => 0,
+            FooterMode::Explicit => 2,
+        };
+        let payload_end = bytes
+            .len()
+            .checked_sub(footer_len)
+            .filter(|&end| end >= *offset)
+            .ok_or(DecodeError::NotEnoughBytes)?;
+        // The footer isn't part of the content
+        let content = bytes[..payload_end].read_with(offset, &header)?;
+
+        if header.has_security() {
+            return Err(DecodeError::SecurityEnabled)?;
+        }
+
+        let payload: &[u8] =
+            bytes.read_with(offset, Bytes::Len(payload_end - *offset))?;
+        let footer: u16 = match mode {
+            FooterMode::None => 0,
+            FooterMode::Explicit => bytes.read_with(offset, LE)?,
+        };
+
+        if trailing_bytes == TrailingBytes::Reject
+            && !payload.is_empty()
+            && !header.ie_present
+        {
+            if let FrameContent::Command(command) = content {
+                if !command.has_payload_fields() {
+                    return Err(DecodeError::TrailingBytes.into());
+                }
+            }
+        }
+
+        let frame = Frame {
+            header,
+            content,
+            payload,
+            footer: footer.to_le_bytes(),
+        };
+        Ok((frame, *offset))
+    }
+}
+
+/// The maximum size of a PHY packet (aMaxPHYPacketSize), in octets
+pub const MAX_PHY_PACKET_SIZE: usize = 127;
+
+/// An IEEE 802.15.4 MAC frame that owns its payload
+///
+/// Unlike [`Frame`], which borrows its payload, this type stores the payload
+/// inline. This makes it possible to keep a frame around after the buffer it
+/// was decoded from has been reused, or to deserialize frames with `serde`
+/// from formats that can't lend out bytes.
+///
+/// # Example
+///
+/// ``` rust
+/// use ieee802154::mac::{FooterMode, Frame, OwnedFrame};
+/// use byte::BytesExt;
+///
+/// let bytes = [
+///     0x41u8, 0x88, 0x91, 0x8f, 0x20, 0xff, 0xff, 0x33, 0x44, 0xde, 0xf0,
+/// ];
+/// let frame: Frame = bytes.read_with(&mut 0, FooterMode::None).unwrap();
+///
+/// let owned = OwnedFrame::from_frame(&frame).unwrap();
+/// assert_eq!(&owned.payload[..], &[0xde, 0xf0]);
+/// assert_eq!(owned.as_frame(), frame);
+/// ```
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OwnedFrame {
+    /// Header
+    pub header: Header,
+
+    /// Content
+    pub content: FrameContent,
+
+    /// Payload
+    pub payload: OwnedPayload,
+
+    /// Footer
+    ///
+    /// See [`Frame::footer`]
+    pub footer: [u8; 2],
+}
+
+impl OwnedFrame {
+    /// Create an owned copy of `frame`
+    ///
+    /// Returns `None`, if the payload of `frame` is longer than
+    /// [`MAX_PHY_PACKET_SIZE`].
+    pub fn from_frame(frame: &Frame) -> Option<Self> {
+        Some(OwnedFrame {
+            header: frame.header,
+            content: frame.content,
+            payload: OwnedPayload::new(frame.payload)?,
+            footer: frame.footer,
+        })
+    }
+
+    /// Get a [`Frame`] that borrows the payload of this frame
+    pub fn as_frame(&self) -> Frame<'_> {
+        Frame {
+            header: self.header,
+            content: self.content,
+            payload: &self.payload,
+            footer: self.footer,
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for OwnedFrame {
+    fn arbitrary(
+        u: &mut arbitrary::Unstructured<'a>,
+    ) -> arbitrary::Result<Self> {
+        let frame: Frame = u.arbitrary()?;
+        Ok(OwnedFrame {
+            header: frame.header,
+            content: frame.content,
+            payload: u.arbitrary()?,
+            footer: frame.footer,
+        })
+    }
+}
+
+/// The payload of an [`OwnedFrame`]
+///
+/// Stores up to [`MAX_PHY_PACKET_SIZE`] bytes inline, and dereferences to the
+/// stored bytes.
+#[derive(Copy, Clone)]
+pub struct OwnedPayload {
+    len: usize,
+    bytes: [u8; MAX_PHY_PACKET_SIZE],
+}
+
+impl OwnedPayload {
+    /// Create a new payload that contains a copy of `payload`
+    ///
+    /// Returns `None`, if `payload` is longer than [`MAX_PHY_PACKET_SIZE`].
+    pub fn new(payload: &[u8]) -> Option<Self> {
+        if payload.len() > MAX_PHY_PACKET_SIZE {
+            return None;
+        }
+        let mut bytes = [0u8; MAX_PHY_PACKET_SIZE];
+        bytes[..payload.len()].copy_from_slice(payload);
+        Some(OwnedPayload {
+            len: payload.len(),
+            bytes,
+        })
+    }
+}
+
+impl Deref for OwnedPayload {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for OwnedPayload {
+    fn arbitrary(
+        u: &mut arbitrary::Unstructured<'a>,
+    ) -> arbitrary::Result<Self> {
+        let len = u.int_in_range(0..=MAX_PHY_PACKET_SIZE)?;
+        Ok(OwnedPayload::new(u.bytes(len)?).unwrap())
+    }
+}
+
+impl PartialEq for OwnedPayload {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for OwnedPayload {}
+
+impl Hash for OwnedPayload {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl fmt::Debug for OwnedPayload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for OwnedPayload {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=[u8]}", **self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for OwnedPayload {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_bytes(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for OwnedPayload {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct PayloadVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for PayloadVisitor {
+            type Value = OwnedPayload;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "at most {} bytes", MAX_PHY_PACKET_SIZE)
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<OwnedPayload, E>
+            where
+                E: serde::de::Error,
+            {
+                OwnedPayload::new(v)
+                    .ok_or_else(|| E::invalid_length(v.len(), &self))
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<OwnedPayload, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                let mut payload = OwnedPayload {
+                    len: 0,
+                    bytes: [0u8; MAX_PHY_PACKET_SIZE],
+                };
+                while let Some(byte) = seq.next_element()? {
+                    if payload.len == MAX_PHY_PACKET_SIZE {
+                        return Err(serde::de::Error::invalid_length(
+                            payload.len + 1,
+                            &self,
+                        ));
+                    }
+                    payload.bytes[payload.len] = byte;
+                    payload.len += 1;
+                }
+                Ok(payload)
+            }
+        }
+
+        deserializer.deserialize_bytes(PayloadVisitor)
+    }
+}
+
+/// A decoded frame that remembers the bytes it was decoded from
+///
+/// Decoding a [`Frame`] is lossy: reserved bits, for example, are not
+/// represented in it, so re-encoding a decoded frame does not always produce
+/// the original bytes. A `PreservedFrame` keeps the original bytes around,
+/// and writes them out unchanged as long as [`PreservedFrame::frame`] still
+/// matches them. Once the frame has been modified, it is encoded normally.
+///
+/// This is useful for proxies, differential fuzzing, or rewriting captures,
+/// where frames should pass through byte-identically.
+///
+/// # Example
+///
+/// ``` rust
+/// use ieee802154::mac::{FooterMode, PreservedFrame};
+/// use byte::BytesExt;
+///
+/// // Data frame with reserved frame control bit 7 set
+/// let bytes = [
+///     0x81u8, 0x88, 0x91, 0x8f, 0x20, 0xff, 0xff, 0x33, 0x44, 0xde, 0xf0,
+/// ];
+///
+/// let frame: PreservedFrame =
+///     bytes.read_with(&mut 0, FooterMode::None).unwrap();
+///
+/// let mut buf = [0u8; 32];
+/// let mut len = 0;
+/// buf.write(&mut len, frame).unwrap();
+/// assert_eq!(buf[..len], bytes);
+/// ```
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PreservedFrame<'a> {
+    /// The decoded frame
+    pub frame: Frame<'a>,
+    footer_mode: FooterMode,
+    raw: &'a [u8],
+}
+
+impl<'a> PreservedFrame<'a> {
+    /// The bytes that this frame was decoded from
+    pub fn raw(&self) -> &'a [u8] {
+        self.raw
+    }
 
-        Ok((frame, *offset))
+    /// Whether [`PreservedFrame::frame`] has been modified since it was
+    /// decoded
+    pub fn is_modified(&self) -> bool {
+        match self.raw.read_with::<Frame>(&mut 0, self.footer_mode) {
+            Ok(original) => original != self.frame,
+            Err(_) => true,
+        }
     }
 }
 
-impl<'a> TryRead<'a, FooterMode> for Frame<'a> {
-    /// Try to read a frame
-    ///
-    /// Frames that have security enabled can not be processed by this function, and an
-    /// error will be returned if the frame contained in `bytes` does have it enabled.
-    ///
-    /// If you expect to receive secured frames, use [`Frame::try_read_and_unsecure`] instead,
+impl<'a> TryRead<'a, FooterMode> for PreservedFrame<'a> {
     fn try_read(
         bytes: &'a [u8],
         mode: FooterMode,
     ) -> byte::Result<(Self, usize)> {
-        let offset = &mut 0;
-        let header: Header = bytes.read(offset)?;
-        let content = bytes.read_with(offset, &header)?;
-
-        if header.has_security() {
-            return Err(DecodeError::SecurityEnabled)?;
-        }
-
-        let (payload, footer) = match mode {
-            FooterMode::None => (
-                bytes.read_with(offset, Bytes::Len(bytes.len() - *offset))?,
-                0u16,
-            ),
-            FooterMode::Explicit => (
-                bytes
-                    .read_with(offset, Bytes::Len(bytes.len() - *offset - 2))?,
-                bytes.read_with(offset, LE)?,
-            ),
+        let (frame, len) = Frame::try_read(bytes, mode)?;
+        let frame = PreservedFrame {
+            frame,
+            footer_mode: mode,
+            raw: &bytes[..len],
         };
+        Ok((frame, len))
+    }
+}
 
-        let frame = Frame {
-            header,
-            content,
-            payload,
-            footer: footer.to_le_bytes(),
-        };
-        Ok((frame, *offset))
+impl TryWrite for PreservedFrame<'_> {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        if self.is_modified() {
+            bytes.write_with(offset, self.frame, self.footer_mode)?;
+        } else {
+            bytes.write(offset, self.raw)?;
+        }
+        Ok(*offset)
     }
 }
 
@@ -376,7 +1529,10 @@ impl<'a> TryRead<'a, FooterMode> for Frame<'a> {
 /// For now, only 1 and 3 are supported.
 ///
 /// [`Frame::try_write`](Frame::try_write)
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum FooterMode {
     /// Don't read/write the footer
     None,
@@ -390,41 +1546,117 @@ impl Default for FooterMode {
     }
 }
 
+/// Controls how bytes after the fields of a MAC command are read
+///
+/// Some stacks pad MAC command frames. The fields of commands like the data
+/// request are fully decoded into the [`Command`], so any bytes after them are
+/// padding. This doesn't apply to commands whose fields are in the payload,
+/// see [`Command::has_payload_fields`], or to frames that carry IEs.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum TrailingBytes {
+    /// Expose the trailing bytes as the payload of the frame
+    #[default]
+    Expose,
+    /// Reject frames with trailing bytes with [`DecodeError::TrailingBytes`]
+    Reject,
+}
+
 /// Content of a frame
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-pub enum FrameContent {
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum FrameContent<
+    const GTS: usize = MAX_GTS_DESCRIPTORS,
+    const PENDING: usize = MAX_PENDING_ADDRESSES,
+> {
     /// Beacon frame content
-    Beacon(Beacon),
+    Beacon(Beacon<GTS, PENDING>),
+    /// Enhanced Beacon frame
+    ///
+    /// An Enhanced Beacon is a Beacon frame of version
+    /// [`FrameVersion::Ieee802154`]. Instead of the beacon fields, it carries
+    /// IEs, which are part of the payload.
+    ///
+    /// [`FrameVersion::Ieee802154`]: header::FrameVersion::Ieee802154
+    EnhancedBeacon,
     /// Data frame
     Data,
     /// Acknowledgement frame
     Acknowledgement,
     /// MAC command frame
+    ///
+    /// The Command ID of a frame with IEs follows the IEs, so the payload of
+    /// such a frame holds the IEs and the command. The command is written as
+    /// part of the payload then, and has to match this content.
     Command(Command),
 }
 
-impl TryWrite for FrameContent {
+impl<const GTS: usize, const PENDING: usize> FrameContent<GTS, PENDING> {
+    /// Get the size of this content in octets
+    ///
+    /// The payload of the frame isn't included.
+    pub fn get_octet_size(&self) -> usize {
+        match self {
+            FrameContent::Beacon(beacon) => beacon.get_octet_size(),
+            FrameContent::EnhancedBeacon
+            | FrameContent::Data
+            | FrameContent::Acknowledgement => 0,
+            FrameContent::Command(command) => command.get_octet_size(),
+        }
+    }
+}
+
+impl<const GTS: usize, const PENDING: usize> TryWrite
+    for FrameContent<GTS, PENDING>
+{
     fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
         let offset = &mut 0;
         match self {
             FrameContent::Beacon(beacon) => bytes.write(offset, beacon)?,
-            FrameContent::Data | FrameContent::Acknowledgement => (),
+            FrameContent::EnhancedBeacon
+            | FrameContent::Data
+            | FrameContent::Acknowledgement => (),
             FrameContent::Command(command) => bytes.write(offset, command)?,
         };
         Ok(*offset)
     }
 }
 
-impl TryRead<'_, &Header> for FrameContent {
+impl<const GTS: usize, const PENDING: usize> TryRead<'_, &Header>
+    for FrameContent<GTS, PENDING>
+{
     fn try_read(bytes: &[u8], header: &Header) -> byte::Result<(Self, usize)> {
         let offset = &mut 0;
         Ok((
             match header.frame_type {
+                FrameType::Beacon
+                    if header.version == FrameVersion::Ieee802154 =>
+                {
+                    FrameContent::EnhancedBeacon
+                }
                 FrameType::Beacon => FrameContent::Beacon(bytes.read(offset)?),
                 FrameType::Data => FrameContent::Data,
                 FrameType::Acknowledgement => FrameContent::Acknowledgement,
+                FrameType::MacCommand if header.ie_present => {
+                    // The Command ID follows the header IEs and payload IEs,
+                    // so the command stays in the payload with the IEs
+                    let ies_len = &mut 0;
+                    let _: EncodedInformationElements = bytes.read(ies_len)?;
+                    let (command, _) = Command::try_read_for_version(
+                        &bytes[*ies_len..],
+                        header.version,
+                    )?;
+                    FrameContent::Command(command)
+                }
                 FrameType::MacCommand => {
-                    FrameContent::Command(bytes.read(offset)?)
+                    let (command, len) =
+                        Command::try_read_for_version(bytes, header.version)?;
+                    *offset += len;
+                    FrameContent::Command(command)
                 }
             },
             *offset,
@@ -434,6 +1666,8 @@ impl TryRead<'_, &Header> for FrameContent {
 
 /// Signals an error that occured while decoding bytes
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DecodeError {
     /// Buffer does not contain enough bytes
     NotEnoughBytes,
@@ -464,6 +1698,33 @@ pub enum DecodeError {
 
     /// The data stream contains an invalid value
     InvalidValue,
+
+    /// An IE's element or group ID is not valid where the IE appears
+    InvalidElementId(u8),
+
+    /// An IE's length does not match its content, or runs past the end of
+    /// the IE list
+    IeLengthMismatch,
+
+    /// A list of IEs lacks a termination IE, or a termination IE is not
+    /// followed by the IEs or the payload it announces
+    MissingIeTermination,
+
+    /// A nested IE runs past the end of the IE that contains it
+    NestedIeOverflow,
+
+    /// A MAC command is followed by bytes that aren't part of it
+    TrailingBytes,
+
+    /// A beacon's superframe specification is invalid
+    InvalidSuperframeSpecification(SuperframeSpecificationError),
+
+    /// A beacon has more GTS descriptors or pending addresses than the
+    /// decoded type has room for
+    InsufficientStorage,
+
+    /// A beacon or superframe order is larger than 15
+    InvalidOrder(u8),
 }
 
 impl From<DecodeError> for byte::Error {
@@ -497,17 +1758,107 @@ impl From<DecodeError> for byte::Error {
             DecodeError::SecurityEnabled => byte::Error::BadInput {
                 err: "SecurityEnabled (use Frame::try_read_and_unsecure)",
             },
+            DecodeError::InvalidElementId(_) => byte::Error::BadInput {
+                err: "InvalidElementId",
+            },
+            DecodeError::IeLengthMismatch => byte::Error::BadInput {
+                err: "IeLengthMismatch",
+            },
+            DecodeError::MissingIeTermination => byte::Error::BadInput {
+                err: "MissingIeTermination",
+            },
+            DecodeError::NestedIeOverflow => byte::Error::BadInput {
+                err: "NestedIeOverflow",
+            },
+            DecodeError::TrailingBytes => byte::Error::BadInput {
+                err: "TrailingBytes",
+            },
+            DecodeError::InvalidSuperframeSpecification(_) => {
+                byte::Error::BadInput {
+                    err: "InvalidSuperframeSpecification",
+                }
+            }
+            DecodeError::InsufficientStorage => byte::Error::BadInput {
+                err: "InsufficientStorage",
+            },
+            DecodeError::InvalidOrder(_) => byte::Error::BadInput {
+                err: "InvalidOrder",
+            },
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::NotEnoughBytes => f.write_str("not enough bytes"),
+            DecodeError::InvalidFrameType(t) => {
+                write!(f, "invalid frame type: {}", t)
+            }
+            DecodeError::SecurityEnabled => f.write_str(
+                "security is enabled, use Frame::try_read_and_unsecure",
+            ),
+            DecodeError::InvalidAddressMode(m) => {
+                write!(f, "invalid address mode: {}", m)
+            }
+            DecodeError::InvalidFrameVersion(v) => {
+                write!(f, "invalid frame version: {}", v)
+            }
+            DecodeError::InvalidSecurityLevel(l) => {
+                write!(f, "invalid security level: {}", l)
+            }
+            DecodeError::InvalidKeyIdentifierMode(m) => {
+                write!(f, "invalid key identifier mode: {}", m)
+            }
+            DecodeError::MissingSecurityCtx => {
+                f.write_str("missing security context")
+            }
+            DecodeError::AuxSecHeaderAbsent => {
+                f.write_str("auxiliary security header absent")
+            }
+            DecodeError::InvalidValue => f.write_str("invalid value"),
+            DecodeError::InvalidElementId(id) => {
+                write!(f, "invalid IE element ID: {}", id)
+            }
+            DecodeError::IeLengthMismatch => f.write_str("IE length mismatch"),
+            DecodeError::MissingIeTermination => {
+                f.write_str("missing IE termination")
+            }
+            DecodeError::NestedIeOverflow => f.write_str("nested IE overflow"),
+            DecodeError::TrailingBytes => {
+                f.write_str("trailing bytes after MAC command")
+            }
+            DecodeError::InvalidSuperframeSpecification(e) => {
+                write!(f, "invalid superframe specification: {}", e)
+            }
+            DecodeError::InsufficientStorage => {
+                f.write_str("insufficient storage for beacon fields")
+            }
+            DecodeError::InvalidOrder(order) => {
+                write!(f, "invalid beacon or superframe order: {}", order)
+            }
         }
     }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
 /// Errors that can occur while securing or unsecuring a frame
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EncodeError {
     /// Something went wrong while writing a frame's bytes to the destination
     WriteError,
     /// Security is enabled but no security context is specified
     MissingSecurityCtx,
+    /// PAN ID compression is requested, but the source or destination
+    /// address is absent
+    InvalidPanIdCompress,
+    /// The payload of a MAC command frame with IEs doesn't hold the command
+    /// of its content
+    CommandMismatch,
     /// Something went wrong, but it is unclear what/how it did
     UnknownError,
 }
@@ -521,6 +1872,12 @@ impl From<EncodeError> for byte::Error {
             EncodeError::MissingSecurityCtx => byte::Error::BadInput {
                 err: "MissingSecurityCtx",
             },
+            EncodeError::InvalidPanIdCompress => byte::Error::BadInput {
+                err: "InvalidPanIdCompress",
+            },
+            EncodeError::CommandMismatch => byte::Error::BadInput {
+                err: "CommandMismatch",
+            },
             EncodeError::UnknownError => byte::Error::BadInput {
                 err: "UnknownError",
             },
@@ -528,6 +1885,25 @@ impl From<EncodeError> for byte::Error {
     }
 }
 
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            EncodeError::WriteError => "write error",
+            EncodeError::MissingSecurityCtx => "missing security context",
+            EncodeError::InvalidPanIdCompress => {
+                "PAN ID compression without both addresses"
+            }
+            EncodeError::CommandMismatch => {
+                "command doesn't match the command in the payload"
+            }
+            EncodeError::UnknownError => "unknown error",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EncodeError {}
+
 #[cfg(test)]
 mod tests {
     use crate::mac::beacon;
@@ -602,6 +1978,106 @@ mod tests {
         assert_eq!(frame.header.seq, 139);
     }
 
+    #[test]
+    fn decode_with_metadata() {
+        let data = [
+            0x41, 0x88, 0x91, 0x8f, 0x20, 0xff, 0xff, 0x33, 0x44, 0xde, 0xf0,
+            0x12, 0x34, 0xd4, 0xa8,
+        ];
+
+        let (frame, metadata, len) =
+            Frame::try_read_with_metadata(&data, FooterMode::Explicit, 2)
+                .unwrap();
+        assert_eq!(len, data.len());
+        assert_eq!(frame.header.seq, 145);
+        assert_eq!(frame.payload, &[0xde, 0xf0]);
+        assert_eq!(frame.footer, [0x12, 0x34]);
+        assert_eq!(metadata, &[0xd4, 0xa8]);
+
+        let (frame, metadata, _) =
+            Frame::try_read_with_metadata(&data, FooterMode::None, 0).unwrap();
+        assert_eq!(frame.payload, &[0xde, 0xf0, 0x12, 0x34, 0xd4, 0xa8]);
+        assert!(metadata.is_empty());
+
+        let result =
+            Frame::try_read_with_metadata(&data[..1], FooterMode::None, 2);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn preserved_frame_round_trip() {
+        // Reserved frame control bit 7 is set
+        let data = [
+            0x81, 0x88, 0x91, 0x8f, 0x20, 0xff, 0xff, 0x33, 0x44, 0xde, 0xf0,
+        ];
+
+        let mut frame: PreservedFrame =
+            data.read_with(&mut 0, FooterMode::None).unwrap();
+        assert!(!frame.is_modified());
+        assert_eq!(frame.raw(), &data[..]);
+
+        let mut buf = [0u8; 32];
+        let mut len = 0usize;
+        buf.write(&mut len, frame).unwrap();
+        assert_eq!(buf[..len], data);
+
+        frame.frame.header.seq = 0x92;
+        assert!(frame.is_modified());
+
+        let mut len = 0usize;
+        buf.write(&mut len, frame).unwrap();
+        assert_eq!(
+            buf[..len],
+            [
+                0x01, 0x88, 0x92, 0x8f, 0x20, 0xff, 0xff, 0x33, 0x44, 0xde,
+                0xf0
+            ]
+        );
+    }
+
+    #[test]
+    fn owned_frame() {
+        let data = [
+            0x41, 0x88, 0x91, 0x8f, 0x20, 0xff, 0xff, 0x33, 0x44, 0xde, 0xf0,
+        ];
+        let frame: Frame = data.read_with(&mut 0, FooterMode::None).unwrap();
+
+        let owned = OwnedFrame::from_frame(&frame).unwrap();
+        assert_eq!(owned.header, frame.header);
+        assert_eq!(&owned.payload[..], &[0xde, 0xf0]);
+        assert_eq!(owned.as_frame(), frame);
+
+        let payload = [0u8; MAX_PHY_PACKET_SIZE + 1];
+        assert!(OwnedPayload::new(&payload[..MAX_PHY_PACKET_SIZE]).is_some());
+        assert!(OwnedPayload::new(&payload).is_none());
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn arbitrary_frames_are_encodable() {
+        use arbitrary::{Arbitrary, Unstructured};
+        use rand::RngCore;
+
+        let mut data = [0u8; 512];
+        for _ in 0..1000 {
+            rand::thread_rng().fill_bytes(&mut data);
+            let mut u = Unstructured::new(&data);
+            let frame = Frame::arbitrary(&mut u).unwrap();
+            if frame.header.has_security() {
+                continue;
+            }
+
+            let mut buf = [0u8; 1024];
+            let mut len = 0usize;
+            buf.write_with(&mut len, frame, FooterMode::Explicit)
+                .unwrap();
+
+            let decoded: Frame =
+                buf[..len].read_with(&mut 0, FooterMode::Explicit).unwrap();
+            assert_eq!(decoded.header, frame.header);
+        }
+    }
+
     #[test]
     fn encode_ver0_short() {
         let frame = Frame {
@@ -610,6 +2086,7 @@ mod tests {
                 frame_pending: false,
                 ack_request: false,
                 pan_id_compress: false,
+                ie_present: false,
                 version: FrameVersion::Ieee802154_2003,
                 destination: Some(Address::Short(
                     PanId(0x1234),
@@ -628,12 +2105,7 @@ mod tests {
         };
         let mut buf = [0u8; 32];
         let mut len = 0usize;
-        buf.write_with(
-            &mut len,
-            frame,
-            &mut FrameSerDesContext::no_security(FooterMode::None),
-        )
-        .unwrap();
+        buf.write_with(&mut len, frame, FooterMode::None).unwrap();
         assert_eq!(len, 13);
         assert_eq!(
             buf[..len],
@@ -652,6 +2124,7 @@ mod tests {
                 frame_pending: true,
                 ack_request: false,
                 pan_id_compress: false,
+                ie_present: false,
                 version: FrameVersion::Ieee802154_2006,
                 destination: Some(Address::Extended(
                     PanId(0x1234),
@@ -682,12 +2155,7 @@ mod tests {
         };
         let mut buf = [0u8; 32];
         let mut len = 0usize;
-        buf.write_with(
-            &mut len,
-            frame,
-            &mut FrameSerDesContext::no_security(FooterMode::None),
-        )
-        .unwrap();
+        buf.write_with(&mut len, frame, FooterMode::None).unwrap();
         assert_eq!(len, 23);
         assert_eq!(
             buf[..len],
@@ -707,6 +2175,7 @@ mod tests {
                 frame_pending: false,
                 ack_request: false,
                 pan_id_compress: true,
+                ie_present: false,
                 version: FrameVersion::Ieee802154_2003,
                 destination: Some(Address::Extended(
                     PanId(0x1234),
@@ -723,15 +2192,12 @@ mod tests {
             payload: &[],
             footer: [0x00, 0x00],
         };
+        let header = frame.header;
         let mut buf = [0u8; 32];
         let mut len = 0usize;
-        buf.write_with(
-            &mut len,
-            frame,
-            &mut FrameSerDesContext::no_security(FooterMode::None),
-        )
-        .unwrap();
+        buf.write_with(&mut len, frame, FooterMode::None).unwrap();
         assert_eq!(len, 15);
+        assert_eq!(header.get_octet_size(), len);
         assert_eq!(
             buf[..len],
             [
@@ -741,6 +2207,67 @@ mod tests {
         );
     }
 
+    #[test]
+    fn encode_pan_compress_without_source() {
+        let frame = Frame {
+            header: Header {
+                frame_type: FrameType::Data,
+                frame_pending: false,
+                ack_request: false,
+                pan_id_compress: true,
+                ie_present: false,
+                version: FrameVersion::Ieee802154_2006,
+                destination: Some(Address::Short(
+                    PanId(0x1234),
+                    ShortAddress(0x5678),
+                )),
+                source: None,
+                seq: 0x01,
+                auxiliary_security_header: None,
+            },
+            content: FrameContent::Data,
+            payload: &[],
+            footer: [0x00, 0x00],
+        };
+        let mut buf = [0u8; 32];
+        let res = buf.write_with(&mut 0, frame, FooterMode::None);
+        assert_eq!(res, Err(EncodeError::InvalidPanIdCompress.into()));
+    }
+
+    #[test]
+    fn encode_secured_without_security_context() {
+        use crate::mac::security::{
+            AuxiliarySecurityHeader, SecurityControl, SecurityLevel,
+        };
+
+        let frame = Frame {
+            header: Header {
+                frame_type: FrameType::Data,
+                frame_pending: false,
+                ack_request: false,
+                pan_id_compress: false,
+                ie_present: false,
+                version: FrameVersion::Ieee802154_2006,
+                destination: Some(Address::Short(
+                    PanId(0x1234),
+                    ShortAddress(0x5678),
+                )),
+                source: None,
+                seq: 0x01,
+                auxiliary_security_header: Some(AuxiliarySecurityHeader::new(
+                    SecurityControl::new(SecurityLevel::ENCMIC32),
+                    None,
+                )),
+            },
+            content: FrameContent::Data,
+            payload: &[],
+            footer: [0x00, 0x00],
+        };
+        let mut buf = [0u8; 32];
+        let res = buf.write_with(&mut 0, frame, FooterMode::None);
+        assert_eq!(res, Err(EncodeError::MissingSecurityCtx.into()));
+    }
+
     #[test]
     fn encode_ver2_none() {
         let frame = Frame {
@@ -749,6 +2276,7 @@ mod tests {
                 frame_pending: false,
                 ack_request: true,
                 pan_id_compress: false,
+                ie_present: false,
                 version: FrameVersion::Ieee802154,
                 destination: None,
                 source: Some(Address::Short(
@@ -764,16 +2292,171 @@ mod tests {
         };
         let mut buf = [0u8; 32];
         let mut len = 0usize;
-        buf.write_with(
-            &mut len,
-            frame,
-            &mut FrameSerDesContext::no_security(FooterMode::None),
-        )
-        .unwrap();
+        buf.write_with(&mut len, frame, FooterMode::None).unwrap();
         assert_eq!(len, 8);
         assert_eq!(
             buf[..len],
             [0x23, 0xa0, 0xff, 0x34, 0x12, 0xbc, 0x9a, 0x04]
         );
     }
+
+    #[test]
+    fn decode_command_with_ies() {
+        use crate::mac::ie::IeWriter;
+
+        // A Time Correction IE and a header termination 2 IE, followed by a
+        // data request
+        let data = [
+            0x23, 0xa2, 0xff, 0x34, 0x12, 0xbc, 0x9a, 0x02, 0x0f, 0x01, 0x00,
+            0x80, 0x3f, 0x04,
+        ];
+        let frame: Frame = data.read_with(&mut 0, FooterMode::None).unwrap();
+        assert!(frame.header.ie_present);
+        assert_eq!(
+            frame.content,
+            FrameContent::Command(command::Command::DataRequest)
+        );
+        // The payload holds the IEs and the command
+        assert_eq!(frame.payload, &data[7..]);
+
+        // Encoding writes the IEs before the Command ID again
+        let mut buf = [0u8; 32];
+        let len = &mut 0;
+        buf.write_with(len, frame, FooterMode::None).unwrap();
+        assert_eq!(&buf[..*len], &data[..]);
+
+        // The command in the payload has to match the content
+        let mismatch = Frame {
+            content: FrameContent::Command(command::Command::BeaconRequest),
+            ..frame
+        };
+        let result = buf.write_with(&mut 0, mismatch, FooterMode::None);
+        assert!(result.is_err());
+
+        // Payload IEs precede the Command ID as well
+        let mut data = [0u8; 32];
+        data[..7].copy_from_slice(&[0x23, 0xa2, 0xff, 0x34, 0x12, 0xbc, 0x9a]);
+        let mut writer = IeWriter::new(&mut data[7..]);
+        writer
+            .raw_header_ie(0x20, &[0xaa])
+            .unwrap()
+            .raw_payload_ie(0x2, &[0xbb])
+            .unwrap();
+        let len = 7 + writer.finish(true).unwrap();
+        data[len] = 0x04;
+        let frame: Frame =
+            data[..len + 1].read_with(&mut 0, FooterMode::None).unwrap();
+        assert_eq!(
+            frame.content,
+            FrameContent::Command(command::Command::DataRequest)
+        );
+
+        // IEs that extend until the end of the frame leave no Command ID
+        let result =
+            data[..len - 2].read_with::<Frame>(&mut 0, FooterMode::None);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn decode_coordinator_realignment_explicit_footer() {
+        use crate::mac::command::{Command, CoordinatorRealignmentData};
+
+        // A coordinator realignment without channel page, followed by the FCS
+        let mut data = [
+            0x43, 0x98, 0x01, 0xcd, 0xab, 0xff, 0xff, 0x00, 0x00, 0x08, 0x34,
+            0x12, 0x00, 0x00, 0x0b, 0xff, 0xff, 0xaa, 0xbb, 0x00,
+        ];
+        let mut realignment = CoordinatorRealignmentData {
+            pan_id: PanId(0x1234),
+            coordinator_address: ShortAddress(0x0000),
+            channel: 0x0b,
+            device_address: ShortAddress::BROADCAST,
+            channel_page: None,
+        };
+        let frame: Frame =
+            data[..19].read_with(&mut 0, FooterMode::Explicit).unwrap();
+        assert_eq!(
+            frame.content,
+            FrameContent::Command(Command::CoordinatorRealignment(realignment))
+        );
+        assert!(frame.payload.is_empty());
+        assert_eq!(frame.footer, [0xaa, 0xbb]);
+
+        // With a channel page
+        data[17..].copy_from_slice(&[0x02, 0xaa, 0xbb]);
+        realignment.channel_page = Some(0x02);
+        let frame: Frame =
+            data.read_with(&mut 0, FooterMode::Explicit).unwrap();
+        assert_eq!(
+            frame.content,
+            FrameContent::Command(Command::CoordinatorRealignment(realignment))
+        );
+        assert!(frame.payload.is_empty());
+        assert_eq!(frame.footer, [0xaa, 0xbb]);
+
+        // Too short for the footer
+        let result = data[..4].read_with::<Frame>(&mut 0, FooterMode::Explicit);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn display_errors() {
+        use std::string::ToString;
+
+        assert_eq!(
+            DecodeError::InvalidFrameVersion(3).to_string(),
+            "invalid frame version: 3"
+        );
+        assert_eq!(
+            DecodeError::TrailingBytes.to_string(),
+            "trailing bytes after MAC command"
+        );
+        assert_eq!(
+            DecodeError::InvalidElementId(0x7e).to_string(),
+            "invalid IE element ID: 126"
+        );
+        assert_eq!(
+            EncodeError::MissingSecurityCtx.to_string(),
+            "missing security context"
+        );
+        #[cfg(feature = "security")]
+        assert_eq!(
+            security::SecurityError::ReplayedFrame.to_string(),
+            "replayed frame"
+        );
+    }
+
+    #[test]
+    fn decode_security_disabled() {
+        let mut data = [
+            0x09, 0x98, 0x00, 0x12, 0x34, 0x56, 0x78, 0x12, 0x34, 0x9a, 0xbc,
+            0x05, 0x01, 0x00, 0x00, 0x00, 0xde, 0xf0, 0x12, 0x34, 0x56, 0x78,
+        ];
+
+        assert!(matches!(
+            Frame::try_read_unsecured(&data, FooterMode::None),
+            Err(SecurityError::SecurityDisabled)
+        ));
+        assert!(matches!(
+            Frame::try_read_unsecured(&data[..2], FooterMode::None),
+            Err(SecurityError::ReadError(byte::Error::Incomplete))
+        ));
+
+        #[cfg(feature = "security")]
+        assert!(matches!(
+            Frame::try_read_and_unsecure(
+                &mut data,
+                &mut FrameSerDesContext::no_security(FooterMode::None),
+                &mut security::default::Unimplemented,
+            ),
+            Err(SecurityError::SecurityDisabled)
+        ));
+
+        // Frames without security are read as usual
+        data[0] = 0x01;
+        let (frame, len) =
+            Frame::try_read_unsecured(&data[..13], FooterMode::None).unwrap();
+        assert_eq!(frame.payload, &[0x05, 0x01]);
+        assert_eq!(len, 13);
+    }
 }
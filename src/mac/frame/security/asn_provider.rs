@@ -0,0 +1,61 @@
+//! The Absolute Slot Number that is used in the nonce of TSCH frames
+
+/// Provides the Absolute Slot Number (ASN) of a TSCH network
+///
+/// In TSCH networks, frames can use the ASN of the timeslot that they are
+/// sent in instead of the frame counter to build the CCM* nonce (see
+/// [`SecurityControl::with_asn_in_nonce`]). The ASN is not part of the frame,
+/// so the security context asks this provider for it when securing or
+/// unsecuring such a frame.
+///
+/// The implementation for `()` never provides an ASN, and is what the
+/// constructors of [`SecurityContext`] use.
+///
+/// # Example
+///
+/// ``` rust
+/// use core::cell::Cell;
+/// use ieee802154::mac::frame::security::AsnProvider;
+///
+/// /// The ASN of the current timeslot, kept up to date by the TSCH schedule
+/// struct CurrentSlot<'a> {
+///     asn: &'a Cell<u64>,
+/// }
+///
+/// impl AsnProvider for CurrentSlot<'_> {
+///     fn asn(&self) -> Option<u64> {
+///         Some(self.asn.get())
+///     }
+/// }
+///
+/// let asn = Cell::new(0x01_0000_0000);
+/// let provider = CurrentSlot { asn: &asn };
+/// asn.set(asn.get() + 1);
+/// assert_eq!(provider.asn(), Some(0x01_0000_0001));
+/// ```
+///
+/// [`SecurityControl::with_asn_in_nonce`]: super::SecurityControl::with_asn_in_nonce
+/// [`SecurityContext`]: super::SecurityContext
+pub trait AsnProvider {
+    /// The ASN of the timeslot in which the frame that is being secured or
+    /// unsecured is sent or received
+    ///
+    /// Only the lower 40 bits are used. Returns `None` if the ASN is not
+    /// known, e.g. because the device is not part of a TSCH network.
+    fn asn(&self) -> Option<u64>;
+}
+
+impl AsnProvider for () {
+    fn asn(&self) -> Option<u64> {
+        None
+    }
+}
+
+impl<T> AsnProvider for &T
+where
+    T: AsnProvider + ?Sized,
+{
+    fn asn(&self) -> Option<u64> {
+        (**self).asn()
+    }
+}
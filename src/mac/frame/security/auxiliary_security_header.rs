@@ -1,42 +1,67 @@
 //! All auxiliary security header structs and functions
 
+#[cfg(feature = "security")]
 use super::{
-    KeyDescriptorLookup, KeyIdentifierMode, SecurityContext, SecurityControl,
+    AsnProvider, CcmBackend, FrameCounterStorage, KeyDescriptorLookup,
+    NonceGenerator, SecurityContext, SecurityStatistics,
 };
+use super::{KeyIdentifierMode, SecurityControl, SecurityError, SecurityLevel};
+use crate::mac::{Address, ExtendedAddress, PanId, ShortAddress};
 use byte::{BytesExt, TryRead, TryWrite, LE};
+#[cfg(feature = "security")]
 use cipher::{consts::U16, BlockCipher, NewBlockCipher};
+#[cfg(feature = "security")]
+use core::borrow::BorrowMut;
 
 /// A struct describing the Auxiliary Security Header
 ///
 /// See: section 7.4
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AuxiliarySecurityHeader {
     /// The control field in the Auxiliary Security Header
     pub control: SecurityControl,
     /// The frame counter. This is automatically determined when running [`super::unsecure_frame`] and/or [`super::secure_frame`]
-    pub(crate) frame_counter: u32,
+    ///
+    /// This is `None` if the frame counter is suppressed
+    pub(crate) frame_counter: Option<u32>,
     /// If the key_identifier field in [`AuxiliarySecurityHeader::control`] is not set to None, this field contains the key identifier
     /// of this frame, otherwise it is None
     pub key_identifier: Option<KeyIdentifier>,
 }
 
 impl AuxiliarySecurityHeader {
+    /// The maximum size of an auxiliary security header, in octets
+    pub const MAX_OCTET_SIZE: usize = 14;
+
+    /// Get the size of an auxiliary security header that uses the provided
+    /// key identifier mode, in octets
+    ///
+    /// This includes the frame counter, which is omitted if frame counter
+    /// suppression is used.
+    pub const fn octet_size_for(key_id_mode: KeyIdentifierMode) -> usize {
+        // SecurityControl length + FrameCounter length
+        1 + 4 + key_id_mode.key_identifier_octet_size()
+    }
+
     /// Get the size of this security header, in octets
     pub fn get_octet_size(&self) -> usize {
-        // SecurityControl length + FrameCounter length
-        let length = 1
-            + 4
-            + match self.key_identifier {
-                Some(key_id) => match key_id.key_source {
-                    Some(source) => match source {
-                        KeySource::Short(_) => 5,
-                        KeySource::Long(_) => 9,
-                    },
-                    None => 1,
-                },
-                None => 0,
-            };
-        length
+        let size =
+            Self::octet_size_for(key_identifier_mode(self.key_identifier));
+        if self.control.frame_counter_suppression {
+            size - 4
+        } else {
+            size
+        }
+    }
+
+    /// Start building an auxiliary security header with security level
+    /// `security_level`, see [`AuxiliarySecurityHeaderBuilder`]
+    pub fn builder(
+        security_level: SecurityLevel,
+    ) -> AuxiliarySecurityHeaderBuilder {
+        AuxiliarySecurityHeaderBuilder::new(security_level)
     }
 
     /// Create a new Auxiliary Security Header with the specified control and key identifier
@@ -47,7 +72,7 @@ impl AuxiliarySecurityHeader {
         Self {
             control,
             key_identifier,
-            frame_counter: 0,
+            frame_counter: None,
         }
     }
 
@@ -63,24 +88,205 @@ impl AuxiliarySecurityHeader {
         Self {
             control,
             key_identifier,
-            frame_counter,
+            frame_counter: if control.frame_counter_suppression {
+                None
+            } else {
+                Some(frame_counter)
+            },
         }
     }
 
     /// Get the frame counter of this auxiliary security header.
     ///
-    /// This will be 0 on a non-parsed Auxiliary Security Header
-    pub fn get_frame_counter(&self) -> u32 {
+    /// This is `None` on a non-parsed Auxiliary Security Header, or if the
+    /// frame counter is suppressed
+    pub fn get_frame_counter(&self) -> Option<u32> {
         self.frame_counter
     }
 }
 
+/// A builder of an [`AuxiliarySecurityHeader`] that refuses invalid headers
+///
+/// Unlike constructing a header field by field, [`build`] checks that the
+/// security level, key identifier mode, key source and key index fit
+/// together, and that the frame counter is only suppressed if the nonce does
+/// not need it.
+///
+/// # Example
+///
+/// ``` rust
+/// use ieee802154::mac::frame::security::{
+///     AuxiliarySecurityHeader, KeyIdentifierMode, KeySource, SecurityError,
+///     SecurityLevel,
+/// };
+///
+/// let builder = AuxiliarySecurityHeader::builder(SecurityLevel::ENCMIC32)
+///     .key_id_mode(KeyIdentifierMode::KeySource4)
+///     .key_source(KeySource::Short(0x0102_0304))
+///     .key_index(5);
+/// assert_eq!(builder.octet_size(), 10);
+///
+/// let header = builder.build().unwrap();
+/// assert_eq!(header.get_octet_size(), 10);
+///
+/// // The key source does not fit the key identifier mode
+/// assert!(matches!(
+///     builder.key_source(KeySource::Long(0x0102_0304)).build(),
+///     Err(SecurityError::KeyIdentifierMismatch)
+/// ));
+///
+/// // Without the ASN, the nonce needs the frame counter
+/// assert!(matches!(
+///     builder.frame_counter_suppression(true).build(),
+///     Err(SecurityError::UnsupportedFrameCounterSuppression)
+/// ));
+/// let header = builder
+///     .frame_counter_suppression(true)
+///     .asn_in_nonce(true)
+///     .build()
+///     .unwrap();
+/// assert_eq!(header.get_octet_size(), 6);
+/// ```
+///
+/// [`build`]: AuxiliarySecurityHeaderBuilder::build
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct AuxiliarySecurityHeaderBuilder {
+    security_level: SecurityLevel,
+    key_id_mode: KeyIdentifierMode,
+    key_source: Option<KeySource>,
+    key_index: Option<u8>,
+    frame_counter_suppression: bool,
+    asn_in_nonce: bool,
+}
+
+impl AuxiliarySecurityHeaderBuilder {
+    /// Start building an auxiliary security header with security level
+    /// `security_level`, without a key identifier
+    pub const fn new(security_level: SecurityLevel) -> Self {
+        Self {
+            security_level,
+            key_id_mode: KeyIdentifierMode::None,
+            key_source: None,
+            key_index: None,
+            frame_counter_suppression: false,
+            asn_in_nonce: false,
+        }
+    }
+
+    /// Set the key identifier mode
+    pub const fn key_id_mode(mut self, key_id_mode: KeyIdentifierMode) -> Self {
+        self.key_id_mode = key_id_mode;
+        self
+    }
+
+    /// Set the key source, which must fit the key identifier mode
+    pub const fn key_source(mut self, key_source: KeySource) -> Self {
+        self.key_source = Some(key_source);
+        self
+    }
+
+    /// Set the key index, which is required by all key identifier modes
+    /// except [`KeyIdentifierMode::None`]
+    pub const fn key_index(mut self, key_index: u8) -> Self {
+        self.key_index = Some(key_index);
+        self
+    }
+
+    /// Set the key identifier mode, key source and key index from
+    /// `key_identifier`
+    pub const fn key_identifier(
+        mut self,
+        key_identifier: KeyIdentifier,
+    ) -> Self {
+        self.key_id_mode = key_identifier.key_id_mode();
+        self.key_source = key_identifier.key_source;
+        self.key_index = Some(key_identifier.key_index);
+        self
+    }
+
+    /// Set whether the frame counter is omitted from the header
+    pub const fn frame_counter_suppression(mut self, suppress: bool) -> Self {
+        self.frame_counter_suppression = suppress;
+        self
+    }
+
+    /// Set whether the nonce is built from the Absolute Slot Number (ASN) of
+    /// TSCH networks instead of the frame counter
+    pub const fn asn_in_nonce(mut self, asn_in_nonce: bool) -> Self {
+        self.asn_in_nonce = asn_in_nonce;
+        self
+    }
+
+    /// Get the size of the header that is built, in octets
+    pub const fn octet_size(&self) -> usize {
+        let size = AuxiliarySecurityHeader::octet_size_for(self.key_id_mode);
+        if self.frame_counter_suppression {
+            size - 4
+        } else {
+            size
+        }
+    }
+
+    /// Build the auxiliary security header
+    ///
+    /// # Errors
+    ///
+    /// - [`SecurityError::UnsupportedSecurity`] if the security level is
+    ///   [`SecurityLevel::None`], as such frames do not have an auxiliary
+    ///   security header
+    /// - [`SecurityError::KeyIdentifierMismatch`] if the key source or key
+    ///   index is missing, or present while the key identifier mode does not
+    ///   use it, or if the key source is of the wrong size
+    /// - [`SecurityError::UnsupportedFrameCounterSuppression`] if the frame
+    ///   counter is suppressed, but the nonce is not built from the ASN
+    pub fn build(self) -> Result<AuxiliarySecurityHeader, SecurityError> {
+        if self.security_level == SecurityLevel::None {
+            return Err(SecurityError::UnsupportedSecurity);
+        }
+        if self.frame_counter_suppression && !self.asn_in_nonce {
+            return Err(SecurityError::UnsupportedFrameCounterSuppression);
+        }
+
+        let key_identifier =
+            match (self.key_id_mode, self.key_source, self.key_index) {
+                (KeyIdentifierMode::None, None, None) => None,
+                (KeyIdentifierMode::KeyIndex, None, Some(key_index)) => {
+                    Some(KeyIdentifier::new(key_index))
+                }
+                (
+                    KeyIdentifierMode::KeySource4,
+                    Some(KeySource::Short(_)),
+                    Some(key_index),
+                )
+                | (
+                    KeyIdentifierMode::KeySource8,
+                    Some(KeySource::Long(_)),
+                    Some(key_index),
+                ) => Some(KeyIdentifier {
+                    key_source: self.key_source,
+                    key_index,
+                }),
+                _ => return Err(SecurityError::KeyIdentifierMismatch),
+            };
+
+        let mut control = SecurityControl::new(self.security_level)
+            .with_frame_counter_suppression(self.frame_counter_suppression)
+            .with_asn_in_nonce(self.asn_in_nonce);
+        control.key_id_mode = self.key_id_mode;
+        Ok(AuxiliarySecurityHeader::new(control, key_identifier))
+    }
+}
+
 impl TryRead<'_> for AuxiliarySecurityHeader {
     fn try_read(bytes: &[u8], _ctx: ()) -> byte::Result<(Self, usize)> {
         let offset = &mut 0;
 
         let control: SecurityControl = bytes.read(offset)?;
-        let frame_counter = bytes.read_with(offset, LE)?;
+        let frame_counter = if control.frame_counter_suppression {
+            None
+        } else {
+            Some(bytes.read_with(offset, LE)?)
+        };
 
         let mut header = AuxiliarySecurityHeader {
             control,
@@ -97,11 +303,11 @@ impl TryRead<'_> for AuxiliarySecurityHeader {
             match control.key_id_mode {
                 KeyIdentifierMode::KeySource4 => {
                     key_identifier.key_source =
-                        Some(KeySource::Short(bytes.read(offset)?));
+                        Some(KeySource::Short(bytes.read_with(offset, LE)?));
                 }
                 KeyIdentifierMode::KeySource8 => {
                     key_identifier.key_source =
-                        Some(KeySource::Long(bytes.read(offset)?));
+                        Some(KeySource::Long(bytes.read_with(offset, LE)?));
                 }
                 _ => {}
             }
@@ -114,34 +320,24 @@ impl TryRead<'_> for AuxiliarySecurityHeader {
     }
 }
 
-impl<AEADBLKCIPH, KEYDESCLO> TryWrite<&SecurityContext<AEADBLKCIPH, KEYDESCLO>>
-    for AuxiliarySecurityHeader
-where
-    AEADBLKCIPH: NewBlockCipher + BlockCipher<BlockSize = U16>,
-    KEYDESCLO: KeyDescriptorLookup<AEADBLKCIPH::KeySize>,
-{
-    fn try_write(
+impl AuxiliarySecurityHeader {
+    /// Write the auxiliary security header, with `frame_counter` as its
+    /// frame counter, unless the frame counter is suppressed
+    pub(crate) fn write_with_frame_counter(
         mut self,
         bytes: &mut [u8],
-        sec_ctx: &SecurityContext<AEADBLKCIPH, KEYDESCLO>,
+        frame_counter: u32,
     ) -> byte::Result<usize> {
         let offset = &mut 0;
 
         // Set the key id mode to that corresponding to the configured
         // key identifier in the control field
-        self.control.key_id_mode = match self.key_identifier {
-            Some(key_id) => match key_id.key_source {
-                Some(key_source) => match key_source {
-                    KeySource::Short(_) => KeyIdentifierMode::KeySource4,
-                    KeySource::Long(_) => KeyIdentifierMode::KeySource8,
-                },
-                None => KeyIdentifierMode::KeyIndex,
-            },
-            None => KeyIdentifierMode::None,
-        };
+        self.control.key_id_mode = key_identifier_mode(self.key_identifier);
 
         bytes.write(offset, self.control)?;
-        bytes.write(offset, sec_ctx.frame_counter)?;
+        if !self.control.frame_counter_suppression {
+            bytes.write(offset, frame_counter)?;
+        }
         match self.key_identifier {
             Some(key_identifier) => {
                 bytes.write(offset, key_identifier)?;
@@ -152,8 +348,101 @@ where
     }
 }
 
+#[cfg(feature = "security")]
+impl<
+        AEADBLKCIPH,
+        KEYDESCLO,
+        FRAMECTR,
+        CCMBACKEND,
+        ASNPROV,
+        NONCEGEN,
+        STATS,
+    >
+    TryWrite<
+        &SecurityContext<
+            AEADBLKCIPH,
+            KEYDESCLO,
+            FRAMECTR,
+            CCMBACKEND,
+            ASNPROV,
+            NONCEGEN,
+            STATS,
+        >,
+    > for AuxiliarySecurityHeader
+where
+    AEADBLKCIPH: NewBlockCipher + BlockCipher<BlockSize = U16>,
+    KEYDESCLO: KeyDescriptorLookup<AEADBLKCIPH::KeySize>,
+    FRAMECTR: FrameCounterStorage,
+    CCMBACKEND: CcmBackend<AEADBLKCIPH::KeySize>,
+    ASNPROV: AsnProvider,
+    NONCEGEN: NonceGenerator,
+    STATS: BorrowMut<SecurityStatistics>,
+{
+    fn try_write(
+        self,
+        bytes: &mut [u8],
+        sec_ctx: &SecurityContext<
+            AEADBLKCIPH,
+            KEYDESCLO,
+            FRAMECTR,
+            CCMBACKEND,
+            ASNPROV,
+            NONCEGEN,
+            STATS,
+        >,
+    ) -> byte::Result<usize> {
+        self.write_with_frame_counter(bytes, sec_ctx.frame_counter.load())
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for AuxiliarySecurityHeader {
+    fn arbitrary(
+        u: &mut arbitrary::Unstructured<'a>,
+    ) -> arbitrary::Result<Self> {
+        let key_identifier = u.arbitrary()?;
+        let mut control = SecurityControl::new(u.arbitrary()?)
+            .with_frame_counter_suppression(u.arbitrary()?);
+        control.key_id_mode = key_identifier_mode(key_identifier);
+        let frame_counter = if control.frame_counter_suppression {
+            None
+        } else {
+            Some(u.arbitrary()?)
+        };
+        Ok(AuxiliarySecurityHeader {
+            control,
+            frame_counter,
+            key_identifier,
+        })
+    }
+}
+
+const _: () = assert!(
+    AuxiliarySecurityHeader::octet_size_for(KeyIdentifierMode::KeySource8)
+        == AuxiliarySecurityHeader::MAX_OCTET_SIZE
+);
+
+/// Get the key identifier mode that corresponds to `key_identifier`
+const fn key_identifier_mode(
+    key_identifier: Option<KeyIdentifier>,
+) -> KeyIdentifierMode {
+    match key_identifier {
+        Some(key_id) => match key_id.key_source {
+            Some(key_source) => match key_source {
+                KeySource::Short(_) => KeyIdentifierMode::KeySource4,
+                KeySource::Long(_) => KeyIdentifierMode::KeySource8,
+            },
+            None => KeyIdentifierMode::KeyIndex,
+        },
+        None => KeyIdentifierMode::None,
+    }
+}
+
 /// A key identifier
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct KeyIdentifier {
     /// The key source to be used for determining a key from this key identifier (if any)
     pub key_source: Option<KeySource>,
@@ -161,13 +450,79 @@ pub struct KeyIdentifier {
     pub key_index: u8,
 }
 
+impl KeyIdentifier {
+    /// Create a key identifier that only consists of a key index, using
+    /// [`KeyIdentifierMode::KeyIndex`]
+    pub const fn new(key_index: u8) -> Self {
+        Self {
+            key_source: None,
+            key_index,
+        }
+    }
+
+    /// Create a key identifier with a 4 octet key source that is made up of
+    /// `pan_id` and `short_address`, using [`KeyIdentifierMode::KeySource4`]
+    ///
+    /// # Example
+    ///
+    /// ``` rust
+    /// use byte::BytesExt;
+    /// use ieee802154::mac::{
+    ///     frame::security::{KeyIdentifier, KeyIdentifierMode},
+    ///     Address, PanId, ShortAddress,
+    /// };
+    ///
+    /// let key_identifier =
+    ///     KeyIdentifier::from_short_source(PanId(0x1234), ShortAddress(0x5678), 2);
+    /// assert_eq!(key_identifier.key_id_mode(), KeyIdentifierMode::KeySource4);
+    /// assert_eq!(
+    ///     key_identifier.key_source.unwrap().short_address(),
+    ///     Some(Address::Short(PanId(0x1234), ShortAddress(0x5678)))
+    /// );
+    ///
+    /// let mut bytes = [0u8; 5];
+    /// bytes.write(&mut 0, key_identifier).unwrap();
+    /// assert_eq!(bytes, [0x78, 0x56, 0x34, 0x12, 0x02]);
+    /// ```
+    pub const fn from_short_source(
+        pan_id: PanId,
+        short_address: ShortAddress,
+        key_index: u8,
+    ) -> Self {
+        Self {
+            key_source: Some(KeySource::from_short_address(
+                pan_id,
+                short_address,
+            )),
+            key_index,
+        }
+    }
+
+    /// Create a key identifier with an 8 octet key source that is `address`,
+    /// using [`KeyIdentifierMode::KeySource8`]
+    pub const fn from_extended_source(
+        address: ExtendedAddress,
+        key_index: u8,
+    ) -> Self {
+        Self {
+            key_source: Some(KeySource::from_extended_address(address)),
+            key_index,
+        }
+    }
+
+    /// Get the key identifier mode that is used to write this key identifier
+    pub const fn key_id_mode(&self) -> KeyIdentifierMode {
+        key_identifier_mode(Some(*self))
+    }
+}
+
 impl TryWrite for KeyIdentifier {
     fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
         let offset = &mut 0;
         match self.key_source {
             Some(source) => match source {
-                KeySource::Short(src) => bytes.write(offset, src)?,
-                KeySource::Long(src) => bytes.write(offset, src)?,
+                KeySource::Short(src) => bytes.write_with(offset, src, LE)?,
+                KeySource::Long(src) => bytes.write_with(offset, src, LE)?,
             },
             _ => {}
         }
@@ -180,9 +535,52 @@ impl TryWrite for KeyIdentifier {
 
 /// A key source
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum KeySource {
     /// A key source that is 4 octets long
     Short(u32),
     /// A key source that is 8 octets long
     Long(u64),
 }
+
+impl KeySource {
+    /// Create a 4 octet key source from a PAN identifier and short address,
+    /// i.e. `pan_id || short_address`
+    pub const fn from_short_address(
+        pan_id: PanId,
+        short_address: ShortAddress,
+    ) -> Self {
+        KeySource::Short((pan_id.0 as u32) << 16 | short_address.0 as u32)
+    }
+
+    /// Create an 8 octet key source from an extended address
+    pub const fn from_extended_address(address: ExtendedAddress) -> Self {
+        KeySource::Long(address.0)
+    }
+
+    /// Get the PAN identifier and short address that make up a 4 octet key
+    /// source
+    ///
+    /// Returns `None` for an 8 octet key source.
+    pub const fn short_address(&self) -> Option<Address> {
+        match *self {
+            KeySource::Short(source) => Some(Address::Short(
+                PanId((source >> 16) as u16),
+                ShortAddress(source as u16),
+            )),
+            KeySource::Long(_) => None,
+        }
+    }
+
+    /// Get the extended address of an 8 octet key source
+    ///
+    /// Returns `None` for a 4 octet key source.
+    pub const fn extended_address(&self) -> Option<ExtendedAddress> {
+        match *self {
+            KeySource::Short(_) => None,
+            KeySource::Long(source) => Some(ExtendedAddress(source)),
+        }
+    }
+}
@@ -0,0 +1,220 @@
+//! The CCM* transformation that is used to secure and unsecure frames
+
+use super::SecurityError;
+use ccm::{
+    aead::{
+        generic_array::{
+            typenum::consts::{U13, U16, U4, U8},
+            ArrayLength, GenericArray,
+        },
+        AeadInPlace, NewAead,
+    },
+    Ccm,
+};
+use cipher::{Block, BlockCipher, BlockEncrypt, NewBlockCipher};
+use core::marker::PhantomData;
+
+/// Performs the CCM* transformation for frames with a key of size `N`
+///
+/// This is what [`SecurityContext`] uses to secure and unsecure frames. By
+/// default, that is [`SoftwareCcm`]. Implementations can delegate to the
+/// hardware CCM engine that many SoCs with a radio have instead.
+///
+/// The tag length, and thus the MIC size of the security level, is given by
+/// the length of the `tag` slice, and is one of 4, 8 or 16 octets. For
+/// security levels without encryption, `payload` is empty and the data that
+/// is to be authenticated is passed as `aad`.
+///
+/// For [`SecurityLevel::ENC`], which encrypts without authenticating, `tag`
+/// is empty. Backends that do not support this should return
+/// [`SecurityError::TransformationError`].
+///
+/// [`SecurityLevel::ENC`]: super::SecurityLevel::ENC
+///
+/// [`SecurityContext`]: super::SecurityContext
+pub trait CcmBackend<N>
+where
+    N: ArrayLength<u8>,
+{
+    /// Encrypt `payload` in place, and write the authentication tag over
+    /// `aad` and `payload` to `tag`
+    fn encrypt_and_tag(
+        &mut self,
+        key: &GenericArray<u8, N>,
+        nonce: &[u8; 13],
+        aad: &[u8],
+        payload: &mut [u8],
+        tag: &mut [u8],
+    ) -> Result<(), SecurityError>;
+
+    /// Verify `tag` over `aad` and `payload`, and decrypt `payload` in place
+    ///
+    /// Should return [`SecurityError::MicMismatch`] if the tag does
+    /// not match.
+    fn decrypt_and_verify(
+        &mut self,
+        key: &GenericArray<u8, N>,
+        nonce: &[u8; 13],
+        aad: &[u8],
+        payload: &mut [u8],
+        tag: &[u8],
+    ) -> Result<(), SecurityError>;
+}
+
+impl<N, T> CcmBackend<N> for &mut T
+where
+    N: ArrayLength<u8>,
+    T: CcmBackend<N> + ?Sized,
+{
+    fn encrypt_and_tag(
+        &mut self,
+        key: &GenericArray<u8, N>,
+        nonce: &[u8; 13],
+        aad: &[u8],
+        payload: &mut [u8],
+        tag: &mut [u8],
+    ) -> Result<(), SecurityError> {
+        (**self).encrypt_and_tag(key, nonce, aad, payload, tag)
+    }
+
+    fn decrypt_and_verify(
+        &mut self,
+        key: &GenericArray<u8, N>,
+        nonce: &[u8; 13],
+        aad: &[u8],
+        payload: &mut [u8],
+        tag: &[u8],
+    ) -> Result<(), SecurityError> {
+        (**self).decrypt_and_verify(key, nonce, aad, payload, tag)
+    }
+}
+
+/// A [`CcmBackend`] that performs CCM* in software, using the block cipher
+/// `AEADBLKCIPH`
+pub struct SoftwareCcm<AEADBLKCIPH> {
+    phantom_data: PhantomData<AEADBLKCIPH>,
+}
+
+impl<AEADBLKCIPH> SoftwareCcm<AEADBLKCIPH> {
+    /// Create a new software CCM* backend
+    pub fn new() -> Self {
+        Self {
+            phantom_data: PhantomData,
+        }
+    }
+}
+
+/// A [`CcmBackend`] that performs AES-128 CCM* in software
+#[cfg(feature = "soft-crypto")]
+pub type Aes128Ccm = SoftwareCcm<aes::Aes128>;
+
+impl<AEADBLKCIPH> Default for SoftwareCcm<AEADBLKCIPH> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<AEADBLKCIPH> Clone for SoftwareCcm<AEADBLKCIPH> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<AEADBLKCIPH> Copy for SoftwareCcm<AEADBLKCIPH> {}
+
+impl<AEADBLKCIPH> CcmBackend<AEADBLKCIPH::KeySize> for SoftwareCcm<AEADBLKCIPH>
+where
+    AEADBLKCIPH: NewBlockCipher + BlockCipher<BlockSize = U16> + BlockEncrypt,
+{
+    fn encrypt_and_tag(
+        &mut self,
+        key: &GenericArray<u8, AEADBLKCIPH::KeySize>,
+        nonce: &[u8; 13],
+        aad: &[u8],
+        payload: &mut [u8],
+        tag: &mut [u8],
+    ) -> Result<(), SecurityError> {
+        macro_rules! encrypt {
+            ($tag_size:ty) => {
+                Ccm::<AEADBLKCIPH, $tag_size, U13>::new(key)
+                    .encrypt_in_place_detached(
+                        GenericArray::from_slice(nonce),
+                        aad,
+                        payload,
+                    )
+                    .map(|computed| tag.copy_from_slice(&computed))
+            };
+        }
+
+        match tag.len() {
+            0 => {
+                ctr::<AEADBLKCIPH>(key, nonce, payload);
+                return Ok(());
+            }
+            4 => encrypt!(U4),
+            8 => encrypt!(U8),
+            16 => encrypt!(U16),
+            _ => return Err(SecurityError::TransformationError),
+        }
+        .map_err(|_| SecurityError::TransformationError)
+    }
+
+    fn decrypt_and_verify(
+        &mut self,
+        key: &GenericArray<u8, AEADBLKCIPH::KeySize>,
+        nonce: &[u8; 13],
+        aad: &[u8],
+        payload: &mut [u8],
+        tag: &[u8],
+    ) -> Result<(), SecurityError> {
+        macro_rules! decrypt {
+            ($tag_size:ty) => {
+                Ccm::<AEADBLKCIPH, $tag_size, U13>::new(key)
+                    .decrypt_in_place_detached(
+                        GenericArray::from_slice(nonce),
+                        aad,
+                        payload,
+                        GenericArray::from_slice(tag),
+                    )
+            };
+        }
+
+        match tag.len() {
+            0 => {
+                ctr::<AEADBLKCIPH>(key, nonce, payload);
+                return Ok(());
+            }
+            4 => decrypt!(U4),
+            8 => decrypt!(U8),
+            16 => decrypt!(U16),
+            _ => return Err(SecurityError::TransformationError),
+        }
+        .map_err(|_| SecurityError::MicMismatch)
+    }
+}
+
+/// Encrypt or decrypt `payload` in place without authentication, as CCM*
+/// does if the tag is empty
+///
+/// This is CTR mode, with the counter blocks A1, A2, … of CCM* (B.4.1.3 of
+/// 802.15.4-2006).
+fn ctr<AEADBLKCIPH>(
+    key: &GenericArray<u8, AEADBLKCIPH::KeySize>,
+    nonce: &[u8; 13],
+    payload: &mut [u8],
+) where
+    AEADBLKCIPH: NewBlockCipher + BlockCipher<BlockSize = U16> + BlockEncrypt,
+{
+    let cipher = AEADBLKCIPH::new(key);
+    for (i, chunk) in payload.chunks_mut(16).enumerate() {
+        // Flags: the length field is 2 octets long (L - 1 = 1)
+        let mut block = Block::<AEADBLKCIPH>::default();
+        block[0] = 0x01;
+        block[1..14].copy_from_slice(nonce);
+        block[14..].copy_from_slice(&(i as u16 + 1).to_be_bytes());
+        cipher.encrypt_block(&mut block);
+        for (byte, key_stream) in chunk.iter_mut().zip(block.iter()) {
+            *byte ^= key_stream;
+        }
+    }
+}
@@ -57,6 +57,7 @@ impl DeviceDescriptorLookup for Unimplemented {
         &mut self,
         _addressing_mode: AddressingMode,
         _address: Address,
+        _key_identifier: Option<KeyIdentifier>,
     ) -> Option<&mut DeviceDescriptor> {
         None
     }
@@ -0,0 +1,276 @@
+//! A device table that is backed by a slice of entries
+
+use super::{
+    AddressingMode, DeviceDescriptor, DeviceDescriptorLookup, KeyIdentifier,
+};
+use crate::mac::Address;
+
+/// An entry in a [`DeviceTable`]
+///
+/// Associates a [`DeviceDescriptor`] (and thus the last accepted frame
+/// counter) with the key that is used to secure frames from that device.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceTableEntry {
+    /// The key identifier of the key used by the device
+    pub key_identifier: Option<KeyIdentifier>,
+    /// The descriptor of the device
+    pub descriptor: DeviceDescriptor,
+    /// Whether the device may no longer use the key
+    ///
+    /// Blacklisted entries are not returned by
+    /// [`DeviceDescriptorLookup::lookup_device`].
+    pub blacklisted: bool,
+}
+
+/// A table of device descriptors, keyed by device and key identifier
+///
+/// Devices are identified by the addresses in their [`DeviceDescriptor`].
+///
+/// The table does not allocate: it stores its entries in a slice provided by
+/// the user, e.g. a static array. Users that need a different kind of storage
+/// (a map, or external storage) can implement [`DeviceDescriptorLookup`]
+/// themselves.
+///
+/// # Example
+///
+/// ``` rust
+/// use ieee802154::mac::{
+///     frame::security::{
+///         AddressingMode, DeviceDescriptor, DeviceDescriptorLookup,
+///         DeviceTable, DeviceTableEntry,
+///     },
+///     Address, ExtendedAddress, PanId, ShortAddress,
+/// };
+///
+/// let mut storage = [None; 8];
+/// let mut table = DeviceTable::new(&mut storage);
+/// table
+///     .insert(DeviceTableEntry {
+///         key_identifier: None,
+///         descriptor: DeviceDescriptor {
+///             pan_id: PanId(0x1234),
+///             short_address: ShortAddress(0x5678),
+///             extended_address: ExtendedAddress(0x0102030405060708),
+///             frame_counter: 0,
+///             exempt: false,
+///         },
+///         blacklisted: false,
+///     })
+///     .unwrap();
+///
+/// let address = Address::Short(PanId(0x1234), ShortAddress(0x5678));
+/// let device = table
+///     .lookup_device(AddressingMode::SrcAddrMode, address, None)
+///     .unwrap();
+/// assert_eq!(device.frame_counter, 0);
+/// ```
+pub struct DeviceTable<'a> {
+    entries: &'a mut [Option<DeviceTableEntry>],
+}
+
+impl<'a> DeviceTable<'a> {
+    /// Create a new device table that stores its entries in `entries`
+    ///
+    /// Slots that are `Some` are treated as existing entries.
+    pub fn new(entries: &'a mut [Option<DeviceTableEntry>]) -> Self {
+        Self { entries }
+    }
+
+    /// Insert an entry into the table
+    ///
+    /// An existing entry with the same extended address and key identifier
+    /// is replaced. Returns the entry if the table is full.
+    pub fn insert(
+        &mut self,
+        entry: DeviceTableEntry,
+    ) -> Result<(), DeviceTableEntry> {
+        let address = Address::Extended(
+            entry.descriptor.pan_id,
+            entry.descriptor.extended_address,
+        );
+        let slot = match self.position(address, entry.key_identifier) {
+            Some(index) => Some(index),
+            None => self.entries.iter().position(|e| e.is_none()),
+        };
+
+        match slot {
+            Some(index) => {
+                self.entries[index] = Some(entry);
+                Ok(())
+            }
+            None => Err(entry),
+        }
+    }
+
+    /// Remove the entry for the device with the given address and key
+    /// identifier from the table, returning its descriptor
+    pub fn remove(
+        &mut self,
+        address: Address,
+        key_identifier: Option<KeyIdentifier>,
+    ) -> Option<DeviceDescriptor> {
+        let index = self.position(address, key_identifier)?;
+        self.entries[index].take().map(|entry| entry.descriptor)
+    }
+
+    /// Blacklist the device with the given address for the key with the
+    /// given key identifier
+    ///
+    /// Returns whether the table has an entry for the device and key.
+    pub fn blacklist(
+        &mut self,
+        address: Address,
+        key_identifier: Option<KeyIdentifier>,
+    ) -> bool {
+        match self.position(address, key_identifier) {
+            Some(index) => {
+                if let Some(entry) = self.entries[index].as_mut() {
+                    entry.blacklisted = true;
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Iterate over all entries in the table
+    pub fn iter(&self) -> impl Iterator<Item = &DeviceTableEntry> {
+        self.entries.iter().filter_map(|entry| entry.as_ref())
+    }
+
+    fn position(
+        &self,
+        address: Address,
+        key_identifier: Option<KeyIdentifier>,
+    ) -> Option<usize> {
+        self.entries.iter().position(|entry| match entry {
+            Some(entry) => {
+                entry.descriptor.matches(address)
+                    && entry.key_identifier == key_identifier
+            }
+            None => false,
+        })
+    }
+}
+
+impl DeviceDescriptorLookup for DeviceTable<'_> {
+    fn lookup_device(
+        &mut self,
+        _addressing_mode: AddressingMode,
+        address: Address,
+        key_identifier: Option<KeyIdentifier>,
+    ) -> Option<&mut DeviceDescriptor> {
+        let index = self.position(address, key_identifier)?;
+        self.entries[index]
+            .as_mut()
+            .filter(|entry| !entry.blacklisted)
+            .map(|entry| &mut entry.descriptor)
+    }
+
+    fn blacklist_device(
+        &mut self,
+        address: Address,
+        key_identifier: Option<KeyIdentifier>,
+    ) {
+        self.blacklist(address, key_identifier);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mac::{ExtendedAddress, PanId, ShortAddress};
+
+    fn entry(
+        extended_address: u64,
+        key_index: u8,
+        frame_counter: u32,
+    ) -> DeviceTableEntry {
+        DeviceTableEntry {
+            key_identifier: Some(KeyIdentifier {
+                key_source: None,
+                key_index,
+            }),
+            descriptor: DeviceDescriptor {
+                pan_id: PanId(0x1234),
+                short_address: ShortAddress(extended_address as u16),
+                extended_address: ExtendedAddress(extended_address),
+                frame_counter,
+                exempt: false,
+            },
+            blacklisted: false,
+        }
+    }
+
+    #[test]
+    fn insert_lookup_remove() {
+        let short = Address::Short(PanId(0x1234), ShortAddress(0x0001));
+        let extended = Address::Extended(PanId(0x1234), ExtendedAddress(0x02));
+
+        let mut storage = [None; 2];
+        let mut table = DeviceTable::new(&mut storage);
+
+        assert_eq!(table.insert(entry(0x01, 1, 10)), Ok(()));
+        assert_eq!(table.insert(entry(0x01, 2, 20)), Ok(()));
+        // Replacing an existing entry does not need a free slot
+        assert_eq!(table.insert(entry(0x01, 1, 11)), Ok(()));
+        assert_eq!(table.insert(entry(0x02, 1, 30)), Err(entry(0x02, 1, 30)));
+        assert_eq!(table.iter().count(), 2);
+
+        // Frame counters are kept per device and key
+        let key_1 = entry(0, 1, 0).key_identifier;
+        let device = table
+            .lookup_device(AddressingMode::SrcAddrMode, short, key_1)
+            .unwrap();
+        assert_eq!(device.frame_counter, 11);
+        device.frame_counter = 12;
+
+        let key_2 = entry(0, 2, 0).key_identifier;
+        let device = table
+            .lookup_device(AddressingMode::SrcAddrMode, short, key_2)
+            .unwrap();
+        assert_eq!(device.frame_counter, 20);
+
+        assert!(table
+            .lookup_device(AddressingMode::SrcAddrMode, extended, key_1)
+            .is_none());
+
+        assert_eq!(table.remove(short, key_1).unwrap().frame_counter, 12);
+        assert_eq!(table.remove(short, key_1), None);
+        assert_eq!(table.insert(entry(0x02, 1, 30)), Ok(()));
+        assert!(table
+            .lookup_device(AddressingMode::SrcAddrMode, extended, key_1)
+            .is_some());
+    }
+
+    #[test]
+    fn blacklist() {
+        let short = Address::Short(PanId(0x1234), ShortAddress(0x0001));
+        let key_1 = entry(0, 1, 0).key_identifier;
+        let key_2 = entry(0, 2, 0).key_identifier;
+
+        let mut storage = [None; 2];
+        let mut table = DeviceTable::new(&mut storage);
+        table.insert(entry(0x01, 1, 0xFFFF_FFFD)).unwrap();
+        table.insert(entry(0x01, 2, 0)).unwrap();
+
+        let device = table
+            .lookup_device(AddressingMode::SrcAddrMode, short, key_1)
+            .unwrap();
+        assert!(!device.update_frame_counter(0xFFFF_FFFD));
+        assert!(device.update_frame_counter(0xFFFF_FFFE));
+        assert_eq!(device.frame_counter, 0xFFFF_FFFF);
+
+        table.blacklist_device(short, key_1);
+        assert!(table
+            .lookup_device(AddressingMode::SrcAddrMode, short, key_1)
+            .is_none());
+        // Only the exhausted key is blacklisted
+        assert!(table
+            .lookup_device(AddressingMode::SrcAddrMode, short, key_2)
+            .is_some());
+        assert!(!table.blacklist(short, None));
+    }
+}
@@ -0,0 +1,109 @@
+//! Storage of the frame counter of outgoing frames
+
+use super::SecurityError;
+
+/// Storage for the frame counter that is used to secure outgoing frames
+///
+/// A frame counter value must never be used twice with the same key, so the
+/// counter has to survive reboots. Implementations can persist it to flash or
+/// EEPROM. To avoid writing on every frame, they can do so in batches: store
+/// a value ahead of the current counter, and only write again once the
+/// counter reaches it. After a reboot, counting continues from the stored
+/// value, skipping the values that may have been used.
+///
+/// The implementation for `u32` is volatile, and is what
+/// [`SecurityContext::new`] uses.
+///
+/// # Example
+///
+/// ``` rust
+/// use ieee802154::mac::frame::security::{FrameCounterStorage, SecurityError};
+///
+/// /// Stores the frame counter in batches of 1024
+/// struct BatchedFrameCounter {
+///     frame_counter: u32,
+///     stored: u32,
+/// }
+///
+/// impl BatchedFrameCounter {
+///     fn write_to_flash(&mut self, value: u32) -> Result<(), SecurityError> {
+///         // Write `value` to flash here
+///         self.stored = value;
+///         Ok(())
+///     }
+/// }
+///
+/// impl FrameCounterStorage for BatchedFrameCounter {
+///     fn load(&self) -> u32 {
+///         self.frame_counter
+///     }
+///
+///     fn increment(&mut self) {
+///         self.frame_counter += 1;
+///     }
+///
+///     fn commit(&mut self) -> Result<(), SecurityError> {
+///         if self.frame_counter >= self.stored {
+///             let next = self.frame_counter.saturating_add(1024);
+///             self.write_to_flash(next)?;
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// let mut storage = BatchedFrameCounter {
+///     frame_counter: 0,
+///     stored: 0,
+/// };
+/// storage.increment();
+/// storage.commit().unwrap();
+/// assert_eq!(storage.load(), 1);
+/// assert_eq!(storage.stored, 1025);
+/// ```
+///
+/// [`SecurityContext::new`]: super::SecurityContext::new
+pub trait FrameCounterStorage {
+    /// Load the frame counter to use for the next outgoing secured frame
+    fn load(&self) -> u32;
+
+    /// Increment the frame counter, after its current value has been used
+    /// to secure a frame
+    fn increment(&mut self);
+
+    /// Make sure that the incremented frame counter survives a reboot
+    ///
+    /// This is called after every increment, before the secured frame is
+    /// written out. If this fails, the frame is not written.
+    fn commit(&mut self) -> Result<(), SecurityError>;
+}
+
+impl FrameCounterStorage for u32 {
+    fn load(&self) -> u32 {
+        *self
+    }
+
+    fn increment(&mut self) {
+        *self += 1;
+    }
+
+    fn commit(&mut self) -> Result<(), SecurityError> {
+        Ok(())
+    }
+}
+
+impl<T> FrameCounterStorage for &mut T
+where
+    T: FrameCounterStorage + ?Sized,
+{
+    fn load(&self) -> u32 {
+        (**self).load()
+    }
+
+    fn increment(&mut self) {
+        (**self).increment()
+    }
+
+    fn commit(&mut self) -> Result<(), SecurityError> {
+        (**self).commit()
+    }
+}
@@ -0,0 +1,240 @@
+//! Keys that are derived on demand, instead of being stored in a key table
+
+use super::{AddressingMode, KeyDescriptorLookup, KeyIdentifier, KeySource};
+use crate::mac::Address;
+use ccm::aead::generic_array::{ArrayLength, GenericArray};
+
+/// Derives MAC keys from the key identifier of a frame
+///
+/// Higher layers such as Thread and Zigbee derive the MAC keys from a network
+/// master key and a key sequence, instead of distributing every key. The key
+/// sequence is carried in the key source and/or key index of the key
+/// identifier: Thread, for example, uses key identifier mode 2, with the key
+/// sequence as the 4 octet key source and `(key sequence mod 128) + 1` as the
+/// key index.
+///
+/// Use [`DerivedKeys`] to look up keys with a derivation, without
+/// maintaining a materialized [`KeyTable`].
+///
+/// # Example
+///
+/// ``` rust
+/// use ieee802154::mac::frame::security::{
+///     AddressingMode, DerivedKeys, KeyDerivation, KeyDescriptorLookup,
+///     KeyIdentifier, KeySource, U16,
+/// };
+/// use ieee802154::mac::{Address, ExtendedAddress, PanId};
+/// use ccm::aead::generic_array::GenericArray;
+///
+/// /// Derives the key of a key sequence from a master key
+/// struct MasterKey([u8; 16]);
+///
+/// impl KeyDerivation<U16> for MasterKey {
+///     fn derive_key(
+///         &self,
+///         key_source: Option<KeySource>,
+///         key_index: u8,
+///     ) -> Option<GenericArray<u8, U16>> {
+///         let sequence = match key_source {
+///             Some(KeySource::Short(sequence)) => sequence,
+///             _ => return None,
+///         };
+///         if key_index != (sequence % 128) as u8 + 1 {
+///             return None;
+///         }
+///         // Use a proper key derivation function, such as HMAC-SHA256, here
+///         let mut key = self.0;
+///         for (k, s) in key.iter_mut().zip(sequence.to_be_bytes().iter()) {
+///             *k ^= s;
+///         }
+///         Some(GenericArray::clone_from_slice(&key))
+///     }
+/// }
+///
+/// let keys = DerivedKeys::new(MasterKey([0xC0; 16]));
+/// let key_identifier = KeyIdentifier {
+///     key_source: Some(KeySource::Short(1)),
+///     key_index: 2,
+/// };
+/// let source = Address::Extended(PanId(0x1234), ExtendedAddress(0x0102));
+///
+/// let (euid, _key) = keys
+///     .lookup_key_descriptor(
+///         AddressingMode::SrcAddrMode,
+///         Some(key_identifier),
+///         Some(source),
+///     )
+///     .unwrap();
+/// assert_eq!(euid, 0x0102);
+/// ```
+///
+/// [`KeyTable`]: super::KeyTable
+pub trait KeyDerivation<N>
+where
+    N: ArrayLength<u8>,
+{
+    /// Derive the key that is identified by `key_source` and `key_index`
+    ///
+    /// This should return `None` if no key can be derived, e.g. because the
+    /// key sequence is too far from the current one.
+    fn derive_key(
+        &self,
+        key_source: Option<KeySource>,
+        key_index: u8,
+    ) -> Option<GenericArray<u8, N>>;
+
+    /// Look up the extended address of the device with address `address`
+    ///
+    /// This is used to calculate the nonce of incoming frames. By default,
+    /// only extended addresses can be resolved.
+    fn device_euid(&self, address: Address) -> Option<u64> {
+        match address {
+            Address::Extended(_, extended_address) => Some(extended_address.0),
+            Address::Short(..) => None,
+        }
+    }
+}
+
+impl<N, T> KeyDerivation<N> for &T
+where
+    N: ArrayLength<u8>,
+    T: KeyDerivation<N> + ?Sized,
+{
+    fn derive_key(
+        &self,
+        key_source: Option<KeySource>,
+        key_index: u8,
+    ) -> Option<GenericArray<u8, N>> {
+        (**self).derive_key(key_source, key_index)
+    }
+
+    fn device_euid(&self, address: Address) -> Option<u64> {
+        (**self).device_euid(address)
+    }
+}
+
+/// A key descriptor lookup that derives keys with a [`KeyDerivation`]
+///
+/// Only explicitly identified keys can be derived, so frames that use the
+/// implicit key identifier mode are not secured or unsecured.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct DerivedKeys<D> {
+    /// The derivation that keys are derived with
+    pub derivation: D,
+}
+
+impl<D> DerivedKeys<D> {
+    /// Look up keys by deriving them with `derivation`
+    pub const fn new(derivation: D) -> Self {
+        Self { derivation }
+    }
+}
+
+impl<N, D> KeyDescriptorLookup<N> for DerivedKeys<D>
+where
+    N: ArrayLength<u8>,
+    D: KeyDerivation<N>,
+{
+    fn lookup_key_descriptor(
+        &self,
+        address_mode: AddressingMode,
+        key_identifier: Option<KeyIdentifier>,
+        device_address: Option<Address>,
+    ) -> Option<(u64, GenericArray<u8, N>)> {
+        let key_identifier = key_identifier?;
+        let key = self
+            .derivation
+            .derive_key(key_identifier.key_source, key_identifier.key_index)?;
+
+        match address_mode {
+            // The EUID is not used for outgoing frames
+            AddressingMode::DstAddrMode => Some((0, key)),
+            AddressingMode::SrcAddrMode => {
+                let euid = self.derivation.device_euid(device_address?)?;
+                Some((euid, key))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mac::{ExtendedAddress, PanId, ShortAddress};
+    use ccm::aead::generic_array::typenum::consts::U16;
+
+    /// Derives keys from the key index only
+    struct IndexDerivation;
+
+    impl KeyDerivation<U16> for IndexDerivation {
+        fn derive_key(
+            &self,
+            key_source: Option<KeySource>,
+            key_index: u8,
+        ) -> Option<GenericArray<u8, U16>> {
+            match key_source {
+                Some(KeySource::Short(_)) => {
+                    Some(GenericArray::clone_from_slice(&[key_index; 16]))
+                }
+                _ => None,
+            }
+        }
+    }
+
+    const KEY_ID: KeyIdentifier = KeyIdentifier {
+        key_source: Some(KeySource::Short(7)),
+        key_index: 8,
+    };
+    const SHORT: Address = Address::Short(PanId(0x1234), ShortAddress(0x0001));
+    const EXTENDED: Address =
+        Address::Extended(PanId(0x1234), ExtendedAddress(0x0102));
+
+    #[test]
+    fn lookup_derived_key() {
+        let keys = DerivedKeys::new(IndexDerivation);
+
+        let (euid, key) = keys
+            .lookup_key_descriptor(
+                AddressingMode::SrcAddrMode,
+                Some(KEY_ID),
+                Some(EXTENDED),
+            )
+            .unwrap();
+        assert_eq!(euid, 0x0102);
+        assert_eq!(key.as_slice(), &[8; 16]);
+
+        let (euid, _) = keys
+            .lookup_key_descriptor(
+                AddressingMode::DstAddrMode,
+                Some(KEY_ID),
+                Some(SHORT),
+            )
+            .unwrap();
+        assert_eq!(euid, 0);
+
+        // The EUID of a short address is unknown by default
+        assert!(keys
+            .lookup_key_descriptor(
+                AddressingMode::SrcAddrMode,
+                Some(KEY_ID),
+                Some(SHORT),
+            )
+            .is_none());
+
+        // Implicit keys and keys that can't be derived are unavailable
+        assert!(keys
+            .lookup_key_descriptor(
+                AddressingMode::DstAddrMode,
+                None,
+                Some(EXTENDED),
+            )
+            .is_none());
+        assert!(keys
+            .lookup_key_descriptor(
+                AddressingMode::DstAddrMode,
+                Some(KeyIdentifier::new(8)),
+                Some(EXTENDED),
+            )
+            .is_none());
+    }
+}
@@ -0,0 +1,559 @@
+//! A key table that is backed by a slice of key descriptors
+
+use super::{AddressingMode, KeyDescriptorLookup, KeyIdentifier};
+use crate::mac::{
+    command::CommandId, Address, ExtendedAddress, FrameType, PanId,
+    ShortAddress,
+};
+use ccm::aead::generic_array::{ArrayLength, GenericArray};
+
+/// Describes how a key is identified
+///
+/// See 9.5 of the 802.15.4-2015 standard.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KeyIdLookupDescriptor {
+    /// The key is identified implicitly (key identifier mode 0), by the
+    /// address of the device the frame is sent to or received from
+    Implicit(Address),
+    /// The key is identified explicitly by a key identifier
+    Explicit(KeyIdentifier),
+}
+
+impl KeyIdLookupDescriptor {
+    /// Check whether this descriptor identifies the key that is referred to by
+    /// `key_identifier` and `device_address`
+    pub fn matches(
+        &self,
+        key_identifier: Option<KeyIdentifier>,
+        device_address: Option<Address>,
+    ) -> bool {
+        match (self, key_identifier) {
+            (KeyIdLookupDescriptor::Implicit(address), None) => {
+                device_address == Some(*address)
+            }
+            (KeyIdLookupDescriptor::Explicit(id), Some(key_identifier)) => {
+                *id == key_identifier
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Describes a device that may use a key
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyDeviceDescriptor {
+    /// The extended address of the device
+    pub extended_address: ExtendedAddress,
+    /// The PAN identifier and short address of the device, if it uses a
+    /// short address
+    pub short_address: Option<(PanId, ShortAddress)>,
+    /// Whether the device is no longer allowed to use the key
+    pub blacklisted: bool,
+}
+
+impl KeyDeviceDescriptor {
+    /// Check whether `address` refers to this device
+    pub fn matches(&self, address: Address) -> bool {
+        match address {
+            Address::Short(pan_id, short_address) => {
+                self.short_address == Some((pan_id, short_address))
+            }
+            Address::Extended(_, extended_address) => {
+                extended_address == self.extended_address
+            }
+        }
+    }
+}
+
+/// Describes a type of frame that a key may be used for
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyUsageDescriptor {
+    /// The frame type
+    pub frame_type: FrameType,
+    /// The command, if `frame_type` is [`FrameType::MacCommand`]
+    ///
+    /// If this is `None`, the key may be used for all commands.
+    pub command_id: Option<CommandId>,
+}
+
+/// A key descriptor
+///
+/// Describes a key, how it is identified, which devices may use it, and
+/// which frames it may be used for. See 9.5 of the 802.15.4-2015 standard.
+pub struct KeyDescriptor<'a, N>
+where
+    N: ArrayLength<u8>,
+{
+    /// The ways in which this key can be identified
+    pub id_lookup_list: &'a [KeyIdLookupDescriptor],
+    /// The devices that may use this key
+    pub device_list: &'a [KeyDeviceDescriptor],
+    /// The frame types this key may be used for
+    pub usage_list: &'a [KeyUsageDescriptor],
+    /// The key
+    pub key: GenericArray<u8, N>,
+}
+
+impl<N> KeyDescriptor<'_, N>
+where
+    N: ArrayLength<u8>,
+{
+    /// Check whether this key is identified by `key_identifier` and
+    /// `device_address`
+    pub fn matches(
+        &self,
+        key_identifier: Option<KeyIdentifier>,
+        device_address: Option<Address>,
+    ) -> bool {
+        self.id_lookup_list
+            .iter()
+            .any(|lookup| lookup.matches(key_identifier, device_address))
+    }
+
+    /// Look up the device with the provided address in the device list
+    pub fn device(&self, address: Address) -> Option<&KeyDeviceDescriptor> {
+        self.device_list
+            .iter()
+            .find(|device| device.matches(address))
+    }
+
+    /// Check whether this key may be used for frames of the provided type
+    ///
+    /// Implementation of the incoming key usage policy checking procedure.
+    pub fn allows_usage(
+        &self,
+        frame_type: FrameType,
+        command_id: Option<CommandId>,
+    ) -> bool {
+        self.usage_list.iter().any(|usage| {
+            usage.frame_type == frame_type
+                && (frame_type != FrameType::MacCommand
+                    || usage.command_id.is_none()
+                    || usage.command_id == command_id)
+        })
+    }
+}
+
+/// A table of key descriptors
+///
+/// Keys are looked up by their [`KeyIdLookupDescriptor`]s. Keys for
+/// incoming frames are only returned if the source device is in the
+/// device list of the key, and is not blacklisted.
+///
+/// # Key rotation
+///
+/// Keys that are identified explicitly, by the same key source but a
+/// different key index, can be rotated. Once an outgoing key is selected with
+/// [`KeyTable::with_outgoing_key`], it is the only key of its key source that
+/// can be used, for both outgoing and incoming frames.
+/// [`KeyTable::rotate`] switches to a new outgoing key. Frames secured with
+/// the previous key are still accepted until [`KeyTable::end_rotation`] is
+/// called, so devices that have not switched yet are not cut off. Keys with
+/// a different key source, or that are identified implicitly, are not
+/// affected.
+///
+/// # Example
+///
+/// ``` rust
+/// use ccm::aead::generic_array::GenericArray;
+/// use ieee802154::mac::{
+///     frame::security::{
+///         AddressingMode, KeyDescriptor, KeyDescriptorLookup,
+///         KeyDeviceDescriptor, KeyIdLookupDescriptor, KeyIdentifier,
+///         KeyTable, KeyUsageDescriptor,
+///     },
+///     Address, ExtendedAddress, FrameType, PanId,
+/// };
+///
+/// let key_identifier = KeyIdentifier {
+///     key_source: None,
+///     key_index: 1,
+/// };
+///
+/// let descriptors = [KeyDescriptor {
+///     id_lookup_list: &[KeyIdLookupDescriptor::Explicit(key_identifier)],
+///     device_list: &[KeyDeviceDescriptor {
+///         extended_address: ExtendedAddress(0x0102030405060708),
+///         short_address: None,
+///         blacklisted: false,
+///     }],
+///     usage_list: &[KeyUsageDescriptor {
+///         frame_type: FrameType::Data,
+///         command_id: None,
+///     }],
+///     key: GenericArray::from([0u8; 16]),
+/// }];
+/// let table = KeyTable::new(&descriptors);
+///
+/// let source =
+///     Address::Extended(PanId(0x1234), ExtendedAddress(0x0102030405060708));
+/// let (euid, _key) = table
+///     .lookup_key_descriptor(
+///         AddressingMode::SrcAddrMode,
+///         Some(key_identifier),
+///         Some(source),
+///     )
+///     .unwrap();
+/// assert_eq!(euid, 0x0102030405060708);
+/// ```
+pub struct KeyTable<'a, N>
+where
+    N: ArrayLength<u8>,
+{
+    descriptors: &'a [KeyDescriptor<'a, N>],
+    outgoing_key: Option<KeyIdentifier>,
+    previous_key: Option<KeyIdentifier>,
+}
+
+impl<'a, N> KeyTable<'a, N>
+where
+    N: ArrayLength<u8>,
+{
+    /// Create a new key table containing `descriptors`
+    pub fn new(descriptors: &'a [KeyDescriptor<'a, N>]) -> Self {
+        Self {
+            descriptors,
+            outgoing_key: None,
+            previous_key: None,
+        }
+    }
+
+    /// Select the key that is used for outgoing frames
+    pub fn with_outgoing_key(mut self, key_identifier: KeyIdentifier) -> Self {
+        self.outgoing_key = Some(key_identifier);
+        self
+    }
+
+    /// The key identifier to use in the auxiliary security header of
+    /// outgoing frames, if an outgoing key is selected
+    pub fn outgoing_key(&self) -> Option<KeyIdentifier> {
+        self.outgoing_key
+    }
+
+    /// The key that was used for outgoing frames before the current
+    /// rotation, if a rotation is in progress
+    pub fn previous_key(&self) -> Option<KeyIdentifier> {
+        self.previous_key
+    }
+
+    /// Switch to `key_identifier` for outgoing frames
+    ///
+    /// Incoming frames that are secured with the key that was used until
+    /// now are still accepted, until [`KeyTable::end_rotation`] is called.
+    pub fn rotate(&mut self, key_identifier: KeyIdentifier) {
+        self.previous_key = self.outgoing_key;
+        self.outgoing_key = Some(key_identifier);
+    }
+
+    /// Stop accepting incoming frames that are secured with the previous key
+    pub fn end_rotation(&mut self) {
+        self.previous_key = None;
+    }
+
+    /// Check whether the key identified by `key_identifier` may be used, with
+    /// regard to key rotation
+    fn is_usable(
+        &self,
+        address_mode: &AddressingMode,
+        key_identifier: Option<KeyIdentifier>,
+    ) -> bool {
+        let (outgoing_key, key_identifier) =
+            match (self.outgoing_key, key_identifier) {
+                (Some(outgoing), Some(id)) => (outgoing, id),
+                _ => return true,
+            };
+
+        if key_identifier.key_source != outgoing_key.key_source
+            || key_identifier == outgoing_key
+        {
+            return true;
+        }
+
+        match address_mode {
+            AddressingMode::DstAddrMode => false,
+            AddressingMode::SrcAddrMode => {
+                self.previous_key == Some(key_identifier)
+            }
+        }
+    }
+
+    fn find(
+        &self,
+        key_identifier: Option<KeyIdentifier>,
+        device_address: Option<Address>,
+    ) -> Option<&KeyDescriptor<'a, N>> {
+        self.descriptors.iter().find(|descriptor| {
+            descriptor.matches(key_identifier, device_address)
+        })
+    }
+}
+
+impl<N> KeyDescriptorLookup<N> for KeyTable<'_, N>
+where
+    N: ArrayLength<u8>,
+{
+    fn lookup_key_descriptor(
+        &self,
+        address_mode: AddressingMode,
+        key_identifier: Option<KeyIdentifier>,
+        device_address: Option<Address>,
+    ) -> Option<(u64, GenericArray<u8, N>)> {
+        if !self.is_usable(&address_mode, key_identifier) {
+            return None;
+        }
+        let descriptor = self.find(key_identifier, device_address)?;
+
+        match address_mode {
+            // The EUID is not used for outgoing frames
+            AddressingMode::DstAddrMode => Some((0, descriptor.key.clone())),
+            AddressingMode::SrcAddrMode => {
+                let device = descriptor.device(device_address?)?;
+                if device.blacklisted {
+                    None
+                } else {
+                    Some((device.extended_address.0, descriptor.key.clone()))
+                }
+            }
+        }
+    }
+
+    fn key_usage_allowed(
+        &self,
+        key_identifier: Option<KeyIdentifier>,
+        device_address: Option<Address>,
+        frame_type: FrameType,
+        command_id: Option<CommandId>,
+    ) -> bool {
+        match self.find(key_identifier, device_address) {
+            Some(descriptor) => descriptor.allows_usage(frame_type, command_id),
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::KeySource;
+    use super::*;
+    use ccm::aead::generic_array::typenum::consts::U16;
+
+    const KEY_ID: KeyIdentifier = KeyIdentifier {
+        key_source: None,
+        key_index: 1,
+    };
+    const SHORT: Address = Address::Short(PanId(0x1234), ShortAddress(0x0001));
+    const EXTENDED: Address =
+        Address::Extended(PanId(0x1234), ExtendedAddress(0x0102));
+
+    #[test]
+    fn lookup_key() {
+        let devices = [
+            KeyDeviceDescriptor {
+                extended_address: ExtendedAddress(0x0102),
+                short_address: Some((PanId(0x1234), ShortAddress(0x0001))),
+                blacklisted: false,
+            },
+            KeyDeviceDescriptor {
+                extended_address: ExtendedAddress(0x0304),
+                short_address: None,
+                blacklisted: true,
+            },
+        ];
+        let descriptors = [
+            KeyDescriptor::<U16> {
+                id_lookup_list: &[KeyIdLookupDescriptor::Explicit(KEY_ID)],
+                device_list: &devices,
+                usage_list: &[],
+                key: GenericArray::from([1u8; 16]),
+            },
+            KeyDescriptor::<U16> {
+                id_lookup_list: &[KeyIdLookupDescriptor::Implicit(EXTENDED)],
+                device_list: &devices,
+                usage_list: &[],
+                key: GenericArray::from([2u8; 16]),
+            },
+        ];
+        let table = KeyTable::new(&descriptors);
+
+        // Short addresses are resolved to the EUID of the device
+        let (euid, key) = table
+            .lookup_key_descriptor(
+                AddressingMode::SrcAddrMode,
+                Some(KEY_ID),
+                Some(SHORT),
+            )
+            .unwrap();
+        assert_eq!(euid, 0x0102);
+        assert_eq!(key, GenericArray::from([1u8; 16]));
+
+        // Implicit key identification
+        let (_, key) = table
+            .lookup_key_descriptor(
+                AddressingMode::SrcAddrMode,
+                None,
+                Some(EXTENDED),
+            )
+            .unwrap();
+        assert_eq!(key, GenericArray::from([2u8; 16]));
+        assert!(table
+            .lookup_key_descriptor(
+                AddressingMode::SrcAddrMode,
+                None,
+                Some(SHORT)
+            )
+            .is_none());
+
+        // Blacklisted and unknown devices may not use the key
+        let blacklisted =
+            Address::Extended(PanId(0x1234), ExtendedAddress(0x0304));
+        let unknown = Address::Extended(PanId(0x1234), ExtendedAddress(0x0506));
+        for address in [blacklisted, unknown].iter() {
+            assert!(table
+                .lookup_key_descriptor(
+                    AddressingMode::SrcAddrMode,
+                    Some(KEY_ID),
+                    Some(*address),
+                )
+                .is_none());
+        }
+
+        // Outgoing frames only need the key
+        assert!(table
+            .lookup_key_descriptor(
+                AddressingMode::DstAddrMode,
+                Some(KEY_ID),
+                Some(unknown),
+            )
+            .is_some());
+    }
+
+    #[test]
+    fn key_usage() {
+        let descriptors = [KeyDescriptor::<U16> {
+            id_lookup_list: &[KeyIdLookupDescriptor::Explicit(KEY_ID)],
+            device_list: &[],
+            usage_list: &[
+                KeyUsageDescriptor {
+                    frame_type: FrameType::Data,
+                    command_id: None,
+                },
+                KeyUsageDescriptor {
+                    frame_type: FrameType::MacCommand,
+                    command_id: Some(CommandId::DataRequest),
+                },
+            ],
+            key: GenericArray::default(),
+        }];
+        let table = KeyTable::new(&descriptors);
+
+        let allowed = |frame_type, command_id| {
+            table.key_usage_allowed(
+                Some(KEY_ID),
+                Some(SHORT),
+                frame_type,
+                command_id,
+            )
+        };
+        assert!(allowed(FrameType::Data, None));
+        assert!(allowed(FrameType::MacCommand, Some(CommandId::DataRequest)));
+        assert!(!allowed(
+            FrameType::MacCommand,
+            Some(CommandId::BeaconRequest)
+        ));
+        assert!(!allowed(FrameType::Beacon, None));
+    }
+
+    #[test]
+    fn rotate_keys() {
+        const fn key_id(key_source: u32, key_index: u8) -> KeyIdentifier {
+            KeyIdentifier {
+                key_source: Some(KeySource::Short(key_source)),
+                key_index,
+            }
+        }
+        const KEY_1: KeyIdentifier = key_id(0xAABBCCDD, 1);
+        const KEY_2: KeyIdentifier = key_id(0xAABBCCDD, 2);
+        const KEY_3: KeyIdentifier = key_id(0xAABBCCDD, 3);
+        const OTHER_SOURCE: KeyIdentifier = key_id(0x11223344, 1);
+
+        let devices = [KeyDeviceDescriptor {
+            extended_address: ExtendedAddress(0x0102),
+            short_address: None,
+            blacklisted: false,
+        }];
+        let descriptors = [
+            KeyDescriptor::<U16> {
+                id_lookup_list: &[KeyIdLookupDescriptor::Explicit(KEY_1)],
+                device_list: &devices,
+                usage_list: &[],
+                key: GenericArray::from([1u8; 16]),
+            },
+            KeyDescriptor::<U16> {
+                id_lookup_list: &[KeyIdLookupDescriptor::Explicit(KEY_2)],
+                device_list: &devices,
+                usage_list: &[],
+                key: GenericArray::from([2u8; 16]),
+            },
+            KeyDescriptor::<U16> {
+                id_lookup_list: &[KeyIdLookupDescriptor::Explicit(KEY_3)],
+                device_list: &devices,
+                usage_list: &[],
+                key: GenericArray::from([3u8; 16]),
+            },
+            KeyDescriptor::<U16> {
+                id_lookup_list: &[KeyIdLookupDescriptor::Explicit(
+                    OTHER_SOURCE,
+                )],
+                device_list: &devices,
+                usage_list: &[],
+                key: GenericArray::from([4u8; 16]),
+            },
+        ];
+        let mut table = KeyTable::new(&descriptors).with_outgoing_key(KEY_1);
+
+        let lookup = |table: &KeyTable<U16>, address_mode, key_identifier| {
+            table
+                .lookup_key_descriptor(
+                    address_mode,
+                    Some(key_identifier),
+                    Some(EXTENDED),
+                )
+                .map(|(_, key)| key[0])
+        };
+        let incoming = |table: &KeyTable<U16>, key_identifier| {
+            lookup(table, AddressingMode::SrcAddrMode, key_identifier)
+        };
+        let outgoing = |table: &KeyTable<U16>, key_identifier| {
+            lookup(table, AddressingMode::DstAddrMode, key_identifier)
+        };
+
+        // Only the outgoing key of a key source can be used
+        assert_eq!(incoming(&table, KEY_1), Some(1));
+        assert_eq!(incoming(&table, KEY_2), None);
+        assert_eq!(outgoing(&table, KEY_2), None);
+        assert_eq!(incoming(&table, OTHER_SOURCE), Some(4));
+
+        // During the rotation, the previous key is only accepted for
+        // incoming frames
+        table.rotate(KEY_2);
+        assert_eq!(table.outgoing_key(), Some(KEY_2));
+        assert_eq!(table.previous_key(), Some(KEY_1));
+        assert_eq!(outgoing(&table, KEY_2), Some(2));
+        assert_eq!(outgoing(&table, KEY_1), None);
+        assert_eq!(incoming(&table, KEY_1), Some(1));
+        assert_eq!(incoming(&table, KEY_2), Some(2));
+        assert_eq!(incoming(&table, KEY_3), None);
+
+        table.end_rotation();
+        assert_eq!(table.previous_key(), None);
+        assert_eq!(incoming(&table, KEY_1), None);
+        assert_eq!(incoming(&table, KEY_2), Some(2));
+        assert_eq!(incoming(&table, OTHER_SOURCE), Some(4));
+    }
+}
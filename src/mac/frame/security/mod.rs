@@ -5,7 +5,8 @@
 //! # Example on how to use frames with security
 //! Note that the example below is _very insecure_, and should not be used in any production setting
 //!
-//! ```rust
+#![cfg_attr(feature = "security", doc = "```rust")]
+#![cfg_attr(not(feature = "security"), doc = "```ignore")]
 //! use ieee802154::mac::{
 //!     frame::security::{
 //!         KeyDescriptorLookup,
@@ -23,6 +24,7 @@
 //!     Address,
 //!     ExtendedAddress,
 //!     PanId,
+//!     ShortAddress,
 //!     Header,
 //!     Frame,
 //!     FrameVersion,
@@ -73,6 +75,7 @@
 //!         &mut self,
 //!         _addressing_mode: AddressingMode,
 //!         _address: Address,
+//!         _key_identifier: Option<KeyIdentifier>,
 //!     ) -> Option<&mut DeviceDescriptor> {
 //!         Some(self.descriptor)
 //!     }
@@ -93,6 +96,9 @@
 //!     let destination = Some(Address::Extended(PanId(0x111), ExtendedAddress(0x02)));
 //!
 //!     let device_desc = &mut DeviceDescriptor {
+//!         pan_id: PanId(0x111),
+//!         short_address: ShortAddress(0xfffe),
+//!         extended_address: ExtendedAddress(source_euid),
 //!         frame_counter: FRAME_CTR,
 //!         exempt: false,
 //!     };
@@ -113,6 +119,7 @@
 //!             frame_pending: false,
 //!             ack_request: false,
 //!             pan_id_compress: false,
+//!             ie_present: false,
 //!             version: FrameVersion::Ieee802154,
 //!             seq: 127,
 //!             destination,
@@ -136,12 +143,13 @@
 //!         Err(e) => 0,
 //!     };
 //!
-//!     // Verify that encryption succeeded and tag was appended correctly
+//!     // Verify that encryption succeeded and tag was appended correctly. The tag
+//!     // covers the header as well
 //!     assert_eq!(&buffer[..len], &[
 //!         0x9, 0xec, 0x7f, 0x11, 0x1, 0x2, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x11, 0x1, 0x1, 0x0, 0x0,
 //!         0x0, 0x0, 0x0, 0x0, 0x0, 0x1f, 0x0, 0x0, 0x0, 0x0, 0xaa, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
-//!         0x30, 0xf8, 0x58, 0x90, 0xd2, 0x7f, 0x46, 0xcf, 0x1a, 0x73, 0xd3, 0xad, 0x65, 0xda, 0x6c, 0xd1,
-//!         0x4b, 0x73, 0xef, 0xbe, 0x79, 0x31,
+//!         0x30, 0x26, 0x6b, 0x98, 0x7e, 0x84, 0x32, 0xd9, 0x2c, 0x96, 0xa3, 0x35, 0xa3, 0x69, 0xd2,
+//!         0x22, 0xc1, 0xbc, 0x24, 0x9a, 0x1, 0x0,
 //!     ]);
 //!     // Read/"receive" a MAC frame. Unsecuring it is attempted if the header
 //!     // has security enabled
@@ -157,35 +165,91 @@
 //! }
 //! ```
 //!
+#[cfg(feature = "security")]
+mod asn_provider;
 mod auxiliary_security_header;
+#[cfg(feature = "security")]
+mod ccm_backend;
+#[cfg(feature = "security")]
 pub mod default;
+#[cfg(feature = "security")]
+mod device_table;
+#[cfg(feature = "security")]
+mod frame_counter;
+#[cfg(feature = "security")]
+mod key_derivation;
+#[cfg(feature = "security")]
+mod key_table;
+#[cfg(feature = "security")]
+mod nonce;
+mod policy;
 mod security_control;
+#[cfg(feature = "security")]
+mod statistics;
+#[cfg(all(feature = "security", any(test, feature = "test-vectors")))]
+pub mod test_vectors;
 
+#[cfg(feature = "security")]
 use self::default::Unimplemented;
 
-use super::{FooterMode, Frame, Header};
-use crate::mac::{Address, FrameType, FrameVersion};
-use byte::BytesExt;
-use ccm::{
-    aead::{
-        generic_array::{
-            typenum::consts::{U13 as CcmU13, U16 as CcmU16, U4, U8},
-            ArrayLength, GenericArray,
-        },
-        AeadInPlace, NewAead,
-    },
-    Ccm,
+#[cfg(feature = "security")]
+use super::{Frame, FrameContent, Header, MAX_PHY_PACKET_SIZE};
+#[cfg(feature = "security")]
+use crate::mac::beacon::Beacon;
+#[cfg(feature = "security")]
+use crate::mac::ie::{EncodedInformationElements, HeaderIeIter};
+#[cfg(feature = "security")]
+use crate::mac::FrameVersion;
+use crate::mac::{
+    command::CommandId, Address, ExtendedAddress, FrameType, PanId,
+    ShortAddress,
 };
+#[cfg(feature = "security")]
+use crate::utils::OptionalFrom;
+#[cfg(feature = "security")]
+use byte::{check_len, BytesExt};
+#[cfg(feature = "security")]
+use ccm::aead::generic_array::{ArrayLength, GenericArray};
+#[cfg(feature = "security")]
+use core::borrow::BorrowMut;
+use core::fmt;
+#[cfg(feature = "security")]
 use core::marker::PhantomData;
 
+#[cfg(feature = "soft-crypto")]
+pub use aes::Aes128;
+#[cfg(feature = "security")]
+pub use asn_provider::AsnProvider;
 pub use auxiliary_security_header::{
-    AuxiliarySecurityHeader, KeyIdentifier, KeySource,
+    AuxiliarySecurityHeader, AuxiliarySecurityHeaderBuilder, KeyIdentifier,
+    KeySource,
 };
+#[cfg(feature = "soft-crypto")]
+pub use ccm_backend::Aes128Ccm;
+#[cfg(feature = "security")]
+pub use ccm_backend::{CcmBackend, SoftwareCcm};
+#[cfg(feature = "security")]
 pub use cipher::{
     generic_array::typenum::consts::U16, BlockCipher, BlockEncrypt,
     NewBlockCipher,
 };
+#[cfg(feature = "security")]
+pub use device_table::{DeviceTable, DeviceTableEntry};
+#[cfg(feature = "security")]
+pub use frame_counter::FrameCounterStorage;
+#[cfg(feature = "security")]
+pub use key_derivation::{DerivedKeys, KeyDerivation};
+#[cfg(feature = "security")]
+pub use key_table::{
+    KeyDescriptor, KeyDeviceDescriptor, KeyIdLookupDescriptor, KeyTable,
+    KeyUsageDescriptor,
+};
+#[cfg(feature = "security")]
+pub use nonce::{NonceGenerator, StandardNonce};
+pub use policy::{SecurityParameters, SecurityPolicy};
 pub use security_control::{KeyIdentifierMode, SecurityControl, SecurityLevel};
+#[cfg(feature = "security")]
+pub use statistics::SecurityStatistics;
 
 /// The addressing mode to use during descriptor lookups
 pub enum AddressingMode {
@@ -195,16 +259,181 @@ pub enum AddressingMode {
     SrcAddrMode,
 }
 
-#[derive(Clone)]
-/// A partial device descriptor
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A device descriptor
+///
+/// Describes a device that frames can be received from, see 9.5 of the
+/// 802.15.4-2015 standard.
 pub struct DeviceDescriptor {
+    /// The PAN identifier of the device
+    pub pan_id: PanId,
+    /// The short address of the device, or `0xfffe` if the device does not
+    /// use a short address
+    pub short_address: ShortAddress,
+    /// The extended address of the device
+    pub extended_address: ExtendedAddress,
     /// The frame counter associated with this device
     pub frame_counter: u32,
     /// Whether this device is allowed to override the minimum security level
     pub exempt: bool,
 }
 
+impl DeviceDescriptor {
+    /// Update the frame counter after a frame with frame counter
+    /// `frame_counter` is accepted from this device
+    ///
+    /// Frames with a lower frame counter are rejected as replayed from then
+    /// on. Returns whether the frame counter is exhausted, in which case the
+    /// device must be blacklisted for the key that secured the frame, see
+    /// [`DeviceDescriptorLookup::blacklist_device`].
+    pub fn update_frame_counter(&mut self, frame_counter: u32) -> bool {
+        self.frame_counter = frame_counter.saturating_add(1);
+        self.frame_counter == 0xFFFFFFFF
+    }
+
+    /// Check whether `address` refers to this device
+    pub fn matches(&self, address: Address) -> bool {
+        match address {
+            Address::Short(pan_id, short_address) => {
+                short_address != ShortAddress(0xfffe)
+                    && pan_id == self.pan_id
+                    && short_address == self.short_address
+            }
+            Address::Extended(_, extended_address) => {
+                extended_address == self.extended_address
+            }
+        }
+    }
+}
+
+/// A security level descriptor
+///
+/// Describes the minimum security level that incoming frames of a certain
+/// type must have, see 9.5 of the 802.15.4-2015 standard.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SecurityLevelDescriptor {
+    /// The frame type this descriptor applies to
+    pub frame_type: FrameType,
+    /// The command this descriptor applies to, if `frame_type` is
+    /// [`FrameType::MacCommand`]
+    ///
+    /// If this is `None`, the descriptor applies to all commands.
+    pub command_id: Option<CommandId>,
+    /// The minimum security level of incoming frames
+    pub security_minimum: SecurityLevel,
+    /// Whether devices that are marked as exempt may send frames of this
+    /// type without security
+    pub device_override_security_minimum: bool,
+}
+
+/// The result of the incoming security level checking procedure
+#[cfg(feature = "security")]
+enum SecurityLevelCheck {
+    Passed,
+    ConditionallyPassed,
+    Failed,
+}
+
+/// Check the security level of an incoming frame against `table`
+///
+/// Implementation of the incoming security level checking procedure
+#[cfg(feature = "security")]
+fn check_security_level(
+    table: &[SecurityLevelDescriptor],
+    frame_type: FrameType,
+    command_id: Option<CommandId>,
+    security_level: SecurityLevel,
+) -> SecurityLevelCheck {
+    let descriptor = table.iter().find(|descriptor| {
+        descriptor.frame_type == frame_type
+            && (frame_type != FrameType::MacCommand
+                || descriptor.command_id.is_none()
+                || descriptor.command_id == command_id)
+    });
+
+    match descriptor {
+        None => SecurityLevelCheck::Passed,
+        Some(descriptor) => {
+            if security_level.satisfies(descriptor.security_minimum) {
+                SecurityLevelCheck::Passed
+            } else if descriptor.device_override_security_minimum
+                && security_level == SecurityLevel::None
+            {
+                SecurityLevelCheck::ConditionallyPassed
+            } else {
+                SecurityLevelCheck::Failed
+            }
+        }
+    }
+}
+
+/// Read the command identifier from the MAC payload of a MAC command frame
+///
+/// The Command ID of a frame with IEs follows the header IEs and payload IEs.
+#[cfg(feature = "security")]
+fn read_command_id(header: &Header, mac_payload: &[u8]) -> Option<CommandId> {
+    let offset = &mut 0;
+    if header.ie_present {
+        let _: EncodedInformationElements = mac_payload.read(offset).ok()?;
+    }
+    mac_payload
+        .get(*offset)
+        .copied()
+        .and_then(CommandId::optional_from)
+}
+
+/// Perform the incoming security level checking (7.2.3h, 7.2.3i) and key
+/// usage policy checking (7.2.3j) procedures
+#[cfg(feature = "security")]
+fn check_incoming_frame<N, KEYDESCLO>(
+    security_level_table: &[SecurityLevelDescriptor],
+    key_provider: &KEYDESCLO,
+    header: &Header,
+    aux_sec_header: &AuxiliarySecurityHeader,
+    exempt: bool,
+    command_id: Option<CommandId>,
+) -> Result<(), SecurityError>
+where
+    N: ArrayLength<u8>,
+    KEYDESCLO: KeyDescriptorLookup<N>,
+{
+    match check_security_level(
+        security_level_table,
+        header.frame_type,
+        command_id,
+        aux_sec_header.control.security_level,
+    ) {
+        SecurityLevelCheck::Passed => {}
+        SecurityLevelCheck::ConditionallyPassed if exempt => {}
+        _ => return Err(SecurityError::ImproperSecurityLevel),
+    }
+
+    if !key_provider.key_usage_allowed(
+        aux_sec_header.key_identifier,
+        header.source,
+        header.frame_type,
+        command_id,
+    ) {
+        return Err(SecurityError::ImproperKeyType);
+    }
+    Ok(())
+}
+
+/// Get the command identifier of a frame, if it is a MAC command frame
+#[cfg(feature = "security")]
+fn command_id(content: &FrameContent) -> Option<CommandId> {
+    match content {
+        FrameContent::Command(command) => command.command_id(),
+        _ => None,
+    }
+}
+
 /// Used to create a KeyDescriptor from a KeyIdentifier and device address
+#[cfg(feature = "security")]
 pub trait KeyDescriptorLookup<N>
 where
     N: ArrayLength<u8>,
@@ -226,16 +455,88 @@ where
         key_identifier: Option<KeyIdentifier>,
         device_address: Option<Address>,
     ) -> Option<(u64, GenericArray<u8, N>)>;
+
+    /// Check whether the key identified by `key_identifier` and
+    /// `device_address` may be used to secure frames of the given type
+    ///
+    /// This is the interface that is used to perform the incoming key usage
+    /// policy checking procedure (7.2.3j). By default, keys may be used for
+    /// all frames.
+    fn key_usage_allowed(
+        &self,
+        _key_identifier: Option<KeyIdentifier>,
+        _device_address: Option<Address>,
+        _frame_type: FrameType,
+        _command_id: Option<CommandId>,
+    ) -> bool {
+        true
+    }
+}
+
+#[cfg(feature = "security")]
+impl<N, T> KeyDescriptorLookup<N> for &T
+where
+    N: ArrayLength<u8>,
+    T: KeyDescriptorLookup<N> + ?Sized,
+{
+    fn lookup_key_descriptor(
+        &self,
+        address_mode: AddressingMode,
+        key_identifier: Option<KeyIdentifier>,
+        device_address: Option<Address>,
+    ) -> Option<(u64, GenericArray<u8, N>)> {
+        (**self).lookup_key_descriptor(
+            address_mode,
+            key_identifier,
+            device_address,
+        )
+    }
+
+    fn key_usage_allowed(
+        &self,
+        key_identifier: Option<KeyIdentifier>,
+        device_address: Option<Address>,
+        frame_type: FrameType,
+        command_id: Option<CommandId>,
+    ) -> bool {
+        (**self).key_usage_allowed(
+            key_identifier,
+            device_address,
+            frame_type,
+            command_id,
+        )
+    }
 }
 
 /// Perform a lookup of a device descriptor based on the provided address
+///
+/// The descriptor holds the last accepted frame counter, which is used to
+/// reject replayed frames. Implementations can keep a separate descriptor per
+/// key, based on `key_identifier`. See [`DeviceTable`] for an implementation
+/// that is backed by a slice.
+#[cfg(feature = "security")]
 pub trait DeviceDescriptorLookup {
     /// look up a device
     fn lookup_device(
         &mut self,
         addressing_mode: AddressingMode,
         address: Address,
+        key_identifier: Option<KeyIdentifier>,
     ) -> Option<&mut DeviceDescriptor>;
+
+    /// Blacklist the device with address `address` for the key identified by
+    /// `key_identifier`
+    ///
+    /// This is called once the frame counter of the device is exhausted, as
+    /// the device can not secure any more frames with the key. Afterwards,
+    /// [`DeviceDescriptorLookup::lookup_device`] should no longer return the
+    /// device for the key. By default, nothing is done.
+    fn blacklist_device(
+        &mut self,
+        _address: Address,
+        _key_identifier: Option<KeyIdentifier>,
+    ) {
+    }
 }
 
 /// A context that used to keep track of cryptographic properties that
@@ -244,44 +545,332 @@ pub trait DeviceDescriptorLookup {
 /// AEAD is the type that will provide an AEAD instance to be used for the
 /// encryption/decryption process
 ///
-/// NONCEGEN is the type that will convert the nonce created using the 802.15.4 standard
-/// into a nonce of the size that can be accepted by the provided AEAD algorithm
+/// NONCEGEN is the type that generates the nonce of frames, see
+/// [`NonceGenerator`]
+///
+/// STATS is where the [`SecurityStatistics`] of the context are kept, which
+/// is either the statistics themselves or a mutable reference to them
+#[cfg(feature = "security")]
 #[derive(Clone, Copy)]
-pub struct SecurityContext<AEADBLKCIPH, KEYDESCLO>
-where
+pub struct SecurityContext<
+    AEADBLKCIPH,
+    KEYDESCLO,
+    FRAMECTR = u32,
+    CCMBACKEND = SoftwareCcm<AEADBLKCIPH>,
+    ASNPROV = (),
+    NONCEGEN = StandardNonce,
+    STATS = SecurityStatistics,
+> where
     AEADBLKCIPH: NewBlockCipher + BlockCipher<BlockSize = U16>,
     KEYDESCLO: KeyDescriptorLookup<AEADBLKCIPH::KeySize>,
+    FRAMECTR: FrameCounterStorage,
+    CCMBACKEND: CcmBackend<AEADBLKCIPH::KeySize>,
+    ASNPROV: AsnProvider,
+    NONCEGEN: NonceGenerator,
+    STATS: BorrowMut<SecurityStatistics>,
 {
     /// The EUID used for calculating the nonce of outgoing frames
     pub euid: u64,
-    /// The current frame counter
-    pub frame_counter: u32,
+    /// The frame counter of outgoing frames
+    pub frame_counter: FRAMECTR,
     /// The key descriptor lookup to use to look up keys
     pub key_provider: KEYDESCLO,
+    /// The minimum security levels that incoming frames must satisfy
+    ///
+    /// Frames of a type that has no descriptor in this table are accepted
+    /// regardless of their security level.
+    pub security_level_table: &'static [SecurityLevelDescriptor],
+    /// The security parameters of outgoing frames that have no auxiliary
+    /// security header
+    ///
+    /// By default, such frames are not secured.
+    pub security_policy: SecurityPolicy,
+    /// Whether frames with security level [`SecurityLevel::ENC`], which are
+    /// encrypted but not authenticated, are secured and unsecured
+    ///
+    /// This security level is deprecated by the 802.15.4-2020 standard, so
+    /// such frames are rejected with
+    /// [`SecurityError::UnsupportedSecurityLevel`] by default.
+    pub allow_encryption_only: bool,
+    /// Counters of the frames that were secured and unsecured with this
+    /// context, and of the reasons that they were rejected
+    pub statistics: STATS,
+    /// The backend that performs the CCM* transformation
+    pub ccm_backend: CCMBACKEND,
+    /// The provider of the Absolute Slot Number for frames that use it in
+    /// their nonce
+    pub asn_provider: ASNPROV,
+    /// The generator of the nonce of outgoing and incoming frames
+    pub nonce_generator: NONCEGEN,
     /// This is phantom data as we use AEAD to actually instantiate an instance
     /// of AEAD, as opposed to actually using a provided AEAD instance somewhere
-    ///
-    /// The NONCEGEN is phantom data as well
     phantom_data: PhantomData<AEADBLKCIPH>,
 }
 
+/// A [`SecurityContext`] that secures frames with AES-128 CCM*, in software
+///
+/// # Example
+///
+/// ``` rust
+/// use ieee802154::mac::frame::security::{Aes128SecurityContext, KeyTable};
+///
+/// let sec_ctx = Aes128SecurityContext::new(0x01, 0, KeyTable::new(&[]));
+/// assert_eq!(sec_ctx.frame_counter, 0);
+/// ```
+#[cfg(feature = "soft-crypto")]
+pub type Aes128SecurityContext<KEYDESCLO, FRAMECTR = u32> =
+    SecurityContext<Aes128, KEYDESCLO, FRAMECTR>;
+
+/// A [`SecurityContext`] whose key lookup, frame counter storage, CCM*
+/// backend, ASN provider and nonce generator are trait objects, and whose
+/// statistics are borrowed
+///
+/// Code that uses this type does not have to be generic over the types that
+/// make up a security context. Any security context with a 16 octet key can
+/// be borrowed as one, using [`SecurityContext::as_any`]. The CCM*
+/// transformation is performed by the backend, so the block cipher type is
+/// not needed.
+#[cfg(feature = "security")]
+pub type AnySecurityContext<'a> = SecurityContext<
+    Unimplemented,
+    &'a dyn KeyDescriptorLookup<U16>,
+    &'a mut dyn FrameCounterStorage,
+    &'a mut dyn CcmBackend<U16>,
+    &'a dyn AsnProvider,
+    &'a dyn NonceGenerator,
+    &'a mut SecurityStatistics,
+>;
+
+#[cfg(feature = "security")]
 impl<AEADBLKCIPH, KEYDESCLO> SecurityContext<AEADBLKCIPH, KEYDESCLO>
 where
-    AEADBLKCIPH: NewBlockCipher + BlockCipher<BlockSize = U16>,
+    AEADBLKCIPH: NewBlockCipher + BlockCipher<BlockSize = U16> + BlockEncrypt,
     KEYDESCLO: KeyDescriptorLookup<AEADBLKCIPH::KeySize>,
 {
     /// Create a new security context using the provided key provider and
     /// frame counter
     pub fn new(euid: u64, frame_counter: u32, key_provider: KEYDESCLO) -> Self {
+        Self::with_frame_counter_storage(euid, frame_counter, key_provider)
+    }
+}
+
+#[cfg(feature = "security")]
+impl<AEADBLKCIPH, KEYDESCLO, FRAMECTR>
+    SecurityContext<AEADBLKCIPH, KEYDESCLO, FRAMECTR>
+where
+    AEADBLKCIPH: NewBlockCipher + BlockCipher<BlockSize = U16> + BlockEncrypt,
+    KEYDESCLO: KeyDescriptorLookup<AEADBLKCIPH::KeySize>,
+    FRAMECTR: FrameCounterStorage,
+{
+    /// Create a new security context using the provided key provider and
+    /// frame counter storage
+    pub fn with_frame_counter_storage(
+        euid: u64,
+        frame_counter: FRAMECTR,
+        key_provider: KEYDESCLO,
+    ) -> Self {
+        Self::with_ccm_backend(
+            euid,
+            frame_counter,
+            key_provider,
+            SoftwareCcm::new(),
+        )
+    }
+}
+
+#[cfg(feature = "security")]
+impl<AEADBLKCIPH, KEYDESCLO, FRAMECTR, CCMBACKEND>
+    SecurityContext<AEADBLKCIPH, KEYDESCLO, FRAMECTR, CCMBACKEND>
+where
+    AEADBLKCIPH: NewBlockCipher + BlockCipher<BlockSize = U16>,
+    KEYDESCLO: KeyDescriptorLookup<AEADBLKCIPH::KeySize>,
+    FRAMECTR: FrameCounterStorage,
+    CCMBACKEND: CcmBackend<AEADBLKCIPH::KeySize>,
+{
+    /// Create a new security context using the provided key provider, frame
+    /// counter storage and CCM* backend
+    ///
+    /// `AEADBLKCIPH` only determines the key size if the backend does not
+    /// use it.
+    pub fn with_ccm_backend(
+        euid: u64,
+        frame_counter: FRAMECTR,
+        key_provider: KEYDESCLO,
+        ccm_backend: CCMBACKEND,
+    ) -> Self {
         Self {
             euid,
             frame_counter,
             key_provider,
+            security_level_table: &[],
+            security_policy: SecurityPolicy::NONE,
+            allow_encryption_only: false,
+            statistics: SecurityStatistics::new(),
+            ccm_backend,
+            asn_provider: (),
+            nonce_generator: StandardNonce,
+            phantom_data: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "security")]
+impl<
+        AEADBLKCIPH,
+        KEYDESCLO,
+        FRAMECTR,
+        CCMBACKEND,
+        ASNPROV,
+        NONCEGEN,
+        STATS,
+    >
+    SecurityContext<
+        AEADBLKCIPH,
+        KEYDESCLO,
+        FRAMECTR,
+        CCMBACKEND,
+        ASNPROV,
+        NONCEGEN,
+        STATS,
+    >
+where
+    AEADBLKCIPH: NewBlockCipher + BlockCipher<BlockSize = U16>,
+    KEYDESCLO: KeyDescriptorLookup<AEADBLKCIPH::KeySize>,
+    FRAMECTR: FrameCounterStorage,
+    CCMBACKEND: CcmBackend<AEADBLKCIPH::KeySize>,
+    ASNPROV: AsnProvider,
+    NONCEGEN: NonceGenerator,
+    STATS: BorrowMut<SecurityStatistics>,
+{
+    /// Use `asn_provider` to look up the Absolute Slot Number of frames that
+    /// use it in their nonce
+    pub fn with_asn_provider<P: AsnProvider>(
+        self,
+        asn_provider: P,
+    ) -> SecurityContext<
+        AEADBLKCIPH,
+        KEYDESCLO,
+        FRAMECTR,
+        CCMBACKEND,
+        P,
+        NONCEGEN,
+        STATS,
+    > {
+        SecurityContext {
+            euid: self.euid,
+            frame_counter: self.frame_counter,
+            key_provider: self.key_provider,
+            security_level_table: self.security_level_table,
+            security_policy: self.security_policy,
+            allow_encryption_only: self.allow_encryption_only,
+            statistics: self.statistics,
+            ccm_backend: self.ccm_backend,
+            asn_provider,
+            nonce_generator: self.nonce_generator,
+            phantom_data: PhantomData,
+        }
+    }
+
+    /// Use `nonce_generator` to generate the nonce of frames, instead of the
+    /// nonce that is specified by the standard
+    pub fn with_nonce_generator<G: NonceGenerator>(
+        self,
+        nonce_generator: G,
+    ) -> SecurityContext<
+        AEADBLKCIPH,
+        KEYDESCLO,
+        FRAMECTR,
+        CCMBACKEND,
+        ASNPROV,
+        G,
+        STATS,
+    > {
+        SecurityContext {
+            euid: self.euid,
+            frame_counter: self.frame_counter,
+            key_provider: self.key_provider,
+            security_level_table: self.security_level_table,
+            security_policy: self.security_policy,
+            allow_encryption_only: self.allow_encryption_only,
+            statistics: self.statistics,
+            ccm_backend: self.ccm_backend,
+            asn_provider: self.asn_provider,
+            nonce_generator,
+            phantom_data: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "security")]
+impl<
+        AEADBLKCIPH,
+        KEYDESCLO,
+        FRAMECTR,
+        CCMBACKEND,
+        ASNPROV,
+        NONCEGEN,
+        STATS,
+    >
+    SecurityContext<
+        AEADBLKCIPH,
+        KEYDESCLO,
+        FRAMECTR,
+        CCMBACKEND,
+        ASNPROV,
+        NONCEGEN,
+        STATS,
+    >
+where
+    AEADBLKCIPH: NewBlockCipher<KeySize = U16> + BlockCipher<BlockSize = U16>,
+    KEYDESCLO: KeyDescriptorLookup<U16>,
+    FRAMECTR: FrameCounterStorage,
+    CCMBACKEND: CcmBackend<U16>,
+    ASNPROV: AsnProvider,
+    NONCEGEN: NonceGenerator,
+    STATS: BorrowMut<SecurityStatistics>,
+{
+    /// Borrow this security context as an [`AnySecurityContext`]
+    ///
+    /// The frame counter and statistics of the returned context are those of
+    /// this context, so securing frames with it increments the frame counter
+    /// of this context, and updates its statistics.
+    ///
+    /// # Example
+    ///
+    /// ``` rust
+    /// use aes::Aes128;
+    /// use ieee802154::mac::frame::security::{
+    ///     AnySecurityContext, FrameCounterStorage, KeyTable, SecurityContext,
+    ///     U16,
+    /// };
+    ///
+    /// /// Not generic over the security context
+    /// fn frame_counter(sec_ctx: &AnySecurityContext) -> u32 {
+    ///     sec_ctx.frame_counter.load()
+    /// }
+    ///
+    /// let mut sec_ctx: SecurityContext<Aes128, KeyTable<U16>> =
+    ///     SecurityContext::new(0x01, 5, KeyTable::new(&[]));
+    /// assert_eq!(frame_counter(&sec_ctx.as_any()), 5);
+    /// ```
+    pub fn as_any(&mut self) -> AnySecurityContext<'_> {
+        SecurityContext {
+            euid: self.euid,
+            frame_counter: &mut self.frame_counter,
+            key_provider: &self.key_provider,
+            security_level_table: self.security_level_table,
+            security_policy: self.security_policy,
+            allow_encryption_only: self.allow_encryption_only,
+            statistics: self.statistics.borrow_mut(),
+            ccm_backend: &mut self.ccm_backend,
+            asn_provider: &self.asn_provider,
+            nonce_generator: &self.nonce_generator,
             phantom_data: PhantomData,
         }
     }
 }
 
+#[cfg(feature = "security")]
 impl SecurityContext<Unimplemented, Unimplemented> {
     /// A security context that is not actually capable of providing any security
     pub fn no_security() -> Self {
@@ -289,62 +878,150 @@ impl SecurityContext<Unimplemented, Unimplemented> {
             euid: 0,
             frame_counter: 0,
             key_provider: Unimplemented {},
+            security_level_table: &[],
+            security_policy: SecurityPolicy::NONE,
+            allow_encryption_only: false,
+            statistics: SecurityStatistics::new(),
+            ccm_backend: SoftwareCcm::new(),
+            asn_provider: (),
+            nonce_generator: StandardNonce,
             phantom_data: PhantomData,
         }
     }
 }
 
+/// Calculate the CCM* nonce for a frame (see 9.3.2 of 802.15.4-2015)
+///
+/// The nonce consists of the extended source address and the frame counter,
+/// both in big endian (unlike their over-the-air representation), followed by
+/// the security level.
+#[cfg(feature = "security")]
 fn calculate_nonce(
     source_addr: u64,
     frame_counter: u32,
     sec_level: SecurityLevel,
 ) -> [u8; 13] {
     let mut output = [0u8; 13];
-    for i in 0..8 {
-        output[i] = (source_addr >> (8 * i) & 0xFF) as u8;
-    }
+    output[..8].copy_from_slice(&source_addr.to_be_bytes());
+    output[8..12].copy_from_slice(&frame_counter.to_be_bytes());
+    output[12] = sec_level.to_bits();
+    output
+}
+
+/// Calculate the CCM* nonce for a frame in a TSCH network (see 9.3.2.2 of
+/// 802.15.4-2015)
+///
+/// The nonce consists of the extended source address and the lower 5 octets of
+/// the Absolute Slot Number, both in big endian.
+#[cfg(feature = "security")]
+fn calculate_asn_nonce(source_addr: u64, asn: u64) -> [u8; 13] {
+    let mut output = [0u8; 13];
+    output[..8].copy_from_slice(&source_addr.to_be_bytes());
+    output[8..].copy_from_slice(&asn.to_be_bytes()[3..]);
+    output
+}
 
-    for i in 0..4 {
-        output[i + 8] = (frame_counter >> (8 * i) & 0xFF) as u8;
+/// Whether frames with `header` can be secured and unsecured
+///
+/// Acknowledgement frames can only be secured if they are Enhanced
+/// Acknowledgements, which are of the 802.15.4-2015 frame version.
+#[cfg(feature = "security")]
+pub(crate) fn is_securable(header: &Header) -> bool {
+    match header.frame_type {
+        FrameType::Acknowledgement => {
+            header.version == FrameVersion::Ieee802154
+        }
+        _ => true,
     }
+}
 
-    output[12] = sec_level.to_bits();
-    output
+/// Get the length of the open part of the MAC payload of a frame, which is
+/// authenticated but never encrypted (see 9.3.5 of 802.15.4-2015)
+///
+/// `mac_payload` is everything between the header and the MIC. Header IEs are
+/// never encrypted, so if the frame carries IEs, the open part ends with the
+/// header IEs and their termination IE, and the Payload IEs and the payload
+/// that follow are private. Without IEs, the open part of a Beacon frame
+/// consists of the beacon fields, and that of a MAC command frame of the
+/// Command Frame Identifier. The MAC payload of a Data frame or an Enhanced
+/// Acknowledgement is private entirely.
+#[cfg(feature = "security")]
+fn open_payload_len(
+    header: &Header,
+    mac_payload: &[u8],
+) -> byte::Result<usize> {
+    if header.ie_present {
+        let mut ies = HeaderIeIter::new(mac_payload);
+        for ie in &mut ies {
+            ie?;
+        }
+        return Ok(ies.offset());
+    }
+    let len = match header.frame_type {
+        FrameType::Beacon if header.version == FrameVersion::Ieee802154 => 0,
+        FrameType::Beacon => {
+            let offset = &mut 0;
+            let _: Beacon = mac_payload.read(offset)?;
+            *offset
+        }
+        FrameType::Data | FrameType::Acknowledgement => 0,
+        FrameType::MacCommand => {
+            check_len(mac_payload, 1)?;
+            1
+        }
+    };
+    Ok(len)
 }
 
-/// Appends the secured payload of a [Frame] to the provided buffer, secured according to the
-/// security settings specified in the [Frame]'s [`super::Header`] and [AuxiliarySecurityHeader].
+/// Appends the secured content and payload of a [Frame] to the provided buffer, secured according
+/// to the security settings specified in the [Frame]'s [`super::Header`] and [AuxiliarySecurityHeader].
 ///
-/// Offset is updated with the amount of bytes that is written
+/// The first `header_len` bytes of `buffer` must contain the header of the frame, which is
+/// authenticated as well. The content and payload are written after it, and
+/// are secured in place, so no intermediate buffer is needed. `buffer` must
+/// have room for the MIC as well.
 ///
-/// Currently only supports the securing of Data frames with extended addresses
+/// All checks that can fail are done before the frame counter is
+/// incremented, so a frame that cannot be secured does not use it up.
 ///
-/// Partial implementation of 7.2.1
+/// Returns the amount of bytes that is written after the header
+///
+/// Currently only supports the securing of frames with extended addresses
 ///
-/// # Panics
-/// if footer_mode is not None due to currently absent implementation of explicit footers
-pub(crate) fn secure_frame<'a, AEADBLKCIPH, KEYDESCLO>(
+/// Partial implementation of 7.2.1
+#[cfg(feature = "security")]
+pub(crate) fn secure_frame<
+    AEADBLKCIPH,
+    KEYDESCLO,
+    FRAMECTR,
+    CCMBACKEND,
+    ASNPROV,
+    NONCEGEN,
+    STATS,
+>(
     frame: Frame<'_>,
-    context: &mut SecurityContext<AEADBLKCIPH, KEYDESCLO>,
-    footer_mode: FooterMode,
+    context: &mut SecurityContext<
+        AEADBLKCIPH,
+        KEYDESCLO,
+        FRAMECTR,
+        CCMBACKEND,
+        ASNPROV,
+        NONCEGEN,
+        STATS,
+    >,
     buffer: &mut [u8],
+    header_len: usize,
 ) -> Result<usize, SecurityError>
 where
     AEADBLKCIPH: NewBlockCipher + BlockCipher<BlockSize = U16> + BlockEncrypt,
     KEYDESCLO: KeyDescriptorLookup<AEADBLKCIPH::KeySize>,
+    FRAMECTR: FrameCounterStorage,
+    CCMBACKEND: CcmBackend<AEADBLKCIPH::KeySize>,
+    ASNPROV: AsnProvider,
+    NONCEGEN: NonceGenerator,
+    STATS: BorrowMut<SecurityStatistics>,
 {
-    match footer_mode {
-        FooterMode::None => {}
-        FooterMode::Explicit => {
-            // We should panic here, as having an explicit footer is not supported
-            // and it is not something that can be altered at runtime in a way that affects
-            // the availability of the system. Doing so here also ensures that the program always
-            // panics if FooterMode::Explicit is used, instead of doing other checks first
-            unimplemented!()
-        }
-    }
-
-    let mut offset = 0 as usize;
+    let mut offset = header_len;
     let header = frame.header;
 
     if header.has_security() {
@@ -352,188 +1029,198 @@ where
         let source = context.euid;
 
         // Check for unimplemented behaviour before performing any operations on the buffer
-        match header.frame_type {
-            FrameType::Data => {}
-            _ => return Err(SecurityError::NotImplemented),
+        if !is_securable(&header) {
+            return Err(SecurityError::NotImplemented);
         }
 
         // Procedure 7.2.1
         if let Some(aux_sec_header) = header.auxiliary_security_header {
             let auth_len =
                 aux_sec_header.control.security_level.get_mic_octet_size();
-            let aux_len = aux_sec_header.get_octet_size();
+
+            // Write unencrypted data to the buffer, 7.2.1c, preparation for in-place AEAD in 7.2.1g
+            super::write_content_and_payload(
+                buffer,
+                &mut offset,
+                &frame.header,
+                frame.content,
+                frame.payload,
+            )
+            .map_err(SecurityError::WriteError)?;
 
             // If frame size plus AuthLen plus AuxLen plus FCS is bigger than aMaxPHYPacketSize
-            // 7.2.1b4
-            if !(frame.payload.len()
-                + frame.header.get_octet_size()
-                + aux_len
-                + auth_len
-                + 2
-                <= 127)
-            {
+            // 7.2.1b4. The header size includes AuxLen.
+            let frame_len = offset - header_len + frame.header.get_octet_size();
+            if frame_len + auth_len + 2 > MAX_PHY_PACKET_SIZE {
                 return Err(SecurityError::FrameTooLong);
             }
 
-            // Write unencrypted data to the buffer, 7.2.1c, preparation for in-place AEAD in 7.2.1g
-            if let Err(e) = buffer.write(&mut offset, frame.payload) {
-                return Err(SecurityError::WriteError(e));
-            }
-
             // Success if the security level is none (7.2.1c)
             if aux_sec_header.control.security_level == SecurityLevel::None {
-                return Ok(offset);
+                return Ok(offset - header_len);
             }
 
-            // 7.2.1d
-            if *frame_counter == 0xFFFFFFFF {
-                return Err(SecurityError::CounterError);
+            if aux_sec_header.control.security_level == SecurityLevel::ENC
+                && !context.allow_encryption_only
+            {
+                return Err(SecurityError::UnsupportedSecurityLevel);
             }
 
-            let nonce = calculate_nonce(
-                source,
-                *frame_counter,
-                aux_sec_header.control.security_level,
-            );
-
-            *frame_counter += 1;
+            // Check everything that can fail before the frame counter is
+            // used up
+            if buffer.len() < offset + auth_len {
+                return Err(SecurityError::WriteError(byte::Error::Incomplete));
+            }
 
             // Partial 7.2.1e, 7.2.2 is only partially implemented
-            if let Some((_, key)) = context.key_provider.lookup_key_descriptor(
-                AddressingMode::DstAddrMode,
-                aux_sec_header.key_identifier,
-                header.destination,
-            ) {
-                // 7.2.1g
-                let sec_l = aux_sec_header.control.security_level;
-
-                // Macro that invokes the security procedure for block ciphers with different tag
-                // sizes
-                macro_rules! do_secure {
-                    ($tag_size:ty, $mic:pat, $encmic:pat) => {
-                        let aead =
-                            Ccm::<AEADBLKCIPH, $tag_size, CcmU13>::new(&key);
-
-                        let auth_enc_part = match footer_mode {
-                            FooterMode::None => &mut buffer[..offset],
-                            FooterMode::Explicit => {
-                                return Err(SecurityError::NotImplemented)
-                            }
-                        };
-
-                        let tag = match sec_l {
-                            $mic => aead.encrypt_in_place_detached(
-                                &GenericArray::from_slice(&nonce),
-                                auth_enc_part,
-                                &mut [],
-                            ),
-                            $encmic => aead.encrypt_in_place_detached(
-                                &GenericArray::from_slice(&nonce),
-                                &mut [],
-                                auth_enc_part,
-                            ),
-                            _ => {
-                                panic!("Impossible")
-                            }
-                        };
-
-                        if let Ok(tag) = tag {
-                            if let Err(e) =
-                                buffer.write(&mut offset, tag.as_slice())
-                            {
-                                return Err(SecurityError::WriteError(e));
-                            }
-                        } else {
-                            return Err(SecurityError::TransformationError);
-                        }
-                    };
-                }
+            let (_, key) = context
+                .key_provider
+                .lookup_key_descriptor(
+                    AddressingMode::DstAddrMode,
+                    aux_sec_header.key_identifier,
+                    header.destination,
+                )
+                .ok_or(SecurityError::UnavailableKey)?;
+
+            let (frame_data, tag) =
+                buffer[..offset + auth_len].split_at_mut(offset);
+
+            // The a-data starts with the header. Without encryption, the
+            // MAC payload is a-data entirely
+            let sec_l = aux_sec_header.control.security_level;
+            let private_start = if sec_l.is_encrypted() {
+                header_len
+                    + open_payload_len(&header, &frame_data[header_len..])
+                        .map_err(SecurityError::WriteError)?
+            } else {
+                frame_data.len()
+            };
 
-                match sec_l {
-                    SecurityLevel::None => {}
-                    SecurityLevel::ENC => {
-                        // No support for encryption without authentication
-                        return Err(SecurityError::NotImplemented);
-                    }
-                    SecurityLevel::MIC32 | SecurityLevel::ENCMIC32 => {
-                        do_secure!(
-                            U4,
-                            SecurityLevel::MIC32,
-                            SecurityLevel::ENCMIC32
-                        );
-                    }
-                    SecurityLevel::MIC64 | SecurityLevel::ENCMIC64 => {
-                        do_secure!(
-                            U8,
-                            SecurityLevel::MIC64,
-                            SecurityLevel::ENCMIC64
-                        );
-                    }
-                    SecurityLevel::MIC128 | SecurityLevel::ENCMIC128 => {
-                        do_secure!(
-                            CcmU16,
-                            SecurityLevel::MIC128,
-                            SecurityLevel::ENCMIC128
-                        );
-                    }
-                    #[allow(unreachable_patterns)]
-                    _ => {}
-                };
-                return Ok(offset);
+            let control = aux_sec_header.control;
+            let nonce = if control.asn_in_nonce {
+                let asn = context
+                    .asn_provider
+                    .asn()
+                    .ok_or(SecurityError::InvalidSecContext)?;
+                context.nonce_generator.asn_nonce(source, asn)
+            } else if control.frame_counter_suppression {
+                // Without the ASN, the nonce would be reused
+                return Err(SecurityError::UnsupportedFrameCounterSuppression);
             } else {
-                return Err(SecurityError::UnavailableKey);
+                // 7.2.1d
+                if frame_counter.load() == 0xFFFFFFFF {
+                    return Err(SecurityError::CounterExhausted);
+                }
+
+                context.nonce_generator.frame_counter_nonce(
+                    source,
+                    frame_counter.load(),
+                    control.security_level,
+                )
+            };
+
+            if !control.frame_counter_suppression {
+                frame_counter.increment();
+                frame_counter.commit()?;
             }
+
+            // 7.2.1g, the content and payload are secured in place
+            let (a_data, m_data) = frame_data.split_at_mut(private_start);
+            context
+                .ccm_backend
+                .encrypt_and_tag(&key, &nonce, a_data, m_data, tag)?;
+            Ok(offset + auth_len - header_len)
         } else {
-            return Err(SecurityError::AuxSecHeaderAbsent);
+            Err(SecurityError::AuxSecHeaderAbsent)
         }
     } else {
-        return Err(SecurityError::SecurityNotEnabled);
+        Err(SecurityError::SecurityNotEnabled)
     }
 }
 
+/// The security metadata of a frame that was unsecured
+///
+/// This tells which key, security level and frame counter were used to
+/// secure the frame, e.g. to enforce the policies of higher layers. See
+/// [`Frame::try_read_and_unsecure_with_metadata`].
+#[cfg(feature = "security")]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnsecuredMetadata {
+    /// The extended address of the device that secured the frame, as found by
+    /// the key lookup
+    pub source_euid: u64,
+    /// The security level of the frame
+    pub security_level: SecurityLevel,
+    /// The identifier of the key that the frame was secured with, or `None`
+    /// if the key was determined implicitly
+    pub key_identifier: Option<KeyIdentifier>,
+    /// The key identifier mode of the frame
+    pub key_id_mode: KeyIdentifierMode,
+    /// The frame counter of the frame, or `None` if it was suppressed
+    pub frame_counter: Option<u32>,
+}
+
 /// Unsecure a currently secured frame, based on the
-/// settings found in the header of `frame`. `buffer` should be
-/// the slice containing the authenticated and possibly encrypted data,
-/// and its tag.
+/// settings found in `header`. `buffer` should be the slice
+/// containing the frame, but not the footer. Its first `header_len` bytes
+/// are the header of the frame, which is authenticated. They are followed by
+/// the content and payload of the frame, which are authenticated and
+/// possibly encrypted, and the tag.
 ///
 /// # Returns
-/// In case of success, the function returns the length of the authentication tag,
-/// i.e. the amount of bytes at the end of the payload that should be ignored
+/// In case of success, the function returns the security metadata of the
+/// frame. The MIC of its security level is at the end of the payload, and
+/// should be ignored
 ///
-/// Replaces the payload of `frame` with the unsecured version
+/// Replaces the content and payload in `buffer` with the unsecured version
 ///
-/// Partial implementation of 7.2.3
-/// Currently not implemented: 7.2.3h, 7.2.3i, 7.2.3j, 7.2.3k, 7.2.3n
+/// The frame counter of the source device is only updated if
+/// `update_frame_counter` is set. If that exhausts the frame counter, the
+/// device is blacklisted for the key
 ///
-/// # Panics
-/// if footer_mode is not None due to currently absent implementation of explicit footers
-pub(crate) fn unsecure_frame<'a, AEADBLKCIPH, KEYDESCLO, DEVDESCLO>(
+/// Implementation of 7.2.3
+#[cfg(feature = "security")]
+pub(crate) fn unsecure_frame<
+    AEADBLKCIPH,
+    KEYDESCLO,
+    FRAMECTR,
+    CCMBACKEND,
+    ASNPROV,
+    NONCEGEN,
+    STATS,
+    DEVDESCLO,
+>(
     header: &Header,
     buffer: &mut [u8],
-    context: &mut SecurityContext<AEADBLKCIPH, KEYDESCLO>,
-    footer_mode: FooterMode,
+    header_len: usize,
+    context: &mut SecurityContext<
+        AEADBLKCIPH,
+        KEYDESCLO,
+        FRAMECTR,
+        CCMBACKEND,
+        ASNPROV,
+        NONCEGEN,
+        STATS,
+    >,
     dev_desc_lo: &mut DEVDESCLO,
-) -> Result<usize, SecurityError>
+    update_frame_counter: bool,
+) -> Result<UnsecuredMetadata, SecurityError>
 where
     AEADBLKCIPH: NewBlockCipher + BlockCipher<BlockSize = U16> + BlockEncrypt,
     KEYDESCLO: KeyDescriptorLookup<AEADBLKCIPH::KeySize>,
-    DEVDESCLO: DeviceDescriptorLookup,
+    FRAMECTR: FrameCounterStorage,
+    CCMBACKEND: CcmBackend<AEADBLKCIPH::KeySize>,
+    ASNPROV: AsnProvider,
+    NONCEGEN: NonceGenerator,
+    STATS: BorrowMut<SecurityStatistics>,
+    DEVDESCLO: DeviceDescriptorLookup + ?Sized,
 {
-    match footer_mode {
-        FooterMode::None => {}
-        FooterMode::Explicit => {
-            // We should panic here, as having an explicit footer is not supported
-            // and it is not something that can be altered at runtime in a way that affects
-            // the availability of the system
-            unimplemented!()
-        }
-    }
-
     if header.has_security() {
         // Check for unimplemented behaviour before performing any operations on the buffer
-        match header.frame_type {
-            FrameType::Data => {}
-            _ => return Err(SecurityError::NotImplemented),
+        if !is_securable(header) {
+            return Err(SecurityError::NotImplemented);
         }
 
         // 7.2.3b
@@ -550,8 +1237,27 @@ where
         if aux_sec_header.control.security_level == SecurityLevel::None {
             return Err(SecurityError::UnsupportedSecurity);
         }
+        if aux_sec_header.control.security_level == SecurityLevel::ENC
+            && !context.allow_encryption_only
+        {
+            return Err(SecurityError::UnsupportedSecurityLevel);
+        }
+
+        // The Command Frame Identifier is only encrypted if it follows IEs.
+        // The checks that depend on it are then performed once the frame is
+        // decrypted
+        let is_command = header.frame_type == FrameType::MacCommand;
+        let command_id_open = !(header.ie_present
+            && aux_sec_header.control.security_level.is_encrypted());
+        let command_id = match (is_command, command_id_open) {
+            (true, true) => buffer
+                .get(header_len..)
+                .and_then(|mac_payload| read_command_id(header, mac_payload)),
+            _ => None,
+        };
 
-        let mut taglen = 0;
+        let metadata;
+        let mut exhausted = false;
         // 7.2.3f
         if let Some((source_u64_address, key)) =
             context.key_provider.lookup_key_descriptor(
@@ -565,101 +1271,113 @@ where
                 None => return Err(SecurityError::NoSourceAddress),
             };
 
-            match dev_desc_lo
-                .lookup_device(AddressingMode::SrcAddrMode, source_addr)
-            {
+            match dev_desc_lo.lookup_device(
+                AddressingMode::SrcAddrMode,
+                source_addr,
+                aux_sec_header.key_identifier,
+            ) {
                 Some(device) => {
-                    let frame_counter = &mut device.frame_counter;
-                    // 7.2.3l, 7.2.3m
-                    if *frame_counter == 0xFFFFFFFF
-                        || aux_sec_header.get_frame_counter() < *frame_counter
-                    {
-                        return Err(SecurityError::CounterError);
+                    // 7.2.3h, 7.2.3i, 7.2.3j
+                    if !is_command || command_id_open {
+                        check_incoming_frame(
+                            context.security_level_table,
+                            &context.key_provider,
+                            header,
+                            &aux_sec_header,
+                            device.exempt,
+                            command_id,
+                        )?;
                     }
 
-                    let nonce = calculate_nonce(
-                        source_u64_address,
-                        aux_sec_header.get_frame_counter(),
-                        aux_sec_header.control.security_level,
-                    );
+                    let control = aux_sec_header.control;
+                    let received_counter = aux_sec_header.get_frame_counter();
+                    let frame_counter = &mut device.frame_counter;
+                    if let Some(received_counter) = received_counter {
+                        // 7.2.3k
+                        if received_counter == 0xFFFFFFFF {
+                            return Err(SecurityError::CounterExhausted);
+                        }
+                        // 7.2.3l
+                        if received_counter < *frame_counter {
+                            return Err(SecurityError::ReplayedFrame);
+                        }
+                    }
 
-                    let data_and_tag = match footer_mode {
-                        FooterMode::None => buffer,
-                        FooterMode::Explicit => unimplemented!(),
+                    let nonce = match (control.asn_in_nonce, received_counter) {
+                        (true, _) => {
+                            let asn = context
+                                .asn_provider
+                                .asn()
+                                .ok_or(SecurityError::InvalidSecContext)?;
+                            context
+                                .nonce_generator
+                                .asn_nonce(source_u64_address, asn)
+                        }
+                        (false, Some(received_counter)) => {
+                            context.nonce_generator.frame_counter_nonce(
+                                source_u64_address,
+                                received_counter,
+                                control.security_level,
+                            )
+                        }
+                        (false, None) => return Err(
+                            SecurityError::UnsupportedFrameCounterSuppression,
+                        ),
                     };
 
                     let sec_l = aux_sec_header.control.security_level;
-                    macro_rules! do_unsecure {
-                        ($tag_size:ty, $mic:pat, $encmic:pat) => {
-                            let aead =
-                                Ccm::<AEADBLKCIPH, $tag_size, CcmU13>::new(
-                                    &key,
-                                );
-                            taglen = sec_l.get_mic_octet_size() as usize;
-                            // Copy the tag out of the aead slice
-                            let buffer_len = data_and_tag.len();
-                            let tag = GenericArray::from_slice(
-                                &data_and_tag[buffer_len - taglen..buffer_len],
+
+                    let taglen = sec_l.get_mic_octet_size();
+                    if buffer.len() < header_len + taglen {
+                        return Err(SecurityError::ReadError(
+                            byte::Error::Incomplete,
+                        ));
+                    }
+                    let (frame_data, tag) =
+                        buffer.split_at_mut(buffer.len() - taglen);
+
+                    // The a-data starts with the header. Without encryption,
+                    // the MAC payload is a-data entirely
+                    let private_start = if sec_l.is_encrypted() {
+                        header_len
+                            + open_payload_len(
+                                header,
+                                &frame_data[header_len..],
                             )
-                            .clone();
-
-                            let auth_enc_part =
-                                &mut data_and_tag[..buffer_len - taglen];
-
-                            let verify = match sec_l {
-                                $mic => aead.decrypt_in_place_detached(
-                                    &GenericArray::from_slice(&nonce),
-                                    auth_enc_part,
-                                    &mut [],
-                                    &tag,
-                                ),
-                                $encmic => aead.decrypt_in_place_detached(
-                                    &GenericArray::from_slice(&nonce),
-                                    &mut [],
-                                    auth_enc_part,
-                                    &tag,
-                                ),
-                                _ => {
-                                    panic!("Impossible")
-                                }
-                            };
-                            if let Ok(_) = verify {
-                                *frame_counter =
-                                    aux_sec_header.get_frame_counter() + 1;
-                            } else {
-                                return Err(SecurityError::TransformationError);
-                            }
-                        };
+                            .map_err(SecurityError::ReadError)?
+                    } else {
+                        frame_data.len()
+                    };
+                    let (a_data, m_data) =
+                        frame_data.split_at_mut(private_start);
+
+                    // 7.2.3m, 7.2.3n
+                    context.ccm_backend.decrypt_and_verify(
+                        &key, &nonce, a_data, m_data, tag,
+                    )?;
+                    if is_command && !command_id_open {
+                        check_incoming_frame(
+                            context.security_level_table,
+                            &context.key_provider,
+                            header,
+                            &aux_sec_header,
+                            device.exempt,
+                            read_command_id(header, &frame_data[header_len..]),
+                        )?;
                     }
-                    match sec_l {
-                        SecurityLevel::None => {}
-                        SecurityLevel::ENC => {
-                            // No support for encryption without authentication
-                            return Err(SecurityError::NotImplemented);
-                        }
-                        SecurityLevel::MIC32 | SecurityLevel::ENCMIC32 => {
-                            do_unsecure!(
-                                U4,
-                                SecurityLevel::MIC32,
-                                SecurityLevel::ENCMIC32
-                            );
-                        }
-                        SecurityLevel::MIC64 | SecurityLevel::ENCMIC64 => {
-                            do_unsecure!(
-                                U8,
-                                SecurityLevel::MIC64,
-                                SecurityLevel::ENCMIC64
-                            );
-                        }
-                        SecurityLevel::MIC128 | SecurityLevel::ENCMIC128 => {
-                            do_unsecure!(
-                                U16,
-                                SecurityLevel::MIC128,
-                                SecurityLevel::ENCMIC128
-                            );
-                        }
-                        #[allow(unreachable_patterns)]
-                        _ => {}
+                    if let (true, Some(received_counter)) =
+                        (update_frame_counter, received_counter)
+                    {
+                        // 7.2.3o
+                        exhausted =
+                            device.update_frame_counter(received_counter);
+                    }
+                    metadata = UnsecuredMetadata {
+                        source_euid: source_u64_address,
+                        security_level: sec_l,
+                        key_identifier: aux_sec_header.key_identifier,
+                        key_id_mode: control.key_id_mode,
+                        frame_counter: received_counter,
                     };
                 }
                 None => return Err(SecurityError::UnavailableDevice),
@@ -667,9 +1385,75 @@ where
         } else {
             return Err(SecurityError::UnavailableKey);
         }
-        return Ok(taglen);
+        if let (true, Some(source)) = (exhausted, header.source) {
+            // 7.2.3o, the device can not use the key anymore
+            dev_desc_lo.blacklist_device(source, aux_sec_header.key_identifier);
+        }
+        Ok(metadata)
     } else {
-        return Err(SecurityError::SecurityNotEnabled);
+        Err(SecurityError::SecurityNotEnabled)
+    }
+}
+
+/// Check whether a frame without security may be accepted, according to the
+/// security level table in `context`
+///
+/// Frames that are only conditionally accepted by the security level table
+/// are accepted if the source device is exempt.
+#[cfg(feature = "security")]
+pub(crate) fn check_unsecured_frame<
+    AEADBLKCIPH,
+    KEYDESCLO,
+    FRAMECTR,
+    CCMBACKEND,
+    ASNPROV,
+    NONCEGEN,
+    STATS,
+    DEVDESCLO,
+>(
+    header: &Header,
+    content: &FrameContent,
+    context: &SecurityContext<
+        AEADBLKCIPH,
+        KEYDESCLO,
+        FRAMECTR,
+        CCMBACKEND,
+        ASNPROV,
+        NONCEGEN,
+        STATS,
+    >,
+    dev_desc_lo: &mut DEVDESCLO,
+) -> Result<(), SecurityError>
+where
+    AEADBLKCIPH: NewBlockCipher + BlockCipher<BlockSize = U16>,
+    KEYDESCLO: KeyDescriptorLookup<AEADBLKCIPH::KeySize>,
+    FRAMECTR: FrameCounterStorage,
+    CCMBACKEND: CcmBackend<AEADBLKCIPH::KeySize>,
+    ASNPROV: AsnProvider,
+    NONCEGEN: NonceGenerator,
+    STATS: BorrowMut<SecurityStatistics>,
+    DEVDESCLO: DeviceDescriptorLookup + ?Sized,
+{
+    match check_security_level(
+        context.security_level_table,
+        header.frame_type,
+        command_id(content),
+        SecurityLevel::None,
+    ) {
+        SecurityLevelCheck::Passed => Ok(()),
+        SecurityLevelCheck::Failed => Err(SecurityError::ImproperSecurityLevel),
+        SecurityLevelCheck::ConditionallyPassed => {
+            let source = header.source.ok_or(SecurityError::NoSourceAddress)?;
+            match dev_desc_lo.lookup_device(
+                AddressingMode::SrcAddrMode,
+                source,
+                None,
+            ) {
+                Some(device) if device.exempt => Ok(()),
+                Some(_) => Err(SecurityError::ImproperSecurityLevel),
+                None => Err(SecurityError::UnavailableDevice),
+            }
+        }
     }
 }
 
@@ -678,28 +1462,36 @@ where
 pub enum SecurityError {
     /// Security is not enabled for this frame
     SecurityNotEnabled,
-    /// The provided security context cannot be used to secure the frame
+    /// An incoming frame is secured, but security is disabled on this device,
+    /// i.e. macSecurityEnabled is FALSE
+    SecurityDisabled,
+    /// The provided security context cannot be used to secure the frame, e.g.
+    /// because the frame uses the Absolute Slot Number in its nonce and the
+    /// context does not know it
     InvalidSecContext,
     /// The frame is too long after appending all security data
     FrameTooLong,
-    /// The counter used for securing a frame is invalid (0xFFFFFFFF)
-    CounterError,
+    /// The frame counter has reached its maximum value (0xFFFFFFFF), so no
+    /// more frames can be secured with the key
+    CounterExhausted,
+    /// The frame counter of an incoming frame is lower than that of a frame
+    /// that was already accepted from the same device
+    ReplayedFrame,
+    /// The frame counter of outgoing frames could not be stored
+    CounterStorageError,
     /// No key could be found for the provided context
     UnavailableKey,
-    /// The key could not be used in an adequate manner
-    KeyFailure,
     /// The frame to be unsecured has no source address specified. The source
     /// address is necessary to calculate the nonce, in some cases
     NoSourceAddress,
-    /// The frame to be secured has no destination address specified. The destination
-    /// address is necessary to calculate the nonce, in some cases
-    NoDestinationAddress,
     /// The security (CCM*) transformation could not be completed successfully
     TransformationError,
+    /// The MIC of an incoming frame does not match its contents
+    MicMismatch,
     /// Something went wrong while writing the frame's payload bytes to the buffer
     WriteError(byte::Error),
-    /// Something went wrong while writing a frame's tag to the buffer
-    TagWriteError,
+    /// Something went wrong while reading a secured frame from the buffer
+    ReadError(byte::Error),
     /// When functionality that is not implemented is used
     NotImplemented,
     /// Security is enabled, but no auxiliary security header is present
@@ -711,16 +1503,97 @@ pub enum SecurityError {
     UnsupportedLegacy,
     /// The security level of an incomin frame is zero
     UnsupportedSecurity,
+    /// The security level of the frame is not supported, e.g. encryption
+    /// without authentication
+    UnsupportedSecurityLevel,
+    /// The frame counter is suppressed, but the nonce is not built from the
+    /// Absolute Slot Number, so it would be reused
+    UnsupportedFrameCounterSuppression,
     /// The device descriptor that belongs to an address can not be found
     UnavailableDevice,
-    /// The address returned by the specific implementation of [`KeyDescriptorLookup`] is not
-    /// of type [`Address::Extended`]
-    KeyLookupAddressTypeMismatch,
+    /// The security level of an incoming frame is lower than the minimum
+    /// security level, and the sending device is not exempt
+    ImproperSecurityLevel,
+    /// The key that was used to secure an incoming frame may not be used for
+    /// frames of its type
+    ImproperKeyType,
+}
+
+impl fmt::Display for SecurityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let description = match self {
+            SecurityError::SecurityNotEnabled => "security is not enabled",
+            SecurityError::SecurityDisabled => "security is disabled",
+            SecurityError::InvalidSecContext => "invalid security context",
+            SecurityError::FrameTooLong => "frame too long",
+            SecurityError::CounterExhausted => "frame counter exhausted",
+            SecurityError::ReplayedFrame => "replayed frame",
+            SecurityError::CounterStorageError => {
+                "frame counter could not be stored"
+            }
+            SecurityError::UnavailableKey => "key lookup failed",
+            SecurityError::NoSourceAddress => "no source address",
+            SecurityError::TransformationError => "CCM* transformation failed",
+            SecurityError::MicMismatch => "MIC mismatch",
+            SecurityError::WriteError(e) => {
+                return write!(f, "write error: {:?}", e)
+            }
+            SecurityError::ReadError(e) => {
+                return write!(f, "read error: {:?}", e)
+            }
+            SecurityError::NotImplemented => "not implemented",
+            SecurityError::AuxSecHeaderAbsent => {
+                "auxiliary security header absent"
+            }
+            SecurityError::KeyIdentifierMismatch => {
+                "key identifier mode mismatch"
+            }
+            SecurityError::UnsupportedLegacy => {
+                "unsupported legacy frame version"
+            }
+            SecurityError::UnsupportedSecurity => "security level is zero",
+            SecurityError::UnsupportedSecurityLevel => {
+                "unsupported security level"
+            }
+            SecurityError::UnsupportedFrameCounterSuppression => {
+                "frame counter suppressed without ASN in nonce"
+            }
+            SecurityError::UnavailableDevice => "device lookup failed",
+            SecurityError::ImproperSecurityLevel => {
+                "security level below minimum"
+            }
+            SecurityError::ImproperKeyType => {
+                "key may not be used for this frame"
+            }
+        };
+        f.write_str(description)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SecurityError {}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for SecurityError {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            SecurityError::WriteError(e) => {
+                defmt::write!(f, "WriteError({=str})", byte_error_name(e))
+            }
+            SecurityError::ReadError(e) => {
+                defmt::write!(f, "ReadError({=str})", byte_error_name(e))
+            }
+            e => defmt::write!(f, "{=str}", byte_error_name(&(*e).into())),
+        }
+    }
 }
 
-impl From<byte::Error> for SecurityError {
-    fn from(e: byte::Error) -> Self {
-        SecurityError::WriteError(e)
+#[cfg(feature = "defmt")]
+fn byte_error_name(e: &byte::Error) -> &'static str {
+    match e {
+        byte::Error::Incomplete => "Incomplete",
+        byte::Error::BadOffset(_) => "BadOffset",
+        byte::Error::BadInput { err } => err,
     }
 }
 
@@ -733,31 +1606,34 @@ impl From<SecurityError> for byte::Error {
             SecurityError::FrameTooLong => byte::Error::BadInput {
                 err: "FrameTooLong",
             },
-            SecurityError::CounterError => byte::Error::BadInput {
-                err: "CounterError",
+            SecurityError::CounterExhausted => byte::Error::BadInput {
+                err: "CounterExhausted",
+            },
+            SecurityError::ReplayedFrame => byte::Error::BadInput {
+                err: "ReplayedFrame",
+            },
+            SecurityError::CounterStorageError => byte::Error::BadInput {
+                err: "CounterStorageError",
             },
             SecurityError::UnavailableKey => byte::Error::BadInput {
                 err: "UnavailableKey",
             },
-            SecurityError::KeyFailure => {
-                byte::Error::BadInput { err: "KeyFailure" }
-            }
             SecurityError::NoSourceAddress => byte::Error::BadInput {
                 err: "NoSourceAddress",
             },
-            SecurityError::NoDestinationAddress => byte::Error::BadInput {
-                err: "NoDstAddress",
-            },
             SecurityError::TransformationError => byte::Error::BadInput {
                 err: "TransformationError",
             },
-            SecurityError::TagWriteError => byte::Error::BadInput {
-                err: "TagWriteError",
-            },
+            SecurityError::MicMismatch => {
+                byte::Error::BadInput { err: "MicMismatch" }
+            }
             SecurityError::SecurityNotEnabled => byte::Error::BadInput {
                 err: "SecurityNotEnabled",
             },
-            SecurityError::WriteError(e) => e,
+            SecurityError::SecurityDisabled => byte::Error::BadInput {
+                err: "SecurityDisabled",
+            },
+            SecurityError::WriteError(e) | SecurityError::ReadError(e) => e,
             SecurityError::NotImplemented => byte::Error::BadInput {
                 err: "NotImplemented",
             },
@@ -773,19 +1649,28 @@ impl From<SecurityError> for byte::Error {
             SecurityError::UnsupportedSecurity => byte::Error::BadInput {
                 err: "UnsupportedSecurity",
             },
-            SecurityError::UnavailableDevice => byte::Error::BadInput {
-                err: "UnavailableDevice",
+            SecurityError::UnsupportedSecurityLevel => byte::Error::BadInput {
+                err: "UnsupportedSecurityLevel",
             },
-            SecurityError::KeyLookupAddressTypeMismatch => {
+            SecurityError::UnsupportedFrameCounterSuppression => {
                 byte::Error::BadInput {
-                    err: "KeyLookupAddressTypeMismatch",
+                    err: "UnsupportedFrameCounterSuppression",
                 }
             }
+            SecurityError::UnavailableDevice => byte::Error::BadInput {
+                err: "UnavailableDevice",
+            },
+            SecurityError::ImproperSecurityLevel => byte::Error::BadInput {
+                err: "ImproperSecurityLevel",
+            },
+            SecurityError::ImproperKeyType => byte::Error::BadInput {
+                err: "ImproperKeyType",
+            },
         }
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "security"))]
 mod tests {
     use crate::mac::frame::header::*;
     use crate::mac::frame::security::{security_control::*, *};
@@ -828,11 +1713,22 @@ mod tests {
             &mut self,
             _addressing_mode: AddressingMode,
             _address: Address,
+            _key_identifier: Option<KeyIdentifier>,
         ) -> Option<&mut DeviceDescriptor> {
             Some(self.descriptor)
         }
     }
 
+    fn device_descriptor(frame_counter: u32, exempt: bool) -> DeviceDescriptor {
+        DeviceDescriptor {
+            pan_id: PanId(0x111),
+            short_address: ShortAddress(0xfffe),
+            extended_address: ExtendedAddress(0x08),
+            frame_counter,
+            exempt,
+        }
+    }
+
     const STATIC_KEY_LOOKUP: StaticKeyLookup = StaticKeyLookup();
     const FRAME_CTR: u32 = 0x03030303;
 
@@ -855,6 +1751,7 @@ mod tests {
                 frame_pending: false,
                 ack_request: false,
                 pan_id_compress: false,
+                ie_present: false,
                 version: FrameVersion::Ieee802154,
                 seq: 127,
                 destination,
@@ -907,40 +1804,33 @@ mod tests {
             let buf = &mut storage[..plaintext_len + $level.get_mic_octet_size() as usize];
 
             let mut sec_ctx = aes_sec_ctx(source_u64, FRAME_CTR);
-            let write_res = security::secure_frame(frame, &mut sec_ctx, FooterMode::None, buf);
+            let write_res = security::secure_frame(frame, &mut sec_ctx, buf, 0);
 
             match write_res {
-                Err(e) => {
-                    assert!(false, "Failed to secure frame {:?}!", e);
-                }
+                Err(e) => panic!("Failed to secure frame {:?}!", e),
                 Ok(_) => {}
             }
 
             let mut frame = get_frame(Some(source), Some(destination), &[], aux_sec_header);
 
-            let device_desc = &mut DeviceDescriptor {
-                frame_counter: FRAME_CTR - 1,
-                exempt: false,
-            };
+            let device_desc = &mut device_descriptor(FRAME_CTR - 1, false);
 
             let read_res = security::unsecure_frame(
                 &mut frame.header,
                 buf,
+                0,
                 &mut sec_ctx,
-                FooterMode::None,
                 &mut BasicDevDescriptorLookup::new(device_desc),
+                true,
             );
 
             match read_res {
                 Err(e) => match e {
-                    SecurityError::WriteError(err) => {
-                        assert!(
-                            false,
-                            "Write error occured while unsecuring frame. {:?}",
-                            err
-                        );
-                    }
-                    _ => assert!(false, "Failed to unsecure frame: {:?}! ", e),
+                    SecurityError::WriteError(err) => panic!(
+                        "Write error occured while unsecuring frame. {:?}",
+                        err
+                    ),
+                    _ => panic!("Failed to unsecure frame: {:?}! ", e),
                 },
                 _ => {}
             }
@@ -960,12 +1850,8 @@ mod tests {
 
         let mut buf = [0u8; 127];
         let mut sec_ctx = aes_sec_ctx(source_euid, FRAME_CTR);
-        let write_res = security::secure_frame(
-            frame,
-            &mut sec_ctx,
-            FooterMode::None,
-            &mut buf,
-        );
+        let write_res =
+            security::secure_frame(frame, &mut sec_ctx, &mut buf, 0);
         match write_res {
             Ok(_) => {}
             Err(e) => match e {
@@ -1010,6 +1896,20 @@ mod tests {
         test_security_level!(SecurityLevel::ENCMIC128);
     }
 
+    #[test]
+    fn nonce() {
+        // Address and frame counter are big endian, unlike over the air
+        let nonce =
+            calculate_nonce(0xACDE_4800_0000_0001, 5, SecurityLevel::ENCMIC32);
+        assert_eq!(
+            nonce,
+            [
+                0xAC, 0xDE, 0x48, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
+                0x00, 0x05, 0x05
+            ]
+        );
+    }
+
     #[test]
     fn encode_decode_secured_frame() {
         let source_euid = 0x08;
@@ -1057,29 +1957,27 @@ mod tests {
             &mut FrameSerDesContext::new(FooterMode::None, Some(&mut sec_ctx)),
         ) {
             Ok(size) => size,
-            Err(e) => {
-                assert!(false, "Failed to write secured frame! {:?}", e);
-                // Panic to make the match-arm matcher happy
-                panic!();
-            }
+            Err(e) => panic!("Failed to write secured frame! {:?}", e),
         };
 
         // Assert that the length is correct (header field lengths, etc)
         assert_eq!(len, 2 + 1 + 2 + 8 + 2 + 8 + 1 + 4 + 9 + plaintext_len + 4);
+        assert_eq!(
+            frame.header.get_octet_size(),
+            2 + 1 + 2 + 8 + 2 + 8 + 1 + 4 + 9
+        );
 
+        // The MIC covers the header, including the auxiliary security header
         assert_eq!(
             &buf[..len],
             &[
                 9, 236, 127, 34, 34, 9, 0, 0, 0, 0, 0, 0, 0, 17, 1, 8, 0, 0, 0,
                 0, 0, 0, 0, 29, 3, 3, 3, 3, 171, 171, 171, 171, 171, 171, 171,
-                171, 48, 125, 232, 44, 201, 215, 168, 157, 77, 13, 6
+                171, 48, 216, 83, 242, 211, 93, 75, 191, 118, 28, 6
             ]
         );
 
-        let device_desc = &mut DeviceDescriptor {
-            frame_counter: FRAME_CTR,
-            exempt: false,
-        };
+        let device_desc = &mut device_descriptor(FRAME_CTR, false);
 
         let frame = match Frame::try_read_and_unsecure(
             &mut buf[..len],
@@ -1087,10 +1985,7 @@ mod tests {
             &mut BasicDevDescriptorLookup::new(device_desc),
         ) {
             Ok((frame, _)) => frame,
-            Err(e) => {
-                assert!(false, "Could not unsecure frame! {:?}", e);
-                panic!();
-            }
+            Err(e) => panic!("Could not unsecure frame! {:?}", e),
         };
 
         assert!(frame.header.auxiliary_security_header.is_some());
@@ -1108,20 +2003,15 @@ mod tests {
                             assert_eq!(id, 0xABABABABABABABAB);
                         }
                         KeySource::Short(_) => {
-                            assert!(
-                                false,
-                                "Did not parse key identifier correctly"
-                            )
+                            panic!("Did not parse key identifier correctly")
                         }
                     },
-                    None => {
-                        assert!(false, "Did not parse key identifier correctly")
-                    }
+                    None => panic!("Did not parse key identifier correctly"),
                 }
             }
-            None => assert!(false, "Did not parse key identifier correctly"),
+            None => panic!("Did not parse key identifier correctly"),
         }
-        assert_eq!(aux_sec.get_frame_counter(), FRAME_CTR);
+        assert_eq!(aux_sec.get_frame_counter(), Some(FRAME_CTR));
         assert_eq!(sec_ctx.frame_counter, FRAME_CTR + 1);
         assert_eq!(device_desc.frame_counter, sec_ctx.frame_counter);
     }
@@ -1157,11 +2047,7 @@ mod tests {
             &mut FrameSerDesContext::new(FooterMode::None, Some(&mut sec_ctx)),
         ) {
             Ok(size) => size,
-            Err(e) => {
-                assert!(false, "Failed to write secured frame! {:?}", e);
-                // Panic to make the match-arm matcher happy
-                panic!();
-            }
+            Err(e) => panic!("Failed to write secured frame! {:?}", e),
         };
 
         // Assert that the length is correct (header field lengths, etc)
@@ -1175,79 +2061,1723 @@ mod tests {
         // Simulate a bit change
         buf[33] ^= 0x01;
 
-        let device_desc = &mut DeviceDescriptor {
-            frame_counter: FRAME_CTR,
-            exempt: false,
-        };
+        let device_desc = &mut device_descriptor(FRAME_CTR, false);
+
+        assert!(matches!(
+            Frame::try_read_and_unsecure(
+                buf,
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx)
+                ),
+                &mut BasicDevDescriptorLookup::new(device_desc),
+            ),
+            Err(SecurityError::MicMismatch)
+        ));
+
+        // Unflip bit
+        buf[33] ^= 0x01;
+
+        // The header is authenticated as well, so changing the sequence
+        // number is detected
+        buf[2] ^= 0x01;
+
+        let device_desc = &mut device_descriptor(FRAME_CTR, false);
+
+        assert!(matches!(
+            Frame::try_read_and_unsecure(
+                buf,
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx)
+                ),
+                &mut BasicDevDescriptorLookup::new(device_desc),
+            ),
+            Err(SecurityError::MicMismatch)
+        ));
+
+        buf[2] ^= 0x01;
+
+        //
+        // Test counter errors
+        //
+        let device_desc = &mut device_descriptor(FRAME_CTR + 5, false);
+
+        assert!(matches!(
+            Frame::try_read_and_unsecure(
+                buf,
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx)
+                ),
+                &mut BasicDevDescriptorLookup::new(device_desc),
+            ),
+            Err(SecurityError::ReplayedFrame)
+        ));
+
+        // The frame counter of the incoming frame is checked before its MIC
+        buf[24..28].copy_from_slice(&[0xFF; 4]);
+        let device_desc = &mut device_descriptor(FRAME_CTR, false);
 
         match Frame::try_read_and_unsecure(
             buf,
             &mut FrameSerDesContext::new(FooterMode::None, Some(&mut sec_ctx)),
             &mut BasicDevDescriptorLookup::new(device_desc),
         ) {
-            Ok(_) => assert!(false, "Successfully unsecured an altered frame!"),
+            Ok(_) => assert!(
+                false,
+                "Successfully unsecured a frame with overflowing counter!"
+            ),
             Err(e) => match e {
-                SecurityError::TransformationError => {}
+                SecurityError::CounterExhausted => {}
                 _ => {
                     assert!(
                         false,
-                        "Got an error different from TransformationError"
+                        "Got an error different from CounterExhausted"
                     );
                     // Panic to make the match-arm matcher happy
                     panic!();
                 }
             },
         };
+    }
 
-        // Unflip bit
-        buf[33] ^= 0x01;
+    #[test]
+    fn decode_improper_security_level() {
+        let source_euid = 0x08;
+        let (source, destination) = (
+            Address::Extended(PanId(0x111), ExtendedAddress(source_euid)),
+            Address::Extended(PanId(0x2222), ExtendedAddress(0x09)),
+        );
 
-        //
-        // Test counter errors
-        //
-        let device_desc = &mut DeviceDescriptor {
-            frame_counter: FRAME_CTR + 5,
-            exempt: false,
-        };
+        let aux_sec_header = Some(AuxiliarySecurityHeader::new(
+            SecurityControl::new(SecurityLevel::MIC32),
+            None,
+        ));
+
+        let frame = get_frame(
+            Some(source),
+            Some(destination),
+            &[0x01, 0x02, 0x03],
+            aux_sec_header,
+        );
+
+        let mut buf = [0u8; 127];
+        let mut sec_ctx = aes_sec_ctx(source_euid, FRAME_CTR);
+
+        let len = frame
+            .try_write(
+                &mut buf,
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx),
+                ),
+            )
+            .unwrap();
+        let buf = &mut buf[..len];
+
+        sec_ctx.security_level_table = &[SecurityLevelDescriptor {
+            frame_type: FrameType::Data,
+            command_id: None,
+            security_minimum: SecurityLevel::ENCMIC32,
+            device_override_security_minimum: true,
+        }];
+
+        // Exempt devices may only omit security, not use a lower level
+        let device_desc = &mut device_descriptor(FRAME_CTR, true);
 
         match Frame::try_read_and_unsecure(
             buf,
             &mut FrameSerDesContext::new(FooterMode::None, Some(&mut sec_ctx)),
             &mut BasicDevDescriptorLookup::new(device_desc),
         ) {
-            Ok(_) => assert!(false, "Successfully unsecured a replayed frame!"),
-            Err(e) => match e {
-                SecurityError::CounterError => {}
-                _ => {
-                    assert!(false, "Got an error different from CounterError");
-                    // Panic to make the match-arm matcher happy
-                    panic!();
-                }
-            },
-        };
+            Err(SecurityError::ImproperSecurityLevel) => {}
+            Err(e) => panic!("Got an unexpected error: {:?}", e),
+            Ok(_) => panic!("Accepted a frame with too low security level!"),
+        }
+        assert_eq!(device_desc.frame_counter, FRAME_CTR);
 
-        let device_desc = &mut DeviceDescriptor {
-            frame_counter: 0xFFFFFFFF,
-            exempt: false,
-        };
+        // Descriptors for other frame types do not apply
+        sec_ctx.security_level_table = &[SecurityLevelDescriptor {
+            frame_type: FrameType::MacCommand,
+            command_id: None,
+            security_minimum: SecurityLevel::ENCMIC32,
+            device_override_security_minimum: false,
+        }];
+
+        let (frame, _) = Frame::try_read_and_unsecure(
+            buf,
+            &mut FrameSerDesContext::new(FooterMode::None, Some(&mut sec_ctx)),
+            &mut BasicDevDescriptorLookup::new(device_desc),
+        )
+        .unwrap();
+        assert_eq!(frame.payload, &[0x01, 0x02, 0x03]);
+        assert_eq!(device_desc.frame_counter, FRAME_CTR + 1);
+    }
+
+    #[test]
+    fn decode_unsecured_frame_security_level() {
+        let (source_euid, source, destination) = get_rand_addrpair();
+        let frame = get_frame(Some(source), Some(destination), &[0x01], None);
+
+        let mut buf = [0u8; 127];
+        let len = frame
+            .try_write(
+                &mut buf,
+                &mut FrameSerDesContext::no_security(FooterMode::None),
+            )
+            .unwrap();
+        let buf = &mut buf[..len];
 
+        let mut sec_ctx = aes_sec_ctx(source_euid, FRAME_CTR);
+        sec_ctx.security_level_table = &[SecurityLevelDescriptor {
+            frame_type: FrameType::Data,
+            command_id: None,
+            security_minimum: SecurityLevel::MIC32,
+            device_override_security_minimum: true,
+        }];
+
+        let device_desc = &mut device_descriptor(FRAME_CTR, false);
         match Frame::try_read_and_unsecure(
             buf,
             &mut FrameSerDesContext::new(FooterMode::None, Some(&mut sec_ctx)),
             &mut BasicDevDescriptorLookup::new(device_desc),
         ) {
-            Ok(_) => assert!(
-                false,
-                "Successfully unsecured a frame with overflowing counter!"
+            Err(SecurityError::ImproperSecurityLevel) => {}
+            Err(e) => panic!("Got an unexpected error: {:?}", e),
+            Ok(_) => panic!("Accepted an unsecured frame!"),
+        }
+
+        // Exempt devices may send unsecured frames
+        device_desc.exempt = true;
+        let (frame, _) = Frame::try_read_and_unsecure(
+            buf,
+            &mut FrameSerDesContext::new(FooterMode::None, Some(&mut sec_ctx)),
+            &mut BasicDevDescriptorLookup::new(device_desc),
+        )
+        .unwrap();
+        assert_eq!(frame.payload, &[0x01]);
+
+        // Unless the security level descriptor does not allow it
+        sec_ctx.security_level_table = &[SecurityLevelDescriptor {
+            frame_type: FrameType::Data,
+            command_id: None,
+            security_minimum: SecurityLevel::MIC32,
+            device_override_security_minimum: false,
+        }];
+        match Frame::try_read_and_unsecure(
+            buf,
+            &mut FrameSerDesContext::new(FooterMode::None, Some(&mut sec_ctx)),
+            &mut BasicDevDescriptorLookup::new(device_desc),
+        ) {
+            Err(SecurityError::ImproperSecurityLevel) => {}
+            Err(e) => panic!("Got an unexpected error: {:?}", e),
+            Ok(_) => panic!("Accepted an unsecured frame!"),
+        }
+    }
+
+    #[test]
+    fn encode_decode_secured_frame_explicit_footer() {
+        let source_euid = 0x08;
+        let (source, destination) = (
+            Address::Extended(PanId(0x111), ExtendedAddress(source_euid)),
+            Address::Extended(PanId(0x2222), ExtendedAddress(0x09)),
+        );
+
+        let aux_sec_header = Some(AuxiliarySecurityHeader::new(
+            SecurityControl::new(SecurityLevel::ENCMIC64),
+            None,
+        ));
+
+        let mut frame = get_frame(
+            Some(source),
+            Some(destination),
+            &[0x01, 0x02, 0x03],
+            aux_sec_header,
+        );
+        frame.footer = [0x12, 0x34];
+
+        let mut buf = [0u8; 127];
+        let mut sec_ctx = aes_sec_ctx(source_euid, FRAME_CTR);
+
+        let len = frame
+            .try_write(
+                &mut buf,
+                &mut FrameSerDesContext::new(
+                    FooterMode::Explicit,
+                    Some(&mut sec_ctx),
+                ),
+            )
+            .unwrap();
+        assert_eq!(len, 2 + 1 + 2 + 8 + 2 + 8 + 1 + 4 + 3 + 8 + 2);
+        // The MIC is followed by the footer
+        assert_eq!(&buf[len - 2..len], &[0x12, 0x34]);
+
+        let device_desc = &mut device_descriptor(FRAME_CTR, false);
+
+        let (frame, read_len) = Frame::try_read_and_unsecure(
+            &mut buf[..len],
+            &mut FrameSerDesContext::new(
+                FooterMode::Explicit,
+                Some(&mut sec_ctx),
             ),
-            Err(e) => match e {
-                SecurityError::CounterError => {}
-                _ => {
-                    assert!(false, "Got an error different from CounterError");
-                    // Panic to make the match-arm matcher happy
-                    panic!();
+            &mut BasicDevDescriptorLookup::new(device_desc),
+        )
+        .unwrap();
+        assert_eq!(read_len, len);
+        assert_eq!(frame.payload, &[0x01, 0x02, 0x03]);
+        assert_eq!(frame.footer, [0x12, 0x34]);
+    }
+
+    #[test]
+    fn verify_secured_frame() {
+        let (source_euid, source, destination) = get_rand_addrpair();
+        let frame = get_frame(
+            Some(source),
+            Some(destination),
+            &[0x01, 0x02, 0x03],
+            Some(AuxiliarySecurityHeader::new(
+                SecurityControl::new(SecurityLevel::ENCMIC32),
+                None,
+            )),
+        );
+
+        let mut buf = [0u8; 127];
+        let mut sec_ctx = aes_sec_ctx(source_euid, FRAME_CTR);
+        let len = frame
+            .try_write(
+                &mut buf,
+                &mut FrameSerDesContext::new(
+                    FooterMode::Explicit,
+                    Some(&mut sec_ctx),
+                ),
+            )
+            .unwrap();
+        let received = buf;
+        let payload_start = frame.header.get_octet_size();
+
+        let mut scratch = [0u8; 127];
+        let device_desc = &mut device_descriptor(FRAME_CTR, false);
+        let (verified, read_len) = Frame::try_read_and_verify(
+            &buf[..len],
+            &mut scratch,
+            &mut FrameSerDesContext::new(
+                FooterMode::Explicit,
+                Some(&mut sec_ctx),
+            ),
+            &mut BasicDevDescriptorLookup::new(device_desc),
+        )
+        .unwrap();
+        assert_eq!(read_len, len);
+        assert!(verified.mic_valid);
+        assert_eq!(verified.header.source, frame.header.source);
+        assert_eq!(
+            verified.secured_payload,
+            &received[payload_start..payload_start + 3]
+        );
+        assert_ne!(verified.secured_payload, &[0x01, 0x02, 0x03]);
+        assert_eq!(verified.mic, &received[len - 6..len - 2]);
+        // The frame is not unsecured, so the frame counter is not updated
+        assert_eq!(device_desc.frame_counter, FRAME_CTR);
+        assert_eq!(buf, received);
+
+        buf[payload_start] ^= 0x01;
+        let (verified, _) = Frame::try_read_and_verify(
+            &buf[..len],
+            &mut scratch,
+            &mut FrameSerDesContext::new(
+                FooterMode::Explicit,
+                Some(&mut sec_ctx),
+            ),
+            &mut BasicDevDescriptorLookup::new(device_desc),
+        )
+        .unwrap();
+        assert!(!verified.mic_valid);
+
+        // The scratch buffer has to fit the frame
+        assert!(matches!(
+            Frame::try_read_and_verify(
+                &buf[..len],
+                &mut scratch[..len - 3],
+                &mut FrameSerDesContext::new(
+                    FooterMode::Explicit,
+                    Some(&mut sec_ctx),
+                ),
+                &mut BasicDevDescriptorLookup::new(device_desc),
+            ),
+            Err(SecurityError::WriteError(byte::Error::Incomplete))
+        ));
+    }
+
+    #[test]
+    fn encode_frame_counter_storage() {
+        struct TestStorage {
+            frame_counter: u32,
+            commits: usize,
+            fail: bool,
+        }
+
+        impl FrameCounterStorage for TestStorage {
+            fn load(&self) -> u32 {
+                self.frame_counter
+            }
+
+            fn increment(&mut self) {
+                self.frame_counter += 1;
+            }
+
+            fn commit(&mut self) -> Result<(), SecurityError> {
+                if self.fail {
+                    return Err(SecurityError::CounterStorageError);
                 }
-            },
-        };
+                self.commits += 1;
+                Ok(())
+            }
+        }
+
+        let (source_euid, source, destination) = get_rand_addrpair();
+        let frame = get_frame(
+            Some(source),
+            Some(destination),
+            &[0x01, 0x02, 0x03],
+            Some(AuxiliarySecurityHeader::new(
+                SecurityControl::new(SecurityLevel::ENCMIC32),
+                None,
+            )),
+        );
+
+        let mut sec_ctx =
+            SecurityContext::<Aes128, _, _>::with_frame_counter_storage(
+                source_euid,
+                TestStorage {
+                    frame_counter: FRAME_CTR,
+                    commits: 0,
+                    fail: false,
+                },
+                STATIC_KEY_LOOKUP,
+            );
+
+        let mut buf = [0u8; 127];
+        security::secure_frame(frame, &mut sec_ctx, &mut buf, 0).unwrap();
+        assert_eq!(sec_ctx.frame_counter.load(), FRAME_CTR + 1);
+        assert_eq!(sec_ctx.frame_counter.commits, 1);
+
+        sec_ctx.frame_counter.fail = true;
+        assert!(matches!(
+            security::secure_frame(frame, &mut sec_ctx, &mut buf, 0),
+            Err(SecurityError::CounterStorageError)
+        ));
+    }
+
+    #[test]
+    fn encode_failure_keeps_frame_counter() {
+        let (source_euid, source, destination) = get_rand_addrpair();
+        let payload = [0x01, 0x02, 0x03];
+        let aux_sec_header = Some(AuxiliarySecurityHeader::new(
+            SecurityControl::new(SecurityLevel::ENCMIC64),
+            None,
+        ));
+        let mut sec_ctx = aes_sec_ctx(source_euid, FRAME_CTR);
+
+        // No room for the MIC
+        let frame = get_frame(
+            Some(source),
+            Some(destination),
+            &payload,
+            aux_sec_header,
+        );
+        let mut buf = [0u8; 127];
+        assert!(matches!(
+            security::secure_frame(frame, &mut sec_ctx, &mut buf[..4], 0),
+            Err(SecurityError::WriteError(byte::Error::Incomplete))
+        ));
+        assert_eq!(sec_ctx.frame_counter, FRAME_CTR);
+
+        // No key for a short destination address
+        let short_destination =
+            Address::Short(PanId(0x2222), ShortAddress(0x1234));
+        let frame = get_frame(
+            Some(source),
+            Some(short_destination),
+            &payload,
+            aux_sec_header,
+        );
+        assert!(matches!(
+            security::secure_frame(frame, &mut sec_ctx, &mut buf, 0),
+            Err(SecurityError::UnavailableKey)
+        ));
+        assert_eq!(sec_ctx.frame_counter, FRAME_CTR);
+
+        // The payload is secured in place, right after the header
+        let frame = get_frame(
+            Some(source),
+            Some(destination),
+            &payload,
+            aux_sec_header,
+        );
+        let len =
+            security::secure_frame(frame, &mut sec_ctx, &mut buf, 0).unwrap();
+        assert_eq!(len, payload.len() + 8);
+        assert_eq!(sec_ctx.frame_counter, FRAME_CTR + 1);
+    }
+
+    #[test]
+    fn encode_decode_ccm_backend() {
+        /// Delegates to the software implementation, and counts the calls
+        #[derive(Default)]
+        struct CountingCcm {
+            software: SoftwareCcm<Aes128>,
+            encrypted: usize,
+            decrypted: usize,
+        }
+
+        impl CcmBackend<U16> for CountingCcm {
+            fn encrypt_and_tag(
+                &mut self,
+                key: &GenericArray<u8, U16>,
+                nonce: &[u8; 13],
+                aad: &[u8],
+                payload: &mut [u8],
+                tag: &mut [u8],
+            ) -> Result<(), SecurityError> {
+                self.encrypted += 1;
+                self.software.encrypt_and_tag(key, nonce, aad, payload, tag)
+            }
+
+            fn decrypt_and_verify(
+                &mut self,
+                key: &GenericArray<u8, U16>,
+                nonce: &[u8; 13],
+                aad: &[u8],
+                payload: &mut [u8],
+                tag: &[u8],
+            ) -> Result<(), SecurityError> {
+                self.decrypted += 1;
+                self.software
+                    .decrypt_and_verify(key, nonce, aad, payload, tag)
+            }
+        }
+
+        let (source_euid, source, destination) = get_rand_addrpair();
+        let frame = get_frame(
+            Some(source),
+            Some(destination),
+            &[0x01, 0x02, 0x03],
+            Some(AuxiliarySecurityHeader::new(
+                SecurityControl::new(SecurityLevel::ENCMIC64),
+                None,
+            )),
+        );
+
+        let mut sec_ctx = SecurityContext::<Aes128, _, _, _>::with_ccm_backend(
+            source_euid,
+            FRAME_CTR,
+            STATIC_KEY_LOOKUP,
+            CountingCcm::default(),
+        );
+
+        let mut buf = [0u8; 127];
+        let len = frame
+            .try_write(
+                &mut buf,
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx),
+                ),
+            )
+            .unwrap();
+        assert_eq!(sec_ctx.ccm_backend.encrypted, 1);
+
+        let device_desc = &mut device_descriptor(FRAME_CTR, false);
+        let (frame, _) = Frame::try_read_and_unsecure(
+            &mut buf[..len],
+            &mut FrameSerDesContext::new(FooterMode::None, Some(&mut sec_ctx)),
+            &mut BasicDevDescriptorLookup::new(device_desc),
+        )
+        .unwrap();
+        assert_eq!(frame.payload, &[0x01, 0x02, 0x03]);
+        assert_eq!(sec_ctx.ccm_backend.decrypted, 1);
+    }
+
+    #[test]
+    fn asn_nonce() {
+        // Only the lower 5 octets of the ASN are used, in big endian
+        let nonce =
+            calculate_asn_nonce(0xACDE_4800_0000_0001, 0xFF01_0203_0405);
+        assert_eq!(
+            nonce,
+            [
+                0xAC, 0xDE, 0x48, 0x00, 0x00, 0x00, 0x00, 0x01, 0x01, 0x02,
+                0x03, 0x04, 0x05
+            ]
+        );
+    }
+
+    #[test]
+    fn encode_decode_asn_nonce_frame() {
+        struct Slot(Option<u64>);
+
+        impl AsnProvider for Slot {
+            fn asn(&self) -> Option<u64> {
+                self.0
+            }
+        }
+
+        let (source_euid, source, destination) = get_rand_addrpair();
+        let control = SecurityControl::new(SecurityLevel::ENCMIC32)
+            .with_frame_counter_suppression(true)
+            .with_asn_in_nonce(true);
+        let frame = get_frame(
+            Some(source),
+            Some(destination),
+            &[0x01, 0x02, 0x03],
+            Some(AuxiliarySecurityHeader::new(control, None)),
+        );
+
+        let mut sec_ctx = aes_sec_ctx(source_euid, FRAME_CTR)
+            .with_asn_provider(Slot(Some(0x01_0000_0010)));
+
+        let mut buf = [0u8; 127];
+        let len = frame
+            .try_write(
+                &mut buf,
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx),
+                ),
+            )
+            .unwrap();
+        // The auxiliary security header only contains the security control
+        assert_eq!(len, frame.header.get_octet_size() + 3 + 4);
+        assert_eq!(frame.header.get_octet_size(), 2 + 1 + 2 + 8 + 2 + 8 + 1);
+        // The frame counter is neither used nor incremented
+        assert_eq!(sec_ctx.frame_counter, FRAME_CTR);
+
+        let mut received = buf;
+        let device_desc = &mut device_descriptor(FRAME_CTR, false);
+        let (decoded, _) = Frame::try_read_and_unsecure(
+            &mut received[..len],
+            &mut FrameSerDesContext::new(FooterMode::None, Some(&mut sec_ctx)),
+            &mut BasicDevDescriptorLookup::new(device_desc),
+        )
+        .unwrap();
+        assert_eq!(decoded.payload, &[0x01, 0x02, 0x03]);
+        let aux_sec = decoded.header.auxiliary_security_header.unwrap();
+        assert!(aux_sec.control.frame_counter_suppression());
+        assert!(aux_sec.control.asn_in_nonce());
+        assert_eq!(aux_sec.get_frame_counter(), None);
+        assert_eq!(aux_sec.get_octet_size(), 1);
+        assert_eq!(device_desc.frame_counter, FRAME_CTR);
+
+        // A different ASN results in a different nonce
+        sec_ctx.asn_provider = Slot(Some(0x01_0000_0011));
+        let device_desc = &mut device_descriptor(FRAME_CTR, false);
+        assert!(Frame::try_read_and_unsecure(
+            &mut buf[..len],
+            &mut FrameSerDesContext::new(FooterMode::None, Some(&mut sec_ctx)),
+            &mut BasicDevDescriptorLookup::new(device_desc),
+        )
+        .is_err());
+
+        // Without an ASN, the frame cannot be secured
+        sec_ctx.asn_provider = Slot(None);
+        assert!(matches!(
+            security::secure_frame(frame, &mut sec_ctx, &mut buf, 0),
+            Err(SecurityError::InvalidSecContext)
+        ));
+
+        // Neither can it be if the nonce isn't built from the ASN
+        let control = control.with_asn_in_nonce(false);
+        let frame = get_frame(
+            Some(source),
+            Some(destination),
+            &[0x01, 0x02, 0x03],
+            Some(AuxiliarySecurityHeader::new(control, None)),
+        );
+        sec_ctx.asn_provider = Slot(Some(0x01_0000_0010));
+        assert!(matches!(
+            security::secure_frame(frame, &mut sec_ctx, &mut buf, 0),
+            Err(SecurityError::UnsupportedFrameCounterSuppression)
+        ));
+        assert_eq!(sec_ctx.frame_counter, FRAME_CTR);
+    }
+
+    #[test]
+    fn encode_decode_enhanced_ack() {
+        let (source_euid, source, destination) = get_rand_addrpair();
+        // A Time Correction Header IE, followed by the Header Termination 2 IE
+        let header_ies = [0x02, 0x1E, 0x34, 0x12, 0x00, 0x3F];
+        let mut frame = get_frame(
+            Some(source),
+            Some(destination),
+            &header_ies,
+            Some(AuxiliarySecurityHeader::new(
+                SecurityControl::new(SecurityLevel::ENCMIC64),
+                None,
+            )),
+        );
+        frame.header.frame_type = FrameType::Acknowledgement;
+        frame.header.ie_present = true;
+        frame.content = FrameContent::Acknowledgement;
+
+        let mut buf = [0u8; 127];
+        let mut sec_ctx = aes_sec_ctx(source_euid, FRAME_CTR);
+        let len = frame
+            .try_write(
+                &mut buf,
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx),
+                ),
+            )
+            .unwrap();
+
+        // The Header IEs are authenticated, but not encrypted
+        let ie_start = frame.header.get_octet_size();
+        assert_eq!(len, ie_start + header_ies.len() + 8);
+        assert_eq!(&buf[ie_start..ie_start + header_ies.len()], &header_ies);
+
+        let mut received = buf;
+        let device_desc = &mut device_descriptor(FRAME_CTR, false);
+        let (decoded, _) = Frame::try_read_and_unsecure(
+            &mut received[..len],
+            &mut FrameSerDesContext::new(FooterMode::None, Some(&mut sec_ctx)),
+            &mut BasicDevDescriptorLookup::new(device_desc),
+        )
+        .unwrap();
+        assert_eq!(decoded.content, FrameContent::Acknowledgement);
+        assert_eq!(decoded.payload, &header_ies);
+
+        // Tampering with the Header IEs is detected
+        let mut received = buf;
+        received[ie_start + 2] ^= 0x01;
+        let device_desc = &mut device_descriptor(FRAME_CTR, false);
+        assert!(matches!(
+            Frame::try_read_and_unsecure(
+                &mut received[..len],
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx)
+                ),
+                &mut BasicDevDescriptorLookup::new(device_desc),
+            ),
+            Err(SecurityError::MicMismatch)
+        ));
+
+        // An Enh-Ack without Header IEs has empty a-data
+        frame.payload = &[];
+        let len = frame
+            .try_write(
+                &mut buf,
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx),
+                ),
+            )
+            .unwrap();
+        let device_desc = &mut device_descriptor(FRAME_CTR, false);
+        let (decoded, _) = Frame::try_read_and_unsecure(
+            &mut buf[..len],
+            &mut FrameSerDesContext::new(FooterMode::None, Some(&mut sec_ctx)),
+            &mut BasicDevDescriptorLookup::new(device_desc),
+        )
+        .unwrap();
+        assert!(decoded.payload.is_empty());
+
+        // Imm-Acks cannot be secured
+        frame.header.version = FrameVersion::Ieee802154_2006;
+        assert!(matches!(
+            security::secure_frame(frame, &mut sec_ctx, &mut buf, 0),
+            Err(SecurityError::NotImplemented)
+        ));
+    }
+
+    #[test]
+    fn encode_decode_secured_beacon() {
+        use crate::mac::beacon::*;
+
+        let (source_euid, source, destination) = get_rand_addrpair();
+        let beacon = Beacon {
+            superframe_spec: SuperframeSpecification {
+                beacon_order: BeaconOrder::OnDemand,
+                superframe_order: SuperframeOrder::Inactive,
+                final_cap_slot: 15,
+                battery_life_extension: false,
+                pan_coordinator: true,
+                association_permit: true,
+            },
+            guaranteed_time_slot_info: GuaranteedTimeSlotInformation::new(),
+            pending_address: PendingAddress::new(),
+        };
+        let mut frame = get_frame(
+            Some(source),
+            Some(destination),
+            &[0xde, 0xf0],
+            Some(AuxiliarySecurityHeader::new(
+                SecurityControl::new(SecurityLevel::ENCMIC32),
+                None,
+            )),
+        );
+        frame.header.frame_type = FrameType::Beacon;
+        frame.header.version = FrameVersion::Ieee802154_2006;
+        frame.content = FrameContent::Beacon(beacon);
+
+        let mut buf = [0u8; 127];
+        let mut sec_ctx = aes_sec_ctx(source_euid, FRAME_CTR);
+        let len = frame
+            .try_write(
+                &mut buf,
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx),
+                ),
+            )
+            .unwrap();
+
+        // The beacon fields are open, the beacon payload is private
+        let content_start = frame.header.get_octet_size();
+        assert_eq!(len, content_start + 4 + 2 + 4);
+        assert_eq!(&buf[content_start..content_start + 4], &[0xff, 0xcf, 0, 0]);
+        assert_ne!(&buf[content_start + 4..content_start + 6], &[0xde, 0xf0]);
+
+        let device_desc = &mut device_descriptor(FRAME_CTR, false);
+        let (decoded, _) = Frame::try_read_and_unsecure(
+            &mut buf[..len],
+            &mut FrameSerDesContext::new(FooterMode::None, Some(&mut sec_ctx)),
+            &mut BasicDevDescriptorLookup::new(device_desc),
+        )
+        .unwrap();
+        assert_eq!(decoded.content, FrameContent::Beacon(beacon));
+        assert_eq!(decoded.payload, &[0xde, 0xf0]);
+    }
+
+    #[test]
+    fn encode_decode_secured_enhanced_beacon() {
+        use crate::mac::ie::IeWriter;
+
+        let (source_euid, source, destination) = get_rand_addrpair();
+        let mut ies = [0u8; 16];
+        let mut writer = IeWriter::new(&mut ies);
+        writer
+            .raw_header_ie(0x20, &[0xaa])
+            .unwrap()
+            .raw_payload_ie(0x2, &[0xbb])
+            .unwrap();
+        let ies_len = writer.finish(false).unwrap();
+
+        let mut frame = get_frame(
+            Some(source),
+            Some(destination),
+            &ies[..ies_len],
+            Some(AuxiliarySecurityHeader::new(
+                SecurityControl::new(SecurityLevel::ENCMIC32),
+                None,
+            )),
+        );
+        frame.header.frame_type = FrameType::Beacon;
+        frame.header.ie_present = true;
+        frame.content = FrameContent::EnhancedBeacon;
+
+        let mut buf = [0u8; 127];
+        let mut sec_ctx = aes_sec_ctx(source_euid, FRAME_CTR);
+        let len = frame
+            .try_write(
+                &mut buf,
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx),
+                ),
+            )
+            .unwrap();
+
+        // The header IEs are open, the payload IEs are private
+        let content_start = frame.header.get_octet_size();
+        assert_eq!(len, content_start + ies_len + 4);
+        assert_eq!(&buf[content_start..content_start + 5], &ies[..5]);
+        assert_ne!(&buf[content_start + 5..content_start + 8], &ies[5..8]);
+
+        let device_desc = &mut device_descriptor(FRAME_CTR, false);
+        let (decoded, _) = Frame::try_read_and_unsecure(
+            &mut buf[..len],
+            &mut FrameSerDesContext::new(FooterMode::None, Some(&mut sec_ctx)),
+            &mut BasicDevDescriptorLookup::new(device_desc),
+        )
+        .unwrap();
+        assert_eq!(decoded.content, FrameContent::EnhancedBeacon);
+        assert_eq!(decoded.payload, &ies[..ies_len]);
+    }
+
+    #[test]
+    fn header_ies_are_open() {
+        // A Time Correction IE and a header termination 2 IE, followed by a
+        // payload
+        let with_ht2 = [0x02, 0x0f, 0x01, 0x00, 0x80, 0x3f, 0x04, 0xaa];
+        // The header termination 1 IE, followed by an MLME IE and a payload
+        // termination IE
+        let with_ht1 = [0x00, 0x3f, 0x01, 0x88, 0xbb, 0x00, 0xf8, 0x04];
+
+        let mut header = get_frame(None, None, &[], None).header;
+        header.ie_present = true;
+        for frame_type in [
+            FrameType::Data,
+            FrameType::Acknowledgement,
+            FrameType::MacCommand,
+        ] {
+            header.frame_type = frame_type;
+            assert_eq!(open_payload_len(&header, &with_ht2), Ok(6));
+            assert_eq!(open_payload_len(&header, &with_ht1), Ok(2));
+            // Without termination IE, the header IEs end with the frame
+            assert_eq!(open_payload_len(&header, &with_ht2[..4]), Ok(4));
+        }
+
+        // Without IEs, only the Command ID of a command frame is open
+        header.ie_present = false;
+        header.frame_type = FrameType::MacCommand;
+        assert_eq!(open_payload_len(&header, &[0x04, 0xaa]), Ok(1));
+        header.frame_type = FrameType::Data;
+        assert_eq!(open_payload_len(&header, &[0x04, 0xaa]), Ok(0));
+        header.frame_type = FrameType::Acknowledgement;
+        assert_eq!(open_payload_len(&header, &[0x04, 0xaa]), Ok(0));
+    }
+
+    #[test]
+    fn encode_decode_secured_data_with_ies() {
+        use crate::mac::ie::IeWriter;
+
+        let (source_euid, source, destination) = get_rand_addrpair();
+        let mut payload = [0u8; 16];
+        let mut writer = IeWriter::new(&mut payload);
+        writer.raw_header_ie(0x20, &[0xaa]).unwrap();
+        let ies_len = writer.finish(true).unwrap();
+        payload[ies_len..ies_len + 3].copy_from_slice(&[1, 2, 3]);
+        let payload = &payload[..ies_len + 3];
+
+        let mut frame = get_frame(
+            Some(source),
+            Some(destination),
+            payload,
+            Some(AuxiliarySecurityHeader::new(
+                SecurityControl::new(SecurityLevel::ENCMIC32),
+                None,
+            )),
+        );
+        frame.header.ie_present = true;
+
+        let mut buf = [0u8; 127];
+        let mut sec_ctx = aes_sec_ctx(source_euid, FRAME_CTR);
+        let len = frame
+            .try_write(
+                &mut buf,
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx),
+                ),
+            )
+            .unwrap();
+
+        // The header IEs and the header termination IE are open, the data
+        // payload is private
+        let content_start = frame.header.get_octet_size();
+        assert_eq!(len, content_start + payload.len() + 4);
+        assert_eq!(
+            &buf[content_start..content_start + ies_len],
+            &payload[..ies_len]
+        );
+        assert_ne!(
+            &buf[content_start + ies_len..content_start + payload.len()],
+            &[1, 2, 3]
+        );
+
+        let device_desc = &mut device_descriptor(FRAME_CTR, false);
+        let (decoded, _) = Frame::try_read_and_unsecure(
+            &mut buf[..len],
+            &mut FrameSerDesContext::new(FooterMode::None, Some(&mut sec_ctx)),
+            &mut BasicDevDescriptorLookup::new(device_desc),
+        )
+        .unwrap();
+        assert_eq!(decoded.content, FrameContent::Data);
+        assert_eq!(decoded.payload, payload);
+    }
+
+    #[test]
+    fn encode_decode_secured_enhanced_ack() {
+        use crate::mac::ie::IeWriter;
+
+        let (source_euid, source, destination) = get_rand_addrpair();
+        let mut payload = [0u8; 16];
+        let mut writer = IeWriter::new(&mut payload);
+        writer
+            .raw_header_ie(0x20, &[0xaa])
+            .unwrap()
+            .raw_payload_ie(0x2, &[0xbb])
+            .unwrap();
+        let ies_len = writer.finish(true).unwrap();
+        payload[ies_len..ies_len + 2].copy_from_slice(&[0xde, 0xf0]);
+        let payload = &payload[..ies_len + 2];
+
+        let mut frame = get_frame(
+            Some(source),
+            Some(destination),
+            payload,
+            Some(AuxiliarySecurityHeader::new(
+                SecurityControl::new(SecurityLevel::ENCMIC32),
+                None,
+            )),
+        );
+        frame.header.frame_type = FrameType::Acknowledgement;
+        frame.header.ie_present = true;
+        frame.content = FrameContent::Acknowledgement;
+
+        let mut buf = [0u8; 127];
+        let mut sec_ctx = aes_sec_ctx(source_euid, FRAME_CTR);
+        let len = frame
+            .try_write(
+                &mut buf,
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx),
+                ),
+            )
+            .unwrap();
+
+        // The header IE and the header termination 1 IE are open, the
+        // payload IEs and the payload are private
+        let content_start = frame.header.get_octet_size();
+        assert_eq!(len, content_start + payload.len() + 4);
+        assert_eq!(&buf[content_start..content_start + 5], &payload[..5]);
+        assert_ne!(
+            &buf[content_start + 5..content_start + payload.len()],
+            &payload[5..]
+        );
+
+        let device_desc = &mut device_descriptor(FRAME_CTR, false);
+        let (decoded, _) = Frame::try_read_and_unsecure(
+            &mut buf[..len],
+            &mut FrameSerDesContext::new(FooterMode::None, Some(&mut sec_ctx)),
+            &mut BasicDevDescriptorLookup::new(device_desc),
+        )
+        .unwrap();
+        assert_eq!(decoded.content, FrameContent::Acknowledgement);
+        assert_eq!(decoded.payload, payload);
+    }
+
+    #[test]
+    fn encode_decode_secured_command() {
+        use crate::mac::command::*;
+
+        let (source_euid, source, destination) = get_rand_addrpair();
+        let command = Command::AssociationResponse(
+            ShortAddress(0x1234),
+            AssociationStatus::Successful,
+        );
+        let mut frame = get_frame(
+            Some(source),
+            Some(destination),
+            &[],
+            Some(AuxiliarySecurityHeader::new(
+                SecurityControl::new(SecurityLevel::ENCMIC32),
+                None,
+            )),
+        );
+        frame.header.frame_type = FrameType::MacCommand;
+        frame.content = FrameContent::Command(command);
+
+        let mut buf = [0u8; 127];
+        let mut sec_ctx = aes_sec_ctx(source_euid, FRAME_CTR);
+        let len = frame
+            .try_write(
+                &mut buf,
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx),
+                ),
+            )
+            .unwrap();
+
+        // The Command Frame Identifier is open, the command payload private
+        let content_start = frame.header.get_octet_size();
+        assert_eq!(len, content_start + 1 + 3 + 4);
+        assert_eq!(buf[content_start], 0x02);
+        assert_ne!(
+            &buf[content_start + 1..content_start + 4],
+            &[0x34, 0x12, 0]
+        );
+
+        let device_desc = &mut device_descriptor(FRAME_CTR, false);
+        let (decoded, _) = Frame::try_read_and_unsecure(
+            &mut buf[..len],
+            &mut FrameSerDesContext::new(FooterMode::None, Some(&mut sec_ctx)),
+            &mut BasicDevDescriptorLookup::new(device_desc),
+        )
+        .unwrap();
+        assert_eq!(decoded.content, FrameContent::Command(command));
+        assert!(decoded.payload.is_empty());
+    }
+
+    #[test]
+    fn decode_secured_command_with_ies_security_level() {
+        use crate::mac::command::*;
+
+        let source_euid = 0x08;
+        let (source, destination) = (
+            Address::Extended(PanId(0x111), ExtendedAddress(source_euid)),
+            Address::Extended(PanId(0x2222), ExtendedAddress(0x09)),
+        );
+        // A Time Correction IE and a header termination 2 IE, followed by a
+        // data request
+        let payload = [0x02, 0x0f, 0x01, 0x00, 0x80, 0x3f, 0x04];
+        let data_request_table = &[SecurityLevelDescriptor {
+            frame_type: FrameType::MacCommand,
+            command_id: Some(CommandId::DataRequest),
+            security_minimum: SecurityLevel::ENCMIC64,
+            device_override_security_minimum: false,
+        }];
+        let beacon_request_table = &[SecurityLevelDescriptor {
+            frame_type: FrameType::MacCommand,
+            command_id: Some(CommandId::BeaconRequest),
+            security_minimum: SecurityLevel::ENCMIC64,
+            device_override_security_minimum: false,
+        }];
+
+        // The Command ID follows the IEs, whether it is open or private
+        for level in [SecurityLevel::MIC32, SecurityLevel::ENCMIC32] {
+            let mut frame = get_frame(
+                Some(source),
+                Some(destination),
+                &payload,
+                Some(AuxiliarySecurityHeader::new(
+                    SecurityControl::new(level),
+                    None,
+                )),
+            );
+            frame.header.frame_type = FrameType::MacCommand;
+            frame.header.ie_present = true;
+            frame.content = FrameContent::Command(Command::DataRequest);
+
+            let mut buf = [0u8; 127];
+            let mut sec_ctx = aes_sec_ctx(source_euid, FRAME_CTR);
+            let len = frame
+                .try_write(
+                    &mut buf,
+                    &mut FrameSerDesContext::new(
+                        FooterMode::None,
+                        Some(&mut sec_ctx),
+                    ),
+                )
+                .unwrap();
+
+            sec_ctx.security_level_table = data_request_table;
+            let device_desc = &mut device_descriptor(FRAME_CTR, false);
+            let mut rejected = buf;
+            let result = Frame::try_read_and_unsecure(
+                &mut rejected[..len],
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx),
+                ),
+                &mut BasicDevDescriptorLookup::new(device_desc),
+            );
+            assert!(matches!(
+                result,
+                Err(SecurityError::ImproperSecurityLevel)
+            ));
+            assert_eq!(device_desc.frame_counter, FRAME_CTR);
+
+            // Descriptors for other commands do not apply
+            sec_ctx.security_level_table = beacon_request_table;
+            let (decoded, _) = Frame::try_read_and_unsecure(
+                &mut buf[..len],
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx),
+                ),
+                &mut BasicDevDescriptorLookup::new(device_desc),
+            )
+            .unwrap();
+            assert_eq!(
+                decoded.content,
+                FrameContent::Command(Command::DataRequest)
+            );
+            assert_eq!(decoded.payload, &payload);
+            assert_eq!(device_desc.frame_counter, FRAME_CTR + 1);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "soft-crypto")]
+    fn encode_decode_aes128_security_context() {
+        let (source_euid, source, destination) = get_rand_addrpair();
+        let frame = get_frame(
+            Some(source),
+            Some(destination),
+            &[1, 2, 3],
+            Some(AuxiliarySecurityHeader::new(
+                SecurityControl::new(SecurityLevel::ENCMIC32),
+                None,
+            )),
+        );
+
+        let mut sec_ctx: Aes128SecurityContext<_> = Aes128SecurityContext::new(
+            source_euid,
+            FRAME_CTR,
+            STATIC_KEY_LOOKUP,
+        );
+        let mut buf = [0u8; 127];
+        let len = frame
+            .try_write(
+                &mut buf,
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx),
+                ),
+            )
+            .unwrap();
+
+        // The alias is interchangeable with an explicitly typed context
+        let mut aes_ctx = aes_sec_ctx(source_euid, FRAME_CTR);
+        let device_desc = &mut device_descriptor(FRAME_CTR, false);
+        let (decoded, _) = Frame::try_read_and_unsecure(
+            &mut buf[..len],
+            &mut FrameSerDesContext::new(FooterMode::None, Some(&mut aes_ctx)),
+            &mut BasicDevDescriptorLookup::new(device_desc),
+        )
+        .unwrap();
+        assert_eq!(decoded.payload, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn security_statistics() {
+        let (source_euid, source, destination) = get_rand_addrpair();
+        let aux_sec_header = Some(AuxiliarySecurityHeader::new(
+            SecurityControl::new(SecurityLevel::ENCMIC32),
+            None,
+        ));
+        let frame = get_frame(
+            Some(source),
+            Some(destination),
+            &[1, 2, 3],
+            aux_sec_header,
+        );
+
+        let mut sec_ctx = aes_sec_ctx(source_euid, FRAME_CTR);
+        let mut secured = [0u8; 127];
+        let len = frame
+            .try_write(
+                &mut secured,
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx),
+                ),
+            )
+            .unwrap();
+        let secured = &secured[..len];
+
+        // No key for a short destination address
+        let short_destination =
+            Address::Short(PanId(0x2222), ShortAddress(0x1234));
+        let frame = get_frame(
+            Some(source),
+            Some(short_destination),
+            &[1, 2, 3],
+            aux_sec_header,
+        );
+        assert!(frame
+            .try_write(
+                &mut [0u8; 127],
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx),
+                ),
+            )
+            .is_err());
+
+        assert_eq!(
+            sec_ctx.statistics,
+            SecurityStatistics {
+                frames_secured: 1,
+                key_lookup_failures: 1,
+                ..SecurityStatistics::new()
+            }
+        );
+
+        let mut rx_ctx = aes_sec_ctx(source_euid, FRAME_CTR);
+        let device_desc = &mut device_descriptor(FRAME_CTR, false);
+        let mut read = |frame: &[u8], rx_ctx: &mut SecurityContext<_, _>| {
+            let mut buf = [0u8; 127];
+            buf[..frame.len()].copy_from_slice(frame);
+            Frame::try_read_and_unsecure(
+                &mut buf[..frame.len()],
+                &mut FrameSerDesContext::new(FooterMode::None, Some(rx_ctx)),
+                &mut BasicDevDescriptorLookup::new(device_desc),
+            )
+            .map(|_| ())
+        };
+
+        read(secured, &mut rx_ctx).unwrap();
+        assert!(matches!(
+            read(secured, &mut rx_ctx),
+            Err(SecurityError::ReplayedFrame)
+        ));
+
+        let mut tampered = [0u8; 127];
+        tampered[..len].copy_from_slice(secured);
+        tampered[len - 1] ^= 0xFF;
+        let mut fresh_ctx = aes_sec_ctx(source_euid, FRAME_CTR);
+        let device_desc = &mut device_descriptor(FRAME_CTR, false);
+        assert!(matches!(
+            Frame::try_read_and_unsecure(
+                &mut tampered[..len],
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut fresh_ctx),
+                ),
+                &mut BasicDevDescriptorLookup::new(device_desc),
+            ),
+            Err(SecurityError::MicMismatch)
+        ));
+
+        assert_eq!(
+            rx_ctx.statistics,
+            SecurityStatistics {
+                frames_unsecured: 1,
+                replayed_frames: 1,
+                ..SecurityStatistics::new()
+            }
+        );
+        assert_eq!(fresh_ctx.statistics.mic_failures, 1);
+
+        rx_ctx.statistics.reset();
+        assert_eq!(rx_ctx.statistics, SecurityStatistics::default());
+    }
+
+    #[test]
+    fn decode_unsecured_metadata() {
+        let (source_euid, source, destination) = get_rand_addrpair();
+        let key_identifier = Some(KeyIdentifier {
+            key_source: Some(KeySource::Short(0x0102_0304)),
+            key_index: 5,
+        });
+        let frame = get_frame(
+            Some(source),
+            Some(destination),
+            &[1, 2, 3],
+            Some(AuxiliarySecurityHeader::new(
+                SecurityControl::new(SecurityLevel::MIC64),
+                key_identifier,
+            )),
+        );
+
+        let mut sec_ctx = aes_sec_ctx(source_euid, FRAME_CTR);
+        let mut buf = [0u8; 127];
+        let len = frame
+            .try_write(
+                &mut buf,
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx),
+                ),
+            )
+            .unwrap();
+
+        let device_desc = &mut device_descriptor(FRAME_CTR, false);
+        let (decoded, metadata, _) =
+            Frame::try_read_and_unsecure_with_metadata(
+                &mut buf[..len],
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx),
+                ),
+                &mut BasicDevDescriptorLookup::new(device_desc),
+            )
+            .unwrap();
+        assert_eq!(decoded.payload, &[1, 2, 3]);
+        assert_eq!(
+            metadata,
+            Some(UnsecuredMetadata {
+                source_euid,
+                security_level: SecurityLevel::MIC64,
+                key_identifier,
+                key_id_mode: KeyIdentifierMode::KeySource4,
+                frame_counter: Some(FRAME_CTR),
+            })
+        );
+
+        // Frames without security have no metadata
+        let frame =
+            get_frame(Some(source), Some(destination), &[1, 2, 3], None);
+        let len = frame
+            .try_write(
+                &mut buf,
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx),
+                ),
+            )
+            .unwrap();
+        let (_, metadata, _) = Frame::try_read_and_unsecure_with_metadata(
+            &mut buf[..len],
+            &mut FrameSerDesContext::new(FooterMode::None, Some(&mut sec_ctx)),
+            &mut BasicDevDescriptorLookup::new(device_desc),
+        )
+        .unwrap();
+        assert_eq!(metadata, None);
+    }
+
+    #[test]
+    fn encode_decode_nonce_generator() {
+        /// Inverts the source address, unlike the standard
+        struct InvertedNonce;
+
+        impl NonceGenerator for InvertedNonce {
+            fn frame_counter_nonce(
+                &self,
+                source: u64,
+                frame_counter: u32,
+                security_level: SecurityLevel,
+            ) -> [u8; 13] {
+                calculate_nonce(!source, frame_counter, security_level)
+            }
+        }
+
+        let (source_euid, source, destination) = get_rand_addrpair();
+        let frame = get_frame(
+            Some(source),
+            Some(destination),
+            &[1, 2, 3],
+            Some(AuxiliarySecurityHeader::new(
+                SecurityControl::new(SecurityLevel::ENCMIC32),
+                None,
+            )),
+        );
+
+        let mut sec_ctx = aes_sec_ctx(source_euid, FRAME_CTR)
+            .with_nonce_generator(InvertedNonce);
+        let mut buf = [0u8; 127];
+        let len = frame
+            .try_write(
+                &mut buf,
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx),
+                ),
+            )
+            .unwrap();
+        let mut received = buf;
+
+        // The standard nonce does not match
+        let mut standard_ctx = aes_sec_ctx(source_euid, FRAME_CTR);
+        let device_desc = &mut device_descriptor(FRAME_CTR, false);
+        assert!(matches!(
+            Frame::try_read_and_unsecure(
+                &mut received[..len],
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut standard_ctx),
+                ),
+                &mut BasicDevDescriptorLookup::new(device_desc),
+            ),
+            Err(SecurityError::MicMismatch)
+        ));
+
+        let (decoded, _) = Frame::try_read_and_unsecure(
+            &mut buf[..len],
+            &mut FrameSerDesContext::new(FooterMode::None, Some(&mut sec_ctx)),
+            &mut BasicDevDescriptorLookup::new(device_desc),
+        )
+        .unwrap();
+        assert_eq!(decoded.payload, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn encode_secured_length() {
+        let (source_euid, source, destination) = get_rand_addrpair();
+        let frame = get_frame(
+            Some(source),
+            Some(destination),
+            &[1, 2, 3],
+            Some(AuxiliarySecurityHeader::new(
+                SecurityControl::new(SecurityLevel::ENCMIC64),
+                None,
+            )),
+        );
+
+        let mut sec_ctx = aes_sec_ctx(source_euid, FRAME_CTR);
+        let mut buf = [0u8; 127];
+        let length = frame
+            .try_write_and_secure(
+                &mut buf,
+                &mut FrameSerDesContext::new(
+                    FooterMode::Explicit,
+                    Some(&mut sec_ctx),
+                ),
+            )
+            .unwrap();
+        assert_eq!(length.mic_len, 8);
+        assert_eq!(length.mpdu_len, length.len);
+
+        let mut sec_ctx = aes_sec_ctx(source_euid, FRAME_CTR);
+        let len = frame
+            .try_write(
+                &mut buf,
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx),
+                ),
+            )
+            .unwrap();
+        assert_eq!(len + 2, length.len);
+    }
+
+    #[test]
+    fn decode_blacklists_exhausted_device() {
+        let (source_euid, source, destination) = get_rand_addrpair();
+        let frame = get_frame(
+            Some(source),
+            Some(destination),
+            &[1, 2, 3],
+            Some(AuxiliarySecurityHeader::new(
+                SecurityControl::new(SecurityLevel::ENCMIC32),
+                None,
+            )),
+        );
+
+        let mut sec_ctx = aes_sec_ctx(source_euid, 0xFFFF_FFFE);
+        let mut buf = [0u8; 127];
+        let len = frame
+            .try_write(
+                &mut buf,
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx),
+                ),
+            )
+            .unwrap();
+
+        let mut storage = [None; 1];
+        let mut devices = DeviceTable::new(&mut storage);
+        devices
+            .insert(DeviceTableEntry {
+                key_identifier: None,
+                descriptor: DeviceDescriptor {
+                    pan_id: PanId(0x111),
+                    short_address: ShortAddress(0xfffe),
+                    extended_address: ExtendedAddress(source_euid),
+                    frame_counter: 0,
+                    exempt: false,
+                },
+                blacklisted: false,
+            })
+            .unwrap();
+
+        let mut rx_ctx = aes_sec_ctx(source_euid, FRAME_CTR);
+        Frame::try_read_and_unsecure(
+            &mut buf[..len],
+            &mut FrameSerDesContext::new(FooterMode::None, Some(&mut rx_ctx)),
+            &mut devices,
+        )
+        .unwrap();
+
+        assert!(devices.iter().all(|entry| entry.blacklisted));
+        assert!(devices
+            .lookup_device(AddressingMode::SrcAddrMode, source, None)
+            .is_none());
+    }
+
+    #[test]
+    fn encode_decode_any_security_context() {
+        /// Secures a frame without being generic over the security context
+        fn write_frame(
+            frame: Frame,
+            buf: &mut [u8],
+            ctx: &mut AnyFrameSerDesContext,
+        ) -> usize {
+            frame.try_write(buf, ctx).unwrap()
+        }
+
+        let (source_euid, source, destination) = get_rand_addrpair();
+        let frame = get_frame(
+            Some(source),
+            Some(destination),
+            &[1, 2, 3],
+            Some(AuxiliarySecurityHeader::new(
+                SecurityControl::new(SecurityLevel::ENCMIC64),
+                None,
+            )),
+        );
+
+        let mut sec_ctx = aes_sec_ctx(source_euid, FRAME_CTR);
+        let mut buf = [0u8; 127];
+        let len = write_frame(
+            frame,
+            &mut buf,
+            &mut FrameSerDesContext::new(
+                FooterMode::None,
+                Some(&mut sec_ctx.as_any()),
+            ),
+        );
+        assert_eq!(sec_ctx.frame_counter, FRAME_CTR + 1);
+
+        let device_desc = &mut device_descriptor(FRAME_CTR, false);
+        let dev_desc_lo: &mut dyn DeviceDescriptorLookup =
+            &mut BasicDevDescriptorLookup::new(device_desc);
+        let mut any_ctx = sec_ctx.as_any();
+        let (decoded, _) = Frame::try_read_and_unsecure(
+            &mut buf[..len],
+            &mut FrameSerDesContext::new(FooterMode::None, Some(&mut any_ctx)),
+            dev_desc_lo,
+        )
+        .unwrap();
+        assert_eq!(decoded.payload, &[1, 2, 3]);
+        assert_eq!(device_desc.frame_counter, FRAME_CTR + 1);
+
+        // Both frames are counted in the statistics of the borrowed context
+        assert_eq!(sec_ctx.statistics.frames_secured, 1);
+        assert_eq!(sec_ctx.statistics.frames_unsecured, 1);
+    }
+
+    #[test]
+    fn encode_decode_encryption_only() {
+        let (source_euid, source, destination) = get_rand_addrpair();
+        let payload = [0x11; 20];
+        let frame = get_frame(
+            Some(source),
+            Some(destination),
+            &payload,
+            Some(AuxiliarySecurityHeader::new(
+                SecurityControl::new(SecurityLevel::ENC),
+                None,
+            )),
+        );
+
+        let mut buf = [0u8; 127];
+        let mut sec_ctx = aes_sec_ctx(source_euid, FRAME_CTR);
+        assert!(frame
+            .try_write(
+                &mut buf,
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx),
+                ),
+            )
+            .is_err());
+        // Rejected frames do not use up a frame counter value
+        assert_eq!(sec_ctx.frame_counter, FRAME_CTR);
+
+        sec_ctx.allow_encryption_only = true;
+        let len = frame
+            .try_write(
+                &mut buf,
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx),
+                ),
+            )
+            .unwrap();
+        // The payload is encrypted, and there is no MIC
+        let header_len = frame.header.get_octet_size();
+        assert_eq!(len, header_len + payload.len());
+        assert_ne!(&buf[header_len..len], &payload);
+
+        let received = buf;
+        sec_ctx.allow_encryption_only = false;
+        let device_desc = &mut device_descriptor(FRAME_CTR, false);
+        assert!(matches!(
+            Frame::try_read_and_unsecure(
+                &mut buf[..len],
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx)
+                ),
+                &mut BasicDevDescriptorLookup::new(device_desc),
+            ),
+            Err(SecurityError::UnsupportedSecurityLevel)
+        ));
+
+        let mut buf = received;
+        sec_ctx.allow_encryption_only = true;
+        let (decoded, _) = Frame::try_read_and_unsecure(
+            &mut buf[..len],
+            &mut FrameSerDesContext::new(FooterMode::None, Some(&mut sec_ctx)),
+            &mut BasicDevDescriptorLookup::new(device_desc),
+        )
+        .unwrap();
+        assert_eq!(decoded.payload, &payload);
+    }
+
+    #[test]
+    fn encode_security_policy() {
+        let (source_euid, source, destination) = get_rand_addrpair();
+        let key_identifier = Some(KeyIdentifier {
+            key_source: None,
+            key_index: 7,
+        });
+        let mut sec_ctx = aes_sec_ctx(source_euid, FRAME_CTR);
+        sec_ctx.security_policy.data =
+            SecurityParameters::new(SecurityLevel::ENCMIC64, key_identifier);
+
+        // The policy selects the auxiliary security header of data frames
+        let frame = get_frame(Some(source), Some(destination), &[1, 2], None);
+        let mut buf = [0u8; 127];
+        let len = frame
+            .try_write(
+                &mut buf,
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx),
+                ),
+            )
+            .unwrap();
+        assert_eq!(sec_ctx.frame_counter, FRAME_CTR + 1);
+
+        let device_desc = &mut device_descriptor(FRAME_CTR, false);
+        let (decoded, _) = Frame::try_read_and_unsecure(
+            &mut buf[..len],
+            &mut FrameSerDesContext::new(FooterMode::None, Some(&mut sec_ctx)),
+            &mut BasicDevDescriptorLookup::new(device_desc),
+        )
+        .unwrap();
+        let aux_sec = decoded.header.auxiliary_security_header.unwrap();
+        assert_eq!(aux_sec.control.security_level, SecurityLevel::ENCMIC64);
+        assert_eq!(aux_sec.key_identifier, key_identifier);
+        assert_eq!(decoded.payload, &[1, 2]);
+
+        // An explicit auxiliary security header takes precedence
+        let frame = get_frame(
+            Some(source),
+            Some(destination),
+            &[1, 2],
+            Some(AuxiliarySecurityHeader::new(
+                SecurityControl::new(SecurityLevel::MIC32),
+                None,
+            )),
+        );
+        let len = frame
+            .try_write(
+                &mut buf,
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx),
+                ),
+            )
+            .unwrap();
+        let (header, _) = Header::try_read(&buf[..len], ()).unwrap();
+        let aux_sec = header.auxiliary_security_header.unwrap();
+        assert_eq!(aux_sec.control.security_level, SecurityLevel::MIC32);
+
+        // Frame types without security in the policy are not secured
+        let mut frame = get_frame(Some(source), Some(destination), &[], None);
+        frame.header.frame_type = FrameType::Acknowledgement;
+        frame.content = FrameContent::Acknowledgement;
+        let len = frame
+            .try_write(
+                &mut buf,
+                &mut FrameSerDesContext::new(
+                    FooterMode::None,
+                    Some(&mut sec_ctx),
+                ),
+            )
+            .unwrap();
+        let (header, _) = Header::try_read(&buf[..len], ()).unwrap();
+        assert!(!header.has_security());
     }
 }
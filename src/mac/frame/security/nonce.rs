@@ -0,0 +1,89 @@
+//! Generation of the CCM* nonce of frames
+
+use super::{calculate_asn_nonce, calculate_nonce, SecurityLevel};
+
+/// Generates the CCM* nonce that frames are secured with
+///
+/// The default implementations of the methods generate the nonces that are
+/// specified by the standard. Override them to interoperate with stacks that
+/// deviate from it.
+///
+/// [`StandardNonce`] uses the default implementations, and is what a
+/// [`SecurityContext`] uses unless [`SecurityContext::with_nonce_generator`]
+/// is called.
+///
+/// # Example
+///
+/// ``` rust
+/// use ieee802154::mac::frame::security::{NonceGenerator, SecurityLevel};
+///
+/// /// Puts the frame counter in little endian, unlike the standard
+/// struct LittleEndianNonce;
+///
+/// impl NonceGenerator for LittleEndianNonce {
+///     fn frame_counter_nonce(
+///         &self,
+///         source: u64,
+///         frame_counter: u32,
+///         security_level: SecurityLevel,
+///     ) -> [u8; 13] {
+///         let mut nonce = [0u8; 13];
+///         nonce[..8].copy_from_slice(&source.to_be_bytes());
+///         nonce[8..12].copy_from_slice(&frame_counter.to_le_bytes());
+///         nonce[12] = security_level as u8;
+///         nonce
+///     }
+/// }
+///
+/// let nonce = LittleEndianNonce.frame_counter_nonce(1, 2, SecurityLevel::MIC32);
+/// assert_eq!(nonce, [0, 0, 0, 0, 0, 0, 0, 1, 2, 0, 0, 0, 1]);
+/// ```
+///
+/// [`SecurityContext`]: super::SecurityContext
+/// [`SecurityContext::with_nonce_generator`]: super::SecurityContext::with_nonce_generator
+pub trait NonceGenerator {
+    /// Generate the nonce of a frame that is secured with a frame counter
+    ///
+    /// By default, this is the nonce of 9.3.2.1 of 802.15.4-2015: the
+    /// extended source address and the frame counter, both in big endian,
+    /// followed by the security level.
+    fn frame_counter_nonce(
+        &self,
+        source: u64,
+        frame_counter: u32,
+        security_level: SecurityLevel,
+    ) -> [u8; 13] {
+        calculate_nonce(source, frame_counter, security_level)
+    }
+
+    /// Generate the nonce of a frame that is secured with the Absolute Slot
+    /// Number of a TSCH network
+    ///
+    /// By default, this is the nonce of 9.3.2.2 of 802.15.4-2015: the
+    /// extended source address and the lower 5 octets of the ASN, both in big
+    /// endian.
+    fn asn_nonce(&self, source: u64, asn: u64) -> [u8; 13] {
+        calculate_asn_nonce(source, asn)
+    }
+}
+
+impl<T: NonceGenerator + ?Sized> NonceGenerator for &T {
+    fn frame_counter_nonce(
+        &self,
+        source: u64,
+        frame_counter: u32,
+        security_level: SecurityLevel,
+    ) -> [u8; 13] {
+        (**self).frame_counter_nonce(source, frame_counter, security_level)
+    }
+
+    fn asn_nonce(&self, source: u64, asn: u64) -> [u8; 13] {
+        (**self).asn_nonce(source, asn)
+    }
+}
+
+/// Generates the nonces that are specified by the standard
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct StandardNonce;
+
+impl NonceGenerator for StandardNonce {}
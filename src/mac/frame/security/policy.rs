@@ -0,0 +1,149 @@
+//! Selection of the security parameters of outgoing frames
+
+use super::{
+    AuxiliarySecurityHeader, KeyIdentifier, SecurityControl, SecurityLevel,
+};
+use crate::mac::FrameType;
+
+/// The security parameters that outgoing frames are secured with
+///
+/// The key identifier mode follows from `key_identifier`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SecurityParameters {
+    /// The security level of outgoing frames
+    pub security_level: SecurityLevel,
+    /// The key identifier of outgoing frames, or `None` if the key is
+    /// determined implicitly
+    pub key_identifier: Option<KeyIdentifier>,
+}
+
+impl SecurityParameters {
+    /// Parameters that leave outgoing frames unsecured
+    pub const NONE: Self = Self::new(SecurityLevel::None, None);
+
+    /// Create new security parameters
+    pub const fn new(
+        security_level: SecurityLevel,
+        key_identifier: Option<KeyIdentifier>,
+    ) -> Self {
+        Self {
+            security_level,
+            key_identifier,
+        }
+    }
+
+    /// Get the auxiliary security header that outgoing frames are secured
+    /// with, or `None` if they are not secured
+    pub fn auxiliary_security_header(&self) -> Option<AuxiliarySecurityHeader> {
+        if self.security_level == SecurityLevel::None {
+            None
+        } else {
+            Some(AuxiliarySecurityHeader::new(
+                SecurityControl::new(self.security_level),
+                self.key_identifier,
+            ))
+        }
+    }
+}
+
+/// The security parameters of outgoing frames, per frame type
+///
+/// This plays the role of the macAutoRequestSecurityLevel,
+/// macAutoRequestKeyIdMode, macAutoRequestKeySource and
+/// macAutoRequestKeyIndex attributes of the MAC PIB, for all frame types.
+///
+/// When a frame without an auxiliary security header is written with a
+/// [`SecurityContext`], the header that this policy selects for its frame
+/// type is added, and the frame is secured accordingly. Frames that already
+/// have an auxiliary security header are written as they are.
+///
+/// # Example
+///
+/// ``` rust
+/// use ieee802154::mac::{
+///     frame::security::{
+///         AuxiliarySecurityHeader, KeyIdentifier, SecurityControl,
+///         SecurityLevel, SecurityParameters, SecurityPolicy,
+///     },
+///     FrameType,
+/// };
+///
+/// let key_identifier = Some(KeyIdentifier {
+///     key_source: None,
+///     key_index: 1,
+/// });
+/// let mut policy = SecurityPolicy::uniform(SecurityParameters::new(
+///     SecurityLevel::ENCMIC32,
+///     key_identifier,
+/// ));
+/// policy.beacon = SecurityParameters::NONE;
+///
+/// assert_eq!(policy.auxiliary_security_header(FrameType::Beacon), None);
+/// assert_eq!(
+///     policy.auxiliary_security_header(FrameType::Data),
+///     Some(AuxiliarySecurityHeader::new(
+///         SecurityControl::new(SecurityLevel::ENCMIC32),
+///         key_identifier,
+///     ))
+/// );
+/// ```
+///
+/// [`SecurityContext`]: super::SecurityContext
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SecurityPolicy {
+    /// The security parameters of beacon frames
+    pub beacon: SecurityParameters,
+    /// The security parameters of data frames
+    pub data: SecurityParameters,
+    /// The security parameters of acknowledgement frames
+    ///
+    /// Only Enhanced Acknowledgements can be secured, so this does not apply
+    /// to acknowledgements of older frame versions.
+    pub acknowledgement: SecurityParameters,
+    /// The security parameters of MAC command frames
+    pub mac_command: SecurityParameters,
+}
+
+impl SecurityPolicy {
+    /// A policy that leaves all outgoing frames unsecured
+    pub const NONE: Self = Self::uniform(SecurityParameters::NONE);
+
+    /// Create a policy that uses `parameters` for all frame types
+    pub const fn uniform(parameters: SecurityParameters) -> Self {
+        Self {
+            beacon: parameters,
+            data: parameters,
+            acknowledgement: parameters,
+            mac_command: parameters,
+        }
+    }
+
+    /// Get the security parameters of frames of type `frame_type`
+    pub fn parameters(&self, frame_type: FrameType) -> SecurityParameters {
+        match frame_type {
+            FrameType::Beacon => self.beacon,
+            FrameType::Data => self.data,
+            FrameType::Acknowledgement => self.acknowledgement,
+            FrameType::MacCommand => self.mac_command,
+        }
+    }
+
+    /// Get the auxiliary security header that frames of type `frame_type`
+    /// are secured with, or `None` if they are not secured
+    pub fn auxiliary_security_header(
+        &self,
+        frame_type: FrameType,
+    ) -> Option<AuxiliarySecurityHeader> {
+        self.parameters(frame_type).auxiliary_security_header()
+    }
+}
+
+impl Default for SecurityPolicy {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
@@ -7,6 +7,9 @@ use byte::{BytesExt, TryRead, TryWrite, LE};
 ///
 /// Contains fields describing the security applied to the incoming frame
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct SecurityControl {
     /// The security level applied to the incoming frame
     pub(crate) security_level: SecurityLevel,
@@ -15,6 +18,12 @@ pub struct SecurityControl {
     /// This field is set/overwritten when the AuxiliarySecurityHeader that contains this
     /// SecurityControl is written, based on the [super::KeyIdentifier] that it contains
     pub(crate) key_id_mode: KeyIdentifierMode,
+    /// Whether the frame counter is omitted from the auxiliary security
+    /// header
+    pub(crate) frame_counter_suppression: bool,
+    /// Whether the nonce is built from the Absolute Slot Number (ASN) instead
+    /// of the frame counter
+    pub(crate) asn_in_nonce: bool,
 }
 
 impl SecurityControl {
@@ -25,8 +34,39 @@ impl SecurityControl {
         Self {
             security_level,
             key_id_mode: KeyIdentifierMode::None,
+            frame_counter_suppression: false,
+            asn_in_nonce: false,
         }
     }
+
+    /// Set whether the frame counter is omitted from the auxiliary security
+    /// header
+    ///
+    /// This is used in TSCH networks, together with
+    /// [`SecurityControl::with_asn_in_nonce`].
+    pub fn with_frame_counter_suppression(mut self, suppress: bool) -> Self {
+        self.frame_counter_suppression = suppress;
+        self
+    }
+
+    /// Set whether the nonce is built from the Absolute Slot Number (ASN) of
+    /// TSCH networks instead of the frame counter
+    pub fn with_asn_in_nonce(mut self, asn_in_nonce: bool) -> Self {
+        self.asn_in_nonce = asn_in_nonce;
+        self
+    }
+
+    /// Whether the frame counter is omitted from the auxiliary security
+    /// header
+    pub fn frame_counter_suppression(&self) -> bool {
+        self.frame_counter_suppression
+    }
+
+    /// Whether the nonce is built from the Absolute Slot Number (ASN) instead
+    /// of the frame counter
+    pub fn asn_in_nonce(&self) -> bool {
+        self.asn_in_nonce
+    }
 }
 
 impl TryRead<'_> for SecurityControl {
@@ -47,6 +87,9 @@ impl TryRead<'_> for SecurityControl {
         let control = SecurityControl {
             security_level,
             key_id_mode,
+            frame_counter_suppression: bits & mask::FRAME_COUNTER_SUPPRESSION
+                != 0,
+            asn_in_nonce: bits & mask::ASN_IN_NONCE != 0,
         };
 
         Ok((control, *offset))
@@ -57,7 +100,10 @@ impl TryWrite for SecurityControl {
     fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
         let offset = &mut 0;
         let bits = (self.security_level.to_bits() << offset::SECURITY_LEVEL)
-            | (self.key_id_mode.to_bits() << offset::KEY_IDENTIFIER_MODE);
+            | (self.key_id_mode.to_bits() << offset::KEY_IDENTIFIER_MODE)
+            | ((self.frame_counter_suppression as u8)
+                << offset::FRAME_COUNTER_SUPPRESSION)
+            | ((self.asn_in_nonce as u8) << offset::ASN_IN_NONCE);
         bytes.write(offset, bits)?;
         Ok(*offset)
     }
@@ -65,6 +111,9 @@ impl TryWrite for SecurityControl {
 
 /// The level of security applied to the payload
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum SecurityLevel {
     /// No encryption and no data authentication
     None = 0b000,
@@ -122,10 +171,38 @@ impl SecurityLevel {
             SecurityLevel::MIC128 | SecurityLevel::ENCMIC128 => 16,
         }
     }
+
+    /// Whether frames secured with this security level are encrypted
+    pub fn is_encrypted(&self) -> bool {
+        self.to_bits() & 0b100 != 0
+    }
+
+    /// Check whether this security level is at least as strong as `minimum`
+    ///
+    /// A security level satisfies another one if it provides encryption
+    /// whenever the other one does, and its MIC is at least as long as the
+    /// other one's.
+    ///
+    /// # Example
+    ///
+    /// ``` rust
+    /// use ieee802154::mac::security::SecurityLevel;
+    ///
+    /// assert!(SecurityLevel::ENCMIC64.satisfies(SecurityLevel::MIC32));
+    /// assert!(!SecurityLevel::MIC128.satisfies(SecurityLevel::ENCMIC32));
+    /// assert!(!SecurityLevel::ENCMIC32.satisfies(SecurityLevel::MIC64));
+    /// ```
+    pub fn satisfies(&self, minimum: SecurityLevel) -> bool {
+        let (bits, min_bits) = (self.to_bits(), minimum.to_bits());
+        bits & 0b100 >= min_bits & 0b100 && bits & 0b011 >= min_bits & 0b011
+    }
 }
 
 /// The key identifier mode
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum KeyIdentifierMode {
     /// No key identifier
     None = 0b00,
@@ -138,6 +215,26 @@ pub enum KeyIdentifierMode {
 }
 
 impl KeyIdentifierMode {
+    /// Get the size of the key identifier field for this mode, in octets
+    ///
+    /// # Example
+    ///
+    /// ``` rust
+    /// use ieee802154::mac::security::KeyIdentifierMode;
+    ///
+    /// const KEY_ID_LEN: usize =
+    ///     KeyIdentifierMode::KeySource4.key_identifier_octet_size();
+    /// assert_eq!(KEY_ID_LEN, 5);
+    /// ```
+    pub const fn key_identifier_octet_size(&self) -> usize {
+        match self {
+            KeyIdentifierMode::None => 0,
+            KeyIdentifierMode::KeyIndex => 1,
+            KeyIdentifierMode::KeySource4 => 5,
+            KeyIdentifierMode::KeySource8 => 9,
+        }
+    }
+
     fn from_bits(bits: u8) -> Option<Self> {
         match bits {
             0b00 => Some(KeyIdentifierMode::None),
@@ -160,21 +257,13 @@ impl KeyIdentifierMode {
 mod mask {
     pub const SECURITY_LEVEL: u8 = 0x07;
     pub const KEY_IDENTIFIER_MODE: u8 = 0x18;
-
-    // The below fields are present only in the 802.15.4-2020 version of the standard
-    /*
     pub const FRAME_COUNTER_SUPPRESSION: u8 = 0x20;
     pub const ASN_IN_NONCE: u8 = 0x40;
-    */
 }
 
 mod offset {
     pub const SECURITY_LEVEL: u8 = 0;
     pub const KEY_IDENTIFIER_MODE: u8 = 3;
-
-    // The below fields are present only in the 802.15.4-2020 version of the standard
-    /*
-    pub const FRAME_COUNTER_SUPPRESSION: u8 = 4;
-    pub const ASN_IN_NONCE: u8 = 5;
-    */
+    pub const FRAME_COUNTER_SUPPRESSION: u8 = 5;
+    pub const ASN_IN_NONCE: u8 = 6;
 }
@@ -0,0 +1,94 @@
+//! Statistics of the securing and unsecuring of frames
+
+use super::SecurityError;
+
+/// Counters of the outcomes of securing and unsecuring frames
+///
+/// A [`SecurityContext`] updates its statistics whenever a frame is written
+/// or read with it. They can be used for commissioning diagnostics, or to
+/// detect attacks in the field, e.g. by watching for a rising number of
+/// replayed frames or MIC failures.
+///
+/// All counters saturate at `u32::MAX`.
+///
+/// [`SecurityContext`]: super::SecurityContext
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SecurityStatistics {
+    /// The number of outgoing frames that were secured
+    pub frames_secured: u32,
+    /// The number of incoming frames that were unsecured
+    pub frames_unsecured: u32,
+    /// The number of incoming frames whose MIC did not match
+    pub mic_failures: u32,
+    /// The number of incoming frames that were rejected as replayed
+    pub replayed_frames: u32,
+    /// The number of frames for which no key could be found
+    pub key_lookup_failures: u32,
+    /// The number of frames that could not be secured or unsecured because
+    /// the frame counter was exhausted
+    pub counter_exhausted: u32,
+}
+
+impl SecurityStatistics {
+    /// Statistics with all counters at zero
+    pub const fn new() -> Self {
+        Self {
+            frames_secured: 0,
+            frames_unsecured: 0,
+            mic_failures: 0,
+            replayed_frames: 0,
+            key_lookup_failures: 0,
+            counter_exhausted: 0,
+        }
+    }
+
+    /// Set all counters to zero
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Record the result of securing a frame
+    pub(crate) fn record_secure<T>(
+        &mut self,
+        result: &Result<T, SecurityError>,
+    ) {
+        match result {
+            Ok(_) => increment(&mut self.frames_secured),
+            Err(e) => self.record_error(e),
+        }
+    }
+
+    /// Record the result of unsecuring a frame
+    pub(crate) fn record_unsecure<T>(
+        &mut self,
+        result: &Result<T, SecurityError>,
+    ) {
+        match result {
+            Ok(_) => increment(&mut self.frames_unsecured),
+            Err(e) => self.record_error(e),
+        }
+    }
+
+    /// Record an error that occurred while securing or unsecuring a frame
+    pub(crate) fn record_error(&mut self, error: &SecurityError) {
+        match error {
+            SecurityError::MicMismatch => increment(&mut self.mic_failures),
+            SecurityError::ReplayedFrame => {
+                increment(&mut self.replayed_frames)
+            }
+            SecurityError::UnavailableKey => {
+                increment(&mut self.key_lookup_failures)
+            }
+            SecurityError::CounterExhausted => {
+                increment(&mut self.counter_exhausted)
+            }
+            _ => {}
+        }
+    }
+}
+
+fn increment(counter: &mut u32) {
+    *counter = counter.saturating_add(1);
+}
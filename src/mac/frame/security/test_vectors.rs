@@ -0,0 +1,252 @@
+//! The example secured frames of Annex C of the 802.15.4-2006 standard
+//!
+//! These can be used to check that a [`CcmBackend`] produces output that
+//! conforms to the standard, e.g. when it delegates to a hardware CCM engine.
+//! [`check`] secures and unsecures a vector's frame with a [`SecurityContext`]
+//! that uses the backend, and compares the result with the standard's.
+//!
+//! All vectors use the key `C0 C1 … CF`, are sent by the device with extended
+//! address `0xACDE480000000001`, and use frame counter 5 and the implicit key
+//! identifier mode.
+//!
+//! # Example
+//!
+//! ``` rust
+//! use aes::Aes128;
+//! use ieee802154::mac::frame::security::{test_vectors, SoftwareCcm};
+//!
+//! for vector in &test_vectors::ALL {
+//!     test_vectors::check::<Aes128, _>(vector, SoftwareCcm::<Aes128>::new()).unwrap();
+//! }
+//! ```
+//!
+//! [`CcmBackend`]: super::CcmBackend
+//! [`SecurityContext`]: super::SecurityContext
+
+use super::{
+    AddressingMode, CcmBackend, DeviceDescriptor, DeviceDescriptorLookup,
+    KeyDescriptorLookup, KeyIdentifier, SecurityContext, SecurityError,
+};
+use crate::mac::{
+    frame::Frame, Address, ExtendedAddress, FooterMode, FrameSerDesContext,
+    PanId, ShortAddress,
+};
+use byte::TryWrite;
+use ccm::aead::generic_array::{typenum::consts::U16, GenericArray};
+use cipher::{BlockCipher, BlockEncrypt, NewBlockCipher};
+
+/// An example secured frame
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TestVector {
+    /// A short description of the frame
+    pub name: &'static str,
+    /// The key that the frame is secured with
+    pub key: [u8; 16],
+    /// The extended address of the device that sends the frame
+    pub source: u64,
+    /// The frame counter that the frame is secured with
+    pub frame_counter: u32,
+    /// The frame before it is secured
+    ///
+    /// This already contains the auxiliary security header, and does not
+    /// contain the MIC.
+    pub unsecured: &'static [u8],
+    /// The frame after it is secured
+    pub secured: &'static [u8],
+}
+
+/// The key that is used by all vectors
+const KEY: [u8; 16] = [
+    0xC0, 0xC1, 0xC2, 0xC3, 0xC4, 0xC5, 0xC6, 0xC7, 0xC8, 0xC9, 0xCA, 0xCB,
+    0xCC, 0xCD, 0xCE, 0xCF,
+];
+
+/// The extended address of the sender of all vectors
+const SOURCE: u64 = 0xACDE_4800_0000_0001;
+
+/// A beacon frame with security level MIC-64 (C.2.1)
+pub const BEACON: TestVector = TestVector {
+    name: "MAC beacon frame",
+    key: KEY,
+    source: SOURCE,
+    frame_counter: 5,
+    unsecured: &[
+        0x08, 0xD0, 0x84, 0x21, 0x43, 0x01, 0x00, 0x00, 0x00, 0x00, 0x48, 0xDE,
+        0xAC, 0x02, 0x05, 0x00, 0x00, 0x00, 0x55, 0xCF, 0x00, 0x00, 0x51, 0x52,
+        0x53, 0x54,
+    ],
+    secured: &[
+        0x08, 0xD0, 0x84, 0x21, 0x43, 0x01, 0x00, 0x00, 0x00, 0x00, 0x48, 0xDE,
+        0xAC, 0x02, 0x05, 0x00, 0x00, 0x00, 0x55, 0xCF, 0x00, 0x00, 0x51, 0x52,
+        0x53, 0x54, 0x22, 0x3B, 0xC1, 0xEC, 0x84, 0x1A, 0xB5, 0x53,
+    ],
+};
+
+/// A data frame with security level ENC (C.2.2)
+///
+/// This security level is deprecated, so [`check`] allows it explicitly, see
+/// [`SecurityContext::allow_encryption_only`].
+pub const DATA: TestVector = TestVector {
+    name: "MAC data frame",
+    key: KEY,
+    source: SOURCE,
+    frame_counter: 5,
+    unsecured: &[
+        0x69, 0xDC, 0x84, 0x21, 0x43, 0x02, 0x00, 0x00, 0x00, 0x00, 0x48, 0xDE,
+        0xAC, 0x01, 0x00, 0x00, 0x00, 0x00, 0x48, 0xDE, 0xAC, 0x04, 0x05, 0x00,
+        0x00, 0x00, 0x61, 0x62, 0x63, 0x64,
+    ],
+    secured: &[
+        0x69, 0xDC, 0x84, 0x21, 0x43, 0x02, 0x00, 0x00, 0x00, 0x00, 0x48, 0xDE,
+        0xAC, 0x01, 0x00, 0x00, 0x00, 0x00, 0x48, 0xDE, 0xAC, 0x04, 0x05, 0x00,
+        0x00, 0x00, 0xD4, 0x3E, 0x02, 0x2B,
+    ],
+};
+
+/// An Association Request command frame with security level ENC-MIC-64
+/// (C.2.3)
+pub const COMMAND: TestVector = TestVector {
+    name: "MAC command frame",
+    key: KEY,
+    source: SOURCE,
+    frame_counter: 5,
+    unsecured: &[
+        0x2B, 0xDC, 0x84, 0x21, 0x43, 0x02, 0x00, 0x00, 0x00, 0x00, 0x48, 0xDE,
+        0xAC, 0xFF, 0xFF, 0x01, 0x00, 0x00, 0x00, 0x00, 0x48, 0xDE, 0xAC, 0x06,
+        0x05, 0x00, 0x00, 0x00, 0x01, 0xCE,
+    ],
+    secured: &[
+        0x2B, 0xDC, 0x84, 0x21, 0x43, 0x02, 0x00, 0x00, 0x00, 0x00, 0x48, 0xDE,
+        0xAC, 0xFF, 0xFF, 0x01, 0x00, 0x00, 0x00, 0x00, 0x48, 0xDE, 0xAC, 0x06,
+        0x05, 0x00, 0x00, 0x00, 0x01, 0xD8, 0x4F, 0xDE, 0x52, 0x90, 0x61, 0xF9,
+        0xC6, 0xF1,
+    ],
+};
+
+/// All vectors of Annex C
+pub const ALL: [TestVector; 3] = [BEACON, DATA, COMMAND];
+
+/// The ways in which checking a [`TestVector`] can fail
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TestVectorError {
+    /// The secured frame could not be unsecured
+    Unsecure(SecurityError),
+    /// The unsecured frame could not be secured
+    Secure(SecurityError),
+    /// Unsecuring the secured frame did not result in the unsecured frame
+    UnsecuredMismatch,
+    /// Securing the unsecured frame did not result in the secured frame
+    SecuredMismatch,
+}
+
+/// Provides the key of a vector for any lookup
+struct VectorKey {
+    key: [u8; 16],
+    source: u64,
+}
+
+impl KeyDescriptorLookup<U16> for VectorKey {
+    fn lookup_key_descriptor(
+        &self,
+        _address_mode: AddressingMode,
+        _key_identifier: Option<KeyIdentifier>,
+        _device_address: Option<Address>,
+    ) -> Option<(u64, GenericArray<u8, U16>)> {
+        Some((self.source, GenericArray::clone_from_slice(&self.key)))
+    }
+}
+
+/// Provides the sender of a vector for any lookup
+struct VectorDevice(DeviceDescriptor);
+
+impl DeviceDescriptorLookup for VectorDevice {
+    fn lookup_device(
+        &mut self,
+        _addressing_mode: AddressingMode,
+        _address: Address,
+        _key_identifier: Option<KeyIdentifier>,
+    ) -> Option<&mut DeviceDescriptor> {
+        Some(&mut self.0)
+    }
+}
+
+/// Check that a [`SecurityContext`] that uses `ccm_backend` unsecures and
+/// secures the frame of `vector` as specified
+///
+/// The secured frame is unsecured first, and the result is compared with the
+/// unsecured frame. Then, that frame is secured again, and the result is
+/// compared with the secured frame.
+pub fn check<AEADBLKCIPH, CCMBACKEND>(
+    vector: &TestVector,
+    ccm_backend: CCMBACKEND,
+) -> Result<(), TestVectorError>
+where
+    AEADBLKCIPH: NewBlockCipher<KeySize = U16>
+        + BlockCipher<BlockSize = U16>
+        + BlockEncrypt,
+    CCMBACKEND: CcmBackend<U16>,
+{
+    let key_provider = VectorKey {
+        key: vector.key,
+        source: vector.source,
+    };
+    let mut sec_ctx = SecurityContext::<AEADBLKCIPH, _, _, _>::with_ccm_backend(
+        vector.source,
+        vector.frame_counter,
+        key_provider,
+        ccm_backend,
+    );
+    sec_ctx.allow_encryption_only = true;
+    let mut device = VectorDevice(DeviceDescriptor {
+        pan_id: PanId(0xffff),
+        short_address: ShortAddress(0xfffe),
+        extended_address: ExtendedAddress(vector.source),
+        frame_counter: vector.frame_counter,
+        exempt: false,
+    });
+
+    let mut received = [0u8; 127];
+    let received = &mut received[..vector.secured.len()];
+    received.copy_from_slice(vector.secured);
+    let mut sent = [0u8; 127];
+
+    let (frame, _) = Frame::try_read_and_unsecure(
+        received,
+        &mut FrameSerDesContext::new(FooterMode::None, Some(&mut sec_ctx)),
+        &mut device,
+    )
+    .map_err(TestVectorError::Unsecure)?;
+    let len = frame
+        .try_write(
+            &mut sent,
+            &mut FrameSerDesContext::new(FooterMode::None, Some(&mut sec_ctx)),
+        )
+        .map_err(|e| TestVectorError::Secure(SecurityError::WriteError(e)))?;
+
+    if received.get(..vector.unsecured.len()) != Some(vector.unsecured) {
+        return Err(TestVectorError::UnsecuredMismatch);
+    }
+    if &sent[..len] != vector.secured {
+        return Err(TestVectorError::SecuredMismatch);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mac::frame::security::SoftwareCcm;
+    use aes::Aes128;
+
+    #[test]
+    fn annex_c() {
+        for vector in &ALL {
+            if let Err(e) =
+                check::<Aes128, _>(vector, SoftwareCcm::<Aes128>::new())
+            {
+                panic!("{}: {:?}", vector.name, e);
+            }
+        }
+    }
+}
@@ -0,0 +1,545 @@
+//! Guaranteed time slot (GTS) management of a PAN coordinator
+//!
+//! [`GtsManager`] keeps track of the GTSs that a coordinator allocated in
+//! the contention-free period (CFP) of its superframe. It processes the GTS
+//! requests of devices, deallocates GTSs that aren't used anymore, and
+//! provides the GTS fields of the next beacon.
+
+use core::fmt;
+
+use crate::mac::beacon::{
+    BeaconOrder, Direction, GuaranteedTimeSlotInformation, SuperframeOrder,
+    MIN_CAP_LENGTH, NUM_SUPERFRAME_SLOTS,
+};
+use crate::mac::command::{GtsCharacteristics, GtsRequestType};
+use crate::mac::ShortAddress;
+
+/// The number of beacons that announce a GTS descriptor
+/// (aGTSDescPersistenceTime)
+pub const GTS_DESC_PERSISTENCE_TIME: u8 = 4;
+
+/// The maximum number of GTSs in a superframe
+pub const MAX_GTS_COUNT: usize = 7;
+
+/// A GTS that is allocated to a device
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GtsAllocation {
+    /// Short address of the device
+    pub short_address: ShortAddress,
+    /// Direction of the GTS, from the device's perspective
+    pub direction: Direction,
+    /// The first superframe slot of the GTS
+    pub starting_slot: u8,
+    /// The number of superframe slots of the GTS
+    pub length: u8,
+}
+
+/// Signals why a GTS request was not granted
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GtsError {
+    /// There already are [`MAX_GTS_COUNT`] GTSs
+    Full,
+    /// The GTS would shorten the CAP below [`MIN_CAP_LENGTH`]
+    ///
+    /// The device can request a GTS of up to `available` slots.
+    InsufficientCapacity {
+        /// The largest GTS length that can be allocated
+        available: u8,
+    },
+    /// The device already has a GTS in this direction
+    AlreadyAllocated,
+    /// The device has no GTS in this direction
+    NotAllocated,
+}
+
+impl fmt::Display for GtsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GtsError::Full => f.write_str("no GTS descriptors left"),
+            GtsError::InsufficientCapacity { available } => {
+                write!(f, "insufficient GTS capacity, {} slots left", available)
+            }
+            GtsError::AlreadyAllocated => f.write_str("GTS already allocated"),
+            GtsError::NotAllocated => f.write_str("GTS not allocated"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for GtsError {}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+struct Gts {
+    allocation: GtsAllocation,
+    /// The number of superframes since the GTS was last used
+    idle_superframes: u16,
+    /// The number of beacons that still announce the GTS
+    announce: u8,
+}
+
+/// A GTS descriptor that announces a deallocation or a denied request
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+struct Notice {
+    short_address: ShortAddress,
+    direction: Direction,
+    length: u8,
+    announce: u8,
+}
+
+/// Keeps track of the GTSs of a PAN coordinator
+///
+/// GTSs are allocated from the end of the superframe, without gaps, and at
+/// least [`MIN_CAP_LENGTH`] symbols are left for the CAP. New and changed
+/// GTSs, deallocations and denied requests are announced in the next
+/// [`GTS_DESC_PERSISTENCE_TIME`] beacons.
+///
+/// Every superframe, get the fields of the beacon with
+/// [`GtsManager::gts_information`] and [`GtsManager::final_cap_slot`], report
+/// the use of GTSs with [`GtsManager::record_activity`], and call
+/// [`GtsManager::end_superframe`] at its end.
+///
+/// # Example
+///
+/// ``` rust
+/// use ieee802154::mac::beacon::{BeaconOrder, Direction, SuperframeOrder};
+/// use ieee802154::mac::command::GtsCharacteristics;
+/// use ieee802154::mac::gts::GtsManager;
+/// use ieee802154::mac::ShortAddress;
+///
+/// let mut gts = GtsManager::new(
+///     BeaconOrder::BeaconOrder(6),
+///     SuperframeOrder::SuperframeOrder(6),
+/// )
+/// .unwrap();
+///
+/// let request = GtsCharacteristics::builder(2)
+///     .direction(Direction::Receive)
+///     .build()
+///     .unwrap();
+/// let allocation = gts.process_request(ShortAddress(0x0001), request)?;
+/// assert_eq!(allocation.starting_slot, 14);
+/// assert_eq!(gts.final_cap_slot(), 13);
+///
+/// let info = gts.gts_information(true);
+/// assert_eq!(info.slots()[0].short_address(), ShortAddress(0x0001));
+/// assert_eq!(info.slots()[0].starting_slot(), 14);
+/// # Ok::<(), ieee802154::mac::gts::GtsError>(())
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct GtsManager {
+    slot_duration: u32,
+    expiration: u16,
+    gts: [Option<Gts>; MAX_GTS_COUNT],
+    notices: [Option<Notice>; MAX_GTS_COUNT],
+}
+
+impl GtsManager {
+    /// Create a manager for the superframe with the given orders
+    ///
+    /// Returns `None` if the PAN isn't beacon-enabled or the superframe is
+    /// inactive.
+    pub fn new(
+        beacon_order: BeaconOrder,
+        superframe_order: SuperframeOrder,
+    ) -> Option<Self> {
+        let order = match beacon_order {
+            BeaconOrder::BeaconOrder(order) if order <= 14 => order,
+            _ => return None,
+        };
+        // A GTS expires if it isn't used for 2 * n superframes
+        let n = if order <= 8 { 1 << (8 - order) } else { 1 };
+        Some(Self {
+            slot_duration: superframe_order.slot_duration()?,
+            expiration: 2 * n,
+            gts: [None; MAX_GTS_COUNT],
+            notices: [None; MAX_GTS_COUNT],
+        })
+    }
+
+    /// Iterate over the allocated GTSs
+    pub fn allocations(&self) -> impl Iterator<Item = GtsAllocation> + '_ {
+        self.gts.iter().flatten().map(|gts| gts.allocation)
+    }
+
+    /// The final superframe slot of the CAP
+    pub fn final_cap_slot(&self) -> u8 {
+        self.first_gts_slot() - 1
+    }
+
+    /// The largest GTS length that can be allocated
+    pub fn available_slots(&self) -> u8 {
+        // Slot 0 to `first - 1` are the CAP
+        let min_cap_slots = MIN_CAP_LENGTH.div_ceil(self.slot_duration);
+        (self.first_gts_slot() as u32).saturating_sub(min_cap_slots.max(1))
+            as u8
+    }
+
+    /// The first superframe slot of the CFP, or the number of slots in a
+    /// superframe if there are no GTSs
+    fn first_gts_slot(&self) -> u8 {
+        self.allocations()
+            .map(|allocation| allocation.starting_slot)
+            .min()
+            .unwrap_or(NUM_SUPERFRAME_SLOTS as u8)
+    }
+
+    fn position(
+        &self,
+        short_address: ShortAddress,
+        direction: Direction,
+    ) -> Option<usize> {
+        self.gts.iter().position(|gts| {
+            gts.is_some_and(|gts| {
+                gts.allocation.short_address == short_address
+                    && gts.allocation.direction == direction
+            })
+        })
+    }
+
+    /// Process the GTS request of the device `short_address`
+    ///
+    /// Returns the allocated GTS, or the deallocated GTS if `characteristics`
+    /// requests a deallocation. A denied allocation is announced in the next
+    /// beacons.
+    pub fn process_request(
+        &mut self,
+        short_address: ShortAddress,
+        characteristics: GtsCharacteristics,
+    ) -> Result<GtsAllocation, GtsError> {
+        let direction = characteristics.direction;
+        match characteristics.request_type {
+            GtsRequestType::Allocate => {
+                let result = self.allocate(
+                    short_address,
+                    direction,
+                    characteristics.length,
+                );
+                if let Err(
+                    GtsError::Full | GtsError::InsufficientCapacity { .. },
+                ) = result
+                {
+                    self.notify(
+                        short_address,
+                        direction,
+                        self.available_slots(),
+                    );
+                }
+                result
+            }
+            GtsRequestType::Deallocate => {
+                let index = self
+                    .position(short_address, direction)
+                    .ok_or(GtsError::NotAllocated)?;
+                Ok(self.remove(index))
+            }
+        }
+    }
+
+    fn allocate(
+        &mut self,
+        short_address: ShortAddress,
+        direction: Direction,
+        length: u8,
+    ) -> Result<GtsAllocation, GtsError> {
+        if self.position(short_address, direction).is_some() {
+            return Err(GtsError::AlreadyAllocated);
+        }
+        let index = self
+            .gts
+            .iter()
+            .position(Option::is_none)
+            .ok_or(GtsError::Full)?;
+        let available = self.available_slots();
+        if length == 0 || length > available {
+            return Err(GtsError::InsufficientCapacity { available });
+        }
+        let allocation = GtsAllocation {
+            short_address,
+            direction,
+            starting_slot: self.first_gts_slot() - length,
+            length,
+        };
+        self.gts[index] = Some(Gts {
+            allocation,
+            idle_superframes: 0,
+            announce: GTS_DESC_PERSISTENCE_TIME,
+        });
+        Ok(allocation)
+    }
+
+    /// Deallocate the GTS at `index`, and move the GTSs before it towards the
+    /// end of the superframe, so the CFP has no gaps
+    fn remove(&mut self, index: usize) -> GtsAllocation {
+        let removed = self.gts[index].take().unwrap().allocation;
+        for gts in self.gts.iter_mut().flatten() {
+            if gts.allocation.starting_slot < removed.starting_slot {
+                gts.allocation.starting_slot += removed.length;
+                gts.announce = GTS_DESC_PERSISTENCE_TIME;
+            }
+        }
+        removed
+    }
+
+    /// Announce a deallocation or a denied request in the next beacons
+    fn notify(
+        &mut self,
+        short_address: ShortAddress,
+        direction: Direction,
+        length: u8,
+    ) {
+        let notice = Notice {
+            short_address,
+            direction,
+            length,
+            announce: GTS_DESC_PERSISTENCE_TIME,
+        };
+        if let Some(slot) = self.notices.iter_mut().find(|n| n.is_none()) {
+            *slot = Some(notice);
+        }
+    }
+
+    /// Record that the GTS of `short_address` in `direction` was used in the
+    /// current superframe
+    pub fn record_activity(
+        &mut self,
+        short_address: ShortAddress,
+        direction: Direction,
+    ) {
+        if let Some(index) = self.position(short_address, direction) {
+            if let Some(gts) = &mut self.gts[index] {
+                gts.idle_superframes = 0;
+            }
+        }
+    }
+
+    /// End the current superframe
+    ///
+    /// This counts down the announcements of the beacon that was sent, and
+    /// deallocates the GTSs that expired because they weren't used. Returns
+    /// the expired GTSs, whose deallocation is announced in the next
+    /// beacons.
+    pub fn end_superframe(&mut self) -> impl Iterator<Item = GtsAllocation> {
+        for notice in self.notices.iter_mut() {
+            if let Some(n) = notice {
+                n.announce -= 1;
+                if n.announce == 0 {
+                    *notice = None;
+                }
+            }
+        }
+
+        let mut expired = [None; MAX_GTS_COUNT];
+        for (index, expired) in expired.iter_mut().enumerate() {
+            let gts = match &mut self.gts[index] {
+                Some(gts) => gts,
+                None => continue,
+            };
+            gts.announce = gts.announce.saturating_sub(1);
+            gts.idle_superframes = gts.idle_superframes.saturating_add(1);
+            if gts.idle_superframes >= self.expiration {
+                let removed = self.remove(index);
+                self.notify(
+                    removed.short_address,
+                    removed.direction,
+                    removed.length,
+                );
+                *expired = Some(removed);
+            }
+        }
+        IntoIterator::into_iter(expired).flatten()
+    }
+
+    /// Get the GTS fields of the next beacon
+    ///
+    /// These are the descriptors of the GTSs that are announced, and of the
+    /// deallocations and denied requests, with a starting slot of 0. The
+    /// descriptors of the announced GTSs come first, and at most 7
+    /// descriptors are included.
+    pub fn gts_information(
+        &self,
+        permit: bool,
+    ) -> GuaranteedTimeSlotInformation {
+        let mut info = GuaranteedTimeSlotInformation::new();
+        info.permit = permit;
+        let announced = self
+            .gts
+            .iter()
+            .flatten()
+            .filter(|gts| gts.announce > 0)
+            .map(|gts| gts.allocation);
+        let notices =
+            self.notices.iter().flatten().map(|notice| GtsAllocation {
+                short_address: notice.short_address,
+                direction: notice.direction,
+                starting_slot: 0,
+                length: notice.length,
+            });
+        for allocation in announced.chain(notices) {
+            if !info.add_slot(
+                allocation.short_address,
+                allocation.starting_slot,
+                allocation.length,
+                allocation.direction,
+            ) {
+                break;
+            }
+        }
+        info
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(length: u8, direction: Direction) -> GtsCharacteristics {
+        GtsCharacteristics::builder(length)
+            .direction(direction)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn allocate_and_deallocate() {
+        let mut manager = GtsManager::new(
+            BeaconOrder::BeaconOrder(6),
+            SuperframeOrder::SuperframeOrder(6),
+        )
+        .unwrap();
+        let tx = request(2, Direction::Transmit);
+        let rx = request(3, Direction::Receive);
+
+        let first = manager.process_request(ShortAddress(1), tx).unwrap();
+        assert_eq!(first.starting_slot, 14);
+        assert_eq!(
+            manager.process_request(ShortAddress(1), tx),
+            Err(GtsError::AlreadyAllocated)
+        );
+        let second = manager.process_request(ShortAddress(2), rx).unwrap();
+        assert_eq!(second.starting_slot, 11);
+        assert_eq!(manager.final_cap_slot(), 10);
+
+        let info = manager.gts_information(true);
+        assert!(info.permit);
+        assert_eq!(info.slots().len(), 2);
+        assert_eq!(info.slots()[1].direction(), Direction::Receive);
+
+        let dealloc = GtsCharacteristics::builder(2)
+            .request_type(GtsRequestType::Deallocate)
+            .build()
+            .unwrap();
+        assert_eq!(
+            manager.process_request(ShortAddress(2), dealloc),
+            Err(GtsError::NotAllocated)
+        );
+        assert_eq!(
+            manager.process_request(ShortAddress(1), dealloc),
+            Ok(first)
+        );
+        let moved = manager.allocations().next().unwrap();
+        assert_eq!(moved.short_address, ShortAddress(2));
+        assert_eq!(moved.starting_slot, 13);
+        assert_eq!(manager.final_cap_slot(), 12);
+    }
+
+    #[test]
+    fn minimum_cap_length() {
+        // Slots of 60 symbols, so the CAP needs at least 8 slots
+        let mut manager = GtsManager::new(
+            BeaconOrder::BeaconOrder(0),
+            SuperframeOrder::SuperframeOrder(0),
+        )
+        .unwrap();
+        assert_eq!(manager.available_slots(), 8);
+        assert_eq!(
+            manager.process_request(
+                ShortAddress(1),
+                request(9, Direction::Transmit)
+            ),
+            Err(GtsError::InsufficientCapacity { available: 8 })
+        );
+        // The denial is announced with a starting slot of 0
+        let info = manager.gts_information(true);
+        assert_eq!(info.slots().len(), 1);
+        assert_eq!(info.slots()[0].starting_slot(), 0);
+        assert_eq!(info.slots()[0].length(), 8);
+
+        for _ in 0..GTS_DESC_PERSISTENCE_TIME {
+            assert_eq!(manager.end_superframe().count(), 0);
+        }
+        assert!(manager.gts_information(true).slots().is_empty());
+    }
+
+    #[test]
+    fn seven_descriptors() {
+        let mut manager = GtsManager::new(
+            BeaconOrder::BeaconOrder(14),
+            SuperframeOrder::SuperframeOrder(14),
+        )
+        .unwrap();
+        for address in 0..7 {
+            manager
+                .process_request(
+                    ShortAddress(address),
+                    request(1, Direction::Transmit),
+                )
+                .unwrap();
+        }
+        assert_eq!(
+            manager.process_request(
+                ShortAddress(7),
+                request(1, Direction::Transmit)
+            ),
+            Err(GtsError::Full)
+        );
+        assert_eq!(manager.allocations().count(), 7);
+        assert_eq!(manager.gts_information(true).slots().len(), 7);
+    }
+
+    #[test]
+    fn expiration() {
+        // With a beacon order of 8, a GTS expires after 2 idle superframes
+        let mut manager = GtsManager::new(
+            BeaconOrder::BeaconOrder(8),
+            SuperframeOrder::SuperframeOrder(8),
+        )
+        .unwrap();
+        let tx = request(1, Direction::Transmit);
+        manager.process_request(ShortAddress(1), tx).unwrap();
+        manager.process_request(ShortAddress(2), tx).unwrap();
+
+        assert_eq!(manager.end_superframe().count(), 0);
+        manager.record_activity(ShortAddress(2), Direction::Transmit);
+        let mut expired = manager.end_superframe();
+        let gts = expired.next().unwrap();
+        assert_eq!(gts.short_address, ShortAddress(1));
+        assert_eq!(expired.next(), None);
+
+        let remaining = manager.allocations().next().unwrap();
+        assert_eq!(remaining.short_address, ShortAddress(2));
+        assert_eq!(remaining.starting_slot, 15);
+        // Both the moved GTS and the deallocation are announced
+        let info = manager.gts_information(true);
+        assert_eq!(info.slots().len(), 2);
+        assert_eq!(info.slots()[1].short_address(), ShortAddress(1));
+        assert_eq!(info.slots()[1].starting_slot(), 0);
+    }
+
+    #[test]
+    fn unsupported_superframes() {
+        assert!(GtsManager::new(
+            BeaconOrder::OnDemand,
+            SuperframeOrder::SuperframeOrder(0)
+        )
+        .is_none());
+        assert!(GtsManager::new(
+            BeaconOrder::BeaconOrder(6),
+            SuperframeOrder::Inactive
+        )
+        .is_none());
+    }
+}
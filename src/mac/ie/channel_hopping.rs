@@ -0,0 +1,239 @@
+//! Channel Hopping IE
+
+use byte::{check_len, BytesExt, TryRead, TryWrite, LE};
+
+use crate::mac::frame::EncodeError;
+
+/// The channels of a hopping sequence
+///
+/// Like [`NestedIes`], these are [`HoppingChannels::Encoded`] when read from
+/// a frame, and can be [`HoppingChannels::Decoded`] when written to one.
+///
+/// [`NestedIes`]: super::NestedIes
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum HoppingChannels<'a> {
+    /// The encoded channels
+    Encoded(&'a [u8]),
+    /// The decoded channels
+    Decoded(&'a [u16]),
+}
+
+impl<'a> HoppingChannels<'a> {
+    /// The number of channels
+    pub fn len(&self) -> usize {
+        match self {
+            HoppingChannels::Encoded(bytes) => bytes.len() / 2,
+            HoppingChannels::Decoded(channels) => channels.len(),
+        }
+    }
+
+    /// Whether there are no channels
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterate over the channels
+    pub fn iter(&self) -> HoppingChannelIterator<'a> {
+        HoppingChannelIterator {
+            channels: *self,
+            index: 0,
+        }
+    }
+}
+
+/// An iterator over the channels of a hopping sequence
+#[derive(Clone, Debug)]
+pub struct HoppingChannelIterator<'a> {
+    channels: HoppingChannels<'a>,
+    index: usize,
+}
+
+impl Iterator for HoppingChannelIterator<'_> {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let channel = match self.channels {
+            HoppingChannels::Encoded(bytes) => {
+                bytes.read_with(&mut (self.index * 2), LE).ok()?
+            }
+            HoppingChannels::Decoded(channels) => *channels.get(self.index)?,
+        };
+        self.index += 1;
+        Some(channel)
+    }
+}
+
+/// An explicit hopping sequence
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct HoppingSequence<'a> {
+    /// The channel page of the channels
+    pub channel_page: u8,
+    /// The number of channels of the channel page
+    pub number_of_channels: u16,
+    /// The PHY configuration, a bitmap of the channels that are supported
+    pub phy_configuration: u32,
+    /// The extended bitmap of the channels that are supported
+    ///
+    /// This is only present for channel pages 9 and 10, in which case it is
+    /// `(number_of_channels + 7) / 8` octets long.
+    pub extended_bitmap: &'a [u8],
+    /// The channels of the hopping sequence
+    pub channels: HoppingChannels<'a>,
+    /// The index of the current hop in the hopping sequence
+    pub current_hop: u16,
+}
+
+impl HoppingSequence<'_> {
+    /// Get the size of this hopping sequence in octets
+    pub fn get_octet_size(&self) -> usize {
+        1 + 2 + 4 + self.extended_bitmap.len() + 2 + self.channels.len() * 2 + 2
+    }
+}
+
+/// The length of the extended bitmap of a channel page
+fn extended_bitmap_len(channel_page: u8, number_of_channels: u16) -> usize {
+    match channel_page {
+        9 | 10 => (number_of_channels as usize).div_ceil(8),
+        _ => 0,
+    }
+}
+
+impl<'a> TryRead<'a> for HoppingSequence<'a> {
+    fn try_read(bytes: &'a [u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        check_len(bytes, 1 + 2 + 4)?;
+        let channel_page = bytes.read(offset)?;
+        let number_of_channels = bytes.read_with(offset, LE)?;
+        let phy_configuration = bytes.read_with(offset, LE)?;
+
+        let bitmap_len = extended_bitmap_len(channel_page, number_of_channels);
+        check_len(&bytes[*offset..], bitmap_len)?;
+        let extended_bitmap = &bytes[*offset..*offset + bitmap_len];
+        *offset += bitmap_len;
+
+        let sequence_len: u16 = bytes.read_with(offset, LE)?;
+        let channels_len = sequence_len as usize * 2;
+        check_len(&bytes[*offset..], channels_len)?;
+        let channels =
+            HoppingChannels::Encoded(&bytes[*offset..*offset + channels_len]);
+        *offset += channels_len;
+
+        let current_hop = bytes.read_with(offset, LE)?;
+        Ok((
+            Self {
+                channel_page,
+                number_of_channels,
+                phy_configuration,
+                extended_bitmap,
+                channels,
+                current_hop,
+            },
+            *offset,
+        ))
+    }
+}
+
+impl TryWrite for HoppingSequence<'_> {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        if self.extended_bitmap.len()
+            != extended_bitmap_len(self.channel_page, self.number_of_channels)
+            || self.channels.len() > u16::MAX as usize
+        {
+            return Err(EncodeError::WriteError.into());
+        }
+        bytes.write(offset, self.channel_page)?;
+        bytes.write_with(offset, self.number_of_channels, LE)?;
+        bytes.write_with(offset, self.phy_configuration, LE)?;
+        bytes.write(offset, self.extended_bitmap)?;
+        bytes.write_with(offset, self.channels.len() as u16, LE)?;
+        for channel in self.channels.iter() {
+            bytes.write_with(offset, channel, LE)?;
+        }
+        bytes.write_with(offset, self.current_hop, LE)?;
+        Ok(*offset)
+    }
+}
+
+/// Channel Hopping IE
+///
+/// Enhanced beacons of channel hopping networks carry this IE, to advertise
+/// the hopping sequence. The sequence is either identified by its ID only,
+/// or given explicitly.
+///
+/// # Example
+///
+/// ``` rust
+/// use byte::BytesExt;
+/// use ieee802154::mac::ie::{ChannelHoppingIe, HoppingChannels, HoppingSequence};
+///
+/// let ie = ChannelHoppingIe {
+///     hopping_sequence_id: 1,
+///     sequence: Some(HoppingSequence {
+///         channel_page: 0,
+///         number_of_channels: 16,
+///         phy_configuration: 0x07ff_f800,
+///         extended_bitmap: &[],
+///         channels: HoppingChannels::Decoded(&[15, 20, 25, 26]),
+///         current_hop: 0,
+///     }),
+/// };
+///
+/// let mut bytes = [0u8; 20];
+/// let len = &mut 0;
+/// bytes.write(len, ie).unwrap();
+/// assert_eq!(*len, ie.get_octet_size());
+///
+/// let decoded: ChannelHoppingIe = bytes[..*len].read(&mut 0).unwrap();
+/// let channels = decoded.sequence.unwrap().channels;
+/// assert!(channels.iter().eq([15, 20, 25, 26].iter().copied()));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ChannelHoppingIe<'a> {
+    /// The ID of the hopping sequence
+    pub hopping_sequence_id: u8,
+    /// The hopping sequence, if it is given explicitly
+    pub sequence: Option<HoppingSequence<'a>>,
+}
+
+impl ChannelHoppingIe<'_> {
+    /// Get the size of the content of this IE in octets
+    pub fn get_octet_size(&self) -> usize {
+        1 + self
+            .sequence
+            .map_or(0, |sequence| sequence.get_octet_size())
+    }
+}
+
+impl<'a> TryRead<'a> for ChannelHoppingIe<'a> {
+    fn try_read(bytes: &'a [u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let hopping_sequence_id = bytes.read(offset)?;
+        let sequence = if bytes.len() > *offset {
+            Some(bytes.read(offset)?)
+        } else {
+            None
+        };
+        Ok((
+            Self {
+                hopping_sequence_id,
+                sequence,
+            },
+            *offset,
+        ))
+    }
+}
+
+impl TryWrite for ChannelHoppingIe<'_> {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write(offset, self.hopping_sequence_id)?;
+        if let Some(sequence) = self.sequence {
+            bytes.write(offset, sequence)?;
+        }
+        Ok(*offset)
+    }
+}
@@ -0,0 +1,155 @@
+//! CSL IE
+
+use byte::{check_len, BytesExt, TryRead, TryWrite, LE};
+
+/// Convert a duration in symbols to units of 10 symbols
+///
+/// The duration is rounded down. Returns `None`, if the duration doesn't fit
+/// in 16 bits of 10-symbol units.
+pub const fn to_ten_symbol_units(symbols: u32) -> Option<u16> {
+    let units = symbols / 10;
+    if units > u16::MAX as u32 {
+        None
+    } else {
+        Some(units as u16)
+    }
+}
+
+/// Convert a duration in units of 10 symbols to symbols
+pub const fn from_ten_symbol_units(units: u16) -> u32 {
+    units as u32 * 10
+}
+
+/// Coordinated Sampled Listening (CSL) IE
+///
+/// Frames and enhanced acknowledgements of devices that use CSL carry this
+/// IE, to let their peers know when they next sample the channel. All fields
+/// are in units of 10 symbols.
+///
+/// # Example
+///
+/// ``` rust
+/// use ieee802154::mac::ie::CslIe;
+///
+/// let ie = CslIe::from_symbols(1234, 5000, None).unwrap();
+/// assert_eq!(ie.phase, 123);
+/// assert_eq!(ie.period_symbols(), 5000);
+/// assert_eq!(ie.get_octet_size(), 4);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct CslIe {
+    /// The time from the start of the frame until the next channel sample of
+    /// the sender, in units of 10 symbols
+    pub phase: u16,
+    /// The time between two channel samples of the sender, in units of 10
+    /// symbols
+    ///
+    /// A period of 0 means that the sender doesn't use CSL.
+    pub period: u16,
+    /// The time from the end of the frame until the start of the frame that
+    /// follows it, in units of 10 symbols
+    ///
+    /// This is only present in wake-up frames.
+    pub rendezvous_time: Option<u16>,
+}
+
+impl CslIe {
+    /// Create a CSL IE from durations in symbols
+    ///
+    /// The durations are rounded down to units of 10 symbols. Returns `None`,
+    /// if one of them doesn't fit in 16 bits of 10-symbol units.
+    pub const fn from_symbols(
+        phase: u32,
+        period: u32,
+        rendezvous_time: Option<u32>,
+    ) -> Option<Self> {
+        let phase = match to_ten_symbol_units(phase) {
+            Some(phase) => phase,
+            None => return None,
+        };
+        let period = match to_ten_symbol_units(period) {
+            Some(period) => period,
+            None => return None,
+        };
+        let rendezvous_time = match rendezvous_time {
+            Some(rendezvous_time) => {
+                match to_ten_symbol_units(rendezvous_time) {
+                    Some(rendezvous_time) => Some(rendezvous_time),
+                    None => return None,
+                }
+            }
+            None => None,
+        };
+        Some(Self {
+            phase,
+            period,
+            rendezvous_time,
+        })
+    }
+
+    /// The CSL phase, in symbols
+    pub const fn phase_symbols(&self) -> u32 {
+        from_ten_symbol_units(self.phase)
+    }
+
+    /// The CSL period, in symbols
+    pub const fn period_symbols(&self) -> u32 {
+        from_ten_symbol_units(self.period)
+    }
+
+    /// The rendezvous time, in symbols
+    pub const fn rendezvous_time_symbols(&self) -> Option<u32> {
+        match self.rendezvous_time {
+            Some(rendezvous_time) => {
+                Some(from_ten_symbol_units(rendezvous_time))
+            }
+            None => None,
+        }
+    }
+
+    /// Get the size of the content of this IE in octets
+    pub const fn get_octet_size(&self) -> usize {
+        if self.rendezvous_time.is_some() {
+            6
+        } else {
+            4
+        }
+    }
+}
+
+impl TryRead<'_> for CslIe {
+    fn try_read(bytes: &[u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        check_len(bytes, 4)?;
+        let phase = bytes.read_with(offset, LE)?;
+        let period = bytes.read_with(offset, LE)?;
+        let rendezvous_time = if bytes.len() >= 6 {
+            Some(bytes.read_with(offset, LE)?)
+        } else {
+            None
+        };
+        Ok((
+            Self {
+                phase,
+                period,
+                rendezvous_time,
+            },
+            *offset,
+        ))
+    }
+}
+
+impl TryWrite for CslIe {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with(offset, self.phase, LE)?;
+        bytes.write_with(offset, self.period, LE)?;
+        if let Some(rendezvous_time) = self.rendezvous_time {
+            bytes.write_with(offset, rendezvous_time, LE)?;
+        }
+        Ok(*offset)
+    }
+}
@@ -0,0 +1,378 @@
+//! IEs of DSME (Deterministic and Synchronous Multi-channel Extension) PANs
+
+use byte::{check_len, BytesExt, TryRead, TryWrite, LE};
+
+use crate::mac::beacon::{
+    PendingAddress, SuperframeOrder, SuperframeSpecification,
+    BASE_SUPERFRAME_DURATION,
+};
+use crate::mac::frame::EncodeError;
+
+const MULTI_SUPERFRAME_ORDER_MASK: u8 = 0x0f;
+const CHANNEL_HOPPING: u8 = 0x10;
+const CAP_REDUCTION: u8 = 0x40;
+const DEFERRED_BEACON: u8 = 0x80;
+
+const GROUP_ACK_LEN: usize = 9;
+
+/// DSME Superframe Specification
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct DsmeSuperframeSpecification {
+    /// The multi-superframe order, 0-15
+    ///
+    /// Multi-superframe duration = BaseSuperframeDuration × (2 ^ order)
+    pub multi_superframe_order: u8,
+    /// The channel diversity mode: channel hopping if `true`, and channel
+    /// adaptation if `false`
+    pub channel_hopping: bool,
+    /// The CAP reduction is enabled
+    pub cap_reduction: bool,
+    /// The beacon is deferred
+    pub deferred_beacon: bool,
+}
+
+impl DsmeSuperframeSpecification {
+    /// Decode the DSME superframe specification from its bits
+    pub const fn from_bits(bits: u8) -> Self {
+        Self {
+            multi_superframe_order: bits & MULTI_SUPERFRAME_ORDER_MASK,
+            channel_hopping: bits & CHANNEL_HOPPING != 0,
+            cap_reduction: bits & CAP_REDUCTION != 0,
+            deferred_beacon: bits & DEFERRED_BEACON != 0,
+        }
+    }
+
+    /// Encode the DSME superframe specification into its bits
+    pub const fn to_bits(&self) -> u8 {
+        let mut bits =
+            self.multi_superframe_order & MULTI_SUPERFRAME_ORDER_MASK;
+        if self.channel_hopping {
+            bits |= CHANNEL_HOPPING;
+        }
+        if self.cap_reduction {
+            bits |= CAP_REDUCTION;
+        }
+        if self.deferred_beacon {
+            bits |= DEFERRED_BEACON;
+        }
+        bits
+    }
+
+    /// The duration of a multi-superframe, in symbols
+    ///
+    /// Returns `None` if the multi-superframe order is 15.
+    pub fn multi_superframe_duration(&self) -> Option<u32> {
+        match self.multi_superframe_order {
+            order @ 0..=14 => Some(BASE_SUPERFRAME_DURATION << order),
+            _ => None,
+        }
+    }
+
+    /// The number of superframes in a multi-superframe, with superframe order
+    /// `superframe_order`
+    ///
+    /// Returns `None` if the superframe is inactive, or if the superframe
+    /// order exceeds the multi-superframe order.
+    pub fn superframes_per_multi_superframe(
+        &self,
+        superframe_order: SuperframeOrder,
+    ) -> Option<u16> {
+        match superframe_order {
+            SuperframeOrder::SuperframeOrder(order)
+                if order <= self.multi_superframe_order
+                    && self.multi_superframe_order <= 14 =>
+            {
+                Some(1 << (self.multi_superframe_order - order))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl TryRead<'_> for DsmeSuperframeSpecification {
+    fn try_read(bytes: &[u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let bits = bytes.read(offset)?;
+        Ok((Self::from_bits(bits), *offset))
+    }
+}
+
+impl TryWrite for DsmeSuperframeSpecification {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write(offset, self.to_bits())?;
+        Ok(*offset)
+    }
+}
+
+/// Time Synchronization Specification of a DSME PAN
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct TimeSynchronizationSpecification {
+    /// The time at which the beacon was sent, in symbols
+    ///
+    /// This is a 48-bit value.
+    pub beacon_timestamp: u64,
+    /// The offset between the start of the superframe and the time at which
+    /// the beacon was sent, in symbols
+    pub beacon_offset_timestamp: u16,
+}
+
+impl TimeSynchronizationSpecification {
+    /// The largest beacon timestamp that can be carried
+    pub const MAX_BEACON_TIMESTAMP: u64 = 0xffff_ffff_ffff;
+}
+
+/// Beacon Bitmap of a DSME PAN
+///
+/// The bitmap of the superframes in a beacon interval in which beacons are
+/// sent.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BeaconBitmap<'a> {
+    /// The index of the superframe in which the beacon is sent
+    pub sd_index: u16,
+    /// The bitmap of the superframes in which beacons are sent
+    pub sd_bitmap: &'a [u8],
+}
+
+/// Channel Hopping Specification of a DSME PAN
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelHoppingSpecification<'a> {
+    /// The ID of the hopping sequence
+    pub hopping_sequence_id: u8,
+    /// The beacon sequence number of the PAN coordinator
+    pub pan_coordinator_bsn: u8,
+    /// The channel offset of the sender
+    pub channel_offset: u16,
+    /// The bitmap of the channel offsets that are in use by its neighbors
+    pub channel_offset_bitmap: &'a [u8],
+}
+
+/// DSME PAN Descriptor IE
+///
+/// Enhanced beacons of DSME PANs carry this IE, to describe the
+/// (multi-)superframe structure of the PAN.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DsmePanDescriptorIe<'a> {
+    /// The superframe specification
+    pub superframe_spec: SuperframeSpecification,
+    /// The pending addresses
+    pub pending_address: PendingAddress,
+    /// The DSME superframe specification
+    pub dsme_superframe_spec: DsmeSuperframeSpecification,
+    /// The time synchronization specification
+    pub time_synchronization: TimeSynchronizationSpecification,
+    /// The beacon bitmap
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub beacon_bitmap: BeaconBitmap<'a>,
+    /// The channel hopping specification
+    ///
+    /// This is present if, and only if, the channel diversity mode of the
+    /// DSME superframe specification is channel hopping.
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub channel_hopping: Option<ChannelHoppingSpecification<'a>>,
+    /// The group acknowledgement specification, in its encoded form
+    pub group_ack: Option<[u8; GROUP_ACK_LEN]>,
+}
+
+impl DsmePanDescriptorIe<'_> {
+    /// Get the size of the content of this IE in octets
+    pub fn get_octet_size(&self) -> usize {
+        let channel_hopping = self
+            .channel_hopping
+            .map_or(0, |spec| 1 + 1 + 2 + 1 + spec.channel_offset_bitmap.len());
+        let group_ack = self.group_ack.map_or(0, |_| GROUP_ACK_LEN);
+        SuperframeSpecification::OCTET_SIZE
+            + self.pending_address.get_octet_size()
+            + 1
+            + 8
+            + 2
+            + 1
+            + self.beacon_bitmap.sd_bitmap.len()
+            + channel_hopping
+            + group_ack
+    }
+}
+
+/// Read a bitmap that is preceded by its length in octets
+fn read_bitmap<'a>(
+    bytes: &'a [u8],
+    offset: &mut usize,
+) -> byte::Result<&'a [u8]> {
+    let len: u8 = bytes.read(offset)?;
+    let len = len as usize;
+    check_len(&bytes[*offset..], len)?;
+    let bitmap = &bytes[*offset..*offset + len];
+    *offset += len;
+    Ok(bitmap)
+}
+
+/// Write a bitmap, preceded by its length in octets
+fn write_bitmap(
+    bytes: &mut [u8],
+    offset: &mut usize,
+    bitmap: &[u8],
+) -> byte::Result<()> {
+    if bitmap.len() > u8::MAX as usize {
+        return Err(EncodeError::WriteError.into());
+    }
+    bytes.write(offset, bitmap.len() as u8)?;
+    bytes.write(offset, bitmap)
+}
+
+impl<'a> TryRead<'a> for DsmePanDescriptorIe<'a> {
+    fn try_read(bytes: &'a [u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let superframe_spec = bytes.read(offset)?;
+        let pending_address = bytes.read(offset)?;
+        let dsme_superframe_spec: DsmeSuperframeSpecification =
+            bytes.read(offset)?;
+
+        check_len(&bytes[*offset..], 8)?;
+        let mut beacon_timestamp = [0u8; 8];
+        beacon_timestamp[..6].copy_from_slice(&bytes[*offset..*offset + 6]);
+        *offset += 6;
+        let time_synchronization = TimeSynchronizationSpecification {
+            beacon_timestamp: u64::from_le_bytes(beacon_timestamp),
+            beacon_offset_timestamp: bytes.read_with(offset, LE)?,
+        };
+
+        let beacon_bitmap = BeaconBitmap {
+            sd_index: bytes.read_with(offset, LE)?,
+            sd_bitmap: read_bitmap(bytes, offset)?,
+        };
+
+        let channel_hopping = if dsme_superframe_spec.channel_hopping {
+            Some(ChannelHoppingSpecification {
+                hopping_sequence_id: bytes.read(offset)?,
+                pan_coordinator_bsn: bytes.read(offset)?,
+                channel_offset: bytes.read_with(offset, LE)?,
+                channel_offset_bitmap: read_bitmap(bytes, offset)?,
+            })
+        } else {
+            None
+        };
+
+        // The group acknowledgement specification is present if there is
+        // room for it
+        let group_ack = if bytes.len() - *offset >= GROUP_ACK_LEN {
+            let mut group_ack = [0u8; GROUP_ACK_LEN];
+            group_ack.copy_from_slice(&bytes[*offset..*offset + GROUP_ACK_LEN]);
+            *offset += GROUP_ACK_LEN;
+            Some(group_ack)
+        } else {
+            None
+        };
+
+        Ok((
+            Self {
+                superframe_spec,
+                pending_address,
+                dsme_superframe_spec,
+                time_synchronization,
+                beacon_bitmap,
+                channel_hopping,
+                group_ack,
+            },
+            *offset,
+        ))
+    }
+}
+
+impl TryWrite for DsmePanDescriptorIe<'_> {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        if self.channel_hopping.is_some()
+            != self.dsme_superframe_spec.channel_hopping
+            || self.time_synchronization.beacon_timestamp
+                > TimeSynchronizationSpecification::MAX_BEACON_TIMESTAMP
+        {
+            return Err(EncodeError::WriteError.into());
+        }
+
+        bytes.write(offset, self.superframe_spec)?;
+        bytes.write(offset, self.pending_address)?;
+        bytes.write(offset, self.dsme_superframe_spec)?;
+
+        let time_synchronization = self.time_synchronization;
+        bytes.write(
+            offset,
+            &time_synchronization.beacon_timestamp.to_le_bytes()[..6],
+        )?;
+        bytes.write_with(
+            offset,
+            time_synchronization.beacon_offset_timestamp,
+            LE,
+        )?;
+
+        bytes.write_with(offset, self.beacon_bitmap.sd_index, LE)?;
+        write_bitmap(bytes, offset, self.beacon_bitmap.sd_bitmap)?;
+
+        if let Some(channel_hopping) = self.channel_hopping {
+            bytes.write(offset, channel_hopping.hopping_sequence_id)?;
+            bytes.write(offset, channel_hopping.pan_coordinator_bsn)?;
+            bytes.write_with(offset, channel_hopping.channel_offset, LE)?;
+            write_bitmap(bytes, offset, channel_hopping.channel_offset_bitmap)?;
+        }
+
+        if let Some(group_ack) = self.group_ack {
+            bytes.write(offset, &group_ack[..])?;
+        }
+        Ok(*offset)
+    }
+}
+
+/// Simplified Superframe Specification IE
+///
+/// Frames of DSME PANs that don't carry a full beacon can carry this IE, to
+/// describe the superframe structure of the PAN.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimplifiedSuperframeSpecificationIe {
+    /// The lower 16 bits of the time at which the frame was sent, in symbols
+    pub timestamp: u16,
+    /// The superframe specification
+    pub superframe_spec: SuperframeSpecification,
+    /// The CFP specification, in its encoded form
+    pub cfp_spec: u16,
+}
+
+impl TryRead<'_> for SimplifiedSuperframeSpecificationIe {
+    fn try_read(bytes: &[u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        check_len(bytes, 6)?;
+        let timestamp = bytes.read_with(offset, LE)?;
+        let superframe_spec = bytes.read(offset)?;
+        let cfp_spec = bytes.read_with(offset, LE)?;
+        Ok((
+            Self {
+                timestamp,
+                superframe_spec,
+                cfp_spec,
+            },
+            *offset,
+        ))
+    }
+}
+
+impl TryWrite for SimplifiedSuperframeSpecificationIe {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with(offset, self.timestamp, LE)?;
+        bytes.write(offset, self.superframe_spec)?;
+        bytes.write_with(offset, self.cfp_spec, LE)?;
+        Ok(*offset)
+    }
+}
@@ -0,0 +1,47 @@
+//! Global Time IE
+
+use byte::{check_len, BytesExt, TryRead, TryWrite, LE};
+
+/// Global Time IE
+///
+/// Coordinators carry this IE in beacons, to distribute the global time to
+/// the devices of their PAN. It contains the global time in seconds.
+///
+/// # Example
+///
+/// ``` rust
+/// use byte::BytesExt;
+/// use ieee802154::mac::ie::{GlobalTimeIe, HeaderIe};
+///
+/// let ie = HeaderIe::GlobalTime(GlobalTimeIe { seconds: 0x01020304 });
+///
+/// let mut bytes = [0u8; 6];
+/// bytes.write(&mut 0, ie).unwrap();
+/// assert_eq!(bytes, [0x84, 0x14, 0x04, 0x03, 0x02, 0x01]);
+/// assert_eq!(bytes.read::<HeaderIe>(&mut 0), Ok(ie));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct GlobalTimeIe {
+    /// The global time, in seconds
+    pub seconds: u32,
+}
+
+impl TryRead<'_> for GlobalTimeIe {
+    fn try_read(bytes: &[u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        check_len(bytes, 4)?;
+        let seconds = bytes.read_with(offset, LE)?;
+        Ok((Self { seconds }, *offset))
+    }
+}
+
+impl TryWrite for GlobalTimeIe {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with(offset, self.seconds, LE)?;
+        Ok(*offset)
+    }
+}
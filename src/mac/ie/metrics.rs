@@ -0,0 +1,197 @@
+//! IEs of the exchange of MAC metrics
+
+use byte::{check_len, BytesExt, TryRead, TryWrite, LE};
+
+/// The MAC metrics that can be exchanged
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum MacMetric {
+    /// The number of frames that were transmitted after one retry
+    RetryCount = 0x01,
+    /// The number of frames that were transmitted after more than one retry
+    MultipleRetryCount = 0x02,
+    /// The number of frames that failed to be transmitted
+    TxFailCount = 0x03,
+    /// The number of frames that were transmitted without retries
+    TxSuccessCount = 0x04,
+    /// The number of frames that were received with an FCS error
+    FcsErrorCount = 0x05,
+    /// The number of frames that were received and failed security checks
+    SecurityFailureCount = 0x06,
+    /// The number of duplicate frames that were received
+    DuplicateFrameCount = 0x07,
+    /// The number of frames that were received successfully
+    RxSuccessCount = 0x08,
+    /// The number of frames that were negatively acknowledged
+    NackCount = 0x09,
+}
+
+impl MacMetric {
+    /// Creates an instance of [`MacMetric`] from the provided bits
+    ///
+    /// Returns `None`, if the metric ID is unknown to this crate.
+    pub const fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            0x01 => Some(MacMetric::RetryCount),
+            0x02 => Some(MacMetric::MultipleRetryCount),
+            0x03 => Some(MacMetric::TxFailCount),
+            0x04 => Some(MacMetric::TxSuccessCount),
+            0x05 => Some(MacMetric::FcsErrorCount),
+            0x06 => Some(MacMetric::SecurityFailureCount),
+            0x07 => Some(MacMetric::DuplicateFrameCount),
+            0x08 => Some(MacMetric::RxSuccessCount),
+            0x09 => Some(MacMetric::NackCount),
+            _ => None,
+        }
+    }
+}
+
+/// MAC Metrics IE
+///
+/// Carries the value of a single MAC metric.
+///
+/// # Example
+///
+/// ``` rust
+/// use ieee802154::mac::ie::{MacMetric, MacMetricsIe};
+///
+/// let ie = MacMetricsIe::new(MacMetric::TxFailCount, 3);
+/// assert_eq!(ie.metric(), Some(MacMetric::TxFailCount));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct MacMetricsIe {
+    /// The ID of the metric
+    pub metric_id: u8,
+    /// The value of the metric
+    pub count: u32,
+}
+
+impl MacMetricsIe {
+    /// Create a MAC Metrics IE for `metric`
+    pub const fn new(metric: MacMetric, count: u32) -> Self {
+        Self {
+            metric_id: metric as u8,
+            count,
+        }
+    }
+
+    /// The metric, if it is known to this crate
+    pub const fn metric(&self) -> Option<MacMetric> {
+        MacMetric::from_bits(self.metric_id)
+    }
+}
+
+impl TryRead<'_> for MacMetricsIe {
+    fn try_read(bytes: &[u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        check_len(bytes, 5)?;
+        let metric_id = bytes.read(offset)?;
+        let count = bytes.read_with(offset, LE)?;
+        Ok((Self { metric_id, count }, *offset))
+    }
+}
+
+impl TryWrite for MacMetricsIe {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write(offset, self.metric_id)?;
+        bytes.write_with(offset, self.count, LE)?;
+        Ok(*offset)
+    }
+}
+
+/// All MAC Metrics IE
+///
+/// Carries the values of all MAC metrics, in the order of their IDs.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct AllMacMetricsIe {
+    /// See [`MacMetric::RetryCount`]
+    pub retry_count: u32,
+    /// See [`MacMetric::MultipleRetryCount`]
+    pub multiple_retry_count: u32,
+    /// See [`MacMetric::TxFailCount`]
+    pub tx_fail_count: u32,
+    /// See [`MacMetric::TxSuccessCount`]
+    pub tx_success_count: u32,
+    /// See [`MacMetric::FcsErrorCount`]
+    pub fcs_error_count: u32,
+    /// See [`MacMetric::SecurityFailureCount`]
+    pub security_failure_count: u32,
+    /// See [`MacMetric::DuplicateFrameCount`]
+    pub duplicate_frame_count: u32,
+    /// See [`MacMetric::RxSuccessCount`]
+    pub rx_success_count: u32,
+    /// See [`MacMetric::NackCount`]
+    pub nack_count: u32,
+}
+
+impl AllMacMetricsIe {
+    /// The size of the content of this IE, in octets
+    pub const OCTET_SIZE: usize = 9 * 4;
+
+    /// The value of `metric`
+    pub const fn get(&self, metric: MacMetric) -> u32 {
+        match metric {
+            MacMetric::RetryCount => self.retry_count,
+            MacMetric::MultipleRetryCount => self.multiple_retry_count,
+            MacMetric::TxFailCount => self.tx_fail_count,
+            MacMetric::TxSuccessCount => self.tx_success_count,
+            MacMetric::FcsErrorCount => self.fcs_error_count,
+            MacMetric::SecurityFailureCount => self.security_failure_count,
+            MacMetric::DuplicateFrameCount => self.duplicate_frame_count,
+            MacMetric::RxSuccessCount => self.rx_success_count,
+            MacMetric::NackCount => self.nack_count,
+        }
+    }
+}
+
+impl TryRead<'_> for AllMacMetricsIe {
+    fn try_read(bytes: &[u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        check_len(bytes, Self::OCTET_SIZE)?;
+        Ok((
+            Self {
+                retry_count: bytes.read_with(offset, LE)?,
+                multiple_retry_count: bytes.read_with(offset, LE)?,
+                tx_fail_count: bytes.read_with(offset, LE)?,
+                tx_success_count: bytes.read_with(offset, LE)?,
+                fcs_error_count: bytes.read_with(offset, LE)?,
+                security_failure_count: bytes.read_with(offset, LE)?,
+                duplicate_frame_count: bytes.read_with(offset, LE)?,
+                rx_success_count: bytes.read_with(offset, LE)?,
+                nack_count: bytes.read_with(offset, LE)?,
+            },
+            *offset,
+        ))
+    }
+}
+
+impl TryWrite for AllMacMetricsIe {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        for count in [
+            self.retry_count,
+            self.multiple_retry_count,
+            self.tx_fail_count,
+            self.tx_success_count,
+            self.fcs_error_count,
+            self.security_failure_count,
+            self.duplicate_frame_count,
+            self.rx_success_count,
+            self.nack_count,
+        ]
+        .iter()
+        {
+            bytes.write_with(offset, *count, LE)?;
+        }
+        Ok(*offset)
+    }
+}
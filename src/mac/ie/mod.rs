@@ -0,0 +1,1880 @@
+//! Information Elements
+//!
+//! Information Elements (IEs) carry additional information in frames, e.g.
+//! for TSCH and CSL. Header IEs follow the MAC header, and are terminated by
+//! a header termination IE if payload IEs or a payload follow them. Payload
+//! IEs follow the header IEs, and are terminated by a payload termination IE
+//! if a payload follows them. The MLME payload IE contains nested IEs, and
+//! the MPX payload IE carries upper-layer frames.
+//!
+//! The IEs of Wi-SUN FAN are supported with the `wisun` feature. As that
+//! feature adds variants, the enums of IEs are `#[non_exhaustive]`.
+//!
+//! IEs that this crate knows are decoded into typed values. All other IEs are
+//! kept as their raw content, so they are encoded again unchanged. Use
+//! [`PreservedIe`] to also encode known IEs unchanged, e.g. when relaying
+//! frames.
+
+use byte::{check_len, BytesExt, TryRead, TryWrite, LE};
+
+use crate::mac::frame::EncodeError;
+use crate::mac::DecodeError;
+
+mod channel_hopping;
+mod csl;
+mod dsme;
+mod global_time;
+mod metrics;
+mod mpx;
+mod nested;
+mod payload;
+mod preserved;
+mod raw;
+mod rit;
+mod time_correction;
+mod tsch;
+#[cfg(feature = "wisun")]
+mod wisun;
+mod writer;
+
+pub use channel_hopping::{
+    ChannelHoppingIe, HoppingChannelIterator, HoppingChannels, HoppingSequence,
+};
+pub use csl::{from_ten_symbol_units, to_ten_symbol_units, CslIe};
+pub use dsme::{
+    BeaconBitmap, ChannelHoppingSpecification, DsmePanDescriptorIe,
+    DsmeSuperframeSpecification, SimplifiedSuperframeSpecificationIe,
+    TimeSynchronizationSpecification,
+};
+pub use global_time::GlobalTimeIe;
+pub use metrics::{AllMacMetricsIe, MacMetric, MacMetricsIe};
+pub use mpx::{MpxIe, MpxTransfer, TransferType, MAX_TRANSACTION_ID};
+pub use nested::{
+    LongSubId, NestedIe, NestedIeIterator, NestedIes, ShortSubId,
+    MAX_LONG_NESTED_IE_CONTENT_LEN, MAX_SHORT_NESTED_IE_CONTENT_LEN,
+};
+pub use payload::{
+    PayloadGroupId, PayloadIe, PayloadIeIterator, MAX_PAYLOAD_IE_CONTENT_LEN,
+};
+pub use preserved::PreservedIe;
+pub use raw::{HeaderIeIter, PayloadIeIter};
+pub use rit::{RendezvousTimeIe, RitIe};
+pub use time_correction::TimeCorrectionIe;
+pub use tsch::{
+    LinkDescriptor, LinkDescriptorIterator, LinkDescriptors, LinkOptions,
+    SlotframeDescriptor, SlotframeDescriptorIterator, TimeslotTemplate,
+    TschSlotframeAndLinkIe, TschSynchronizationIe, TschTimeslotIe,
+};
+#[cfg(feature = "wisun")]
+pub use wisun::{
+    BroadcastScheduleIe, BroadcastTimingIe, ChannelFunction, ChannelPlan,
+    ChannelSchedule, ExcludedChannelRangeIterator, ExcludedChannelRanges,
+    ExcludedChannels, FlowControlIe, PanIe, UnicastTimingIe, WisunFrameType,
+    WisunHeaderIe, WisunHeaderSubId, WisunIeIterator, WisunIes, WisunLongSubId,
+    WisunNestedIe, WisunShortSubId, MAX_NETWORK_NAME_LEN,
+};
+pub use writer::IeWriter;
+
+const LENGTH_MASK: u16 = 0x007f;
+const ELEMENT_ID_MASK: u16 = 0x7f80;
+const ELEMENT_ID_OFFSET: u16 = 7;
+const TYPE: u16 = 0x8000;
+
+/// The largest content length of a header IE, in octets
+pub const MAX_HEADER_IE_CONTENT_LEN: usize = 127;
+
+/// Element IDs of the header IEs that this crate knows
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[non_exhaustive]
+pub enum HeaderElementId {
+    /// CSL IE
+    Csl = 0x1a,
+    /// RIT IE
+    Rit = 0x1b,
+    /// DSME PAN Descriptor IE
+    DsmePanDescriptor = 0x1c,
+    /// Rendezvous Time IE
+    RendezvousTime = 0x1d,
+    /// Time Correction IE
+    TimeCorrection = 0x1e,
+    /// Simplified Superframe Specification IE
+    SimplifiedSuperframeSpecification = 0x23,
+    /// Global Time IE
+    GlobalTime = 0x29,
+    /// Wi-SUN header IE (WH-IE)
+    #[cfg(feature = "wisun")]
+    Wisun = 0x2a,
+    /// Header Termination 1 IE, followed by payload IEs
+    HeaderTermination1 = 0x7e,
+    /// Header Termination 2 IE, followed by the frame payload
+    HeaderTermination2 = 0x7f,
+}
+
+impl HeaderElementId {
+    /// Creates an instance of [`HeaderElementId`] from the provided bits
+    ///
+    /// Returns `None`, if the element ID is unknown to this crate.
+    pub const fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            0x1a => Some(HeaderElementId::Csl),
+            0x1b => Some(HeaderElementId::Rit),
+            0x1c => Some(HeaderElementId::DsmePanDescriptor),
+            0x1d => Some(HeaderElementId::RendezvousTime),
+            0x1e => Some(HeaderElementId::TimeCorrection),
+            0x23 => Some(HeaderElementId::SimplifiedSuperframeSpecification),
+            0x29 => Some(HeaderElementId::GlobalTime),
+            #[cfg(feature = "wisun")]
+            0x2a => Some(HeaderElementId::Wisun),
+            0x7e => Some(HeaderElementId::HeaderTermination1),
+            0x7f => Some(HeaderElementId::HeaderTermination2),
+            _ => None,
+        }
+    }
+}
+
+/// A header IE
+///
+/// # Example
+///
+/// ``` rust
+/// use byte::BytesExt;
+/// use ieee802154::mac::ie::{HeaderIe, TimeCorrectionIe};
+///
+/// let ie = HeaderIe::TimeCorrection(TimeCorrectionIe::new(10, false).unwrap());
+///
+/// let mut bytes = [0u8; 4];
+/// let len = &mut 0;
+/// bytes.write(len, ie).unwrap();
+/// assert_eq!(*len, ie.get_octet_size());
+/// assert_eq!(bytes, [0x02, 0x0f, 0x0a, 0x00]);
+///
+/// let decoded: HeaderIe = bytes.read(&mut 0).unwrap();
+/// assert_eq!(decoded, ie);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum HeaderIe<'a> {
+    /// CSL IE
+    Csl(CslIe),
+    /// RIT IE
+    Rit(RitIe),
+    /// DSME PAN Descriptor IE
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    DsmePanDescriptor(DsmePanDescriptorIe<'a>),
+    /// Rendezvous Time IE
+    RendezvousTime(RendezvousTimeIe),
+    /// Time Correction IE
+    TimeCorrection(TimeCorrectionIe),
+    /// Simplified Superframe Specification IE
+    SimplifiedSuperframeSpecification(SimplifiedSuperframeSpecificationIe),
+    /// Global Time IE
+    GlobalTime(GlobalTimeIe),
+    /// Wi-SUN header IE (WH-IE)
+    #[cfg(feature = "wisun")]
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    Wisun(WisunHeaderIe<'a>),
+    /// Header Termination 1 IE
+    HeaderTermination1,
+    /// Header Termination 2 IE
+    HeaderTermination2,
+    /// An IE that is unknown to this crate
+    Unknown {
+        /// The element ID of the IE
+        element_id: u8,
+        /// The content of the IE
+        content: &'a [u8],
+    },
+}
+
+impl HeaderIe<'_> {
+    /// The element ID of this IE
+    pub fn element_id(&self) -> u8 {
+        let element_id = match self {
+            HeaderIe::Csl(_) => HeaderElementId::Csl,
+            HeaderIe::Rit(_) => HeaderElementId::Rit,
+            HeaderIe::DsmePanDescriptor(_) => {
+                HeaderElementId::DsmePanDescriptor
+            }
+            HeaderIe::RendezvousTime(_) => HeaderElementId::RendezvousTime,
+            HeaderIe::TimeCorrection(_) => HeaderElementId::TimeCorrection,
+            HeaderIe::SimplifiedSuperframeSpecification(_) => {
+                HeaderElementId::SimplifiedSuperframeSpecification
+            }
+            HeaderIe::GlobalTime(_) => HeaderElementId::GlobalTime,
+            #[cfg(feature = "wisun")]
+            HeaderIe::Wisun(_) => HeaderElementId::Wisun,
+            HeaderIe::HeaderTermination1 => HeaderElementId::HeaderTermination1,
+            HeaderIe::HeaderTermination2 => HeaderElementId::HeaderTermination2,
+            HeaderIe::Unknown { element_id, .. } => return *element_id,
+        };
+        element_id as u8
+    }
+
+    /// The length of the content of this IE, in octets
+    pub fn content_len(&self) -> usize {
+        match self {
+            HeaderIe::Csl(ie) => ie.get_octet_size(),
+            HeaderIe::Rit(_) => 4,
+            HeaderIe::DsmePanDescriptor(ie) => ie.get_octet_size(),
+            HeaderIe::RendezvousTime(ie) => ie.get_octet_size(),
+            HeaderIe::TimeCorrection(_) => 2,
+            HeaderIe::SimplifiedSuperframeSpecification(_) => 6,
+            HeaderIe::GlobalTime(_) => 4,
+            #[cfg(feature = "wisun")]
+            HeaderIe::Wisun(ie) => ie.get_octet_size(),
+            HeaderIe::HeaderTermination1 | HeaderIe::HeaderTermination2 => 0,
+            HeaderIe::Unknown { content, .. } => content.len(),
+        }
+    }
+
+    /// Get the size of this IE in octets, including its descriptor
+    pub fn get_octet_size(&self) -> usize {
+        2 + self.content_len()
+    }
+
+    /// Whether this IE terminates the list of header IEs
+    pub fn is_termination(&self) -> bool {
+        matches!(
+            self,
+            HeaderIe::HeaderTermination1 | HeaderIe::HeaderTermination2
+        )
+    }
+}
+
+impl<'a> TryRead<'a> for HeaderIe<'a> {
+    fn try_read(bytes: &'a [u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let (element_id, content) = read_raw_header_ie(bytes, offset)?;
+        let len = content.len();
+
+        let ie = match HeaderElementId::from_bits(element_id) {
+            Some(HeaderElementId::Csl) => HeaderIe::Csl(read_content(content)?),
+            Some(HeaderElementId::Rit) => HeaderIe::Rit(read_content(content)?),
+            Some(HeaderElementId::DsmePanDescriptor) => {
+                HeaderIe::DsmePanDescriptor(read_content(content)?)
+            }
+            Some(HeaderElementId::RendezvousTime) => {
+                HeaderIe::RendezvousTime(read_content(content)?)
+            }
+            Some(HeaderElementId::TimeCorrection) => {
+                HeaderIe::TimeCorrection(read_content(content)?)
+            }
+            Some(HeaderElementId::SimplifiedSuperframeSpecification) => {
+                HeaderIe::SimplifiedSuperframeSpecification(read_content(
+                    content,
+                )?)
+            }
+            Some(HeaderElementId::GlobalTime) => {
+                HeaderIe::GlobalTime(read_content(content)?)
+            }
+            #[cfg(feature = "wisun")]
+            Some(HeaderElementId::Wisun) => {
+                HeaderIe::Wisun(read_content(content)?)
+            }
+            Some(HeaderElementId::HeaderTermination1) if len == 0 => {
+                HeaderIe::HeaderTermination1
+            }
+            Some(HeaderElementId::HeaderTermination2) if len == 0 => {
+                HeaderIe::HeaderTermination2
+            }
+            Some(_) => return Err(DecodeError::IeLengthMismatch)?,
+            None => HeaderIe::Unknown {
+                element_id,
+                content,
+            },
+        };
+        Ok((ie, *offset))
+    }
+}
+
+impl TryWrite for HeaderIe<'_> {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        let len = self.content_len();
+        if len > MAX_HEADER_IE_CONTENT_LEN {
+            return Err(EncodeError::WriteError)?;
+        }
+        let descriptor =
+            (self.element_id() as u16) << ELEMENT_ID_OFFSET | len as u16;
+        bytes.write_with(offset, descriptor, LE)?;
+
+        match self {
+            HeaderIe::Csl(ie) => bytes.write(offset, ie)?,
+            HeaderIe::Rit(ie) => bytes.write(offset, ie)?,
+            HeaderIe::DsmePanDescriptor(ie) => bytes.write(offset, ie)?,
+            HeaderIe::RendezvousTime(ie) => bytes.write(offset, ie)?,
+            HeaderIe::TimeCorrection(ie) => bytes.write(offset, ie)?,
+            HeaderIe::SimplifiedSuperframeSpecification(ie) => {
+                bytes.write(offset, ie)?
+            }
+            HeaderIe::GlobalTime(ie) => bytes.write(offset, ie)?,
+            #[cfg(feature = "wisun")]
+            HeaderIe::Wisun(ie) => bytes.write(offset, ie)?,
+            HeaderIe::HeaderTermination1 | HeaderIe::HeaderTermination2 => (),
+            HeaderIe::Unknown { content, .. } => {
+                bytes.write(offset, content)?
+            }
+        }
+        Ok(*offset)
+    }
+}
+
+/// Read the element ID and the content of a header IE
+fn read_raw_header_ie<'a>(
+    bytes: &'a [u8],
+    offset: &mut usize,
+) -> byte::Result<(u8, &'a [u8])> {
+    let bytes = bytes.get(*offset..).ok_or(DecodeError::NotEnoughBytes)?;
+    let start = &mut 0;
+    check_len(bytes, 2)?;
+    let descriptor: u16 = bytes.read_with(start, LE)?;
+    if descriptor & TYPE != 0 {
+        // A payload IE
+        let group_id = ((descriptor & payload::GROUP_ID_MASK)
+            >> payload::GROUP_ID_OFFSET) as u8;
+        return Err(DecodeError::InvalidElementId(group_id).into());
+    }
+    let len = (descriptor & LENGTH_MASK) as usize;
+    let element_id =
+        ((descriptor & ELEMENT_ID_MASK) >> ELEMENT_ID_OFFSET) as u8;
+
+    if bytes.len() - *start < len {
+        return Err(DecodeError::IeLengthMismatch.into());
+    }
+    let content = &bytes[*start..*start + len];
+    *offset += *start + len;
+    Ok((element_id, content))
+}
+
+/// Whether `bytes` start with the descriptor of a payload IE
+fn is_payload_ie(bytes: &[u8]) -> bool {
+    bytes.len() >= 2 && bytes[1] & (TYPE >> 8) as u8 != 0
+}
+
+/// Read the typed content of an IE, which must span all of `content`
+fn read_content<'a, T: TryRead<'a>>(content: &'a [u8]) -> byte::Result<T> {
+    let offset = &mut 0;
+    let value = content.read(offset).map_err(|e| match e {
+        byte::Error::Incomplete => DecodeError::IeLengthMismatch.into(),
+        e => e,
+    })?;
+    if *offset != content.len() {
+        return Err(DecodeError::IeLengthMismatch.into());
+    }
+    Ok(value)
+}
+
+/// An iterator over a list of header IEs
+///
+/// The iterator ends after a header termination IE, at the end of the bytes,
+/// or after yielding an error for a malformed IE. After a header termination
+/// IE, [`HeaderIeIterator::offset`] is where the payload IEs or the payload
+/// start. Use [`HeaderIeIterator::payload_ies`] to iterate over the payload
+/// IEs.
+///
+/// # Example
+///
+/// ``` rust
+/// use ieee802154::mac::ie::{HeaderIe, HeaderIeIterator};
+///
+/// let bytes = [0x02, 0x0f, 0x0a, 0x80, 0x80, 0x3f, 0xaa];
+/// let mut iter = HeaderIeIterator::new(&bytes);
+///
+/// let ie = iter.next().unwrap().unwrap();
+/// match ie {
+///     HeaderIe::TimeCorrection(ie) => {
+///         assert_eq!(ie.time_correction_us(), 10);
+///         assert!(ie.nack());
+///     }
+///     _ => unreachable!(),
+/// }
+/// assert_eq!(iter.next().unwrap().unwrap(), HeaderIe::HeaderTermination2);
+/// assert!(iter.next().is_none());
+/// assert_eq!(&bytes[iter.offset()..], &[0xaa]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct HeaderIeIterator<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    done: bool,
+    payload_ies_follow: bool,
+}
+
+impl<'a> HeaderIeIterator<'a> {
+    /// Iterate over the header IEs at the start of `bytes`
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            offset: 0,
+            done: false,
+            payload_ies_follow: false,
+        }
+    }
+
+    /// The offset of the first octet after the IEs that were read so far
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Iterate over the payload IEs that follow the header IEs
+    ///
+    /// Returns `None`, if no header termination 1 IE has been read, i.e. if
+    /// the iteration over the header IEs hasn't ended yet, or if no payload
+    /// IEs follow them.
+    pub fn payload_ies(&self) -> Option<PayloadIeIterator<'a>> {
+        if self.payload_ies_follow {
+            Some(PayloadIeIterator::new(&self.bytes[self.offset..]))
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a> Iterator for HeaderIeIterator<'a> {
+    type Item = byte::Result<HeaderIe<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.offset >= self.bytes.len() {
+            return None;
+        }
+        match self.bytes.read::<HeaderIe>(&mut self.offset) {
+            Ok(ie) => {
+                self.done = ie.is_termination();
+                self.payload_ies_follow = ie == HeaderIe::HeaderTermination1;
+                Some(Ok(ie))
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// The header IEs and payload IEs of a frame
+///
+/// Writing the IEs inserts the termination IEs that are required, so the
+/// lists must not contain termination IEs themselves. The context of writing
+/// is whether a frame payload follows the IEs. Use
+/// [`EncodedInformationElements`] to read the IEs of a frame.
+///
+/// # Example
+///
+/// ``` rust
+/// use byte::BytesExt;
+/// use ieee802154::mac::ie::{HeaderIe, InformationElements, TimeCorrectionIe};
+///
+/// let header = [HeaderIe::TimeCorrection(TimeCorrectionIe::new(1, false).unwrap())];
+/// let ies = InformationElements {
+///     header: &header,
+///     payload: &[],
+/// };
+///
+/// let mut bytes = [0u8; 6];
+/// let len = &mut 0;
+/// bytes.write_with(len, ies, true).unwrap();
+/// // A header termination 2 IE is added, as a payload follows the IEs
+/// assert_eq!(&bytes[..*len], &[0x02, 0x0f, 0x01, 0x00, 0x80, 0x3f]);
+/// assert_eq!(*len, ies.get_octet_size(true));
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct InformationElements<'a> {
+    /// The header IEs
+    pub header: &'a [HeaderIe<'a>],
+    /// The payload IEs
+    pub payload: &'a [PayloadIe<'a>],
+}
+
+impl InformationElements<'_> {
+    /// Get the size of the IEs in octets, including the termination IEs
+    ///
+    /// `payload_follows` is whether a frame payload follows the IEs.
+    pub fn get_octet_size(&self, payload_follows: bool) -> usize {
+        let header: usize =
+            self.header.iter().map(HeaderIe::get_octet_size).sum();
+        let payload: usize =
+            self.payload.iter().map(PayloadIe::get_octet_size).sum();
+        let mut len = header + payload;
+        if !self.payload.is_empty() {
+            // Header termination 1 IE
+            len += 2;
+            if payload_follows {
+                // Payload termination IE
+                len += 2;
+            }
+        } else if payload_follows {
+            // Header termination 2 IE
+            len += 2;
+        }
+        len
+    }
+}
+
+impl TryWrite<bool> for InformationElements<'_> {
+    fn try_write(
+        self,
+        bytes: &mut [u8],
+        payload_follows: bool,
+    ) -> byte::Result<usize> {
+        let offset = &mut 0;
+        if self.header.iter().any(HeaderIe::is_termination)
+            || self.payload.iter().any(PayloadIe::is_termination)
+        {
+            return Err(EncodeError::WriteError.into());
+        }
+
+        for ie in self.header {
+            bytes.write(offset, *ie)?;
+        }
+        if !self.payload.is_empty() {
+            bytes.write(offset, HeaderIe::HeaderTermination1)?;
+            for ie in self.payload {
+                bytes.write(offset, *ie)?;
+            }
+            if payload_follows {
+                bytes.write(offset, PayloadIe::PayloadTermination)?;
+            }
+        } else if payload_follows {
+            bytes.write(offset, HeaderIe::HeaderTermination2)?;
+        }
+        Ok(*offset)
+    }
+}
+
+/// The header IEs and payload IEs that were read from a frame
+///
+/// Reading checks that all IEs are well-formed, and that the termination IEs
+/// are placed correctly:
+///
+/// - A header termination 1 IE must be followed by payload IEs
+/// - A header termination 2 IE and a payload termination IE must be
+///   followed by a frame payload
+/// - Without termination IEs, the IEs extend until the end of the frame
+///
+/// The number of octets that are read is where the frame payload starts.
+/// The termination IEs are not part of the lists.
+///
+/// # Example
+///
+/// ``` rust
+/// use byte::BytesExt;
+/// use ieee802154::mac::ie::{EncodedInformationElements, HeaderIe};
+///
+/// // A Time Correction IE and a header termination 2 IE, and a payload
+/// let bytes = [0x02, 0x0f, 0x01, 0x00, 0x80, 0x3f, 0xaa];
+/// let offset = &mut 0;
+/// let ies: EncodedInformationElements = bytes.read(offset).unwrap();
+/// assert_eq!(&bytes[*offset..], &[0xaa]);
+/// assert_eq!(ies.header_ies().count(), 1);
+/// assert_eq!(ies.payload_ies().count(), 0);
+///
+/// // A header termination 2 IE without a payload
+/// assert!(bytes[..6].read::<EncodedInformationElements>(&mut 0).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct EncodedInformationElements<'a> {
+    /// The encoded header IEs, without termination IE
+    pub header: &'a [u8],
+    /// The encoded payload IEs, without termination IE
+    pub payload: &'a [u8],
+}
+
+impl<'a> EncodedInformationElements<'a> {
+    /// Iterate over the header IEs
+    pub fn header_ies(&self) -> HeaderIeIterator<'a> {
+        HeaderIeIterator::new(self.header)
+    }
+
+    /// Iterate over the payload IEs
+    pub fn payload_ies(&self) -> PayloadIeIterator<'a> {
+        PayloadIeIterator::new(self.payload)
+    }
+}
+
+impl<'a> TryRead<'a> for EncodedInformationElements<'a> {
+    fn try_read(bytes: &'a [u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+
+        let mut header_termination = None;
+        while *offset < bytes.len() {
+            let start = *offset;
+            if is_payload_ie(&bytes[start..]) {
+                // Payload IEs must follow a header termination 1 IE
+                return Err(DecodeError::MissingIeTermination.into());
+            }
+            let ie: HeaderIe = bytes.read(offset)?;
+            if ie.is_termination() {
+                header_termination = Some((ie, start));
+                break;
+            }
+        }
+
+        let (header, payload) = match header_termination {
+            None => (bytes, &bytes[bytes.len()..]),
+            Some((HeaderIe::HeaderTermination2, start)) => {
+                if *offset == bytes.len() {
+                    return Err(DecodeError::MissingIeTermination.into());
+                }
+                (&bytes[..start], &bytes[*offset..*offset])
+            }
+            Some((_, start)) => {
+                let header = &bytes[..start];
+                let payload_start = *offset;
+                if payload_start == bytes.len() {
+                    return Err(DecodeError::MissingIeTermination.into());
+                }
+                let mut payload_end = bytes.len();
+                while *offset < bytes.len() {
+                    let start = *offset;
+                    if !is_payload_ie(&bytes[start..]) {
+                        // A payload must follow a payload termination IE
+                        return Err(DecodeError::MissingIeTermination.into());
+                    }
+                    let ie: PayloadIe = bytes.read(offset)?;
+                    if ie.is_termination() {
+                        if *offset == bytes.len() {
+                            return Err(
+                                DecodeError::MissingIeTermination.into()
+                            );
+                        }
+                        payload_end = start;
+                        break;
+                    }
+                }
+                (header, &bytes[payload_start..payload_end])
+            }
+        };
+        Ok((Self { header, payload }, *offset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mac::beacon::{
+        BeaconOrder, PendingAddress, SuperframeOrder, SuperframeSpecification,
+    };
+
+    const SUPERFRAME_SPEC: SuperframeSpecification = SuperframeSpecification {
+        beacon_order: BeaconOrder::BeaconOrder(6),
+        superframe_order: SuperframeOrder::SuperframeOrder(4),
+        final_cap_slot: 15,
+        battery_life_extension: false,
+        pan_coordinator: true,
+        association_permit: true,
+    };
+
+    #[test]
+    fn time_correction_bits() {
+        for &(us, nack, bits) in &[
+            (0, false, 0x0000),
+            (1, true, 0x8001),
+            (-1, false, 0x0fff),
+            (TimeCorrectionIe::MAX_US, false, 0x07ff),
+            (TimeCorrectionIe::MIN_US, true, 0x8800),
+        ] {
+            let ie = TimeCorrectionIe::new(us, nack).unwrap();
+            assert_eq!(ie.to_bits(), bits);
+            assert_eq!(TimeCorrectionIe::from_bits(bits), ie);
+        }
+        // Reserved bits are ignored
+        assert_eq!(
+            TimeCorrectionIe::from_bits(0x7001),
+            TimeCorrectionIe::new(1, false).unwrap()
+        );
+
+        assert!(TimeCorrectionIe::new(2048, false).is_none());
+        assert!(TimeCorrectionIe::new(-2049, false).is_none());
+        assert_eq!(
+            TimeCorrectionIe::saturating(-3000, false).time_correction_us(),
+            TimeCorrectionIe::MIN_US
+        );
+        assert_eq!(
+            TimeCorrectionIe::saturating(-30, false).time_correction_us(),
+            -30
+        );
+    }
+
+    #[test]
+    fn csl() {
+        for &(ie, encoded) in &[
+            (
+                CslIe {
+                    phase: 0x0102,
+                    period: 0x0304,
+                    rendezvous_time: None,
+                },
+                &[0x04, 0x0d, 0x02, 0x01, 0x04, 0x03][..],
+            ),
+            (
+                CslIe {
+                    phase: 0x0102,
+                    period: 0x0304,
+                    rendezvous_time: Some(0x0506),
+                },
+                &[0x06, 0x0d, 0x02, 0x01, 0x04, 0x03, 0x06, 0x05][..],
+            ),
+        ] {
+            let ie = HeaderIe::Csl(ie);
+            let mut bytes = [0u8; 8];
+            let len = &mut 0;
+            bytes.write(len, ie).unwrap();
+            assert_eq!(&bytes[..*len], encoded);
+            assert_eq!(encoded.read::<HeaderIe>(&mut 0).unwrap(), ie);
+        }
+
+        // A CSL IE of an invalid length
+        assert!([0x05, 0x0d, 0, 0, 0, 0, 0]
+            .read::<HeaderIe>(&mut 0)
+            .is_err());
+        assert!([0x02, 0x0d, 0, 0].read::<HeaderIe>(&mut 0).is_err());
+    }
+
+    #[test]
+    fn rit_and_rendezvous_time() {
+        for &(ie, encoded) in &[
+            (
+                HeaderIe::Rit(RitIe {
+                    time_to_first_listen: 0x01,
+                    number_of_repeat_listen: 0x02,
+                    repeat_listen_interval: 0x0304,
+                }),
+                &[0x84, 0x0d, 0x01, 0x02, 0x04, 0x03][..],
+            ),
+            (
+                HeaderIe::RendezvousTime(RendezvousTimeIe {
+                    rendezvous_time: 0x0102,
+                    wake_up_interval: None,
+                }),
+                &[0x82, 0x0e, 0x02, 0x01][..],
+            ),
+            (
+                HeaderIe::RendezvousTime(RendezvousTimeIe {
+                    rendezvous_time: 0x0102,
+                    wake_up_interval: Some(0x0304),
+                }),
+                &[0x84, 0x0e, 0x02, 0x01, 0x04, 0x03][..],
+            ),
+        ] {
+            let mut bytes = [0u8; 8];
+            let len = &mut 0;
+            bytes.write(len, ie).unwrap();
+            assert_eq!(&bytes[..*len], encoded);
+            assert_eq!(encoded.read::<HeaderIe>(&mut 0).unwrap(), ie);
+        }
+
+        let ie = RendezvousTimeIe {
+            rendezvous_time: 3,
+            wake_up_interval: Some(50),
+        };
+        assert_eq!(ie.rendezvous_time_symbols(), 30);
+        assert_eq!(ie.wake_up_interval_symbols(), Some(500));
+
+        // A RIT IE of an invalid length
+        assert!([0x83, 0x0d, 0, 0, 0].read::<HeaderIe>(&mut 0).is_err());
+    }
+
+    #[test]
+    fn dsme_pan_descriptor() {
+        let ie = HeaderIe::DsmePanDescriptor(DsmePanDescriptorIe {
+            superframe_spec: SUPERFRAME_SPEC,
+            pending_address: PendingAddress::new(),
+            dsme_superframe_spec: DsmeSuperframeSpecification {
+                multi_superframe_order: 7,
+                channel_hopping: true,
+                cap_reduction: false,
+                deferred_beacon: true,
+            },
+            time_synchronization: TimeSynchronizationSpecification {
+                beacon_timestamp: 0x0605_0403_0201,
+                beacon_offset_timestamp: 0x0807,
+            },
+            beacon_bitmap: BeaconBitmap {
+                sd_index: 0x0a09,
+                sd_bitmap: &[0x0b],
+            },
+            channel_hopping: Some(ChannelHoppingSpecification {
+                hopping_sequence_id: 0x0c,
+                pan_coordinator_bsn: 0x0d,
+                channel_offset: 0x0f0e,
+                channel_offset_bitmap: &[0x10, 0x11],
+            }),
+            group_ack: Some([0x12; 9]),
+        });
+        let encoded = [
+            0x20, 0x0e, // Descriptor
+            0x46, 0xcf, // Superframe specification
+            0x00, // Pending addresses
+            0x97, // DSME superframe specification
+            0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, // Time sync
+            0x09, 0x0a, 0x01, 0x0b, // Beacon bitmap
+            0x0c, 0x0d, 0x0e, 0x0f, 0x02, 0x10, 0x11, // Channel hopping
+            0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12, 0x12,
+            0x12, // Group ack
+        ];
+        assert_eq!(ie.get_octet_size(), encoded.len());
+        let mut bytes = [0u8; 34];
+        bytes.write(&mut 0, ie).unwrap();
+        assert_eq!(bytes, encoded);
+        assert_eq!(encoded.read::<HeaderIe>(&mut 0), Ok(ie));
+
+        // Without channel hopping and group acknowledgements
+        let ie = match ie {
+            HeaderIe::DsmePanDescriptor(ie) => ie,
+            _ => unreachable!(),
+        };
+        let without = DsmePanDescriptorIe {
+            dsme_superframe_spec: DsmeSuperframeSpecification::default(),
+            channel_hopping: None,
+            group_ack: None,
+            ..ie
+        };
+        let mut bytes = [0u8; 18];
+        bytes
+            .write(&mut 0, HeaderIe::DsmePanDescriptor(without))
+            .unwrap();
+        assert_eq!(bytes[..2], [0x10, 0x0e]);
+        assert_eq!(
+            bytes.read::<HeaderIe>(&mut 0),
+            Ok(HeaderIe::DsmePanDescriptor(without))
+        );
+
+        // The channel hopping specification must match the channel
+        // diversity mode
+        let invalid = DsmePanDescriptorIe {
+            channel_hopping: None,
+            ..ie
+        };
+        assert!([0u8; 32]
+            .write(&mut 0, HeaderIe::DsmePanDescriptor(invalid))
+            .is_err());
+    }
+
+    #[test]
+    fn simplified_superframe_specification() {
+        let ie = HeaderIe::SimplifiedSuperframeSpecification(
+            SimplifiedSuperframeSpecificationIe {
+                timestamp: 0x0102,
+                superframe_spec: SUPERFRAME_SPEC,
+                cfp_spec: 0x0304,
+            },
+        );
+        let encoded = [0x86, 0x11, 0x02, 0x01, 0x46, 0xcf, 0x04, 0x03];
+        let mut bytes = [0u8; 8];
+        bytes.write(&mut 0, ie).unwrap();
+        assert_eq!(bytes, encoded);
+        assert_eq!(encoded.read::<HeaderIe>(&mut 0), Ok(ie));
+    }
+
+    #[test]
+    fn ten_symbol_units() {
+        assert_eq!(to_ten_symbol_units(0), Some(0));
+        assert_eq!(to_ten_symbol_units(19), Some(1));
+        assert_eq!(to_ten_symbol_units(655_359), Some(u16::MAX));
+        assert_eq!(to_ten_symbol_units(655_360), None);
+        assert_eq!(from_ten_symbol_units(u16::MAX), 655_350);
+
+        let ie = CslIe::from_symbols(25, 1000, Some(42)).unwrap();
+        assert_eq!(ie.phase, 2);
+        assert_eq!(ie.period, 100);
+        assert_eq!(ie.rendezvous_time, Some(4));
+        assert_eq!(ie.phase_symbols(), 20);
+        assert_eq!(ie.rendezvous_time_symbols(), Some(40));
+        assert!(CslIe::from_symbols(0, 0, Some(1_000_000)).is_none());
+    }
+
+    #[test]
+    fn header_ie_list() {
+        let ies = [
+            HeaderIe::TimeCorrection(
+                TimeCorrectionIe::new(-200, true).unwrap(),
+            ),
+            HeaderIe::Unknown {
+                element_id: 0x20,
+                content: &[1, 2, 3],
+            },
+            HeaderIe::HeaderTermination1,
+        ];
+        let mut bytes = [0u8; 32];
+        let offset = &mut 0;
+        for ie in ies.iter() {
+            bytes.write(offset, *ie).unwrap();
+        }
+        assert_eq!(
+            &bytes[..*offset],
+            &[0x02, 0x0f, 0x38, 0x8f, 0x03, 0x10, 1, 2, 3, 0x00, 0x3f]
+        );
+
+        let mut iter = HeaderIeIterator::new(&bytes);
+        for ie in ies.iter() {
+            assert_eq!(iter.next().unwrap().unwrap(), *ie);
+        }
+        assert!(iter.next().is_none());
+        assert_eq!(iter.offset(), *offset);
+    }
+
+    #[test]
+    fn payload_ies() {
+        let nested = [
+            NestedIe::UnknownShort {
+                sub_id: 0x7f,
+                content: &[1, 2],
+            },
+            NestedIe::UnknownLong {
+                sub_id: 0xf,
+                content: &[3],
+            },
+        ];
+        let payload = [
+            PayloadIe::Mlme(NestedIes::Decoded(&nested)),
+            PayloadIe::Unknown {
+                group_id: 0x2,
+                content: &[4, 5],
+            },
+        ];
+        let header = [HeaderIe::TimeCorrection(
+            TimeCorrectionIe::new(0, false).unwrap(),
+        )];
+        let ies = InformationElements {
+            header: &header,
+            payload: &payload,
+        };
+
+        let mut bytes = [0u8; 32];
+        let len = &mut 0;
+        bytes.write_with(len, ies, true).unwrap();
+        assert_eq!(*len, ies.get_octet_size(true));
+        assert_eq!(
+            &bytes[..*len],
+            &[
+                0x02, 0x0f, 0x00, 0x00, // Time correction IE
+                0x00, 0x3f, // Header termination 1 IE
+                0x07, 0x88, // MLME IE
+                0x02, 0x7f, 1, 2, // Short nested IE
+                0x01, 0xf8, 3, // Long nested IE
+                0x02, 0x90, 4, 5, // Vendor specific IE
+                0x00, 0xf8, // Payload termination IE
+            ][..]
+        );
+
+        let mut header_ies = HeaderIeIterator::new(&bytes[..*len]);
+        assert!(header_ies.payload_ies().is_none());
+        assert_eq!(header_ies.next().unwrap().unwrap(), header[0]);
+        assert_eq!(
+            header_ies.next().unwrap().unwrap(),
+            HeaderIe::HeaderTermination1
+        );
+        assert!(header_ies.next().is_none());
+
+        let mut payload_ies = header_ies.payload_ies().unwrap();
+        match payload_ies.next().unwrap().unwrap() {
+            PayloadIe::Mlme(ies) => {
+                let mut ies = ies.iter();
+                assert_eq!(ies.next().unwrap().unwrap(), nested[0]);
+                assert_eq!(ies.next().unwrap().unwrap(), nested[1]);
+                assert!(ies.next().is_none());
+            }
+            _ => unreachable!(),
+        }
+        assert_eq!(payload_ies.next().unwrap().unwrap(), payload[1]);
+        assert_eq!(
+            payload_ies.next().unwrap().unwrap(),
+            PayloadIe::PayloadTermination
+        );
+        assert!(payload_ies.next().is_none());
+        assert_eq!(header_ies.offset() + payload_ies.offset(), *len);
+
+        // Without a payload, no termination IEs are needed after the
+        // payload IEs, and none at all without payload IEs
+        assert_eq!(ies.get_octet_size(false), *len - 2);
+        let ies = InformationElements {
+            header: &header,
+            payload: &[],
+        };
+        assert_eq!(ies.get_octet_size(false), 4);
+        assert_eq!([0u8; 4].write_with(&mut 0, ies, false), Ok(()));
+
+        // Termination IEs are added when writing
+        let ies = InformationElements {
+            header: &[HeaderIe::HeaderTermination2],
+            payload: &[],
+        };
+        assert!([0u8; 4].write_with(&mut 0, ies, false).is_err());
+    }
+
+    #[test]
+    fn tsch_synchronization() {
+        let ie = NestedIe::TschSynchronization(TschSynchronizationIe {
+            asn: TschSynchronizationIe::MAX_ASN,
+            join_metric: 2,
+        });
+        let encoded = [0x06, 0x1a, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02];
+        let mut bytes = [0u8; 8];
+        bytes.write(&mut 0, ie).unwrap();
+        assert_eq!(bytes, encoded);
+        assert_eq!(encoded.read::<NestedIe>(&mut 0), Ok(ie));
+
+        let ie = NestedIe::TschSynchronization(TschSynchronizationIe {
+            asn: TschSynchronizationIe::MAX_ASN + 1,
+            join_metric: 0,
+        });
+        assert!([0u8; 8].write(&mut 0, ie).is_err());
+        // A TSCH synchronization IE of an invalid length
+        assert!([0x05, 0x1a, 0, 0, 0, 0, 0]
+            .read::<NestedIe>(&mut 0)
+            .is_err());
+    }
+
+    #[test]
+    fn tsch_slotframe_and_link() {
+        let links = [
+            LinkDescriptor {
+                timeslot: 0x0102,
+                channel_offset: 0x0304,
+                options: LinkOptions {
+                    tx: true,
+                    priority: true,
+                    ..Default::default()
+                },
+            },
+            LinkDescriptor::MINIMAL_CELL,
+        ];
+        let slotframes = [
+            SlotframeDescriptor {
+                handle: 1,
+                size: 0x0506,
+                links: LinkDescriptors::Decoded(&links),
+            },
+            SlotframeDescriptor {
+                handle: 2,
+                size: 7,
+                links: LinkDescriptors::Decoded(&[]),
+            },
+        ];
+        let ie = NestedIe::TschSlotframeAndLink(
+            TschSlotframeAndLinkIe::Decoded(&slotframes),
+        );
+        let encoded = [
+            0x13, 0x1b, // Descriptor
+            0x02, // Number of slotframes
+            0x01, 0x06, 0x05, 0x02, // First slotframe
+            0x02, 0x01, 0x04, 0x03, 0x11, // First link
+            0x00, 0x00, 0x00, 0x00, 0x0f, // Second link
+            0x02, 0x07, 0x00, 0x00, // Second slotframe
+        ];
+        assert_eq!(ie.get_octet_size(), encoded.len());
+        let mut bytes = [0u8; 21];
+        bytes.write(&mut 0, ie).unwrap();
+        assert_eq!(bytes, encoded);
+
+        let decoded = match encoded.read(&mut 0).unwrap() {
+            NestedIe::TschSlotframeAndLink(ie) => ie,
+            _ => unreachable!(),
+        };
+        assert_eq!(decoded.len(), 2);
+        for (decoded, slotframe) in decoded.iter().zip(slotframes.iter()) {
+            assert_eq!(decoded.handle, slotframe.handle);
+            assert_eq!(decoded.size, slotframe.size);
+            assert!(decoded.links.iter().eq(slotframe.links.iter()));
+        }
+        assert_eq!(decoded.iter().count(), 2);
+
+        // Re-encoding the encoded IE gives the same bytes
+        let mut bytes = [0u8; 21];
+        bytes
+            .write(&mut 0, NestedIe::TschSlotframeAndLink(decoded))
+            .unwrap();
+        assert_eq!(bytes, encoded);
+
+        // A slotframe with a truncated link
+        assert!([0x08, 0x1b, 0x01, 0x01, 0x00, 0x00, 0x01, 0, 0, 0]
+            .read::<NestedIe>(&mut 0)
+            .is_err());
+    }
+
+    #[test]
+    fn tsch_timeslot() {
+        let ie = NestedIe::TschTimeslot(TschTimeslotIe {
+            id: 1,
+            timing: Some(TimeslotTemplate::DEFAULT_10MS),
+        });
+        let encoded = [
+            0x19, 0x1c, // Descriptor
+            0x01, // Timeslot ID
+            0x08, 0x07, // CCA offset
+            0x80, 0x00, // CCA
+            0x48, 0x08, // TX offset
+            0xfc, 0x03, // RX offset
+            0x20, 0x03, // RX ack delay
+            0xe8, 0x03, // TX ack delay
+            0x98, 0x08, // RX wait
+            0x90, 0x01, // Ack wait
+            0xc0, 0x00, // RX/TX
+            0x60, 0x09, // Max ack
+            0xa0, 0x10, // Max TX
+            0x10, 0x27, // Timeslot length
+        ];
+        let mut bytes = [0u8; 27];
+        bytes.write(&mut 0, ie).unwrap();
+        assert_eq!(bytes, encoded);
+        assert_eq!(encoded.read::<NestedIe>(&mut 0), Ok(ie));
+
+        // Long maximum transmission time and timeslot length
+        let ie = NestedIe::TschTimeslot(TschTimeslotIe {
+            id: 2,
+            timing: Some(TimeslotTemplate {
+                timeslot_length: 0x01_0000,
+                ..TimeslotTemplate::DEFAULT_10MS
+            }),
+        });
+        let mut bytes = [0u8; 29];
+        bytes.write(&mut 0, ie).unwrap();
+        assert_eq!(bytes[..2], [0x1b, 0x1c]);
+        assert_eq!(bytes[23..], [0xa0, 0x10, 0x00, 0x00, 0x00, 0x01]);
+        assert_eq!(bytes.read::<NestedIe>(&mut 0), Ok(ie));
+
+        // The ID-only form
+        let ie = NestedIe::TschTimeslot(TschTimeslotIe::DEFAULT);
+        assert_eq!([0x01, 0x1c, 0x00].read::<NestedIe>(&mut 0), Ok(ie));
+        assert_eq!(
+            TschTimeslotIe {
+                id: 1,
+                timing: None
+            }
+            .template(),
+            None
+        );
+
+        // A timeslot IE of an invalid length
+        assert!([0x03, 0x1c, 0x00, 0x00, 0x00]
+            .read::<NestedIe>(&mut 0)
+            .is_err());
+    }
+
+    #[test]
+    fn channel_hopping() {
+        let ie = NestedIe::ChannelHopping(ChannelHoppingIe {
+            hopping_sequence_id: 3,
+            sequence: None,
+        });
+        let encoded = [0x01, 0xc8, 0x03];
+        let mut bytes = [0u8; 3];
+        bytes.write(&mut 0, ie).unwrap();
+        assert_eq!(bytes, encoded);
+        assert_eq!(encoded.read::<NestedIe>(&mut 0), Ok(ie));
+
+        let ie = ChannelHoppingIe {
+            hopping_sequence_id: 1,
+            sequence: Some(HoppingSequence {
+                channel_page: 9,
+                number_of_channels: 10,
+                phy_configuration: 0x0403_0201,
+                extended_bitmap: &[0xff, 0x03],
+                channels: HoppingChannels::Decoded(&[0x0102, 0x0304]),
+                current_hop: 1,
+            }),
+        };
+        let encoded = [
+            0x12, 0xc8, // Descriptor
+            0x01, // Hopping sequence ID
+            0x09, // Channel page
+            0x0a, 0x00, // Number of channels
+            0x01, 0x02, 0x03, 0x04, // PHY configuration
+            0xff, 0x03, // Extended bitmap
+            0x02, 0x00, // Hopping sequence length
+            0x02, 0x01, 0x04, 0x03, // Hopping sequence
+            0x01, 0x00, // Current hop
+        ];
+        let mut bytes = [0u8; 20];
+        bytes.write(&mut 0, NestedIe::ChannelHopping(ie)).unwrap();
+        assert_eq!(bytes, encoded);
+
+        let decoded = match encoded.read(&mut 0).unwrap() {
+            NestedIe::ChannelHopping(ie) => ie,
+            _ => unreachable!(),
+        };
+        let (decoded_sequence, sequence) =
+            (decoded.sequence.unwrap(), ie.sequence.unwrap());
+        assert_eq!(decoded.hopping_sequence_id, 1);
+        assert_eq!(decoded_sequence.extended_bitmap, sequence.extended_bitmap);
+        assert!(decoded_sequence
+            .channels
+            .iter()
+            .eq(sequence.channels.iter()));
+        assert_eq!(decoded_sequence.current_hop, 1);
+
+        // The extended bitmap must match the channel page
+        let ie = ChannelHoppingIe {
+            sequence: Some(HoppingSequence {
+                channel_page: 0,
+                ..sequence
+            }),
+            ..ie
+        };
+        assert!([0u8; 32].write(&mut 0, ie).is_err());
+
+        // A truncated hopping sequence
+        assert!([0x05, 0xc8, 0x01, 0x00, 0x10, 0x00, 0x00]
+            .read::<NestedIe>(&mut 0)
+            .is_err());
+    }
+
+    #[test]
+    fn mac_metrics() {
+        let ie = NestedIe::MacMetrics(MacMetricsIe::new(
+            MacMetric::NackCount,
+            0x0403_0201,
+        ));
+        let encoded = [0x05, 0x1f, 0x09, 0x01, 0x02, 0x03, 0x04];
+        let mut bytes = [0u8; 7];
+        bytes.write(&mut 0, ie).unwrap();
+        assert_eq!(bytes, encoded);
+        assert_eq!(encoded.read::<NestedIe>(&mut 0), Ok(ie));
+
+        let metrics = AllMacMetricsIe {
+            retry_count: 1,
+            tx_success_count: 4,
+            nack_count: 9,
+            ..Default::default()
+        };
+        let ie = NestedIe::AllMacMetrics(metrics);
+        let mut bytes = [0u8; 38];
+        bytes.write(&mut 0, ie).unwrap();
+        assert_eq!(bytes[..2], [0x24, 0x20]);
+        assert_eq!(bytes[2..6], [0x01, 0x00, 0x00, 0x00]);
+        assert_eq!(bytes[14..18], [0x04, 0x00, 0x00, 0x00]);
+        assert_eq!(bytes[34..], [0x09, 0x00, 0x00, 0x00]);
+        assert_eq!(bytes.read::<NestedIe>(&mut 0), Ok(ie));
+        assert_eq!(metrics.get(MacMetric::TxSuccessCount), 4);
+        assert_eq!(metrics.get(MacMetric::FcsErrorCount), 0);
+
+        assert_eq!(MacMetric::from_bits(0x0a), None);
+        assert_eq!(
+            MacMetricsIe {
+                metric_id: 0x0a,
+                count: 0
+            }
+            .metric(),
+            None
+        );
+    }
+
+    #[test]
+    fn encoded_information_elements() {
+        let header = [HeaderIe::TimeCorrection(
+            TimeCorrectionIe::new(5, false).unwrap(),
+        )];
+        let payload = [PayloadIe::Unknown {
+            group_id: 0x2,
+            content: &[1],
+        }];
+        for &(header, payload) in &[
+            (&header[..], &payload[..]),
+            (&header[..], &[][..]),
+            (&[][..], &payload[..]),
+            (&[][..], &[][..]),
+        ] {
+            let ies = InformationElements { header, payload };
+            for &payload_follows in &[false, true] {
+                let mut bytes = [0u8; 16];
+                let len = &mut 0;
+                bytes.write_with(len, ies, payload_follows).unwrap();
+                if payload_follows {
+                    bytes.write(len, 0xaau8).unwrap();
+                }
+
+                let offset = &mut 0;
+                let decoded: EncodedInformationElements =
+                    bytes[..*len].read(offset).unwrap();
+                assert_eq!(*offset, *len - payload_follows as usize);
+                assert!(decoded
+                    .header_ies()
+                    .map(Result::unwrap)
+                    .eq(header.iter().copied()));
+                assert!(decoded
+                    .payload_ies()
+                    .map(Result::unwrap)
+                    .eq(payload.iter().copied()));
+            }
+        }
+
+        let missing_termination = Err(DecodeError::MissingIeTermination.into());
+        // A header termination 1 IE without payload IEs
+        assert_eq!(
+            [0x00, 0x3f].read::<EncodedInformationElements>(&mut 0),
+            missing_termination
+        );
+        // A payload termination IE without a payload
+        assert_eq!(
+            [0x00, 0x3f, 0x00, 0xf8].read::<EncodedInformationElements>(&mut 0),
+            missing_termination
+        );
+        // Payload IEs without a header termination 1 IE
+        assert_eq!(
+            [0x00, 0x88].read::<EncodedInformationElements>(&mut 0),
+            missing_termination
+        );
+        // A payload after payload IEs without a payload termination IE
+        assert_eq!(
+            [0x00, 0x3f, 0x00, 0x88, 0xaa, 0x00]
+                .read::<EncodedInformationElements>(&mut 0),
+            missing_termination
+        );
+        // A malformed payload IE
+        assert_eq!(
+            [0x00, 0x3f, 0x01, 0x90].read::<EncodedInformationElements>(&mut 0),
+            Err(DecodeError::IeLengthMismatch.into())
+        );
+    }
+
+    #[test]
+    fn malformed_ies_stay_in_bounds() {
+        use rand::Rng;
+
+        fn visit_nested(ies: NestedIes) {
+            for ie in ies.iter() {
+                match ie {
+                    Ok(ie) => assert!(ie.content_len() <= 2047),
+                    Err(_) => break,
+                }
+            }
+        }
+
+        let mut rng = rand::thread_rng();
+        let mut data = [0u8; 64];
+        for _ in 0..10_000 {
+            let len = rng.gen_range(0..=data.len());
+            rng.fill(&mut data[..len]);
+            // Make descriptors with the payload IE type bit more likely
+            if len > 1 && rng.gen() {
+                data[1] |= 0x80;
+            }
+            let bytes = &data[..len];
+
+            for ie in HeaderIeIterator::new(bytes).flatten() {
+                assert!(ie.content_len() <= MAX_HEADER_IE_CONTENT_LEN);
+            }
+            for ie in PayloadIeIterator::new(bytes).flatten() {
+                if let PayloadIe::Mlme(ies) = ie {
+                    visit_nested(ies);
+                }
+            }
+            visit_nested(NestedIes::Encoded(bytes));
+
+            if let Ok((ies, len)) =
+                EncodedInformationElements::try_read(bytes, ())
+            {
+                assert!(len <= bytes.len());
+                ies.header_ies().for_each(drop);
+                ies.payload_ies().for_each(drop);
+            }
+        }
+    }
+
+    #[test]
+    fn raw_iterators() {
+        let bytes = [
+            0x02, 0x0f, 0x01, 0x00, // Time correction IE
+            0x00, 0x3f, // Header termination 1 IE
+            0x01, 0x90, 0x05, // Vendor specific IE
+            0x00, 0xf8, // Payload termination IE
+            0xaa, // Payload
+        ];
+        let mut header = HeaderIeIter::new(&bytes);
+        assert_eq!(header.next(), Some(Ok((0x1e, &[0x01, 0x00][..]))));
+        assert_eq!(header.next(), Some(Ok((0x7e, &[][..]))));
+        assert_eq!(header.next(), None);
+        assert_eq!(header.offset(), 6);
+
+        let mut payload = PayloadIeIter::new(&bytes[header.offset()..]);
+        assert_eq!(payload.next(), Some(Ok((0x2, &[0x05][..]))));
+        assert_eq!(payload.next(), Some(Ok((0xf, &[][..]))));
+        assert_eq!(payload.next(), None);
+        assert_eq!(payload.offset(), 5);
+
+        // The content of known IEs is not checked
+        let mut header = HeaderIeIter::new(&[0x01, 0x0f, 0x00]);
+        assert_eq!(header.next(), Some(Ok((0x1e, &[0x00][..]))));
+        // Malformed descriptors end the iteration
+        let mut payload = PayloadIeIter::new(&[0x02, 0x90, 0x00]);
+        assert!(payload.next().unwrap().is_err());
+        assert_eq!(payload.next(), None);
+    }
+
+    #[test]
+    fn ie_writer() {
+        let nested = [
+            NestedIe::TschTimeslot(TschTimeslotIe::DEFAULT),
+            NestedIe::UnknownLong {
+                sub_id: 0xa,
+                content: &[1, 2],
+            },
+        ];
+        let header = [HeaderIe::Unknown {
+            element_id: 0x20,
+            content: &[3],
+        }];
+        let payload = [
+            PayloadIe::Mlme(NestedIes::Decoded(&nested)),
+            PayloadIe::Unknown {
+                group_id: 0x2,
+                content: &[4],
+            },
+        ];
+        let ies = InformationElements {
+            header: &header,
+            payload: &payload,
+        };
+
+        // The writer writes the same bytes as a list of IEs
+        for &payload_follows in &[false, true] {
+            let mut expected = [0u8; 32];
+            let expected_len = &mut 0;
+            expected
+                .write_with(expected_len, ies, payload_follows)
+                .unwrap();
+
+            let mut bytes = [0u8; 32];
+            let mut writer = IeWriter::new(&mut bytes);
+            writer
+                .raw_header_ie(0x20, &[3])
+                .unwrap()
+                .nested_ie(nested[0])
+                .unwrap()
+                .raw_nested_ie(true, 0xa, &[1, 2])
+                .unwrap()
+                .raw_payload_ie(0x2, &[4])
+                .unwrap();
+            let len = writer.finish(payload_follows).unwrap();
+            assert_eq!(bytes[..len], expected[..*expected_len]);
+        }
+
+        // Only a header IE, followed by a payload
+        let mut bytes = [0u8; 8];
+        let mut writer = IeWriter::new(&mut bytes);
+        writer.raw_header_ie(0x20, &[3]).unwrap();
+        assert_eq!(writer.len(), 3);
+        let len = writer.finish(true).unwrap();
+        assert_eq!(bytes[..len], [0x01, 0x10, 0x03, 0x80, 0x3f]);
+
+        // Header IEs can't follow payload IEs, and termination IEs are
+        // inserted by the writer
+        let mut bytes = [0u8; 16];
+        let mut writer = IeWriter::new(&mut bytes);
+        assert!(writer.header_ie(HeaderIe::HeaderTermination1).is_err());
+        assert!(writer.raw_payload_ie(0xf, &[]).is_err());
+        writer.raw_payload_ie(0x2, &[]).unwrap();
+        assert!(writer.raw_header_ie(0x20, &[]).is_err());
+
+        // The buffer is too small
+        let mut bytes = [0u8; 4];
+        let mut writer = IeWriter::new(&mut bytes);
+        assert!(writer.raw_nested_ie(false, 0x40, &[0; 3]).is_err());
+    }
+
+    #[test]
+    fn mpx() {
+        let frame = [0x01, 0x02, 0x03];
+        for &(transaction_id, transfer, content) in &[
+            (
+                0x1f,
+                MpxTransfer::FullFrame {
+                    multiplex_id: 0xa0ed,
+                    frame: &frame,
+                },
+                &[0xf8, 0xed, 0xa0, 0x01, 0x02, 0x03][..],
+            ),
+            (
+                0x01,
+                MpxTransfer::FullFrameSmallMultiplexId { frame: &frame },
+                &[0x09, 0x01, 0x02, 0x03][..],
+            ),
+            (
+                0x02,
+                MpxTransfer::IntermediateFragment {
+                    fragment_number: 1,
+                    fragment: &frame,
+                },
+                &[0x14, 0x01, 0x01, 0x02, 0x03][..],
+            ),
+            (
+                0x02,
+                MpxTransfer::LastFragment {
+                    fragment_number: 2,
+                    fragment: &frame[..1],
+                },
+                &[0x16, 0x02, 0x01][..],
+            ),
+            (
+                0x02,
+                MpxTransfer::Abort {
+                    max_frame_size: None,
+                },
+                &[0x17][..],
+            ),
+            (
+                0x02,
+                MpxTransfer::Abort {
+                    max_frame_size: Some(1280),
+                },
+                &[0x17, 0x00, 0x05][..],
+            ),
+        ] {
+            let ie = MpxIe {
+                transaction_id,
+                transfer,
+            };
+            assert_eq!(ie.content_len(), content.len());
+            let mut bytes = [0u8; 16];
+            let len = &mut 0;
+            bytes.write(len, ie).unwrap();
+            assert_eq!(bytes[..*len], *content);
+            assert_eq!(read_content(content), Ok(ie));
+        }
+
+        let ie = MpxIe {
+            transaction_id: 0x03,
+            transfer: MpxTransfer::FullFrameSmallMultiplexId { frame: &frame },
+        };
+        assert_eq!(ie.multiplex_id(), Some(0x03));
+        assert_eq!(ie.fragment_number(), None);
+        assert_eq!(ie.data(), frame);
+
+        // Reserved transfer type
+        assert!([0x03].read::<MpxIe>(&mut 0).is_err());
+        // First fragment with a non-zero fragment number
+        assert!([0x02, 0x01, 0x00, 0x01, 0x01, 0x00]
+            .read::<MpxIe>(&mut 0)
+            .is_err());
+        // Truncated first fragment
+        assert!([0x02, 0x00, 0x00, 0x01].read::<MpxIe>(&mut 0).is_err());
+        // Abort with a malformed maximum frame size
+        assert!([0x07, 0x00].read::<MpxIe>(&mut 0).is_err());
+        // Transaction ID out of range
+        let ie = MpxIe {
+            transaction_id: 0x20,
+            transfer: MpxTransfer::Abort {
+                max_frame_size: None,
+            },
+        };
+        assert!([0u8; 4].write(&mut 0, ie).is_err());
+    }
+
+    #[test]
+    fn unknown_ies_round_trip() {
+        let bytes = [
+            0x02, 0x10, 0xaa, 0xbb, // Unknown header IE
+            0x02, 0x0f, 0x0a,
+            0x70, // Time correction IE, reserved bits set
+            0x00, 0x3f, // Header termination 1 IE
+            0x0e, 0x88, // MLME IE
+            0x01, 0x40, 0xcc, // Unknown short nested IE
+            0x02, 0xd0, 0xdd, 0xee, // Unknown long nested IE
+            0x05, 0x1f, 0x01, 0x02, 0x00, 0x00, 0x00, // MAC Metrics IE
+            0x01, 0x90, 0xff, // Unknown payload IE
+        ];
+
+        // Unknown IEs are encoded unchanged
+        let mut header = HeaderIeIterator::new(&bytes);
+        let unknown = header.next().unwrap().unwrap();
+        let mut buf = [0u8; 32];
+        let len = &mut 0;
+        buf.write(len, unknown).unwrap();
+        assert_eq!(buf[..*len], bytes[..4]);
+
+        // Known IEs are encoded unchanged if they are preserved
+        let mut offset = 0;
+        let mut buf = [0u8; 32];
+        let len = &mut 0;
+        for _ in 0..3 {
+            let ie: PreservedIe<HeaderIe> = bytes.read(&mut offset).unwrap();
+            buf.write(len, ie).unwrap();
+        }
+        for _ in 0..2 {
+            let ie: PreservedIe<PayloadIe> = bytes.read(&mut offset).unwrap();
+            assert!(!ie.is_modified());
+            buf.write(len, ie).unwrap();
+        }
+        assert_eq!(buf[..*len], bytes);
+
+        // Modifying a nested IE keeps the unknown nested IEs
+        let mut nested = [NestedIe::UnknownShort {
+            sub_id: 0,
+            content: &[],
+        }; 3];
+        let ies = match bytes[10..].read(&mut 0).unwrap() {
+            PayloadIe::Mlme(ies) => ies,
+            _ => unreachable!(),
+        };
+        for (ie, decoded) in nested.iter_mut().zip(ies.iter()) {
+            *ie = decoded.unwrap();
+        }
+        nested[2] =
+            NestedIe::MacMetrics(MacMetricsIe::new(MacMetric::RetryCount, 3));
+
+        let mut buf = [0u8; 32];
+        let mut writer = IeWriter::new(&mut buf);
+        for ie in nested {
+            writer.nested_ie(ie).unwrap();
+        }
+        let len = writer.finish(false).unwrap();
+        assert_eq!(buf[..11], bytes[8..19]);
+        assert_eq!(buf[11..len], [0x05, 0x1f, 0x01, 0x03, 0x00, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn nested_ie_invalid() {
+        let too_long = [0u8; 256];
+        for ie in [
+            NestedIe::UnknownShort {
+                sub_id: 0x80,
+                content: &[],
+            },
+            NestedIe::UnknownShort {
+                sub_id: 0,
+                content: &too_long,
+            },
+            NestedIe::UnknownLong {
+                sub_id: 0x10,
+                content: &[],
+            },
+        ]
+        .iter()
+        {
+            assert!([0u8; 512].write(&mut 0, *ie).is_err());
+        }
+
+        // Truncated nested IE
+        let mut ies = NestedIes::Encoded(&[0x02, 0x7f, 0x00]).iter();
+        assert_eq!(
+            ies.next().unwrap(),
+            Err(DecodeError::NestedIeOverflow.into())
+        );
+        assert!(ies.next().is_none());
+        // Truncated nested IE descriptor
+        let mut ies = NestedIes::Encoded(&[0x02]).iter();
+        assert_eq!(
+            ies.next().unwrap(),
+            Err(DecodeError::NestedIeOverflow.into())
+        );
+
+        // Payload termination IE with content
+        assert_eq!(
+            [0x01, 0xf8, 0x00].read::<PayloadIe>(&mut 0),
+            Err(DecodeError::IeLengthMismatch.into())
+        );
+        // Header IE where a payload IE is expected
+        assert_eq!(
+            [0x00, 0x3f].read::<PayloadIe>(&mut 0),
+            Err(DecodeError::InvalidElementId(0x7e).into())
+        );
+    }
+
+    #[test]
+    fn header_ie_invalid() {
+        let length_mismatch = Err(DecodeError::IeLengthMismatch.into());
+        // Payload IE
+        assert_eq!(
+            HeaderIeIterator::new(&[0x00, 0x88]).next().unwrap(),
+            Err(DecodeError::InvalidElementId(0x1).into())
+        );
+        // Truncated content
+        assert_eq!(
+            HeaderIeIterator::new(&[0x02, 0x0f, 0x00]).next().unwrap(),
+            length_mismatch
+        );
+        // Time correction of the wrong length
+        assert_eq!(
+            HeaderIeIterator::new(&[0x01, 0x0f, 0x00]).next().unwrap(),
+            length_mismatch
+        );
+        // Global time of the wrong length
+        assert_eq!(
+            HeaderIeIterator::new(&[0x83, 0x14, 0x00, 0x00, 0x00])
+                .next()
+                .unwrap(),
+            length_mismatch
+        );
+        // Header termination IE with content
+        assert_eq!(
+            HeaderIeIterator::new(&[0x01, 0x3f, 0x00]).next().unwrap(),
+            length_mismatch
+        );
+
+        let content = [0u8; 128];
+        let ie = HeaderIe::Unknown {
+            element_id: 0x20,
+            content: &content,
+        };
+        assert!([0u8; 256].write(&mut 0, ie).is_err());
+    }
+
+    #[cfg(feature = "wisun")]
+    #[test]
+    fn wisun_header_ies() {
+        use crate::mac::ExtendedAddress;
+
+        let ies = [
+            HeaderIe::Wisun(WisunHeaderIe::BroadcastTiming(
+                BroadcastTimingIe {
+                    slot_number: 0x0102,
+                    interval_offset: 0x102030,
+                },
+            )),
+            HeaderIe::Wisun(WisunHeaderIe::EapolAuthenticator(
+                ExtendedAddress(0x0011223344556677),
+            )),
+            HeaderIe::Wisun(WisunHeaderIe::FlowControl(FlowControlIe {
+                transmit: 50,
+                receive: 60,
+            })),
+            HeaderIe::Wisun(WisunHeaderIe::ReceivedSignalLevel(100)),
+            HeaderIe::Wisun(WisunHeaderIe::Unknown {
+                sub_id: 0x40,
+                content: &[0xaa],
+            }),
+        ];
+        let mut bytes = [0u8; 64];
+        let len = &mut 0;
+        for ie in ies {
+            bytes.write(len, ie).unwrap();
+        }
+        assert_eq!(
+            bytes[..19],
+            [
+                0x06, 0x15, 0x02, 0x02, 0x01, 0x30, 0x20, 0x10, // BT-IE
+                0x09, 0x15, 0x09, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66,
+                0x77, // EA-IE
+            ]
+        );
+
+        let mut iter = HeaderIeIterator::new(&bytes[..*len]);
+        for ie in ies {
+            assert_eq!(iter.next().unwrap().unwrap(), ie);
+        }
+        assert!(iter.next().is_none());
+
+        // UTT-IE of the wrong length
+        assert!([0x04, 0x15, 0x01, 0x04, 0x00, 0x00]
+            .read::<HeaderIe>(&mut 0)
+            .is_err());
+        let ie =
+            HeaderIe::Wisun(WisunHeaderIe::UnicastTiming(UnicastTimingIe {
+                frame_type: 0,
+                fractional_sequence_interval: 0x100_0000,
+            }));
+        assert!([0u8; 8].write(&mut 0, ie).is_err());
+    }
+
+    #[cfg(feature = "wisun")]
+    #[test]
+    fn wisun_payload_ies() {
+        let unicast = ChannelSchedule {
+            dwell_interval: 255,
+            clock_drift: 255,
+            timing_accuracy: 10,
+            channel_plan: ChannelPlan::Explicit {
+                ch0: 863_100,
+                channel_spacing: 0,
+                number_of_channels: 69,
+            },
+            channel_function: ChannelFunction::DirectHash,
+            excluded_channels: ExcludedChannels::Ranges(
+                ExcludedChannelRanges::Decoded(&[(10, 12)]),
+            ),
+        };
+        let broadcast = BroadcastScheduleIe {
+            broadcast_interval: 1020,
+            schedule_id: 0xabcd,
+            schedule: ChannelSchedule {
+                dwell_interval: 255,
+                clock_drift: 255,
+                timing_accuracy: 10,
+                channel_plan: ChannelPlan::RegulatoryDomain {
+                    domain: 3,
+                    operating_class: 1,
+                },
+                channel_function: ChannelFunction::Fixed(5),
+                excluded_channels: ExcludedChannels::Mask(&[0x00, 0x81]),
+            },
+        };
+        let pan = PanIe {
+            pan_size: 100,
+            routing_cost: 256,
+            use_parent_bs_ie: true,
+            l3_routing: true,
+            fan_tps_version: 1,
+        };
+        let nested = [
+            WisunNestedIe::UnicastSchedule(unicast),
+            WisunNestedIe::BroadcastSchedule(broadcast),
+            WisunNestedIe::Pan(pan),
+            WisunNestedIe::GtkHash([[1; 8], [2; 8], [3; 8], [4; 8]]),
+        ];
+        let ie = PayloadIe::Wisun(WisunIes::Decoded(&nested));
+
+        let mut bytes = [0u8; 128];
+        let len = &mut 0;
+        bytes.write(len, ie).unwrap();
+        assert_eq!(*len, ie.get_octet_size());
+        assert_eq!(
+            bytes[2..19],
+            [
+                0x0f, 0x88, // US-IE
+                0xff, 0xff, 0x0a, 0x51, // Schedule
+                0x7c, 0x2b, 0x0d, 0x00, 0x45,
+                0x00, // Explicit channel plan
+                0x01, 0x0a, 0x00, 0x0c, 0x00, // Excluded channel ranges
+            ]
+        );
+
+        let ies = match bytes.read(&mut 0).unwrap() {
+            PayloadIe::Wisun(ies) => ies,
+            _ => unreachable!(),
+        };
+        let mut iter = ies.iter();
+        match iter.next().unwrap().unwrap() {
+            WisunNestedIe::UnicastSchedule(schedule) => {
+                assert_eq!(schedule.channel_plan, unicast.channel_plan);
+                assert_eq!(schedule.channel_function, unicast.channel_function);
+                match schedule.excluded_channels {
+                    ExcludedChannels::Ranges(ranges) => {
+                        assert!(ranges.iter().eq([(10, 12)]))
+                    }
+                    _ => unreachable!(),
+                }
+                assert!(schedule.excluded_channels.contains(11));
+                assert!(!schedule.excluded_channels.contains(13));
+            }
+            _ => unreachable!(),
+        }
+        let ie = iter.next().unwrap().unwrap();
+        assert_eq!(ie, WisunNestedIe::BroadcastSchedule(broadcast));
+        match ie {
+            WisunNestedIe::BroadcastSchedule(ie) => {
+                let excluded = ie.schedule.excluded_channels;
+                assert!(excluded.contains(8) && excluded.contains(15));
+                assert!(!excluded.contains(9) && !excluded.contains(16));
+            }
+            _ => unreachable!(),
+        }
+        assert_eq!(iter.next().unwrap().unwrap(), nested[2]);
+        assert_eq!(iter.next().unwrap().unwrap(), nested[3]);
+        assert!(iter.next().is_none());
+
+        // Network name that is too long
+        let name = [b'a'; MAX_NETWORK_NAME_LEN + 1];
+        let ie = WisunNestedIe::NetworkName(&name);
+        assert!([0u8; 64].write(&mut 0, ie).is_err());
+        let mut bytes = [b'a'; 35];
+        bytes[..2].copy_from_slice(&[0x21, 0x05]);
+        assert!(bytes.read::<WisunNestedIe>(&mut 0).is_err());
+    }
+}
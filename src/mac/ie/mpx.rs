@@ -0,0 +1,329 @@
+//! MPX IE
+//!
+//! The MPX IE of IEEE 802.15.9 multiplexes upper-layer frames, e.g. key
+//! management messages, over the MAC. Upper-layer frames that don't fit into
+//! a single MAC frame are fragmented. This crate only encodes and decodes the
+//! fragments, reassembling them is up to the upper layer.
+
+use byte::{check_len, BytesExt, TryRead, TryWrite, LE};
+
+use crate::mac::frame::EncodeError;
+use crate::mac::DecodeError;
+
+const TRANSFER_TYPE_MASK: u8 = 0x07;
+const TRANSACTION_ID_OFFSET: u8 = 3;
+
+/// The largest transaction ID of an MPX IE
+pub const MAX_TRANSACTION_ID: u8 = 0x1f;
+
+/// Transfer type of an MPX IE
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum TransferType {
+    /// A full upper-layer frame
+    FullFrame = 0b000,
+    /// A full upper-layer frame, with the multiplex ID in the transaction ID
+    /// field
+    FullFrameSmallMultiplexId = 0b001,
+    /// The first fragment of an upper-layer frame
+    FirstFragment = 0b010,
+    /// An intermediate fragment of an upper-layer frame
+    IntermediateFragment = 0b100,
+    /// The last fragment of an upper-layer frame
+    LastFragment = 0b110,
+    /// Abort the transfer of a fragmented upper-layer frame
+    Abort = 0b111,
+}
+
+impl TransferType {
+    /// Creates an instance of [`TransferType`] from the provided bits
+    ///
+    /// Returns `None`, if the transfer type is reserved.
+    pub const fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            0b000 => Some(TransferType::FullFrame),
+            0b001 => Some(TransferType::FullFrameSmallMultiplexId),
+            0b010 => Some(TransferType::FirstFragment),
+            0b100 => Some(TransferType::IntermediateFragment),
+            0b110 => Some(TransferType::LastFragment),
+            0b111 => Some(TransferType::Abort),
+            _ => None,
+        }
+    }
+}
+
+/// The upper-layer frame, or the fragment of it, that an MPX IE carries
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MpxTransfer<'a> {
+    /// A full upper-layer frame
+    FullFrame {
+        /// The protocol of the upper-layer frame
+        multiplex_id: u16,
+        /// The upper-layer frame
+        frame: &'a [u8],
+    },
+    /// A full upper-layer frame, whose multiplex ID is the transaction ID of
+    /// the IE
+    FullFrameSmallMultiplexId {
+        /// The upper-layer frame
+        frame: &'a [u8],
+    },
+    /// The first fragment of an upper-layer frame, with fragment number 0
+    FirstFragment {
+        /// The size of the reassembled upper-layer frame, in octets
+        total_size: u16,
+        /// The protocol of the upper-layer frame
+        multiplex_id: u16,
+        /// The fragment of the upper-layer frame
+        fragment: &'a [u8],
+    },
+    /// An intermediate fragment of an upper-layer frame
+    IntermediateFragment {
+        /// The number of this fragment
+        fragment_number: u8,
+        /// The fragment of the upper-layer frame
+        fragment: &'a [u8],
+    },
+    /// The last fragment of an upper-layer frame
+    LastFragment {
+        /// The number of this fragment
+        fragment_number: u8,
+        /// The fragment of the upper-layer frame
+        fragment: &'a [u8],
+    },
+    /// Abort the transfer of a fragmented upper-layer frame
+    Abort {
+        /// The largest upper-layer frame size that the sender supports, in
+        /// octets
+        max_frame_size: Option<u16>,
+    },
+}
+
+impl MpxTransfer<'_> {
+    /// The transfer type of this transfer
+    pub fn transfer_type(&self) -> TransferType {
+        match self {
+            MpxTransfer::FullFrame { .. } => TransferType::FullFrame,
+            MpxTransfer::FullFrameSmallMultiplexId { .. } => {
+                TransferType::FullFrameSmallMultiplexId
+            }
+            MpxTransfer::FirstFragment { .. } => TransferType::FirstFragment,
+            MpxTransfer::IntermediateFragment { .. } => {
+                TransferType::IntermediateFragment
+            }
+            MpxTransfer::LastFragment { .. } => TransferType::LastFragment,
+            MpxTransfer::Abort { .. } => TransferType::Abort,
+        }
+    }
+}
+
+/// MPX IE
+///
+/// # Example
+///
+/// ``` rust
+/// use byte::BytesExt;
+/// use ieee802154::mac::ie::{MpxIe, MpxTransfer, PayloadIe};
+///
+/// let ie = PayloadIe::Mpx(MpxIe {
+///     transaction_id: 5,
+///     transfer: MpxTransfer::FirstFragment {
+///         total_size: 300,
+///         multiplex_id: 0x0001,
+///         fragment: &[0xaa, 0xbb],
+///     },
+/// });
+///
+/// let mut bytes = [0u8; 10];
+/// bytes.write(&mut 0, ie).unwrap();
+/// assert_eq!(
+///     bytes,
+///     [0x08, 0x98, 0x2a, 0x00, 0x2c, 0x01, 0x01, 0x00, 0xaa, 0xbb]
+/// );
+/// assert_eq!(bytes.read::<PayloadIe>(&mut 0), Ok(ie));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MpxIe<'a> {
+    /// The transaction ID, which identifies the fragments of an upper-layer
+    /// frame
+    ///
+    /// For [`MpxTransfer::FullFrameSmallMultiplexId`], this is the multiplex
+    /// ID instead.
+    pub transaction_id: u8,
+    /// The upper-layer frame, or the fragment of it
+    pub transfer: MpxTransfer<'a>,
+}
+
+impl<'a> MpxIe<'a> {
+    /// The protocol of the upper-layer frame, if this IE carries it
+    pub fn multiplex_id(&self) -> Option<u16> {
+        match self.transfer {
+            MpxTransfer::FullFrame { multiplex_id, .. }
+            | MpxTransfer::FirstFragment { multiplex_id, .. } => {
+                Some(multiplex_id)
+            }
+            MpxTransfer::FullFrameSmallMultiplexId { .. } => {
+                Some(self.transaction_id as u16)
+            }
+            _ => None,
+        }
+    }
+
+    /// The number of the carried fragment, if this IE carries a fragment
+    pub fn fragment_number(&self) -> Option<u8> {
+        match self.transfer {
+            MpxTransfer::FirstFragment { .. } => Some(0),
+            MpxTransfer::IntermediateFragment {
+                fragment_number, ..
+            }
+            | MpxTransfer::LastFragment {
+                fragment_number, ..
+            } => Some(fragment_number),
+            _ => None,
+        }
+    }
+
+    /// The carried upper-layer frame or fragment of it
+    pub fn data(&self) -> &'a [u8] {
+        match self.transfer {
+            MpxTransfer::FullFrame { frame, .. }
+            | MpxTransfer::FullFrameSmallMultiplexId { frame } => frame,
+            MpxTransfer::FirstFragment { fragment, .. }
+            | MpxTransfer::IntermediateFragment { fragment, .. }
+            | MpxTransfer::LastFragment { fragment, .. } => fragment,
+            MpxTransfer::Abort { .. } => &[],
+        }
+    }
+
+    /// The length of the content of this IE, in octets
+    pub fn content_len(&self) -> usize {
+        let fields = match self.transfer {
+            MpxTransfer::FullFrame { .. } => 2,
+            MpxTransfer::FullFrameSmallMultiplexId { .. } => 0,
+            MpxTransfer::FirstFragment { .. } => 5,
+            MpxTransfer::IntermediateFragment { .. }
+            | MpxTransfer::LastFragment { .. } => 1,
+            MpxTransfer::Abort { max_frame_size } => {
+                if max_frame_size.is_some() {
+                    2
+                } else {
+                    0
+                }
+            }
+        };
+        1 + fields + self.data().len()
+    }
+}
+
+impl<'a> TryRead<'a> for MpxIe<'a> {
+    fn try_read(bytes: &'a [u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        check_len(bytes, 1)?;
+        let control: u8 = bytes.read(offset)?;
+        let transaction_id = control >> TRANSACTION_ID_OFFSET;
+        let transfer_type =
+            TransferType::from_bits(control & TRANSFER_TYPE_MASK)
+                .ok_or(DecodeError::InvalidValue)?;
+
+        let transfer = match transfer_type {
+            TransferType::FullFrame => {
+                check_len(&bytes[*offset..], 2)?;
+                MpxTransfer::FullFrame {
+                    multiplex_id: bytes.read_with(offset, LE)?,
+                    frame: &bytes[*offset..],
+                }
+            }
+            TransferType::FullFrameSmallMultiplexId => {
+                MpxTransfer::FullFrameSmallMultiplexId {
+                    frame: &bytes[*offset..],
+                }
+            }
+            TransferType::FirstFragment => {
+                check_len(&bytes[*offset..], 5)?;
+                let fragment_number: u8 = bytes.read(offset)?;
+                if fragment_number != 0 {
+                    return Err(DecodeError::InvalidValue.into());
+                }
+                MpxTransfer::FirstFragment {
+                    total_size: bytes.read_with(offset, LE)?,
+                    multiplex_id: bytes.read_with(offset, LE)?,
+                    fragment: &bytes[*offset..],
+                }
+            }
+            TransferType::IntermediateFragment => {
+                check_len(&bytes[*offset..], 1)?;
+                MpxTransfer::IntermediateFragment {
+                    fragment_number: bytes.read(offset)?,
+                    fragment: &bytes[*offset..],
+                }
+            }
+            TransferType::LastFragment => {
+                check_len(&bytes[*offset..], 1)?;
+                MpxTransfer::LastFragment {
+                    fragment_number: bytes.read(offset)?,
+                    fragment: &bytes[*offset..],
+                }
+            }
+            TransferType::Abort => {
+                let max_frame_size = match bytes.len() - *offset {
+                    0 => None,
+                    2 => Some(bytes.read_with(offset, LE)?),
+                    _ => return Err(DecodeError::IeLengthMismatch.into()),
+                };
+                MpxTransfer::Abort { max_frame_size }
+            }
+        };
+        Ok((
+            Self {
+                transaction_id,
+                transfer,
+            },
+            bytes.len(),
+        ))
+    }
+}
+
+impl TryWrite for MpxIe<'_> {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        if self.transaction_id > MAX_TRANSACTION_ID {
+            return Err(EncodeError::WriteError.into());
+        }
+        let control = self.transaction_id << TRANSACTION_ID_OFFSET
+            | self.transfer.transfer_type() as u8;
+        bytes.write(offset, control)?;
+
+        match self.transfer {
+            MpxTransfer::FullFrame { multiplex_id, .. } => {
+                bytes.write_with(offset, multiplex_id, LE)?
+            }
+            MpxTransfer::FullFrameSmallMultiplexId { .. } => (),
+            MpxTransfer::FirstFragment {
+                total_size,
+                multiplex_id,
+                ..
+            } => {
+                bytes.write(offset, 0u8)?;
+                bytes.write_with(offset, total_size, LE)?;
+                bytes.write_with(offset, multiplex_id, LE)?;
+            }
+            MpxTransfer::IntermediateFragment {
+                fragment_number, ..
+            }
+            | MpxTransfer::LastFragment {
+                fragment_number, ..
+            } => bytes.write(offset, fragment_number)?,
+            MpxTransfer::Abort { max_frame_size } => {
+                if let Some(max_frame_size) = max_frame_size {
+                    bytes.write_with(offset, max_frame_size, LE)?;
+                }
+            }
+        }
+        bytes.write(offset, self.data())?;
+        Ok(*offset)
+    }
+}
@@ -0,0 +1,384 @@
+//! Nested IEs, which are carried in the MLME payload IE
+
+use byte::{BytesExt, TryRead, TryWrite, LE};
+
+use super::{
+    read_content, AllMacMetricsIe, ChannelHoppingIe, MacMetricsIe,
+    TschSlotframeAndLinkIe, TschSynchronizationIe, TschTimeslotIe,
+};
+use crate::mac::frame::EncodeError;
+use crate::mac::DecodeError;
+
+const LONG: u16 = 0x8000;
+const SHORT_LENGTH_MASK: u16 = 0x00ff;
+const SHORT_SUB_ID_MASK: u16 = 0x7f00;
+const SHORT_SUB_ID_OFFSET: u16 = 8;
+const LONG_LENGTH_MASK: u16 = 0x07ff;
+const LONG_SUB_ID_MASK: u16 = 0x7800;
+const LONG_SUB_ID_OFFSET: u16 = 11;
+
+/// The largest content length of a short nested IE, in octets
+pub const MAX_SHORT_NESTED_IE_CONTENT_LEN: usize = 255;
+/// The largest content length of a long nested IE, in octets
+pub const MAX_LONG_NESTED_IE_CONTENT_LEN: usize = 2047;
+
+/// Sub-IDs of the short nested IEs that this crate knows
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum ShortSubId {
+    /// TSCH Synchronization IE
+    TschSynchronization = 0x1a,
+    /// TSCH Slotframe and Link IE
+    TschSlotframeAndLink = 0x1b,
+    /// TSCH Timeslot IE
+    TschTimeslot = 0x1c,
+    /// MAC Metrics IE
+    MacMetrics = 0x1f,
+    /// All MAC Metrics IE
+    AllMacMetrics = 0x20,
+}
+
+impl ShortSubId {
+    /// Creates an instance of [`ShortSubId`] from the provided bits
+    ///
+    /// Returns `None`, if the sub-ID is unknown to this crate.
+    pub const fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            0x1a => Some(ShortSubId::TschSynchronization),
+            0x1b => Some(ShortSubId::TschSlotframeAndLink),
+            0x1c => Some(ShortSubId::TschTimeslot),
+            0x1f => Some(ShortSubId::MacMetrics),
+            0x20 => Some(ShortSubId::AllMacMetrics),
+            _ => None,
+        }
+    }
+}
+
+/// Sub-IDs of the long nested IEs that this crate knows
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum LongSubId {
+    /// Channel Hopping IE
+    ChannelHopping = 0x9,
+}
+
+impl LongSubId {
+    /// Creates an instance of [`LongSubId`] from the provided bits
+    ///
+    /// Returns `None`, if the sub-ID is unknown to this crate.
+    pub const fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            0x9 => Some(LongSubId::ChannelHopping),
+            _ => None,
+        }
+    }
+}
+
+/// A nested IE
+///
+/// Nested IEs are either short, with a 7-bit sub-ID and up to 255 octets of
+/// content, or long, with a 4-bit sub-ID and up to 2047 octets of content.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum NestedIe<'a> {
+    /// TSCH Synchronization IE
+    TschSynchronization(TschSynchronizationIe),
+    /// TSCH Slotframe and Link IE
+    TschSlotframeAndLink(TschSlotframeAndLinkIe<'a>),
+    /// TSCH Timeslot IE
+    TschTimeslot(TschTimeslotIe),
+    /// MAC Metrics IE
+    MacMetrics(MacMetricsIe),
+    /// All MAC Metrics IE
+    AllMacMetrics(AllMacMetricsIe),
+    /// Channel Hopping IE
+    ChannelHopping(ChannelHoppingIe<'a>),
+    /// A short nested IE that is unknown to this crate
+    UnknownShort {
+        /// The sub-ID of the IE
+        sub_id: u8,
+        /// The content of the IE
+        content: &'a [u8],
+    },
+    /// A long nested IE that is unknown to this crate
+    UnknownLong {
+        /// The sub-ID of the IE
+        sub_id: u8,
+        /// The content of the IE
+        content: &'a [u8],
+    },
+}
+
+impl NestedIe<'_> {
+    /// Whether this is a long nested IE
+    pub fn is_long(&self) -> bool {
+        matches!(
+            self,
+            NestedIe::ChannelHopping(_) | NestedIe::UnknownLong { .. }
+        )
+    }
+
+    /// The sub-ID of this IE
+    pub fn sub_id(&self) -> u8 {
+        let sub_id = match self {
+            NestedIe::TschSynchronization(_) => ShortSubId::TschSynchronization,
+            NestedIe::TschSlotframeAndLink(_) => {
+                ShortSubId::TschSlotframeAndLink
+            }
+            NestedIe::TschTimeslot(_) => ShortSubId::TschTimeslot,
+            NestedIe::MacMetrics(_) => ShortSubId::MacMetrics,
+            NestedIe::AllMacMetrics(_) => ShortSubId::AllMacMetrics,
+            NestedIe::ChannelHopping(_) => {
+                return LongSubId::ChannelHopping as u8
+            }
+            NestedIe::UnknownShort { sub_id, .. }
+            | NestedIe::UnknownLong { sub_id, .. } => return *sub_id,
+        };
+        sub_id as u8
+    }
+
+    /// The length of the content of this IE, in octets
+    pub fn content_len(&self) -> usize {
+        match self {
+            NestedIe::TschSynchronization(_) => 6,
+            NestedIe::TschSlotframeAndLink(ie) => ie.get_octet_size(),
+            NestedIe::TschTimeslot(ie) => ie.get_octet_size(),
+            NestedIe::MacMetrics(_) => 5,
+            NestedIe::AllMacMetrics(_) => AllMacMetricsIe::OCTET_SIZE,
+            NestedIe::ChannelHopping(ie) => ie.get_octet_size(),
+            NestedIe::UnknownShort { content, .. }
+            | NestedIe::UnknownLong { content, .. } => content.len(),
+        }
+    }
+
+    /// Get the size of this IE in octets, including its descriptor
+    pub fn get_octet_size(&self) -> usize {
+        2 + self.content_len()
+    }
+}
+
+impl<'a> TryRead<'a> for NestedIe<'a> {
+    fn try_read(bytes: &'a [u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let (long, sub_id, content) = read_raw_nested_ie(bytes, offset)?;
+
+        let ie = if long {
+            match LongSubId::from_bits(sub_id) {
+                Some(LongSubId::ChannelHopping) => {
+                    NestedIe::ChannelHopping(read_content(content)?)
+                }
+                None => NestedIe::UnknownLong { sub_id, content },
+            }
+        } else {
+            match ShortSubId::from_bits(sub_id) {
+                Some(ShortSubId::TschSynchronization) => {
+                    NestedIe::TschSynchronization(read_content(content)?)
+                }
+                Some(ShortSubId::TschSlotframeAndLink) => {
+                    NestedIe::TschSlotframeAndLink(read_content(content)?)
+                }
+                Some(ShortSubId::TschTimeslot) => {
+                    NestedIe::TschTimeslot(read_content(content)?)
+                }
+                Some(ShortSubId::MacMetrics) => {
+                    NestedIe::MacMetrics(read_content(content)?)
+                }
+                Some(ShortSubId::AllMacMetrics) => {
+                    NestedIe::AllMacMetrics(read_content(content)?)
+                }
+                None => NestedIe::UnknownShort { sub_id, content },
+            }
+        };
+        Ok((ie, *offset))
+    }
+}
+
+/// Read whether a nested IE is long, its sub-ID and its content
+pub(super) fn read_raw_nested_ie<'a>(
+    bytes: &'a [u8],
+    offset: &mut usize,
+) -> byte::Result<(bool, u8, &'a [u8])> {
+    let bytes = bytes.get(*offset..).ok_or(DecodeError::NotEnoughBytes)?;
+    let start = &mut 0;
+    if bytes.len() < 2 {
+        return Err(DecodeError::NestedIeOverflow.into());
+    }
+    let descriptor: u16 = bytes.read_with(start, LE)?;
+    let long = descriptor & LONG != 0;
+    let (len, sub_id) = if long {
+        (
+            descriptor & LONG_LENGTH_MASK,
+            (descriptor & LONG_SUB_ID_MASK) >> LONG_SUB_ID_OFFSET,
+        )
+    } else {
+        (
+            descriptor & SHORT_LENGTH_MASK,
+            (descriptor & SHORT_SUB_ID_MASK) >> SHORT_SUB_ID_OFFSET,
+        )
+    };
+    let (len, sub_id) = (len as usize, sub_id as u8);
+
+    if bytes.len() - *start < len {
+        return Err(DecodeError::NestedIeOverflow.into());
+    }
+    let content = &bytes[*start..*start + len];
+    *offset += *start + len;
+    Ok((long, sub_id, content))
+}
+
+/// Write the descriptor of a nested IE
+pub(super) fn write_nested_ie_descriptor(
+    bytes: &mut [u8],
+    offset: &mut usize,
+    long: bool,
+    sub_id: u8,
+    content_len: usize,
+) -> byte::Result<()> {
+    let sub_id = sub_id as u16;
+    let descriptor = if long {
+        if content_len > MAX_LONG_NESTED_IE_CONTENT_LEN
+            || sub_id > LONG_SUB_ID_MASK >> LONG_SUB_ID_OFFSET
+        {
+            return Err(EncodeError::WriteError.into());
+        }
+        LONG | sub_id << LONG_SUB_ID_OFFSET | content_len as u16
+    } else {
+        if content_len > MAX_SHORT_NESTED_IE_CONTENT_LEN
+            || sub_id > SHORT_SUB_ID_MASK >> SHORT_SUB_ID_OFFSET
+        {
+            return Err(EncodeError::WriteError.into());
+        }
+        sub_id << SHORT_SUB_ID_OFFSET | content_len as u16
+    };
+    bytes.write_with(offset, descriptor, LE)
+}
+
+impl TryWrite for NestedIe<'_> {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        write_nested_ie_descriptor(
+            bytes,
+            offset,
+            self.is_long(),
+            self.sub_id(),
+            self.content_len(),
+        )?;
+
+        match self {
+            NestedIe::TschSynchronization(ie) => bytes.write(offset, ie)?,
+            NestedIe::TschSlotframeAndLink(ie) => bytes.write(offset, ie)?,
+            NestedIe::TschTimeslot(ie) => bytes.write(offset, ie)?,
+            NestedIe::MacMetrics(ie) => bytes.write(offset, ie)?,
+            NestedIe::AllMacMetrics(ie) => bytes.write(offset, ie)?,
+            NestedIe::ChannelHopping(ie) => bytes.write(offset, ie)?,
+            NestedIe::UnknownShort { content, .. }
+            | NestedIe::UnknownLong { content, .. } => {
+                bytes.write(offset, content)?
+            }
+        }
+        Ok(*offset)
+    }
+}
+
+/// A list of nested IEs
+///
+/// Nested IEs that are read from a frame are [`NestedIes::Encoded`], as they
+/// can't be decoded into a list without allocating. Use [`NestedIes::iter`]
+/// to decode them. Nested IEs that are written to a frame can be given as a
+/// [`NestedIes::Decoded`] list instead.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum NestedIes<'a> {
+    /// The encoded nested IEs
+    Encoded(&'a [u8]),
+    /// The decoded nested IEs
+    Decoded(&'a [NestedIe<'a>]),
+}
+
+impl<'a> NestedIes<'a> {
+    /// Iterate over the nested IEs
+    pub fn iter(&self) -> NestedIeIterator<'a> {
+        match *self {
+            NestedIes::Encoded(bytes) => NestedIeIterator {
+                inner: Inner::Encoded {
+                    bytes,
+                    offset: 0,
+                    done: false,
+                },
+            },
+            NestedIes::Decoded(ies) => NestedIeIterator {
+                inner: Inner::Decoded(ies.iter()),
+            },
+        }
+    }
+
+    /// Get the size of the nested IEs in octets
+    pub fn get_octet_size(&self) -> usize {
+        match self {
+            NestedIes::Encoded(bytes) => bytes.len(),
+            NestedIes::Decoded(ies) => {
+                ies.iter().map(NestedIe::get_octet_size).sum()
+            }
+        }
+    }
+}
+
+impl TryWrite for NestedIes<'_> {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        match self {
+            NestedIes::Encoded(encoded) => bytes.write(offset, encoded)?,
+            NestedIes::Decoded(ies) => {
+                for ie in ies {
+                    bytes.write(offset, *ie)?;
+                }
+            }
+        }
+        Ok(*offset)
+    }
+}
+
+/// An iterator over a list of nested IEs
+///
+/// The iterator ends at the end of the list, or after yielding an error for
+/// a malformed IE.
+#[derive(Clone, Debug)]
+pub struct NestedIeIterator<'a> {
+    inner: Inner<'a>,
+}
+
+#[derive(Clone, Debug)]
+enum Inner<'a> {
+    Encoded {
+        bytes: &'a [u8],
+        offset: usize,
+        done: bool,
+    },
+    Decoded(core::slice::Iter<'a, NestedIe<'a>>),
+}
+
+impl<'a> Iterator for NestedIeIterator<'a> {
+    type Item = byte::Result<NestedIe<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            Inner::Encoded {
+                bytes,
+                offset,
+                done,
+            } => {
+                if *done || *offset >= bytes.len() {
+                    return None;
+                }
+                let ie = bytes.read::<NestedIe>(offset);
+                *done = ie.is_err();
+                Some(ie)
+            }
+            Inner::Decoded(ies) => ies.next().map(|ie| Ok(*ie)),
+        }
+    }
+}
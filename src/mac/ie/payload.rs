@@ -0,0 +1,263 @@
+//! Payload IEs
+
+use byte::{check_len, BytesExt, TryRead, TryWrite, LE};
+
+#[cfg(feature = "wisun")]
+use super::WisunIes;
+use super::{
+    read_content, MpxIe, NestedIes, ELEMENT_ID_MASK, ELEMENT_ID_OFFSET,
+};
+use crate::mac::frame::EncodeError;
+use crate::mac::DecodeError;
+
+const TYPE: u16 = 0x8000;
+const LENGTH_MASK: u16 = 0x07ff;
+pub(super) const GROUP_ID_MASK: u16 = 0x7800;
+pub(super) const GROUP_ID_OFFSET: u16 = 11;
+
+/// The largest content length of a payload IE, in octets
+pub const MAX_PAYLOAD_IE_CONTENT_LEN: usize = 2047;
+
+/// Group IDs of the payload IEs that this crate knows
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[non_exhaustive]
+pub enum PayloadGroupId {
+    /// MLME IE, which contains nested IEs
+    Mlme = 0x1,
+    /// MPX IE of IEEE 802.15.9, which multiplexes upper-layer frames
+    Mpx = 0x3,
+    /// Wi-SUN payload IE (WP-IE), which contains Wi-SUN nested IEs
+    #[cfg(feature = "wisun")]
+    Wisun = 0x4,
+    /// Payload Termination IE, followed by the frame payload
+    PayloadTermination = 0xf,
+}
+
+impl PayloadGroupId {
+    /// Creates an instance of [`PayloadGroupId`] from the provided bits
+    ///
+    /// Returns `None`, if the group ID is unknown to this crate.
+    pub const fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            0x1 => Some(PayloadGroupId::Mlme),
+            0x3 => Some(PayloadGroupId::Mpx),
+            #[cfg(feature = "wisun")]
+            0x4 => Some(PayloadGroupId::Wisun),
+            0xf => Some(PayloadGroupId::PayloadTermination),
+            _ => None,
+        }
+    }
+}
+
+/// A payload IE
+///
+/// # Example
+///
+/// ``` rust
+/// use byte::BytesExt;
+/// use ieee802154::mac::ie::{NestedIe, NestedIes, PayloadIe};
+///
+/// let nested = [NestedIe::UnknownShort {
+///     sub_id: 0x40,
+///     content: &[0xaa],
+/// }];
+/// let ie = PayloadIe::Mlme(NestedIes::Decoded(&nested));
+///
+/// let mut bytes = [0u8; 5];
+/// bytes.write(&mut 0, ie).unwrap();
+/// assert_eq!(bytes, [0x03, 0x88, 0x01, 0x40, 0xaa]);
+///
+/// match bytes.read(&mut 0).unwrap() {
+///     PayloadIe::Mlme(ies) => {
+///         let mut ies = ies.iter();
+///         assert_eq!(ies.next().unwrap().unwrap(), nested[0]);
+///         assert!(ies.next().is_none());
+///     }
+///     _ => unreachable!(),
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum PayloadIe<'a> {
+    /// MLME IE
+    Mlme(NestedIes<'a>),
+    /// MPX IE
+    Mpx(MpxIe<'a>),
+    /// Wi-SUN payload IE (WP-IE)
+    #[cfg(feature = "wisun")]
+    Wisun(WisunIes<'a>),
+    /// Payload Termination IE
+    PayloadTermination,
+    /// An IE that is unknown to this crate
+    Unknown {
+        /// The group ID of the IE
+        group_id: u8,
+        /// The content of the IE
+        content: &'a [u8],
+    },
+}
+
+impl PayloadIe<'_> {
+    /// The group ID of this IE
+    pub fn group_id(&self) -> u8 {
+        let group_id = match self {
+            PayloadIe::Mlme(_) => PayloadGroupId::Mlme,
+            PayloadIe::Mpx(_) => PayloadGroupId::Mpx,
+            #[cfg(feature = "wisun")]
+            PayloadIe::Wisun(_) => PayloadGroupId::Wisun,
+            PayloadIe::PayloadTermination => PayloadGroupId::PayloadTermination,
+            PayloadIe::Unknown { group_id, .. } => return *group_id,
+        };
+        group_id as u8
+    }
+
+    /// The length of the content of this IE, in octets
+    pub fn content_len(&self) -> usize {
+        match self {
+            PayloadIe::Mlme(ies) => ies.get_octet_size(),
+            PayloadIe::Mpx(ie) => ie.content_len(),
+            #[cfg(feature = "wisun")]
+            PayloadIe::Wisun(ies) => ies.get_octet_size(),
+            PayloadIe::PayloadTermination => 0,
+            PayloadIe::Unknown { content, .. } => content.len(),
+        }
+    }
+
+    /// Get the size of this IE in octets, including its descriptor
+    pub fn get_octet_size(&self) -> usize {
+        2 + self.content_len()
+    }
+
+    /// Whether this IE terminates the list of payload IEs
+    pub fn is_termination(&self) -> bool {
+        matches!(self, PayloadIe::PayloadTermination)
+    }
+}
+
+impl<'a> TryRead<'a> for PayloadIe<'a> {
+    fn try_read(bytes: &'a [u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let (group_id, content) = read_raw_payload_ie(bytes, offset)?;
+        let len = content.len();
+
+        let ie = match PayloadGroupId::from_bits(group_id) {
+            Some(PayloadGroupId::Mlme) => {
+                PayloadIe::Mlme(NestedIes::Encoded(content))
+            }
+            Some(PayloadGroupId::Mpx) => PayloadIe::Mpx(read_content(content)?),
+            #[cfg(feature = "wisun")]
+            Some(PayloadGroupId::Wisun) => {
+                PayloadIe::Wisun(WisunIes::Encoded(content))
+            }
+            Some(PayloadGroupId::PayloadTermination) if len == 0 => {
+                PayloadIe::PayloadTermination
+            }
+            Some(PayloadGroupId::PayloadTermination) => {
+                return Err(DecodeError::IeLengthMismatch.into())
+            }
+            None => PayloadIe::Unknown { group_id, content },
+        };
+        Ok((ie, *offset))
+    }
+}
+
+/// Read the group ID and the content of a payload IE
+pub(super) fn read_raw_payload_ie<'a>(
+    bytes: &'a [u8],
+    offset: &mut usize,
+) -> byte::Result<(u8, &'a [u8])> {
+    let bytes = bytes.get(*offset..).ok_or(DecodeError::NotEnoughBytes)?;
+    let start = &mut 0;
+    check_len(bytes, 2)?;
+    let descriptor: u16 = bytes.read_with(start, LE)?;
+    if descriptor & TYPE == 0 {
+        // A header IE
+        let element_id =
+            ((descriptor & ELEMENT_ID_MASK) >> ELEMENT_ID_OFFSET) as u8;
+        return Err(DecodeError::InvalidElementId(element_id).into());
+    }
+    let len = (descriptor & LENGTH_MASK) as usize;
+    let group_id = ((descriptor & GROUP_ID_MASK) >> GROUP_ID_OFFSET) as u8;
+
+    if bytes.len() - *start < len {
+        return Err(DecodeError::IeLengthMismatch.into());
+    }
+    let content = &bytes[*start..*start + len];
+    *offset += *start + len;
+    Ok((group_id, content))
+}
+
+impl TryWrite for PayloadIe<'_> {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        let len = self.content_len();
+        let group_id = self.group_id() as u16;
+        if len > MAX_PAYLOAD_IE_CONTENT_LEN
+            || group_id > GROUP_ID_MASK >> GROUP_ID_OFFSET
+        {
+            return Err(EncodeError::WriteError.into());
+        }
+        let descriptor = TYPE | group_id << GROUP_ID_OFFSET | len as u16;
+        bytes.write_with(offset, descriptor, LE)?;
+
+        match self {
+            PayloadIe::Mlme(ies) => bytes.write(offset, ies)?,
+            PayloadIe::Mpx(ie) => bytes.write(offset, ie)?,
+            #[cfg(feature = "wisun")]
+            PayloadIe::Wisun(ies) => bytes.write(offset, ies)?,
+            PayloadIe::PayloadTermination => (),
+            PayloadIe::Unknown { content, .. } => {
+                bytes.write(offset, content)?
+            }
+        }
+        Ok(*offset)
+    }
+}
+
+/// An iterator over a list of payload IEs
+///
+/// The iterator ends after a payload termination IE, at the end of the
+/// bytes, or after yielding an error for a malformed IE. After a payload
+/// termination IE, [`PayloadIeIterator::offset`] is where the payload starts.
+#[derive(Clone, Debug)]
+pub struct PayloadIeIterator<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    done: bool,
+}
+
+impl<'a> PayloadIeIterator<'a> {
+    /// Iterate over the payload IEs at the start of `bytes`
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            offset: 0,
+            done: false,
+        }
+    }
+
+    /// The offset of the first octet after the IEs that were read so far
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl<'a> Iterator for PayloadIeIterator<'a> {
+    type Item = byte::Result<PayloadIe<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.offset >= self.bytes.len() {
+            return None;
+        }
+        let ie = self.bytes.read::<PayloadIe>(&mut self.offset);
+        self.done = match &ie {
+            Ok(ie) => ie.is_termination(),
+            Err(_) => true,
+        };
+        Some(ie)
+    }
+}
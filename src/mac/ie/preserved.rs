@@ -0,0 +1,92 @@
+//! IEs that remember the bytes they were decoded from
+
+use byte::{BytesExt, TryRead, TryWrite};
+
+/// A decoded IE that remembers the bytes it was decoded from
+///
+/// IEs that are unknown to this crate keep their raw content, but decoding a
+/// known IE is lossy: reserved bits, for example, are not represented in
+/// it. Like [`PreservedFrame`], a `PreservedIe` keeps the original bytes
+/// around, and writes them out unchanged as long as [`PreservedIe::ie`] still
+/// matches them. Once the IE has been modified, it is encoded normally.
+///
+/// This works for [`HeaderIe`], [`PayloadIe`] and [`NestedIe`] alike.
+///
+/// # Example
+///
+/// ``` rust
+/// use byte::BytesExt;
+/// use ieee802154::mac::ie::{HeaderIe, PreservedIe, TimeCorrectionIe};
+///
+/// // Time correction IE with reserved bits set
+/// let bytes = [0x02, 0x0f, 0x0a, 0x70];
+/// let mut ie: PreservedIe<HeaderIe> = bytes.read(&mut 0).unwrap();
+///
+/// let mut buf = [0u8; 4];
+/// buf.write(&mut 0, ie).unwrap();
+/// assert_eq!(buf, bytes);
+///
+/// ie.ie = HeaderIe::TimeCorrection(TimeCorrectionIe::new(11, false).unwrap());
+/// assert!(ie.is_modified());
+/// buf.write(&mut 0, ie).unwrap();
+/// assert_eq!(buf, [0x02, 0x0f, 0x0b, 0x00]);
+/// ```
+///
+/// [`PreservedFrame`]: crate::mac::PreservedFrame
+/// [`HeaderIe`]: super::HeaderIe
+/// [`PayloadIe`]: super::PayloadIe
+/// [`NestedIe`]: super::NestedIe
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PreservedIe<'a, T> {
+    /// The decoded IE
+    pub ie: T,
+    raw: &'a [u8],
+}
+
+impl<'a, T> PreservedIe<'a, T>
+where
+    T: TryRead<'a> + PartialEq,
+{
+    /// The bytes that this IE was decoded from, including its descriptor
+    pub fn raw(&self) -> &'a [u8] {
+        self.raw
+    }
+
+    /// Whether [`PreservedIe::ie`] has been modified since it was decoded
+    pub fn is_modified(&self) -> bool {
+        match self.raw.read::<T>(&mut 0) {
+            Ok(original) => original != self.ie,
+            Err(_) => true,
+        }
+    }
+}
+
+impl<'a, T> TryRead<'a> for PreservedIe<'a, T>
+where
+    T: TryRead<'a>,
+{
+    fn try_read(bytes: &'a [u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let (ie, len) = T::try_read(bytes, ())?;
+        let ie = PreservedIe {
+            ie,
+            raw: &bytes[..len],
+        };
+        Ok((ie, len))
+    }
+}
+
+impl<'a, T> TryWrite for PreservedIe<'a, T>
+where
+    T: TryRead<'a> + TryWrite + PartialEq,
+{
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        if self.is_modified() {
+            bytes.write(offset, self.ie)?;
+        } else {
+            bytes.write(offset, self.raw)?;
+        }
+        Ok(*offset)
+    }
+}
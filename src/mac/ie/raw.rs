@@ -0,0 +1,116 @@
+//! Iterators over IEs that don't decode their content
+
+use super::payload::read_raw_payload_ie;
+use super::{read_raw_header_ie, HeaderElementId, PayloadGroupId};
+
+/// An iterator over the element IDs and the raw content of header IEs
+///
+/// Unlike [`HeaderIeIterator`], this doesn't decode the content of the IEs,
+/// which makes it cheap to look for a single IE, e.g. in an interrupt
+/// handler. The iterator ends after a header termination IE, at the end of
+/// the bytes, or after yielding an error for a malformed IE descriptor.
+///
+/// # Example
+///
+/// ``` rust
+/// use ieee802154::mac::ie::{HeaderElementId, HeaderIeIter};
+///
+/// let bytes = [0x01, 0x10, 0xaa, 0x02, 0x0f, 0x0a, 0x80, 0x80, 0x3f];
+/// let time_correction = HeaderIeIter::new(&bytes)
+///     .filter_map(Result::ok)
+///     .find(|&(id, _)| id == HeaderElementId::TimeCorrection as u8)
+///     .map(|(_, content)| content);
+/// assert_eq!(time_correction, Some(&[0x0a, 0x80][..]));
+/// ```
+///
+/// [`HeaderIeIterator`]: super::HeaderIeIterator
+#[derive(Clone, Debug)]
+pub struct HeaderIeIter<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    done: bool,
+}
+
+impl<'a> HeaderIeIter<'a> {
+    /// Iterate over the header IEs at the start of `bytes`
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            offset: 0,
+            done: false,
+        }
+    }
+
+    /// The offset of the first octet after the IEs that were read so far
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl<'a> Iterator for HeaderIeIter<'a> {
+    type Item = byte::Result<(u8, &'a [u8])>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.offset >= self.bytes.len() {
+            return None;
+        }
+        let ie = read_raw_header_ie(self.bytes, &mut self.offset);
+        self.done = match ie {
+            Ok((element_id, _)) => matches!(
+                HeaderElementId::from_bits(element_id),
+                Some(HeaderElementId::HeaderTermination1)
+                    | Some(HeaderElementId::HeaderTermination2)
+            ),
+            Err(_) => true,
+        };
+        Some(ie)
+    }
+}
+
+/// An iterator over the group IDs and the raw content of payload IEs
+///
+/// Unlike [`PayloadIeIterator`], this doesn't decode the content of the IEs.
+/// The iterator ends after a payload termination IE, at the end of the
+/// bytes, or after yielding an error for a malformed IE descriptor.
+///
+/// [`PayloadIeIterator`]: super::PayloadIeIterator
+#[derive(Clone, Debug)]
+pub struct PayloadIeIter<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    done: bool,
+}
+
+impl<'a> PayloadIeIter<'a> {
+    /// Iterate over the payload IEs at the start of `bytes`
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            offset: 0,
+            done: false,
+        }
+    }
+
+    /// The offset of the first octet after the IEs that were read so far
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl<'a> Iterator for PayloadIeIter<'a> {
+    type Item = byte::Result<(u8, &'a [u8])>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.offset >= self.bytes.len() {
+            return None;
+        }
+        let ie = read_raw_payload_ie(self.bytes, &mut self.offset);
+        self.done = match ie {
+            Ok((group_id, _)) => {
+                group_id == PayloadGroupId::PayloadTermination as u8
+            }
+            Err(_) => true,
+        };
+        Some(ie)
+    }
+}
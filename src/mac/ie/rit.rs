@@ -0,0 +1,127 @@
+//! IEs of receiver initiated transmission (RIT) and low energy modes
+
+use byte::{check_len, BytesExt, TryRead, TryWrite, LE};
+
+use super::from_ten_symbol_units;
+
+/// Receiver Initiated Transmission (RIT) IE
+///
+/// RIT data request commands carry this IE, to announce when the sender
+/// listens for frames. See 7.4.2.7 of 802.15.4-2015 for the units of the
+/// fields.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct RitIe {
+    /// The time until the sender first listens for frames
+    pub time_to_first_listen: u8,
+    /// The number of times the sender listens again after the first time
+    pub number_of_repeat_listen: u8,
+    /// The time between two times that the sender listens for frames
+    pub repeat_listen_interval: u16,
+}
+
+impl TryRead<'_> for RitIe {
+    fn try_read(bytes: &[u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        check_len(bytes, 4)?;
+        let time_to_first_listen = bytes.read(offset)?;
+        let number_of_repeat_listen = bytes.read(offset)?;
+        let repeat_listen_interval = bytes.read_with(offset, LE)?;
+        Ok((
+            Self {
+                time_to_first_listen,
+                number_of_repeat_listen,
+                repeat_listen_interval,
+            },
+            *offset,
+        ))
+    }
+}
+
+impl TryWrite for RitIe {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write(offset, self.time_to_first_listen)?;
+        bytes.write(offset, self.number_of_repeat_listen)?;
+        bytes.write_with(offset, self.repeat_listen_interval, LE)?;
+        Ok(*offset)
+    }
+}
+
+/// Rendezvous Time IE
+///
+/// Wake-up frames of the low energy modes carry this IE, to announce when
+/// the frame that they wake up the receiver for is sent. The fields are in
+/// units of 10 symbols.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct RendezvousTimeIe {
+    /// The time from the end of the wake-up frame until the start of the
+    /// frame that follows it
+    pub rendezvous_time: u16,
+    /// The time between two wake-up frames
+    ///
+    /// This may be omitted, e.g. in the last wake-up frame of a sequence.
+    pub wake_up_interval: Option<u16>,
+}
+
+impl RendezvousTimeIe {
+    /// The rendezvous time, in symbols
+    pub const fn rendezvous_time_symbols(&self) -> u32 {
+        from_ten_symbol_units(self.rendezvous_time)
+    }
+
+    /// The wake-up interval, in symbols
+    pub const fn wake_up_interval_symbols(&self) -> Option<u32> {
+        match self.wake_up_interval {
+            Some(wake_up_interval) => {
+                Some(from_ten_symbol_units(wake_up_interval))
+            }
+            None => None,
+        }
+    }
+
+    /// Get the size of the content of this IE in octets
+    pub const fn get_octet_size(&self) -> usize {
+        if self.wake_up_interval.is_some() {
+            4
+        } else {
+            2
+        }
+    }
+}
+
+impl TryRead<'_> for RendezvousTimeIe {
+    fn try_read(bytes: &[u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        check_len(bytes, 2)?;
+        let rendezvous_time = bytes.read_with(offset, LE)?;
+        let wake_up_interval = if bytes.len() >= 4 {
+            Some(bytes.read_with(offset, LE)?)
+        } else {
+            None
+        };
+        Ok((
+            Self {
+                rendezvous_time,
+                wake_up_interval,
+            },
+            *offset,
+        ))
+    }
+}
+
+impl TryWrite for RendezvousTimeIe {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with(offset, self.rendezvous_time, LE)?;
+        if let Some(wake_up_interval) = self.wake_up_interval {
+            bytes.write_with(offset, wake_up_interval, LE)?;
+        }
+        Ok(*offset)
+    }
+}
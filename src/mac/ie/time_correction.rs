@@ -0,0 +1,123 @@
+//! Time Correction IE
+
+use byte::{check_len, BytesExt, TryRead, TryWrite, LE};
+
+const TIME_CORRECTION_MASK: u16 = 0x0fff;
+const NACK: u16 = 0x8000;
+
+/// Time Correction IE
+///
+/// Enhanced acknowledgements in TSCH networks carry this IE, to let the sender
+/// of a frame synchronize to the receiver. It contains the difference between
+/// the time at which the receiver expected the frame, and the time at which it
+/// actually received it, and whether the frame was negatively acknowledged.
+///
+/// The time correction is a signed 12-bit value in microseconds, so it ranges
+/// from [`TimeCorrectionIe::MIN_US`] to [`TimeCorrectionIe::MAX_US`].
+///
+/// # Example
+///
+/// ``` rust
+/// use ieee802154::mac::ie::TimeCorrectionIe;
+///
+/// let ie = TimeCorrectionIe::new(-100, false).unwrap();
+/// assert_eq!(ie.time_correction_us(), -100);
+/// assert_eq!(ie.to_bits(), 0x0f9c);
+///
+/// // Out of range corrections are clamped
+/// let ie = TimeCorrectionIe::saturating(5000, true);
+/// assert_eq!(ie.time_correction_us(), TimeCorrectionIe::MAX_US);
+/// assert!(ie.nack());
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimeCorrectionIe {
+    time_correction: i16,
+    nack: bool,
+}
+
+impl TimeCorrectionIe {
+    /// The smallest time correction that can be carried, in microseconds
+    pub const MIN_US: i16 = -2048;
+    /// The largest time correction that can be carried, in microseconds
+    pub const MAX_US: i16 = 2047;
+
+    /// Create a Time Correction IE
+    ///
+    /// Returns `None`, if `time_correction_us` is outside of
+    /// [`Self::MIN_US`]..=[`Self::MAX_US`].
+    pub const fn new(time_correction_us: i16, nack: bool) -> Option<Self> {
+        if time_correction_us < Self::MIN_US
+            || time_correction_us > Self::MAX_US
+        {
+            return None;
+        }
+        Some(Self {
+            time_correction: time_correction_us,
+            nack,
+        })
+    }
+
+    /// Create a Time Correction IE, clamping `time_correction_us` to
+    /// [`Self::MIN_US`]..=[`Self::MAX_US`]
+    pub const fn saturating(time_correction_us: i32, nack: bool) -> Self {
+        let time_correction = if time_correction_us < Self::MIN_US as i32 {
+            Self::MIN_US
+        } else if time_correction_us > Self::MAX_US as i32 {
+            Self::MAX_US
+        } else {
+            time_correction_us as i16
+        };
+        Self {
+            time_correction,
+            nack,
+        }
+    }
+
+    /// The time correction, in microseconds
+    pub const fn time_correction_us(&self) -> i16 {
+        self.time_correction
+    }
+
+    /// Whether the acknowledged frame was negatively acknowledged
+    pub const fn nack(&self) -> bool {
+        self.nack
+    }
+
+    /// Decode the Time Correction IE from its (little endian) 16-bit value
+    pub const fn from_bits(bits: u16) -> Self {
+        // Sign extend the 12-bit time correction
+        let time_correction = ((bits & TIME_CORRECTION_MASK) << 4) as i16 >> 4;
+        Self {
+            time_correction,
+            nack: bits & NACK != 0,
+        }
+    }
+
+    /// Encode the Time Correction IE into its (little endian) 16-bit value
+    pub const fn to_bits(&self) -> u16 {
+        let mut bits = self.time_correction as u16 & TIME_CORRECTION_MASK;
+        if self.nack {
+            bits |= NACK;
+        }
+        bits
+    }
+}
+
+impl TryRead<'_> for TimeCorrectionIe {
+    fn try_read(bytes: &[u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        check_len(bytes, 2)?;
+        let bits: u16 = bytes.read_with(offset, LE)?;
+        Ok((Self::from_bits(bits), *offset))
+    }
+}
+
+impl TryWrite for TimeCorrectionIe {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with(offset, self.to_bits(), LE)?;
+        Ok(*offset)
+    }
+}
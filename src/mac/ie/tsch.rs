@@ -0,0 +1,721 @@
+//! Nested IEs of TSCH networks
+
+use byte::{check_len, BytesExt, TryRead, TryWrite, LE};
+
+use crate::mac::frame::EncodeError;
+
+/// TSCH Synchronization IE
+///
+/// Enhanced beacons of TSCH networks carry this IE, to let joining devices
+/// synchronize to the network.
+///
+/// # Example
+///
+/// ``` rust
+/// use byte::BytesExt;
+/// use ieee802154::mac::ie::TschSynchronizationIe;
+///
+/// let ie = TschSynchronizationIe {
+///     asn: 0x01_0203_0405,
+///     join_metric: 1,
+/// };
+///
+/// let mut bytes = [0u8; 6];
+/// bytes.write(&mut 0, ie).unwrap();
+/// assert_eq!(bytes, [0x05, 0x04, 0x03, 0x02, 0x01, 0x01]);
+/// assert_eq!(bytes.read::<TschSynchronizationIe>(&mut 0), Ok(ie));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct TschSynchronizationIe {
+    /// The Absolute Slot Number of the slot in which the frame is sent
+    ///
+    /// This is a 40-bit value, so it must not exceed
+    /// [`TschSynchronizationIe::MAX_ASN`].
+    pub asn: u64,
+    /// The join metric of the sender, e.g. its hop count to the PAN
+    /// coordinator
+    pub join_metric: u8,
+}
+
+impl TschSynchronizationIe {
+    /// The largest Absolute Slot Number that can be carried
+    pub const MAX_ASN: u64 = 0xff_ffff_ffff;
+}
+
+impl TryRead<'_> for TschSynchronizationIe {
+    fn try_read(bytes: &[u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        check_len(bytes, 6)?;
+        let mut asn = [0u8; 8];
+        asn[..5].copy_from_slice(&bytes[..5]);
+        *offset += 5;
+        let join_metric = bytes.read(offset)?;
+        Ok((
+            Self {
+                asn: u64::from_le_bytes(asn),
+                join_metric,
+            },
+            *offset,
+        ))
+    }
+}
+
+impl TryWrite for TschSynchronizationIe {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        if self.asn > Self::MAX_ASN {
+            return Err(EncodeError::WriteError.into());
+        }
+        bytes.write(offset, &self.asn.to_le_bytes()[..5])?;
+        bytes.write(offset, self.join_metric)?;
+        Ok(*offset)
+    }
+}
+
+const LINK_TX: u8 = 0x01;
+const LINK_RX: u8 = 0x02;
+const LINK_SHARED: u8 = 0x04;
+const LINK_TIMEKEEPING: u8 = 0x08;
+const LINK_PRIORITY: u8 = 0x10;
+
+const LINK_DESCRIPTOR_LEN: usize = 5;
+const SLOTFRAME_DESCRIPTOR_HEADER_LEN: usize = 4;
+
+/// The options of a TSCH link
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct LinkOptions {
+    /// Frames may be transmitted in the link
+    pub tx: bool,
+    /// Frames may be received in the link
+    pub rx: bool,
+    /// The link is shared, so transmissions in it use backoff
+    pub shared: bool,
+    /// The link is used to keep time
+    pub timekeeping: bool,
+    /// The link is a priority channel access link
+    pub priority: bool,
+}
+
+impl LinkOptions {
+    /// Decode the link options from their bits
+    pub const fn from_bits(bits: u8) -> Self {
+        Self {
+            tx: bits & LINK_TX != 0,
+            rx: bits & LINK_RX != 0,
+            shared: bits & LINK_SHARED != 0,
+            timekeeping: bits & LINK_TIMEKEEPING != 0,
+            priority: bits & LINK_PRIORITY != 0,
+        }
+    }
+
+    /// Encode the link options into their bits
+    pub const fn to_bits(&self) -> u8 {
+        let mut bits = 0;
+        if self.tx {
+            bits |= LINK_TX;
+        }
+        if self.rx {
+            bits |= LINK_RX;
+        }
+        if self.shared {
+            bits |= LINK_SHARED;
+        }
+        if self.timekeeping {
+            bits |= LINK_TIMEKEEPING;
+        }
+        if self.priority {
+            bits |= LINK_PRIORITY;
+        }
+        bits
+    }
+}
+
+/// A link of a TSCH slotframe
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct LinkDescriptor {
+    /// The timeslot of the link within its slotframe
+    pub timeslot: u16,
+    /// The channel offset of the link
+    pub channel_offset: u16,
+    /// The options of the link
+    pub options: LinkOptions,
+}
+
+impl LinkDescriptor {
+    /// The single shared cell of the minimal 6TiSCH configuration (RFC 8180)
+    pub const MINIMAL_CELL: Self = Self {
+        timeslot: 0,
+        channel_offset: 0,
+        options: LinkOptions {
+            tx: true,
+            rx: true,
+            shared: true,
+            timekeeping: true,
+            priority: false,
+        },
+    };
+}
+
+impl TryRead<'_> for LinkDescriptor {
+    fn try_read(bytes: &[u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        check_len(bytes, LINK_DESCRIPTOR_LEN)?;
+        let timeslot = bytes.read_with(offset, LE)?;
+        let channel_offset = bytes.read_with(offset, LE)?;
+        let options = LinkOptions::from_bits(bytes.read(offset)?);
+        Ok((
+            Self {
+                timeslot,
+                channel_offset,
+                options,
+            },
+            *offset,
+        ))
+    }
+}
+
+impl TryWrite for LinkDescriptor {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with(offset, self.timeslot, LE)?;
+        bytes.write_with(offset, self.channel_offset, LE)?;
+        bytes.write(offset, self.options.to_bits())?;
+        Ok(*offset)
+    }
+}
+
+/// The links of a TSCH slotframe
+///
+/// Like [`NestedIes`], these are [`LinkDescriptors::Encoded`] when read from
+/// a frame, and can be [`LinkDescriptors::Decoded`] when written to one.
+///
+/// [`NestedIes`]: super::NestedIes
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum LinkDescriptors<'a> {
+    /// The encoded link descriptors
+    Encoded(&'a [u8]),
+    /// The decoded link descriptors
+    Decoded(&'a [LinkDescriptor]),
+}
+
+impl<'a> LinkDescriptors<'a> {
+    /// The number of links
+    pub fn len(&self) -> usize {
+        match self {
+            LinkDescriptors::Encoded(bytes) => {
+                bytes.len() / LINK_DESCRIPTOR_LEN
+            }
+            LinkDescriptors::Decoded(links) => links.len(),
+        }
+    }
+
+    /// Whether there are no links
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterate over the links
+    pub fn iter(&self) -> LinkDescriptorIterator<'a> {
+        LinkDescriptorIterator {
+            links: *self,
+            index: 0,
+        }
+    }
+}
+
+/// An iterator over the links of a TSCH slotframe
+#[derive(Clone, Debug)]
+pub struct LinkDescriptorIterator<'a> {
+    links: LinkDescriptors<'a>,
+    index: usize,
+}
+
+impl Iterator for LinkDescriptorIterator<'_> {
+    type Item = LinkDescriptor;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let link = match self.links {
+            LinkDescriptors::Encoded(bytes) => {
+                let offset = &mut (self.index * LINK_DESCRIPTOR_LEN);
+                // The length of the encoded links is checked when they're read
+                bytes.read(offset).ok()?
+            }
+            LinkDescriptors::Decoded(links) => *links.get(self.index)?,
+        };
+        self.index += 1;
+        Some(link)
+    }
+}
+
+/// A TSCH slotframe and its links
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SlotframeDescriptor<'a> {
+    /// The handle that identifies the slotframe
+    pub handle: u8,
+    /// The number of timeslots in the slotframe
+    pub size: u16,
+    /// The links of the slotframe
+    pub links: LinkDescriptors<'a>,
+}
+
+impl SlotframeDescriptor<'_> {
+    /// Get the size of this descriptor in octets
+    pub fn get_octet_size(&self) -> usize {
+        SLOTFRAME_DESCRIPTOR_HEADER_LEN + self.links.len() * LINK_DESCRIPTOR_LEN
+    }
+}
+
+impl<'a> TryRead<'a> for SlotframeDescriptor<'a> {
+    fn try_read(bytes: &'a [u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        check_len(bytes, SLOTFRAME_DESCRIPTOR_HEADER_LEN)?;
+        let handle = bytes.read(offset)?;
+        let size = bytes.read_with(offset, LE)?;
+        let link_count: u8 = bytes.read(offset)?;
+        let links_len = link_count as usize * LINK_DESCRIPTOR_LEN;
+        check_len(&bytes[*offset..], links_len)?;
+        let links = LinkDescriptors::Encoded(&bytes[*offset..][..links_len]);
+        *offset += links_len;
+        Ok((
+            Self {
+                handle,
+                size,
+                links,
+            },
+            *offset,
+        ))
+    }
+}
+
+impl TryWrite for SlotframeDescriptor<'_> {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        if self.links.len() > u8::MAX as usize {
+            return Err(EncodeError::WriteError.into());
+        }
+        bytes.write(offset, self.handle)?;
+        bytes.write_with(offset, self.size, LE)?;
+        bytes.write(offset, self.links.len() as u8)?;
+        match self.links {
+            LinkDescriptors::Encoded(encoded) => {
+                bytes.write(offset, encoded)?
+            }
+            LinkDescriptors::Decoded(links) => {
+                for link in links {
+                    bytes.write(offset, *link)?;
+                }
+            }
+        }
+        Ok(*offset)
+    }
+}
+
+/// TSCH Slotframe and Link IE
+///
+/// Enhanced beacons of TSCH networks carry this IE, to advertise the
+/// slotframes and links that joining devices can use.
+///
+/// Like [`NestedIes`], the slotframes are [`TschSlotframeAndLinkIe::Encoded`]
+/// when read from a frame, and can be [`TschSlotframeAndLinkIe::Decoded`]
+/// when written to one.
+///
+/// # Example
+///
+/// ``` rust
+/// use byte::BytesExt;
+/// use ieee802154::mac::ie::{
+///     LinkDescriptor, LinkDescriptors, SlotframeDescriptor,
+///     TschSlotframeAndLinkIe,
+/// };
+///
+/// // The schedule of the minimal 6TiSCH configuration
+/// let slotframes = [SlotframeDescriptor {
+///     handle: 0,
+///     size: 101,
+///     links: LinkDescriptors::Decoded(&[LinkDescriptor::MINIMAL_CELL]),
+/// }];
+/// let ie = TschSlotframeAndLinkIe::Decoded(&slotframes);
+///
+/// let mut bytes = [0u8; 10];
+/// bytes.write(&mut 0, ie).unwrap();
+/// assert_eq!(bytes, [0x01, 0x00, 0x65, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x0f]);
+///
+/// let ie: TschSlotframeAndLinkIe = bytes.read(&mut 0).unwrap();
+/// let slotframe = ie.iter().next().unwrap();
+/// assert_eq!(slotframe.size, 101);
+/// assert_eq!(slotframe.links.iter().next(), Some(LinkDescriptor::MINIMAL_CELL));
+/// ```
+///
+/// [`NestedIes`]: super::NestedIes
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TschSlotframeAndLinkIe<'a> {
+    /// The encoded slotframe descriptors
+    Encoded {
+        /// The number of slotframes
+        count: u8,
+        /// The encoded slotframe descriptors
+        slotframes: &'a [u8],
+    },
+    /// The decoded slotframe descriptors
+    Decoded(&'a [SlotframeDescriptor<'a>]),
+}
+
+impl<'a> TschSlotframeAndLinkIe<'a> {
+    /// The number of slotframes
+    pub fn len(&self) -> usize {
+        match self {
+            TschSlotframeAndLinkIe::Encoded { count, .. } => *count as usize,
+            TschSlotframeAndLinkIe::Decoded(slotframes) => slotframes.len(),
+        }
+    }
+
+    /// Whether there are no slotframes
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterate over the slotframes
+    pub fn iter(&self) -> SlotframeDescriptorIterator<'a> {
+        SlotframeDescriptorIterator {
+            ie: *self,
+            index: 0,
+            offset: 0,
+        }
+    }
+
+    /// Get the size of the content of this IE in octets
+    pub fn get_octet_size(&self) -> usize {
+        match self {
+            TschSlotframeAndLinkIe::Encoded { slotframes, .. } => {
+                1 + slotframes.len()
+            }
+            TschSlotframeAndLinkIe::Decoded(slotframes) => {
+                1 + slotframes
+                    .iter()
+                    .map(SlotframeDescriptor::get_octet_size)
+                    .sum::<usize>()
+            }
+        }
+    }
+}
+
+impl<'a> TryRead<'a> for TschSlotframeAndLinkIe<'a> {
+    fn try_read(bytes: &'a [u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let count: u8 = bytes.read(offset)?;
+        let start = *offset;
+        // Check that all descriptors are complete, so that iterating over
+        // them can't fail
+        for _ in 0..count {
+            bytes.read::<SlotframeDescriptor>(offset)?;
+        }
+        Ok((
+            TschSlotframeAndLinkIe::Encoded {
+                count,
+                slotframes: &bytes[start..*offset],
+            },
+            *offset,
+        ))
+    }
+}
+
+impl TryWrite for TschSlotframeAndLinkIe<'_> {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        if self.len() > u8::MAX as usize {
+            return Err(EncodeError::WriteError.into());
+        }
+        bytes.write(offset, self.len() as u8)?;
+        match self {
+            TschSlotframeAndLinkIe::Encoded { slotframes, .. } => {
+                bytes.write(offset, slotframes)?
+            }
+            TschSlotframeAndLinkIe::Decoded(slotframes) => {
+                for slotframe in slotframes {
+                    bytes.write(offset, *slotframe)?;
+                }
+            }
+        }
+        Ok(*offset)
+    }
+}
+
+/// An iterator over the slotframes of a [`TschSlotframeAndLinkIe`]
+#[derive(Clone, Debug)]
+pub struct SlotframeDescriptorIterator<'a> {
+    ie: TschSlotframeAndLinkIe<'a>,
+    index: usize,
+    offset: usize,
+}
+
+impl<'a> Iterator for SlotframeDescriptorIterator<'a> {
+    type Item = SlotframeDescriptor<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.ie.len() {
+            return None;
+        }
+        let slotframe = match self.ie {
+            TschSlotframeAndLinkIe::Encoded { slotframes, .. } => {
+                // The encoded slotframes are checked when they're read
+                slotframes.read(&mut self.offset).ok()?
+            }
+            TschSlotframeAndLinkIe::Decoded(slotframes) => {
+                slotframes[self.index]
+            }
+        };
+        self.index += 1;
+        Some(slotframe)
+    }
+}
+
+const TIMESLOT_TEMPLATE_LEN: usize = 24;
+const LONG_TIMESLOT_TEMPLATE_LEN: usize = 26;
+
+/// The timing of a TSCH timeslot, in microseconds
+///
+/// The maximum transmission time and the timeslot length are encoded in 3
+/// octets if either doesn't fit in 2, and in 2 octets otherwise.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct TimeslotTemplate {
+    /// The time from the start of the timeslot until the start of the CCA
+    pub cca_offset: u16,
+    /// The duration of the CCA
+    pub cca: u16,
+    /// The time from the start of the timeslot until the start of the
+    /// transmission of a frame
+    pub tx_offset: u16,
+    /// The time from the start of the timeslot until the receiver starts
+    /// listening for a frame
+    pub rx_offset: u16,
+    /// The time from the end of a received frame until the receiver starts
+    /// listening for the acknowledgement
+    pub rx_ack_delay: u16,
+    /// The time from the end of a received frame until the start of the
+    /// transmission of the acknowledgement
+    pub tx_ack_delay: u16,
+    /// The time that the receiver listens for a frame
+    pub rx_wait: u16,
+    /// The time that the sender listens for an acknowledgement
+    pub ack_wait: u16,
+    /// The time to switch between receiving and transmitting
+    pub rx_tx: u16,
+    /// The time to transmit the longest acknowledgement
+    pub max_ack: u16,
+    /// The time to transmit the longest frame
+    pub max_tx: u32,
+    /// The length of the timeslot
+    pub timeslot_length: u32,
+}
+
+impl TimeslotTemplate {
+    /// The default timeslot template of 2.4 GHz O-QPSK PHYs, with 10 ms
+    /// timeslots
+    ///
+    /// This is the template with ID 0.
+    pub const DEFAULT_10MS: Self = Self {
+        cca_offset: 1800,
+        cca: 128,
+        tx_offset: 2120,
+        rx_offset: 1020,
+        rx_ack_delay: 800,
+        tx_ack_delay: 1000,
+        rx_wait: 2200,
+        ack_wait: 400,
+        rx_tx: 192,
+        max_ack: 2400,
+        max_tx: 4256,
+        timeslot_length: 10000,
+    };
+
+    /// The largest value of the maximum transmission time and the timeslot
+    /// length
+    pub const MAX_LONG_VALUE: u32 = 0x00ff_ffff;
+
+    fn is_long(&self) -> bool {
+        self.max_tx > u16::MAX as u32 || self.timeslot_length > u16::MAX as u32
+    }
+
+    /// Get the size of this template in octets
+    pub fn get_octet_size(&self) -> usize {
+        if self.is_long() {
+            LONG_TIMESLOT_TEMPLATE_LEN
+        } else {
+            TIMESLOT_TEMPLATE_LEN
+        }
+    }
+}
+
+impl TryRead<'_> for TimeslotTemplate {
+    fn try_read(bytes: &[u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        check_len(bytes, TIMESLOT_TEMPLATE_LEN)?;
+        let long = bytes.len() >= LONG_TIMESLOT_TEMPLATE_LEN;
+        let cca_offset = bytes.read_with(offset, LE)?;
+        let cca = bytes.read_with(offset, LE)?;
+        let tx_offset = bytes.read_with(offset, LE)?;
+        let rx_offset = bytes.read_with(offset, LE)?;
+        let rx_ack_delay = bytes.read_with(offset, LE)?;
+        let tx_ack_delay = bytes.read_with(offset, LE)?;
+        let rx_wait = bytes.read_with(offset, LE)?;
+        let ack_wait = bytes.read_with(offset, LE)?;
+        let rx_tx = bytes.read_with(offset, LE)?;
+        let max_ack = bytes.read_with(offset, LE)?;
+        let max_tx = read_duration(bytes, offset, long)?;
+        let timeslot_length = read_duration(bytes, offset, long)?;
+        Ok((
+            Self {
+                cca_offset,
+                cca,
+                tx_offset,
+                rx_offset,
+                rx_ack_delay,
+                tx_ack_delay,
+                rx_wait,
+                ack_wait,
+                rx_tx,
+                max_ack,
+                max_tx,
+                timeslot_length,
+            },
+            *offset,
+        ))
+    }
+}
+
+impl TryWrite for TimeslotTemplate {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        if self.max_tx > Self::MAX_LONG_VALUE
+            || self.timeslot_length > Self::MAX_LONG_VALUE
+        {
+            return Err(EncodeError::WriteError.into());
+        }
+        let long = self.is_long();
+        bytes.write_with(offset, self.cca_offset, LE)?;
+        bytes.write_with(offset, self.cca, LE)?;
+        bytes.write_with(offset, self.tx_offset, LE)?;
+        bytes.write_with(offset, self.rx_offset, LE)?;
+        bytes.write_with(offset, self.rx_ack_delay, LE)?;
+        bytes.write_with(offset, self.tx_ack_delay, LE)?;
+        bytes.write_with(offset, self.rx_wait, LE)?;
+        bytes.write_with(offset, self.ack_wait, LE)?;
+        bytes.write_with(offset, self.rx_tx, LE)?;
+        bytes.write_with(offset, self.max_ack, LE)?;
+        let len = if long { 3 } else { 2 };
+        bytes.write(offset, &self.max_tx.to_le_bytes()[..len])?;
+        bytes.write(offset, &self.timeslot_length.to_le_bytes()[..len])?;
+        Ok(*offset)
+    }
+}
+
+/// Read a duration of 2 or 3 octets
+fn read_duration(
+    bytes: &[u8],
+    offset: &mut usize,
+    long: bool,
+) -> byte::Result<u32> {
+    if long {
+        check_len(&bytes[*offset..], 3)?;
+        let mut duration = [0u8; 4];
+        duration[..3].copy_from_slice(&bytes[*offset..*offset + 3]);
+        *offset += 3;
+        Ok(u32::from_le_bytes(duration))
+    } else {
+        let duration: u16 = bytes.read_with(offset, LE)?;
+        Ok(duration as u32)
+    }
+}
+
+/// TSCH Timeslot IE
+///
+/// Enhanced beacons of TSCH networks carry this IE, to advertise the timing
+/// of the timeslots. The timing is either identified by the ID of a
+/// template only, or given in full.
+///
+/// # Example
+///
+/// ``` rust
+/// use byte::BytesExt;
+/// use ieee802154::mac::ie::{TimeslotTemplate, TschTimeslotIe};
+///
+/// let ie = TschTimeslotIe::DEFAULT;
+/// let mut bytes = [0u8; 1];
+/// bytes.write(&mut 0, ie).unwrap();
+/// assert_eq!(bytes, [0x00]);
+/// assert_eq!(ie.template(), Some(TimeslotTemplate::DEFAULT_10MS));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct TschTimeslotIe {
+    /// The ID of the timeslot template
+    pub id: u8,
+    /// The timing of the timeslots, if it is given in full
+    pub timing: Option<TimeslotTemplate>,
+}
+
+impl TschTimeslotIe {
+    /// The IE of the default timeslot template, which only carries its ID
+    pub const DEFAULT: Self = Self {
+        id: 0,
+        timing: None,
+    };
+
+    /// The timing of the timeslots
+    ///
+    /// This is the timing that is given in full, or the default template if
+    /// only its ID is given. Returns `None` for the IDs of other templates.
+    pub fn template(&self) -> Option<TimeslotTemplate> {
+        match (self.timing, self.id) {
+            (Some(timing), _) => Some(timing),
+            (None, 0) => Some(TimeslotTemplate::DEFAULT_10MS),
+            (None, _) => None,
+        }
+    }
+
+    /// Get the size of the content of this IE in octets
+    pub fn get_octet_size(&self) -> usize {
+        1 + self.timing.map_or(0, |timing| timing.get_octet_size())
+    }
+}
+
+impl TryRead<'_> for TschTimeslotIe {
+    fn try_read(bytes: &[u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let id = bytes.read(offset)?;
+        let timing = if bytes.len() > *offset {
+            Some(bytes.read(offset)?)
+        } else {
+            None
+        };
+        Ok((Self { id, timing }, *offset))
+    }
+}
+
+impl TryWrite for TschTimeslotIe {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write(offset, self.id)?;
+        if let Some(timing) = self.timing {
+            bytes.write(offset, timing)?;
+        }
+        Ok(*offset)
+    }
+}
@@ -0,0 +1,1246 @@
+//! Wi-SUN FAN IEs
+//!
+//! Wi-SUN FAN defines its own IEs within the IE framework of IEEE 802.15.4.
+//! Its header IEs are carried in the Wi-SUN header IE (WH-IE), the content
+//! of which starts with the sub-ID of the IE. Its payload IEs are nested IEs,
+//! which are carried in the Wi-SUN payload IE (WP-IE), like the nested IEs in
+//! the MLME IE.
+
+use byte::{check_len, BytesExt, TryRead, TryWrite, BE, LE};
+
+use super::nested::{read_raw_nested_ie, write_nested_ie_descriptor};
+use super::read_content;
+use crate::mac::frame::EncodeError;
+use crate::mac::{DecodeError, ExtendedAddress};
+
+/// The largest 24-bit value
+const MAX_U24: u32 = 0xff_ffff;
+
+/// The largest length of a network name, in octets
+pub const MAX_NETWORK_NAME_LEN: usize = 32;
+
+/// Read a 24-bit value
+fn read_u24(bytes: &[u8], offset: &mut usize) -> byte::Result<u32> {
+    check_len(&bytes[*offset..], 3)?;
+    let mut value = [0u8; 4];
+    value[..3].copy_from_slice(&bytes[*offset..*offset + 3]);
+    *offset += 3;
+    Ok(u32::from_le_bytes(value))
+}
+
+/// Write a 24-bit value
+fn write_u24(
+    bytes: &mut [u8],
+    offset: &mut usize,
+    value: u32,
+) -> byte::Result<()> {
+    if value > MAX_U24 {
+        return Err(EncodeError::WriteError.into());
+    }
+    bytes.write(offset, &value.to_le_bytes()[..3])
+}
+
+/// Sub-IDs of the Wi-SUN header IEs that this crate knows
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum WisunHeaderSubId {
+    /// Unicast Timing and Frame Type IE (UTT-IE)
+    UnicastTiming = 0x01,
+    /// Broadcast Timing IE (BT-IE)
+    BroadcastTiming = 0x02,
+    /// Flow Control IE (FC-IE)
+    FlowControl = 0x03,
+    /// Received Signal Level IE (RSL-IE)
+    ReceivedSignalLevel = 0x04,
+    /// EAPOL Authenticator IE (EA-IE)
+    EapolAuthenticator = 0x09,
+}
+
+impl WisunHeaderSubId {
+    /// Creates an instance of [`WisunHeaderSubId`] from the provided bits
+    ///
+    /// Returns `None`, if the sub-ID is unknown to this crate.
+    pub const fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            0x01 => Some(WisunHeaderSubId::UnicastTiming),
+            0x02 => Some(WisunHeaderSubId::BroadcastTiming),
+            0x03 => Some(WisunHeaderSubId::FlowControl),
+            0x04 => Some(WisunHeaderSubId::ReceivedSignalLevel),
+            0x09 => Some(WisunHeaderSubId::EapolAuthenticator),
+            _ => None,
+        }
+    }
+}
+
+/// Wi-SUN frame types, which are carried in the [`UnicastTimingIe`]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum WisunFrameType {
+    /// PAN Advertisement
+    PanAdvertisement = 0,
+    /// PAN Advertisement Solicit
+    PanAdvertisementSolicit = 1,
+    /// PAN Configuration
+    PanConfiguration = 2,
+    /// PAN Configuration Solicit
+    PanConfigurationSolicit = 3,
+    /// Data
+    Data = 4,
+    /// Acknowledgement
+    Acknowledgement = 5,
+    /// EAPOL
+    Eapol = 6,
+}
+
+impl WisunFrameType {
+    /// Creates an instance of [`WisunFrameType`] from the provided bits
+    ///
+    /// Returns `None`, if the frame type is unknown to this crate.
+    pub const fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            0 => Some(WisunFrameType::PanAdvertisement),
+            1 => Some(WisunFrameType::PanAdvertisementSolicit),
+            2 => Some(WisunFrameType::PanConfiguration),
+            3 => Some(WisunFrameType::PanConfigurationSolicit),
+            4 => Some(WisunFrameType::Data),
+            5 => Some(WisunFrameType::Acknowledgement),
+            6 => Some(WisunFrameType::Eapol),
+            _ => None,
+        }
+    }
+}
+
+/// Unicast Timing and Frame Type IE (UTT-IE)
+///
+/// Carries the Wi-SUN frame type, and the position of the sender in its
+/// unicast schedule.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct UnicastTimingIe {
+    /// The Wi-SUN frame type
+    ///
+    /// Use [`UnicastTimingIe::frame_type`] to decode it.
+    pub frame_type: u8,
+    /// The Unicast Fractional Sequence Interval (UFSI)
+    ///
+    /// This is a 24-bit value.
+    pub fractional_sequence_interval: u32,
+}
+
+impl UnicastTimingIe {
+    /// The Wi-SUN frame type, or `None` if it is unknown to this crate
+    pub fn frame_type(&self) -> Option<WisunFrameType> {
+        WisunFrameType::from_bits(self.frame_type)
+    }
+}
+
+impl TryRead<'_> for UnicastTimingIe {
+    fn try_read(bytes: &[u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let frame_type = bytes.read(offset)?;
+        let fractional_sequence_interval = read_u24(bytes, offset)?;
+        Ok((
+            Self {
+                frame_type,
+                fractional_sequence_interval,
+            },
+            *offset,
+        ))
+    }
+}
+
+impl TryWrite for UnicastTimingIe {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write(offset, self.frame_type)?;
+        write_u24(bytes, offset, self.fractional_sequence_interval)?;
+        Ok(*offset)
+    }
+}
+
+/// Broadcast Timing IE (BT-IE)
+///
+/// Carries the position of the sender in the broadcast schedule.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct BroadcastTimingIe {
+    /// The Broadcast Slot Number (BSN)
+    pub slot_number: u16,
+    /// The Broadcast Interval Offset, in milliseconds
+    ///
+    /// This is a 24-bit value.
+    pub interval_offset: u32,
+}
+
+impl TryRead<'_> for BroadcastTimingIe {
+    fn try_read(bytes: &[u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let slot_number = bytes.read_with(offset, LE)?;
+        let interval_offset = read_u24(bytes, offset)?;
+        Ok((
+            Self {
+                slot_number,
+                interval_offset,
+            },
+            *offset,
+        ))
+    }
+}
+
+impl TryWrite for BroadcastTimingIe {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with(offset, self.slot_number, LE)?;
+        write_u24(bytes, offset, self.interval_offset)?;
+        Ok(*offset)
+    }
+}
+
+/// Flow Control IE (FC-IE)
+///
+/// Carries how long the sender transmits and receives, when it exchanges a
+/// frame without listening to the schedule of the receiver.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct FlowControlIe {
+    /// The transmit flow control, in milliseconds
+    pub transmit: u8,
+    /// The receive flow control, in milliseconds
+    pub receive: u8,
+}
+
+impl TryRead<'_> for FlowControlIe {
+    fn try_read(bytes: &[u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let transmit = bytes.read(offset)?;
+        let receive = bytes.read(offset)?;
+        Ok((Self { transmit, receive }, *offset))
+    }
+}
+
+impl TryWrite for FlowControlIe {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write(offset, self.transmit)?;
+        bytes.write(offset, self.receive)?;
+        Ok(*offset)
+    }
+}
+
+/// A Wi-SUN header IE, which is carried in the Wi-SUN header IE (WH-IE)
+///
+/// # Example
+///
+/// ``` rust
+/// use byte::BytesExt;
+/// use ieee802154::mac::ie::{HeaderIe, UnicastTimingIe, WisunHeaderIe};
+///
+/// let bytes = [0x05, 0x15, 0x01, 0x04, 0x56, 0x34, 0x12];
+/// let ie: HeaderIe = bytes.read(&mut 0).unwrap();
+/// assert_eq!(
+///     ie,
+///     HeaderIe::Wisun(WisunHeaderIe::UnicastTiming(UnicastTimingIe {
+///         frame_type: 4,
+///         fractional_sequence_interval: 0x123456,
+///     }))
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WisunHeaderIe<'a> {
+    /// Unicast Timing and Frame Type IE (UTT-IE)
+    UnicastTiming(UnicastTimingIe),
+    /// Broadcast Timing IE (BT-IE)
+    BroadcastTiming(BroadcastTimingIe),
+    /// Flow Control IE (FC-IE)
+    FlowControl(FlowControlIe),
+    /// Received Signal Level IE (RSL-IE)
+    ///
+    /// The level at which the sender received the last frame from the
+    /// receiver, in dBm offset by 174.
+    ReceivedSignalLevel(u8),
+    /// EAPOL Authenticator IE (EA-IE)
+    ///
+    /// The address of the authenticator of the PAN.
+    EapolAuthenticator(ExtendedAddress),
+    /// An IE that is unknown to this crate
+    Unknown {
+        /// The sub-ID of the IE
+        sub_id: u8,
+        /// The content of the IE, following its sub-ID
+        content: &'a [u8],
+    },
+}
+
+impl WisunHeaderIe<'_> {
+    /// The sub-ID of this IE
+    pub fn sub_id(&self) -> u8 {
+        let sub_id = match self {
+            WisunHeaderIe::UnicastTiming(_) => WisunHeaderSubId::UnicastTiming,
+            WisunHeaderIe::BroadcastTiming(_) => {
+                WisunHeaderSubId::BroadcastTiming
+            }
+            WisunHeaderIe::FlowControl(_) => WisunHeaderSubId::FlowControl,
+            WisunHeaderIe::ReceivedSignalLevel(_) => {
+                WisunHeaderSubId::ReceivedSignalLevel
+            }
+            WisunHeaderIe::EapolAuthenticator(_) => {
+                WisunHeaderSubId::EapolAuthenticator
+            }
+            WisunHeaderIe::Unknown { sub_id, .. } => return *sub_id,
+        };
+        sub_id as u8
+    }
+
+    /// Get the size of this IE in octets, including its sub-ID
+    pub fn get_octet_size(&self) -> usize {
+        1 + match self {
+            WisunHeaderIe::UnicastTiming(_) => 4,
+            WisunHeaderIe::BroadcastTiming(_) => 5,
+            WisunHeaderIe::FlowControl(_) => 2,
+            WisunHeaderIe::ReceivedSignalLevel(_) => 1,
+            WisunHeaderIe::EapolAuthenticator(_) => 8,
+            WisunHeaderIe::Unknown { content, .. } => content.len(),
+        }
+    }
+}
+
+impl<'a> TryRead<'a> for WisunHeaderIe<'a> {
+    fn try_read(bytes: &'a [u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let sub_id = bytes.read(offset)?;
+        let content = &bytes[*offset..];
+
+        let ie = match WisunHeaderSubId::from_bits(sub_id) {
+            Some(WisunHeaderSubId::UnicastTiming) => {
+                WisunHeaderIe::UnicastTiming(read_content(content)?)
+            }
+            Some(WisunHeaderSubId::BroadcastTiming) => {
+                WisunHeaderIe::BroadcastTiming(read_content(content)?)
+            }
+            Some(WisunHeaderSubId::FlowControl) => {
+                WisunHeaderIe::FlowControl(read_content(content)?)
+            }
+            Some(WisunHeaderSubId::ReceivedSignalLevel) => {
+                if content.len() != 1 {
+                    return Err(DecodeError::IeLengthMismatch.into());
+                }
+                WisunHeaderIe::ReceivedSignalLevel(content[0])
+            }
+            Some(WisunHeaderSubId::EapolAuthenticator) => {
+                // The EUI-64 is carried in its canonical byte order
+                if content.len() != 8 {
+                    return Err(DecodeError::IeLengthMismatch.into());
+                }
+                WisunHeaderIe::EapolAuthenticator(ExtendedAddress(
+                    content.read_with(&mut 0, BE)?,
+                ))
+            }
+            None => WisunHeaderIe::Unknown { sub_id, content },
+        };
+        Ok((ie, bytes.len()))
+    }
+}
+
+impl TryWrite for WisunHeaderIe<'_> {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write(offset, self.sub_id())?;
+        match self {
+            WisunHeaderIe::UnicastTiming(ie) => bytes.write(offset, ie)?,
+            WisunHeaderIe::BroadcastTiming(ie) => bytes.write(offset, ie)?,
+            WisunHeaderIe::FlowControl(ie) => bytes.write(offset, ie)?,
+            WisunHeaderIe::ReceivedSignalLevel(level) => {
+                bytes.write(offset, level)?
+            }
+            WisunHeaderIe::EapolAuthenticator(address) => {
+                bytes.write_with(offset, address.0, BE)?
+            }
+            WisunHeaderIe::Unknown { content, .. } => {
+                bytes.write(offset, content)?
+            }
+        }
+        Ok(*offset)
+    }
+}
+
+/// Sub-IDs of the short Wi-SUN nested IEs that this crate knows
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum WisunShortSubId {
+    /// PAN IE
+    Pan = 0x04,
+    /// Network Name IE (NETNAME-IE)
+    NetworkName = 0x05,
+    /// PAN Version IE (PANVER-IE)
+    PanVersion = 0x06,
+    /// GTK Hash IE (GTKHASH-IE)
+    GtkHash = 0x07,
+}
+
+impl WisunShortSubId {
+    /// Creates an instance of [`WisunShortSubId`] from the provided bits
+    ///
+    /// Returns `None`, if the sub-ID is unknown to this crate.
+    pub const fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            0x04 => Some(WisunShortSubId::Pan),
+            0x05 => Some(WisunShortSubId::NetworkName),
+            0x06 => Some(WisunShortSubId::PanVersion),
+            0x07 => Some(WisunShortSubId::GtkHash),
+            _ => None,
+        }
+    }
+}
+
+/// Sub-IDs of the long Wi-SUN nested IEs that this crate knows
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum WisunLongSubId {
+    /// Unicast Schedule IE (US-IE)
+    UnicastSchedule = 0x1,
+    /// Broadcast Schedule IE (BS-IE)
+    BroadcastSchedule = 0x2,
+}
+
+impl WisunLongSubId {
+    /// Creates an instance of [`WisunLongSubId`] from the provided bits
+    ///
+    /// Returns `None`, if the sub-ID is unknown to this crate.
+    pub const fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            0x1 => Some(WisunLongSubId::UnicastSchedule),
+            0x2 => Some(WisunLongSubId::BroadcastSchedule),
+            _ => None,
+        }
+    }
+}
+
+const CHANNEL_PLAN_MASK: u8 = 0x07;
+const CHANNEL_FUNCTION_MASK: u8 = 0x38;
+const CHANNEL_FUNCTION_OFFSET: u8 = 3;
+const EXCLUDED_CHANNELS_MASK: u8 = 0xc0;
+const EXCLUDED_CHANNELS_OFFSET: u8 = 6;
+const CHANNEL_SPACING_MASK: u8 = 0x0f;
+
+/// The channel plan of a schedule
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ChannelPlan {
+    /// Channels defined by a regulatory domain and an operating class
+    RegulatoryDomain {
+        /// The regulatory domain
+        domain: u8,
+        /// The operating class
+        operating_class: u8,
+    },
+    /// Explicitly defined channels
+    Explicit {
+        /// The center frequency of channel 0, in kHz
+        ///
+        /// This is a 24-bit value.
+        ch0: u32,
+        /// The channel spacing
+        ///
+        /// This is a 4-bit value.
+        channel_spacing: u8,
+        /// The number of channels
+        number_of_channels: u16,
+    },
+    /// Channels defined by a regulatory domain and a channel plan ID
+    RegulatoryDomainPlan {
+        /// The regulatory domain
+        domain: u8,
+        /// The channel plan ID
+        plan_id: u8,
+    },
+}
+
+impl ChannelPlan {
+    fn id(&self) -> u8 {
+        match self {
+            ChannelPlan::RegulatoryDomain { .. } => 0,
+            ChannelPlan::Explicit { .. } => 1,
+            ChannelPlan::RegulatoryDomainPlan { .. } => 2,
+        }
+    }
+
+    fn get_octet_size(&self) -> usize {
+        match self {
+            ChannelPlan::RegulatoryDomain { .. }
+            | ChannelPlan::RegulatoryDomainPlan { .. } => 2,
+            ChannelPlan::Explicit { .. } => 6,
+        }
+    }
+}
+
+/// The channel function of a schedule
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ChannelFunction<'a> {
+    /// A single, fixed channel
+    Fixed(u16),
+    /// The TR51 channel function
+    Tr51,
+    /// The Direct Hash channel function (DH1CF)
+    DirectHash,
+    /// A vendor defined channel function, with its list of channels
+    Vendor(&'a [u8]),
+}
+
+impl ChannelFunction<'_> {
+    fn id(&self) -> u8 {
+        match self {
+            ChannelFunction::Fixed(_) => 0,
+            ChannelFunction::Tr51 => 1,
+            ChannelFunction::DirectHash => 2,
+            ChannelFunction::Vendor(_) => 3,
+        }
+    }
+
+    fn get_octet_size(&self) -> usize {
+        match self {
+            ChannelFunction::Fixed(_) => 2,
+            ChannelFunction::Tr51 | ChannelFunction::DirectHash => 0,
+            ChannelFunction::Vendor(channels) => 1 + channels.len(),
+        }
+    }
+}
+
+/// Ranges of excluded channels
+///
+/// Like [`NestedIes`], these are [`ExcludedChannelRanges::Encoded`] when read
+/// from a frame, and can be [`ExcludedChannelRanges::Decoded`] when written to
+/// one. Each range is given by its first and last channel.
+///
+/// [`NestedIes`]: super::NestedIes
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ExcludedChannelRanges<'a> {
+    /// The encoded ranges
+    Encoded(&'a [u8]),
+    /// The decoded ranges
+    Decoded(&'a [(u16, u16)]),
+}
+
+impl<'a> ExcludedChannelRanges<'a> {
+    /// The number of ranges
+    pub fn len(&self) -> usize {
+        match self {
+            ExcludedChannelRanges::Encoded(bytes) => bytes.len() / 4,
+            ExcludedChannelRanges::Decoded(ranges) => ranges.len(),
+        }
+    }
+
+    /// Whether there are no ranges
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterate over the ranges
+    pub fn iter(&self) -> ExcludedChannelRangeIterator<'a> {
+        ExcludedChannelRangeIterator {
+            ranges: *self,
+            index: 0,
+        }
+    }
+}
+
+/// An iterator over ranges of excluded channels
+#[derive(Clone, Debug)]
+pub struct ExcludedChannelRangeIterator<'a> {
+    ranges: ExcludedChannelRanges<'a>,
+    index: usize,
+}
+
+impl Iterator for ExcludedChannelRangeIterator<'_> {
+    type Item = (u16, u16);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let range = match self.ranges {
+            ExcludedChannelRanges::Encoded(bytes) => {
+                let offset = &mut (self.index * 4);
+                (
+                    bytes.read_with(offset, LE).ok()?,
+                    bytes.read_with(offset, LE).ok()?,
+                )
+            }
+            ExcludedChannelRanges::Decoded(ranges) => {
+                *ranges.get(self.index)?
+            }
+        };
+        self.index += 1;
+        Some(range)
+    }
+}
+
+/// The channels that are excluded from a schedule
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ExcludedChannels<'a> {
+    /// No channels are excluded
+    None,
+    /// Ranges of channels are excluded
+    Ranges(ExcludedChannelRanges<'a>),
+    /// A bitmask of excluded channels, which spans the rest of the IE
+    ///
+    /// Bit `n` of the mask is set if channel `n` is excluded.
+    Mask(&'a [u8]),
+}
+
+impl ExcludedChannels<'_> {
+    fn id(&self) -> u8 {
+        match self {
+            ExcludedChannels::None => 0,
+            ExcludedChannels::Ranges(_) => 1,
+            ExcludedChannels::Mask(_) => 2,
+        }
+    }
+
+    fn get_octet_size(&self) -> usize {
+        match self {
+            ExcludedChannels::None => 0,
+            ExcludedChannels::Ranges(ranges) => 1 + ranges.len() * 4,
+            ExcludedChannels::Mask(mask) => mask.len(),
+        }
+    }
+
+    /// Whether `channel` is excluded
+    pub fn contains(&self, channel: u16) -> bool {
+        match self {
+            ExcludedChannels::None => false,
+            ExcludedChannels::Ranges(ranges) => ranges
+                .iter()
+                .any(|(first, last)| (first..=last).contains(&channel)),
+            ExcludedChannels::Mask(mask) => mask
+                .get(channel as usize / 8)
+                .is_some_and(|bits| bits & 1 << (channel % 8) != 0),
+        }
+    }
+}
+
+/// The channel hopping schedule of a node, or of the broadcasts in a PAN
+///
+/// This is the content of the Unicast Schedule IE (US-IE), and part of that
+/// of the [`BroadcastScheduleIe`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ChannelSchedule<'a> {
+    /// The time that is spent on each channel, in milliseconds
+    pub dwell_interval: u8,
+    /// The clock drift, in ±ppm
+    pub clock_drift: u8,
+    /// The timing accuracy, in multiples of 10 microseconds
+    pub timing_accuracy: u8,
+    /// The channel plan
+    pub channel_plan: ChannelPlan,
+    /// The channel function
+    pub channel_function: ChannelFunction<'a>,
+    /// The excluded channels
+    pub excluded_channels: ExcludedChannels<'a>,
+}
+
+impl ChannelSchedule<'_> {
+    /// Get the size of this schedule in octets
+    pub fn get_octet_size(&self) -> usize {
+        4 + self.channel_plan.get_octet_size()
+            + self.channel_function.get_octet_size()
+            + self.excluded_channels.get_octet_size()
+    }
+}
+
+impl<'a> TryRead<'a> for ChannelSchedule<'a> {
+    fn try_read(bytes: &'a [u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        check_len(bytes, 4)?;
+        let dwell_interval = bytes.read(offset)?;
+        let clock_drift = bytes.read(offset)?;
+        let timing_accuracy = bytes.read(offset)?;
+        let info: u8 = bytes.read(offset)?;
+
+        let channel_plan = match info & CHANNEL_PLAN_MASK {
+            0 => ChannelPlan::RegulatoryDomain {
+                domain: bytes.read(offset)?,
+                operating_class: bytes.read(offset)?,
+            },
+            1 => {
+                let ch0 = read_u24(bytes, offset)?;
+                let spacing: u8 = bytes.read(offset)?;
+                ChannelPlan::Explicit {
+                    ch0,
+                    channel_spacing: spacing & CHANNEL_SPACING_MASK,
+                    number_of_channels: bytes.read_with(offset, LE)?,
+                }
+            }
+            2 => ChannelPlan::RegulatoryDomainPlan {
+                domain: bytes.read(offset)?,
+                plan_id: bytes.read(offset)?,
+            },
+            _ => return Err(DecodeError::InvalidValue.into()),
+        };
+
+        let channel_function =
+            match (info & CHANNEL_FUNCTION_MASK) >> CHANNEL_FUNCTION_OFFSET {
+                0 => ChannelFunction::Fixed(bytes.read_with(offset, LE)?),
+                1 => ChannelFunction::Tr51,
+                2 => ChannelFunction::DirectHash,
+                3 => {
+                    let count: u8 = bytes.read(offset)?;
+                    let count = count as usize;
+                    check_len(&bytes[*offset..], count)?;
+                    let channels = &bytes[*offset..*offset + count];
+                    *offset += count;
+                    ChannelFunction::Vendor(channels)
+                }
+                _ => return Err(DecodeError::InvalidValue.into()),
+            };
+
+        let excluded_channels = match (info & EXCLUDED_CHANNELS_MASK)
+            >> EXCLUDED_CHANNELS_OFFSET
+        {
+            0 => ExcludedChannels::None,
+            1 => {
+                let count: u8 = bytes.read(offset)?;
+                let len = count as usize * 4;
+                check_len(&bytes[*offset..], len)?;
+                let ranges = &bytes[*offset..*offset + len];
+                *offset += len;
+                ExcludedChannels::Ranges(ExcludedChannelRanges::Encoded(ranges))
+            }
+            2 => {
+                let mask = &bytes[*offset..];
+                *offset = bytes.len();
+                ExcludedChannels::Mask(mask)
+            }
+            _ => return Err(DecodeError::InvalidValue.into()),
+        };
+
+        Ok((
+            Self {
+                dwell_interval,
+                clock_drift,
+                timing_accuracy,
+                channel_plan,
+                channel_function,
+                excluded_channels,
+            },
+            *offset,
+        ))
+    }
+}
+
+impl TryWrite for ChannelSchedule<'_> {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write(offset, self.dwell_interval)?;
+        bytes.write(offset, self.clock_drift)?;
+        bytes.write(offset, self.timing_accuracy)?;
+        let info = self.channel_plan.id()
+            | self.channel_function.id() << CHANNEL_FUNCTION_OFFSET
+            | self.excluded_channels.id() << EXCLUDED_CHANNELS_OFFSET;
+        bytes.write(offset, info)?;
+
+        match self.channel_plan {
+            ChannelPlan::RegulatoryDomain {
+                domain,
+                operating_class,
+            } => {
+                bytes.write(offset, domain)?;
+                bytes.write(offset, operating_class)?;
+            }
+            ChannelPlan::Explicit {
+                ch0,
+                channel_spacing,
+                number_of_channels,
+            } => {
+                if channel_spacing > CHANNEL_SPACING_MASK {
+                    return Err(EncodeError::WriteError.into());
+                }
+                write_u24(bytes, offset, ch0)?;
+                bytes.write(offset, channel_spacing)?;
+                bytes.write_with(offset, number_of_channels, LE)?;
+            }
+            ChannelPlan::RegulatoryDomainPlan { domain, plan_id } => {
+                bytes.write(offset, domain)?;
+                bytes.write(offset, plan_id)?;
+            }
+        }
+
+        match self.channel_function {
+            ChannelFunction::Fixed(channel) => {
+                bytes.write_with(offset, channel, LE)?
+            }
+            ChannelFunction::Tr51 | ChannelFunction::DirectHash => (),
+            ChannelFunction::Vendor(channels) => {
+                if channels.len() > u8::MAX as usize {
+                    return Err(EncodeError::WriteError.into());
+                }
+                bytes.write(offset, channels.len() as u8)?;
+                bytes.write(offset, channels)?;
+            }
+        }
+
+        match self.excluded_channels {
+            ExcludedChannels::None => (),
+            ExcludedChannels::Ranges(ranges) => {
+                if ranges.len() > u8::MAX as usize {
+                    return Err(EncodeError::WriteError.into());
+                }
+                bytes.write(offset, ranges.len() as u8)?;
+                for (first, last) in ranges.iter() {
+                    bytes.write_with(offset, first, LE)?;
+                    bytes.write_with(offset, last, LE)?;
+                }
+            }
+            ExcludedChannels::Mask(mask) => bytes.write(offset, mask)?,
+        }
+        Ok(*offset)
+    }
+}
+
+/// Broadcast Schedule IE (BS-IE)
+///
+/// Carries the schedule of the broadcasts in a PAN.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BroadcastScheduleIe<'a> {
+    /// The interval between broadcast slots, in milliseconds
+    pub broadcast_interval: u32,
+    /// The Broadcast Schedule Identifier (BSI)
+    pub schedule_id: u16,
+    /// The channel hopping schedule
+    pub schedule: ChannelSchedule<'a>,
+}
+
+impl BroadcastScheduleIe<'_> {
+    /// Get the size of this IE in octets
+    pub fn get_octet_size(&self) -> usize {
+        4 + 2 + self.schedule.get_octet_size()
+    }
+}
+
+impl<'a> TryRead<'a> for BroadcastScheduleIe<'a> {
+    fn try_read(bytes: &'a [u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let broadcast_interval = bytes.read_with(offset, LE)?;
+        let schedule_id = bytes.read_with(offset, LE)?;
+        let schedule = bytes.read(offset)?;
+        Ok((
+            Self {
+                broadcast_interval,
+                schedule_id,
+                schedule,
+            },
+            *offset,
+        ))
+    }
+}
+
+impl TryWrite for BroadcastScheduleIe<'_> {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        bytes.write_with(offset, self.broadcast_interval, LE)?;
+        bytes.write_with(offset, self.schedule_id, LE)?;
+        bytes.write(offset, self.schedule)?;
+        Ok(*offset)
+    }
+}
+
+const PAN_USE_PARENT_BS_IE: u8 = 0x01;
+const PAN_L3_ROUTING: u8 = 0x02;
+const PAN_FAN_TPS_VERSION_MASK: u8 = 0xe0;
+const PAN_FAN_TPS_VERSION_OFFSET: u8 = 5;
+
+/// PAN IE
+///
+/// Carries information about a PAN, which nodes use to select the PAN to
+/// join.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct PanIe {
+    /// The number of nodes in the PAN
+    pub pan_size: u16,
+    /// The routing cost of the sender to the border router
+    pub routing_cost: u16,
+    /// Whether nodes use the broadcast schedule of their parent
+    pub use_parent_bs_ie: bool,
+    /// Whether the PAN uses layer 3 routing (RPL)
+    pub l3_routing: bool,
+    /// The version of the FAN Technical Profile Specification
+    ///
+    /// This is a 3-bit value.
+    pub fan_tps_version: u8,
+}
+
+impl TryRead<'_> for PanIe {
+    fn try_read(bytes: &[u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        check_len(bytes, 5)?;
+        let pan_size = bytes.read_with(offset, LE)?;
+        let routing_cost = bytes.read_with(offset, LE)?;
+        let flags: u8 = bytes.read(offset)?;
+        Ok((
+            Self {
+                pan_size,
+                routing_cost,
+                use_parent_bs_ie: flags & PAN_USE_PARENT_BS_IE != 0,
+                l3_routing: flags & PAN_L3_ROUTING != 0,
+                fan_tps_version: (flags & PAN_FAN_TPS_VERSION_MASK)
+                    >> PAN_FAN_TPS_VERSION_OFFSET,
+            },
+            *offset,
+        ))
+    }
+}
+
+impl TryWrite for PanIe {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        if self.fan_tps_version
+            > PAN_FAN_TPS_VERSION_MASK >> PAN_FAN_TPS_VERSION_OFFSET
+        {
+            return Err(EncodeError::WriteError.into());
+        }
+        bytes.write_with(offset, self.pan_size, LE)?;
+        bytes.write_with(offset, self.routing_cost, LE)?;
+        let mut flags = self.fan_tps_version << PAN_FAN_TPS_VERSION_OFFSET;
+        if self.use_parent_bs_ie {
+            flags |= PAN_USE_PARENT_BS_IE;
+        }
+        if self.l3_routing {
+            flags |= PAN_L3_ROUTING;
+        }
+        bytes.write(offset, flags)?;
+        Ok(*offset)
+    }
+}
+
+/// A Wi-SUN nested IE, which is carried in the Wi-SUN payload IE (WP-IE)
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum WisunNestedIe<'a> {
+    /// Unicast Schedule IE (US-IE)
+    UnicastSchedule(ChannelSchedule<'a>),
+    /// Broadcast Schedule IE (BS-IE)
+    BroadcastSchedule(BroadcastScheduleIe<'a>),
+    /// PAN IE
+    Pan(PanIe),
+    /// Network Name IE (NETNAME-IE)
+    ///
+    /// The name is at most [`MAX_NETWORK_NAME_LEN`] octets long.
+    NetworkName(&'a [u8]),
+    /// PAN Version IE (PANVER-IE)
+    PanVersion(u16),
+    /// GTK Hash IE (GTKHASH-IE)
+    ///
+    /// The hashes of the four group temporal keys of the PAN.
+    GtkHash([[u8; 8]; 4]),
+    /// A short nested IE that is unknown to this crate
+    UnknownShort {
+        /// The sub-ID of the IE
+        sub_id: u8,
+        /// The content of the IE
+        content: &'a [u8],
+    },
+    /// A long nested IE that is unknown to this crate
+    UnknownLong {
+        /// The sub-ID of the IE
+        sub_id: u8,
+        /// The content of the IE
+        content: &'a [u8],
+    },
+}
+
+impl WisunNestedIe<'_> {
+    /// Whether this is a long nested IE
+    pub fn is_long(&self) -> bool {
+        matches!(
+            self,
+            WisunNestedIe::UnicastSchedule(_)
+                | WisunNestedIe::BroadcastSchedule(_)
+                | WisunNestedIe::UnknownLong { .. }
+        )
+    }
+
+    /// The sub-ID of this IE
+    pub fn sub_id(&self) -> u8 {
+        let sub_id = match self {
+            WisunNestedIe::UnicastSchedule(_) => {
+                return WisunLongSubId::UnicastSchedule as u8
+            }
+            WisunNestedIe::BroadcastSchedule(_) => {
+                return WisunLongSubId::BroadcastSchedule as u8
+            }
+            WisunNestedIe::Pan(_) => WisunShortSubId::Pan,
+            WisunNestedIe::NetworkName(_) => WisunShortSubId::NetworkName,
+            WisunNestedIe::PanVersion(_) => WisunShortSubId::PanVersion,
+            WisunNestedIe::GtkHash(_) => WisunShortSubId::GtkHash,
+            WisunNestedIe::UnknownShort { sub_id, .. }
+            | WisunNestedIe::UnknownLong { sub_id, .. } => return *sub_id,
+        };
+        sub_id as u8
+    }
+
+    /// The length of the content of this IE, in octets
+    pub fn content_len(&self) -> usize {
+        match self {
+            WisunNestedIe::UnicastSchedule(schedule) => {
+                schedule.get_octet_size()
+            }
+            WisunNestedIe::BroadcastSchedule(ie) => ie.get_octet_size(),
+            WisunNestedIe::Pan(_) => 5,
+            WisunNestedIe::NetworkName(name) => name.len(),
+            WisunNestedIe::PanVersion(_) => 2,
+            WisunNestedIe::GtkHash(_) => 32,
+            WisunNestedIe::UnknownShort { content, .. }
+            | WisunNestedIe::UnknownLong { content, .. } => content.len(),
+        }
+    }
+
+    /// Get the size of this IE in octets, including its descriptor
+    pub fn get_octet_size(&self) -> usize {
+        2 + self.content_len()
+    }
+}
+
+impl<'a> TryRead<'a> for WisunNestedIe<'a> {
+    fn try_read(bytes: &'a [u8], _ctx: ()) -> byte::Result<(Self, usize)> {
+        let offset = &mut 0;
+        let (long, sub_id, content) = read_raw_nested_ie(bytes, offset)?;
+
+        let ie = if long {
+            match WisunLongSubId::from_bits(sub_id) {
+                Some(WisunLongSubId::UnicastSchedule) => {
+                    WisunNestedIe::UnicastSchedule(read_content(content)?)
+                }
+                Some(WisunLongSubId::BroadcastSchedule) => {
+                    WisunNestedIe::BroadcastSchedule(read_content(content)?)
+                }
+                None => WisunNestedIe::UnknownLong { sub_id, content },
+            }
+        } else {
+            match WisunShortSubId::from_bits(sub_id) {
+                Some(WisunShortSubId::Pan) => {
+                    WisunNestedIe::Pan(read_content(content)?)
+                }
+                Some(WisunShortSubId::NetworkName) => {
+                    if content.len() > MAX_NETWORK_NAME_LEN {
+                        return Err(DecodeError::IeLengthMismatch.into());
+                    }
+                    WisunNestedIe::NetworkName(content)
+                }
+                Some(WisunShortSubId::PanVersion) => {
+                    if content.len() != 2 {
+                        return Err(DecodeError::IeLengthMismatch.into());
+                    }
+                    WisunNestedIe::PanVersion(content.read_with(&mut 0, LE)?)
+                }
+                Some(WisunShortSubId::GtkHash) => {
+                    if content.len() != 32 {
+                        return Err(DecodeError::IeLengthMismatch.into());
+                    }
+                    let mut hashes = [[0u8; 8]; 4];
+                    for (hash, bytes) in
+                        hashes.iter_mut().zip(content.chunks_exact(8))
+                    {
+                        hash.copy_from_slice(bytes);
+                    }
+                    WisunNestedIe::GtkHash(hashes)
+                }
+                None => WisunNestedIe::UnknownShort { sub_id, content },
+            }
+        };
+        Ok((ie, *offset))
+    }
+}
+
+impl TryWrite for WisunNestedIe<'_> {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        write_nested_ie_descriptor(
+            bytes,
+            offset,
+            self.is_long(),
+            self.sub_id(),
+            self.content_len(),
+        )?;
+
+        match self {
+            WisunNestedIe::UnicastSchedule(schedule) => {
+                bytes.write(offset, schedule)?
+            }
+            WisunNestedIe::BroadcastSchedule(ie) => bytes.write(offset, ie)?,
+            WisunNestedIe::Pan(ie) => bytes.write(offset, ie)?,
+            WisunNestedIe::NetworkName(name) => {
+                if name.len() > MAX_NETWORK_NAME_LEN {
+                    return Err(EncodeError::WriteError.into());
+                }
+                bytes.write(offset, name)?
+            }
+            WisunNestedIe::PanVersion(version) => {
+                bytes.write_with(offset, version, LE)?
+            }
+            WisunNestedIe::GtkHash(hashes) => {
+                for hash in &hashes {
+                    bytes.write(offset, &hash[..])?;
+                }
+            }
+            WisunNestedIe::UnknownShort { content, .. }
+            | WisunNestedIe::UnknownLong { content, .. } => {
+                bytes.write(offset, content)?
+            }
+        }
+        Ok(*offset)
+    }
+}
+
+/// A list of Wi-SUN nested IEs
+///
+/// Like [`NestedIes`], these are [`WisunIes::Encoded`] when read from a
+/// frame, and can be [`WisunIes::Decoded`] when written to one.
+///
+/// # Example
+///
+/// ``` rust
+/// use byte::BytesExt;
+/// use ieee802154::mac::ie::{PayloadIe, WisunIes, WisunNestedIe};
+///
+/// let nested = [
+///     WisunNestedIe::NetworkName(b"wisun"),
+///     WisunNestedIe::PanVersion(7),
+/// ];
+/// let ie = PayloadIe::Wisun(WisunIes::Decoded(&nested));
+///
+/// let mut bytes = [0u8; 15];
+/// bytes.write(&mut 0, ie).unwrap();
+/// assert_eq!(
+///     bytes,
+///     [
+///         0x0b, 0xa0, // WP-IE
+///         0x05, 0x05, b'w', b'i', b's', b'u', b'n', // NETNAME-IE
+///         0x02, 0x06, 0x07, 0x00, // PANVER-IE
+///         0x00, 0x00,
+///     ]
+/// );
+///
+/// match bytes.read(&mut 0).unwrap() {
+///     PayloadIe::Wisun(ies) => {
+///         let mut ies = ies.iter();
+///         assert_eq!(ies.next().unwrap().unwrap(), nested[0]);
+///         assert_eq!(ies.next().unwrap().unwrap(), nested[1]);
+///         assert!(ies.next().is_none());
+///     }
+///     _ => unreachable!(),
+/// }
+/// ```
+///
+/// [`NestedIes`]: super::NestedIes
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum WisunIes<'a> {
+    /// The encoded nested IEs
+    Encoded(&'a [u8]),
+    /// The decoded nested IEs
+    Decoded(&'a [WisunNestedIe<'a>]),
+}
+
+impl<'a> WisunIes<'a> {
+    /// Iterate over the nested IEs
+    pub fn iter(&self) -> WisunIeIterator<'a> {
+        match *self {
+            WisunIes::Encoded(bytes) => WisunIeIterator {
+                inner: Inner::Encoded {
+                    bytes,
+                    offset: 0,
+                    done: false,
+                },
+            },
+            WisunIes::Decoded(ies) => WisunIeIterator {
+                inner: Inner::Decoded(ies.iter()),
+            },
+        }
+    }
+
+    /// Get the size of the nested IEs in octets
+    pub fn get_octet_size(&self) -> usize {
+        match self {
+            WisunIes::Encoded(bytes) => bytes.len(),
+            WisunIes::Decoded(ies) => {
+                ies.iter().map(WisunNestedIe::get_octet_size).sum()
+            }
+        }
+    }
+}
+
+impl TryWrite for WisunIes<'_> {
+    fn try_write(self, bytes: &mut [u8], _ctx: ()) -> byte::Result<usize> {
+        let offset = &mut 0;
+        match self {
+            WisunIes::Encoded(encoded) => bytes.write(offset, encoded)?,
+            WisunIes::Decoded(ies) => {
+                for ie in ies {
+                    bytes.write(offset, *ie)?;
+                }
+            }
+        }
+        Ok(*offset)
+    }
+}
+
+/// An iterator over a list of Wi-SUN nested IEs
+///
+/// The iterator ends at the end of the list, or after yielding an error for
+/// a malformed IE.
+#[derive(Clone, Debug)]
+pub struct WisunIeIterator<'a> {
+    inner: Inner<'a>,
+}
+
+#[derive(Clone, Debug)]
+enum Inner<'a> {
+    Encoded {
+        bytes: &'a [u8],
+        offset: usize,
+        done: bool,
+    },
+    Decoded(core::slice::Iter<'a, WisunNestedIe<'a>>),
+}
+
+impl<'a> Iterator for WisunIeIterator<'a> {
+    type Item = byte::Result<WisunNestedIe<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            Inner::Encoded {
+                bytes,
+                offset,
+                done,
+            } => {
+                if *done || *offset >= bytes.len() {
+                    return None;
+                }
+                let ie = bytes.read::<WisunNestedIe>(offset);
+                *done = ie.is_err();
+                Some(ie)
+            }
+            Inner::Decoded(ies) => ies.next().map(|ie| Ok(*ie)),
+        }
+    }
+}
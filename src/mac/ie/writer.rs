@@ -0,0 +1,208 @@
+//! Writing IEs directly into a buffer
+
+use byte::{BytesExt, LE};
+
+use super::{
+    HeaderElementId, HeaderIe, NestedIe, PayloadGroupId, PayloadIe,
+    MAX_PAYLOAD_IE_CONTENT_LEN,
+};
+use crate::mac::frame::EncodeError;
+
+const MLME_DESCRIPTOR: u16 = 0x8000 | (PayloadGroupId::Mlme as u16) << 11;
+
+/// Writes IEs directly into a buffer
+///
+/// Unlike [`InformationElements`], this doesn't need the IEs to be collected
+/// in a list first. IEs are appended in order: header IEs first, then payload
+/// IEs. Nested IEs are appended to an MLME IE, which is started when the
+/// first nested IE is written, and whose length is updated as nested IEs are
+/// written to it.
+///
+/// [`IeWriter::finish`] inserts the termination IEs that are required.
+///
+/// # Example
+///
+/// ``` rust
+/// use ieee802154::mac::ie::{
+///     HeaderIe, IeWriter, NestedIe, TimeCorrectionIe, TschSynchronizationIe,
+/// };
+///
+/// let mut bytes = [0u8; 32];
+/// let mut writer = IeWriter::new(&mut bytes);
+/// writer
+///     .header_ie(HeaderIe::TimeCorrection(TimeCorrectionIe::new(0, false).unwrap()))?
+///     .nested_ie(NestedIe::TschSynchronization(TschSynchronizationIe {
+///         asn: 1,
+///         join_metric: 0,
+///     }))?
+///     .raw_nested_ie(false, 0x40, &[0xaa])?;
+/// let len = writer.finish(false)?;
+///
+/// assert_eq!(
+///     &bytes[..len],
+///     &[
+///         0x02, 0x0f, 0x00, 0x00, // Time correction IE
+///         0x00, 0x3f, // Header termination 1 IE
+///         0x0b, 0x88, // MLME IE
+///         0x06, 0x1a, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, // TSCH synchronization IE
+///         0x01, 0x40, 0xaa, // Raw nested IE
+///     ]
+/// );
+/// # Ok::<(), byte::Error>(())
+/// ```
+///
+/// [`InformationElements`]: super::InformationElements
+#[derive(Debug)]
+pub struct IeWriter<'a> {
+    bytes: &'a mut [u8],
+    offset: usize,
+    payload_ies: bool,
+    mlme_start: Option<usize>,
+}
+
+impl<'a> IeWriter<'a> {
+    /// Write IEs at the start of `bytes`
+    pub fn new(bytes: &'a mut [u8]) -> Self {
+        Self {
+            bytes,
+            offset: 0,
+            payload_ies: false,
+            mlme_start: None,
+        }
+    }
+
+    /// The number of octets that have been written so far
+    pub fn len(&self) -> usize {
+        self.offset
+    }
+
+    /// Whether nothing has been written yet
+    pub fn is_empty(&self) -> bool {
+        self.offset == 0
+    }
+
+    /// Append a header IE
+    ///
+    /// This fails if payload IEs have been written already, or if `ie` is a
+    /// termination IE.
+    pub fn header_ie(&mut self, ie: HeaderIe) -> byte::Result<&mut Self> {
+        if self.payload_ies || is_header_termination(ie.element_id()) {
+            return Err(EncodeError::WriteError.into());
+        }
+        self.bytes.write(&mut self.offset, ie)?;
+        Ok(self)
+    }
+
+    /// Append a header IE with `element_id` and `content`
+    pub fn raw_header_ie(
+        &mut self,
+        element_id: u8,
+        content: &[u8],
+    ) -> byte::Result<&mut Self> {
+        self.header_ie(HeaderIe::Unknown {
+            element_id,
+            content,
+        })
+    }
+
+    /// Append a payload IE
+    ///
+    /// This fails if `ie` is a termination IE.
+    pub fn payload_ie(&mut self, ie: PayloadIe) -> byte::Result<&mut Self> {
+        if ie.group_id() == PayloadGroupId::PayloadTermination as u8 {
+            return Err(EncodeError::WriteError.into());
+        }
+        self.start_payload_ies()?;
+        self.mlme_start = None;
+        self.bytes.write(&mut self.offset, ie)?;
+        Ok(self)
+    }
+
+    /// Append a payload IE with `group_id` and `content`
+    pub fn raw_payload_ie(
+        &mut self,
+        group_id: u8,
+        content: &[u8],
+    ) -> byte::Result<&mut Self> {
+        self.payload_ie(PayloadIe::Unknown { group_id, content })
+    }
+
+    /// Append a nested IE to the current MLME IE
+    ///
+    /// An MLME IE is started if the last IE that was written isn't one that
+    /// was started by this writer.
+    pub fn nested_ie(&mut self, ie: NestedIe) -> byte::Result<&mut Self> {
+        let start = match self.mlme_start {
+            Some(start) => start,
+            None => {
+                self.start_payload_ies()?;
+                let start = self.offset;
+                self.bytes
+                    .write_with(&mut self.offset, MLME_DESCRIPTOR, LE)?;
+                self.mlme_start = Some(start);
+                start
+            }
+        };
+
+        let content_len = self.offset - (start + 2) + ie.get_octet_size();
+        if content_len > MAX_PAYLOAD_IE_CONTENT_LEN {
+            return Err(EncodeError::WriteError.into());
+        }
+        self.bytes.write(&mut self.offset, ie)?;
+        self.bytes.write_with(
+            &mut start.clone(),
+            MLME_DESCRIPTOR | content_len as u16,
+            LE,
+        )?;
+        Ok(self)
+    }
+
+    /// Append a nested IE with `sub_id` and `content` to the current MLME IE
+    pub fn raw_nested_ie(
+        &mut self,
+        long: bool,
+        sub_id: u8,
+        content: &[u8],
+    ) -> byte::Result<&mut Self> {
+        let ie = if long {
+            NestedIe::UnknownLong { sub_id, content }
+        } else {
+            NestedIe::UnknownShort { sub_id, content }
+        };
+        self.nested_ie(ie)
+    }
+
+    /// Insert the termination IEs that are required, and return the number
+    /// of octets that were written
+    ///
+    /// `payload_follows` is whether a frame payload follows the IEs.
+    pub fn finish(mut self, payload_follows: bool) -> byte::Result<usize> {
+        if payload_follows {
+            if self.payload_ies {
+                self.bytes
+                    .write(&mut self.offset, PayloadIe::PayloadTermination)?;
+            } else {
+                self.bytes
+                    .write(&mut self.offset, HeaderIe::HeaderTermination2)?;
+            }
+        }
+        Ok(self.offset)
+    }
+
+    fn start_payload_ies(&mut self) -> byte::Result<()> {
+        if !self.payload_ies {
+            self.bytes
+                .write(&mut self.offset, HeaderIe::HeaderTermination1)?;
+            self.payload_ies = true;
+        }
+        Ok(())
+    }
+}
+
+fn is_header_termination(element_id: u8) -> bool {
+    matches!(
+        HeaderElementId::from_bits(element_id),
+        Some(HeaderElementId::HeaderTermination1)
+            | Some(HeaderElementId::HeaderTermination2)
+    )
+}
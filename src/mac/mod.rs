@@ -3,11 +3,19 @@
 pub mod beacon;
 pub mod command;
 pub mod frame;
+pub mod gts;
+pub mod ie;
+pub mod sync;
 
 pub use frame::header::{
-    Address, AddressMode, ExtendedAddress, FrameType, FrameVersion, Header,
-    PanId, ShortAddress,
+    Address, AddressMode, ExtendedAddress, FrameControl, FrameType,
+    FrameVersion, Header, PanId, ShortAddress,
 };
 pub use frame::{
-    security, DecodeError, FooterMode, Frame, FrameContent, FrameSerDesContext,
+    security, DecodeError, FooterMode, Frame, FrameContent, OwnedFrame,
+    OwnedPayload, PreservedFrame, TrailingBytes, MAX_PHY_PACKET_SIZE,
+};
+#[cfg(feature = "security")]
+pub use frame::{
+    AnyFrameSerDesContext, FrameSerDesContext, SecuredLength, VerifiedFrame,
 };
@@ -0,0 +1,472 @@
+//! Synchronization of a device with the beacons of its coordinator
+//!
+//! [`BeaconTracker`] predicts when the next beacon of a beacon-enabled PAN is
+//! sent, based on the received beacons, and detects when the device lost
+//! synchronization with the coordinator. [`SequenceTracker`] detects
+//! duplicate and missed frames by their sequence numbers.
+
+use crate::mac::beacon::{SuperframeSpecification, SuperframeTiming};
+use crate::mac::{Address, Frame};
+
+/// The number of consecutive missed beacons after which a device lost
+/// synchronization with its coordinator (aMaxLostBeacons)
+pub const MAX_LOST_BEACONS: u8 = 4;
+
+/// The period in which a beacon is expected, in microseconds
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BeaconWindow {
+    /// The time at which the receiver should be enabled
+    pub start: u64,
+    /// The predicted time of the beacon
+    pub expected: u64,
+    /// The time after which the beacon is missed
+    pub end: u64,
+}
+
+impl BeaconWindow {
+    /// Whether `timestamp` is in the window
+    pub fn contains(&self, timestamp: u64) -> bool {
+        (self.start..=self.end).contains(&timestamp)
+    }
+}
+
+/// Synchronization state of a [`BeaconTracker`]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SyncState {
+    /// No beacon was received yet, or synchronization was lost before
+    Unsynchronized,
+    /// The last expected beacon was received
+    Synchronized,
+    /// Some beacons were missed, but less than [`MAX_LOST_BEACONS`]
+    Missed(u8),
+    /// [`MAX_LOST_BEACONS`] consecutive beacons were missed
+    ///
+    /// This is reported once, after which the tracker is unsynchronized.
+    Lost,
+}
+
+/// Tracks the beacons of a coordinator
+///
+/// Timestamps are in microseconds, relative to any fixed point in time.
+/// Report every beacon of the coordinator with
+/// [`BeaconTracker::beacon_received`], enable the receiver during
+/// [`BeaconTracker::next_window`], and check the synchronization with
+/// [`BeaconTracker::update`].
+///
+/// # Example
+///
+/// ``` rust
+/// use ieee802154::mac::beacon::{
+///     BeaconOrder, SuperframeOrder, SuperframeSpecification,
+/// };
+/// use ieee802154::mac::sync::{BeaconTracker, SyncState};
+///
+/// let spec = SuperframeSpecification {
+///     beacon_order: BeaconOrder::BeaconOrder(6),
+///     superframe_order: SuperframeOrder::SuperframeOrder(4),
+///     final_cap_slot: 15,
+///     battery_life_extension: false,
+///     pan_coordinator: true,
+///     association_permit: false,
+/// };
+///
+/// // 2.4 GHz O-QPSK PHY, with a guard time of 1 ms
+/// let mut tracker = BeaconTracker::new(62_500, 1_000);
+/// assert!(tracker.beacon_received(10_000, &spec));
+///
+/// // Beacons are sent every 983.04 ms
+/// let window = tracker.next_window().unwrap();
+/// assert_eq!(window.start, 992_040);
+/// assert_eq!(window.expected, 993_040);
+/// assert_eq!(window.end, 994_040);
+///
+/// assert_eq!(tracker.update(994_040), SyncState::Synchronized);
+/// assert_eq!(tracker.update(994_041), SyncState::Missed(1));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BeaconTracker {
+    symbol_rate: u32,
+    guard_time: u64,
+    synchronization: Option<Synchronization>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Synchronization {
+    timing: SuperframeTiming,
+    last_beacon: u64,
+    missed_beacons: u8,
+}
+
+impl Synchronization {
+    fn expected(&self, beacon: u64) -> u64 {
+        self.last_beacon + beacon * self.timing.beacon_interval_us()
+    }
+}
+
+impl BeaconTracker {
+    /// Create a tracker for a PHY with `symbol_rate` symbols per second
+    ///
+    /// Beacons are expected up to `guard_time` microseconds before or after
+    /// their predicted time, to allow for clock drift.
+    pub fn new(symbol_rate: u32, guard_time: u64) -> Self {
+        Self {
+            symbol_rate,
+            guard_time,
+            synchronization: None,
+        }
+    }
+
+    /// Report a beacon that was received at `timestamp`, with superframe
+    /// specification `spec`
+    ///
+    /// Returns `false`, and the tracker becomes unsynchronized, if the beacon
+    /// doesn't describe a beacon-enabled PAN.
+    pub fn beacon_received(
+        &mut self,
+        timestamp: u64,
+        spec: &SuperframeSpecification,
+    ) -> bool {
+        self.synchronization =
+            spec.timing(self.symbol_rate).map(|timing| Synchronization {
+                timing,
+                last_beacon: timestamp,
+                missed_beacons: 0,
+            });
+        self.synchronization.is_some()
+    }
+
+    /// Whether the tracker is synchronized with the coordinator
+    pub fn is_synchronized(&self) -> bool {
+        self.synchronization.is_some()
+    }
+
+    /// The superframe timing of the last beacon
+    pub fn timing(&self) -> Option<&SuperframeTiming> {
+        self.synchronization.as_ref().map(|sync| &sync.timing)
+    }
+
+    /// The time at which the last beacon was received
+    pub fn last_beacon(&self) -> Option<u64> {
+        self.synchronization.map(|sync| sync.last_beacon)
+    }
+
+    /// The number of consecutive beacons that were missed, as of the last
+    /// [`BeaconTracker::update`]
+    pub fn missed_beacons(&self) -> u8 {
+        self.synchronization.map_or(0, |sync| sync.missed_beacons)
+    }
+
+    /// The window of the next beacon that wasn't missed yet
+    ///
+    /// Returns `None` if the tracker is unsynchronized.
+    pub fn next_window(&self) -> Option<BeaconWindow> {
+        let sync = self.synchronization?;
+        let expected = sync.expected(sync.missed_beacons as u64 + 1);
+        Some(BeaconWindow {
+            start: expected.saturating_sub(self.guard_time),
+            expected,
+            end: expected + self.guard_time,
+        })
+    }
+
+    /// Update the number of missed beacons at time `now`
+    ///
+    /// A beacon is missed once the end of its window passed without a
+    /// received beacon. After [`MAX_LOST_BEACONS`] missed beacons,
+    /// [`SyncState::Lost`] is returned, and the tracker becomes
+    /// unsynchronized.
+    pub fn update(&mut self, now: u64) -> SyncState {
+        let sync = match &mut self.synchronization {
+            Some(sync) => sync,
+            None => return SyncState::Unsynchronized,
+        };
+        let interval = sync.timing.beacon_interval_us();
+        // Beacon `n` is missed if `now > last_beacon + n * interval + guard`
+        let elapsed = now.saturating_sub(sync.last_beacon + self.guard_time);
+        let missed = if elapsed == 0 {
+            0
+        } else {
+            (elapsed - 1) / interval
+        };
+        if missed >= MAX_LOST_BEACONS as u64 {
+            self.synchronization = None;
+            return SyncState::Lost;
+        }
+        sync.missed_beacons = missed as u8;
+        match missed {
+            0 => SyncState::Synchronized,
+            missed => SyncState::Missed(missed as u8),
+        }
+    }
+}
+
+/// The sequence number of a frame, compared to the last frame of its source
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SequenceStatus {
+    /// No frame of the source was recorded
+    First,
+    /// The sequence number follows the last one
+    Next,
+    /// The sequence number equals the last one, so the frame is a duplicate
+    Duplicate,
+    /// The given number of sequence numbers were skipped
+    ///
+    /// As sequence numbers wrap around, an older frame that arrives late is
+    /// also reported as a gap.
+    Gap(u8),
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+struct SequenceEntry {
+    source: Address,
+    seq: u8,
+    last_update: u32,
+}
+
+/// Tracks the sequence numbers of the frames of up to `N` sources
+///
+/// Use separate trackers for the beacon sequence numbers (BSN) of
+/// coordinators and the data sequence numbers (DSN) of other frames. When a
+/// frame of a new source is recorded while `N` sources are tracked, the
+/// source that was updated least recently is forgotten.
+///
+/// # Example
+///
+/// ``` rust
+/// use ieee802154::mac::sync::{SequenceStatus, SequenceTracker};
+/// use ieee802154::mac::{Address, PanId, ShortAddress};
+///
+/// let coordinator = Address::Short(PanId(0x1234), ShortAddress(0x0000));
+/// let mut bsn = SequenceTracker::<4>::new();
+///
+/// assert_eq!(bsn.record(coordinator, 10), SequenceStatus::First);
+/// assert_eq!(bsn.record(coordinator, 11), SequenceStatus::Next);
+/// assert_eq!(bsn.record(coordinator, 11), SequenceStatus::Duplicate);
+/// assert_eq!(bsn.record(coordinator, 14), SequenceStatus::Gap(2));
+/// assert_eq!(bsn.last(coordinator), Some(14));
+/// ```
+///
+/// `N` must be at least 1, a tracker without room for a source doesn't
+/// compile:
+///
+/// ``` rust,compile_fail
+/// use ieee802154::mac::sync::SequenceTracker;
+///
+/// let tracker = SequenceTracker::<0>::new();
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SequenceTracker<const N: usize> {
+    entries: [Option<SequenceEntry>; N],
+    updates: u32,
+}
+
+impl<const N: usize> SequenceTracker<N> {
+    /// Create a tracker without sources
+    pub fn new() -> Self {
+        const { assert!(N > 0, "a sequence tracker needs room for a source") };
+        Self {
+            entries: [None; N],
+            updates: 0,
+        }
+    }
+
+    fn entry(&self, source: Address) -> Option<&SequenceEntry> {
+        self.entries
+            .iter()
+            .flatten()
+            .find(|entry| entry.source == source)
+    }
+
+    /// The last recorded sequence number of `source`
+    pub fn last(&self, source: Address) -> Option<u8> {
+        self.entry(source).map(|entry| entry.seq)
+    }
+
+    /// Record sequence number `seq` of a frame of `source`
+    pub fn record(&mut self, source: Address, seq: u8) -> SequenceStatus {
+        self.updates = self.updates.wrapping_add(1);
+        let now = self.updates;
+        let slot = match self
+            .entries
+            .iter_mut()
+            .position(|entry| entry.is_some_and(|e| e.source == source))
+        {
+            Some(index) => &mut self.entries[index],
+            None => self
+                .entries
+                .iter_mut()
+                .min_by_key(|entry| {
+                    entry.map_or(0, |e| {
+                        // The age of the entry, robust against wrapping
+                        u32::MAX - now.wrapping_sub(e.last_update)
+                    })
+                })
+                .expect("a sequence tracker tracks at least one source"),
+        };
+        let status = match slot {
+            Some(entry) if entry.source == source => {
+                match seq.wrapping_sub(entry.seq) {
+                    0 => SequenceStatus::Duplicate,
+                    1 => SequenceStatus::Next,
+                    diff => SequenceStatus::Gap(diff - 1),
+                }
+            }
+            _ => SequenceStatus::First,
+        };
+        *slot = Some(SequenceEntry {
+            source,
+            seq,
+            last_update: now,
+        });
+        status
+    }
+
+    /// Record the sequence number of `frame`
+    ///
+    /// Returns `None` if the frame has no source address.
+    pub fn record_frame<const GTS: usize, const PENDING: usize>(
+        &mut self,
+        frame: &Frame<'_, GTS, PENDING>,
+    ) -> Option<SequenceStatus> {
+        let source = frame.header.source?;
+        Some(self.record(source, frame.header.seq))
+    }
+
+    /// Forget the sequence numbers of `source`
+    pub fn forget(&mut self, source: Address) {
+        for entry in self.entries.iter_mut() {
+            if entry.is_some_and(|e| e.source == source) {
+                *entry = None;
+            }
+        }
+    }
+}
+
+impl<const N: usize> Default for SequenceTracker<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mac::beacon::{BeaconOrder, SuperframeOrder};
+
+    fn spec(beacon_order: BeaconOrder) -> SuperframeSpecification {
+        SuperframeSpecification {
+            beacon_order,
+            superframe_order: SuperframeOrder::SuperframeOrder(0),
+            final_cap_slot: 15,
+            battery_life_extension: false,
+            pan_coordinator: false,
+            association_permit: false,
+        }
+    }
+
+    #[test]
+    fn track_beacons() {
+        // Beacons every 15.36 ms
+        let spec = spec(BeaconOrder::BeaconOrder(0));
+        let mut tracker = BeaconTracker::new(62_500, 100);
+        assert_eq!(tracker.update(0), SyncState::Unsynchronized);
+        assert_eq!(tracker.next_window(), None);
+
+        assert!(tracker.beacon_received(1_000, &spec));
+        assert_eq!(tracker.timing().unwrap().beacon_interval, 960);
+        assert_eq!(tracker.update(16_460), SyncState::Synchronized);
+        assert_eq!(tracker.update(16_461), SyncState::Missed(1));
+        assert_eq!(tracker.missed_beacons(), 1);
+
+        // The window of the second beacon is next
+        let window = tracker.next_window().unwrap();
+        assert_eq!(window.expected, 31_720);
+        assert!(window.contains(31_620));
+        assert!(!window.contains(31_619));
+        assert!(window.contains(31_820));
+        assert!(!window.contains(31_821));
+
+        assert!(tracker.beacon_received(31_700, &spec));
+        assert_eq!(tracker.missed_beacons(), 0);
+        assert_eq!(tracker.last_beacon(), Some(31_700));
+        assert_eq!(tracker.next_window().unwrap().expected, 47_060);
+    }
+
+    #[test]
+    fn lose_sync() {
+        let spec = spec(BeaconOrder::BeaconOrder(0));
+        let mut tracker = BeaconTracker::new(62_500, 100);
+        tracker.beacon_received(0, &spec);
+
+        let lost = 4 * 15_360 + 100;
+        assert_eq!(tracker.update(lost), SyncState::Missed(3));
+        assert_eq!(tracker.update(lost + 1), SyncState::Lost);
+        assert!(!tracker.is_synchronized());
+        assert_eq!(tracker.update(lost + 2), SyncState::Unsynchronized);
+    }
+
+    #[test]
+    fn non_beacon_enabled() {
+        let mut tracker = BeaconTracker::new(62_500, 100);
+        assert!(tracker.beacon_received(0, &spec(BeaconOrder::BeaconOrder(0))));
+        assert!(!tracker.beacon_received(1_000, &spec(BeaconOrder::OnDemand)));
+        assert!(!tracker.is_synchronized());
+        assert_eq!(tracker.last_beacon(), None);
+    }
+
+    #[test]
+    fn sequence_numbers() {
+        use crate::mac::beacon::{
+            Beacon, BeaconFrame, GuaranteedTimeSlotInformation, PendingAddress,
+        };
+        use crate::mac::{FrameContent, PanId, ShortAddress};
+
+        let a = Address::Short(PanId(1), ShortAddress(1));
+        let b = Address::Short(PanId(1), ShortAddress(2));
+        let c = Address::Short(PanId(2), ShortAddress(1));
+        let mut tracker = SequenceTracker::<2>::default();
+
+        assert_eq!(tracker.record(a, 255), SequenceStatus::First);
+        assert_eq!(tracker.record(a, 0), SequenceStatus::Next);
+        assert_eq!(tracker.record(b, 0), SequenceStatus::First);
+        assert_eq!(tracker.record(a, 0), SequenceStatus::Duplicate);
+        // A late frame wraps around
+        assert_eq!(tracker.record(a, 255), SequenceStatus::Gap(254));
+
+        // `b` was updated least recently, so `c` replaces it
+        assert_eq!(tracker.record(c, 7), SequenceStatus::First);
+        assert_eq!(tracker.last(b), None);
+        assert_eq!(tracker.last(a), Some(255));
+        assert_eq!(tracker.last(c), Some(7));
+
+        tracker.forget(c);
+        assert_eq!(tracker.last(c), None);
+        assert_eq!(tracker.record(c, 8), SequenceStatus::First);
+
+        let mut frame = BeaconFrame {
+            seq: 9,
+            source: c,
+            beacon: Beacon {
+                superframe_spec: spec(BeaconOrder::OnDemand),
+                guaranteed_time_slot_info: GuaranteedTimeSlotInformation::new(),
+                pending_address: PendingAddress::new(),
+            },
+            payload: &[],
+        }
+        .to_frame();
+        assert_eq!(tracker.record_frame(&frame), Some(SequenceStatus::Next));
+        frame.header.source = None;
+        frame.content = FrameContent::Data;
+        assert_eq!(tracker.record_frame(&frame), None);
+    }
+}